@@ -0,0 +1,13 @@
+#version 330 core
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec4 color;
+
+out vec4 v_Color;
+
+uniform mat4 u_VP;
+
+void main() {
+    v_Color = color;
+    gl_Position = u_VP * vec4(position, 1.0f);
+}
@@ -1,249 +1,508 @@
-#version 330 core
-
-out vec4 fragColor;
-
-in vec3 crntPos;
-in vec3 v_normal;
-in vec4 v_Color;
-in vec2 v_TexCoord;
-in vec4 fragPosLight;
-
-uniform sampler2D u_albedoMap;
-uniform sampler2D u_specularMap;
-uniform sampler2D shadowMap;
-
-uniform samplerCube shadowCubeMap;
-
-
-uniform vec4 baseColorFactor;
-
-uniform bool useTexture;
-
-uniform bool useAlphaCutoff;
-uniform float alphaCutoff;
-
-
-uniform vec4 lightColor;
-uniform vec3 lightPos;
-uniform vec3 camPos;
-//uniform float u_farShadowPlane;
-uniform vec3 u_directLightDirection;
-
-//uniform vec3 u_pointLightPosition;
-
-uniform bool u_LightingEnabled;
-
-uniform float farPlane;
-
-uniform float u_SpecularStrength;
-uniform float u_AmbientStrength;
-
-uniform float u_bias;
-
-uniform vec3 u_BackgroundColor;
-
-vec4 shadowLight() {
-    return texture(shadowMap, v_TexCoord);
-    
-}
-
-vec4 pointLight() {
-    vec3 lightVec = lightPos - crntPos;
-    float dist = length(lightVec);
-    float a = 0.1f;
-    float b = 0.02f;
-    float inten = 1.0f / (a * dist * dist + b * dist + 1.0f);
-
-    // ambient light
-    float ambient = 0.20f;
-    
-    // diffuse light
-    vec3 normal = normalize(v_normal);
-    vec3 lightDirection = normalize(lightVec);
-    float diffuse = max(dot(normal, lightDirection), 0.0f);
-
-    // specular light blinn-phong
-    float specular = 0.0f;
-    if (diffuse != 0.0f) // Only calculate specular if there is diffuse light
-    {
-        vec3 viewDirection = normalize(camPos - crntPos);
-        vec3 reflectionDirection = reflect(-lightDirection, normal);
-        vec3 halfwayVec = normalize(lightDirection + viewDirection);
-        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
-        specular = specAmount * u_SpecularStrength;
-    }
-
-    float shadow = 0.0;
-    vec3 fragToLight = crntPos - lightPos;
-    float currentDepth = length(fragToLight);
-    float bias = max(0.5f * (1.0f - dot(normal, lightDirection)), 0.0005f);
-
-    int sampleRadius  = 2;
-    float pixelSize = 1.0f / 1024.0f;
-    for (int z = -sampleRadius; z <= sampleRadius; z++) {
-        for (int y = -sampleRadius; y <= sampleRadius; y++) {
-            for (int x = -sampleRadius; x <= sampleRadius; x++) {
-                float closestDepth = texture(shadowCubeMap, fragToLight + vec3(x, y, z) * pixelSize).r;
-                closestDepth *= farPlane;
-                if (currentDepth > closestDepth + bias) {
-                    shadow += 1.0f;
-                }
-            }
-        }
-    }
-    shadow /= pow((sampleRadius * 2 + 1), 3);
-
-    
-    vec4 texColor = useTexture ? texture(u_albedoMap, v_TexCoord) : baseColorFactor;
-
-    if (useAlphaCutoff && texColor.a < alphaCutoff) {
-        discard; // Discard fragments below alpha cutoff
-    }
-
-    float specMap = texture(u_specularMap, v_TexCoord).r;
-    vec4 finalColor =  (texColor * (diffuse * (1.0f - shadow) * inten + ambient) + specMap * specular * inten) * lightColor;
-
-    return vec4(finalColor.rgb, texColor.a); // Preserve alpha
-}
-
-vec4 directLight() {
-    // Ambient light
-
-    
-    float ambient = 0.20f;
-    
-    // Diffuse light
-    vec3 normal = normalize(v_normal);
-    vec3 lightDirection = normalize(u_directLightDirection); // Directional light
-    float diffuse = max(dot(normal, lightDirection), 0.0f);
-
-    // Specular light blinn-phong
-    float specular = 0.0f;
-    if (diffuse != 0.0f) // Only calculate specular if there is diffuse light
-    {
-        vec3 viewDirection = normalize(camPos - crntPos);
-        vec3 reflectionDirection = reflect(-lightDirection, normal);
-        vec3 halfwayVec = normalize(lightDirection + viewDirection);
-        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
-        specular = specAmount * u_SpecularStrength;
-    }
-
-    float distance = length(lightPos.xyz - fragPosLight.xyz);
-
-    //calculate shadow factor
-    float shadow = 0.0f;
-    vec3 lightCoords = fragPosLight.xyz / fragPosLight.w;
-    if(lightCoords.z <= 1.0f) {
-        lightCoords = (lightCoords + 1.0f) / 2.0f;
-
-        float closestDepth = texture(shadowMap, lightCoords.xy).r;
-        float currentDepth = lightCoords.z;
-
-        
-
-        float bias = max(0.0 * (1.0 - dot(normal, lightDirection)), 0.0); // Bias to prevent shadow acne
-        //float bias = max(.005f * distance / u_farShadowPlane, u_bias); // Bias to prevent shadow acne but also prevent peter panning
-        //soften shadows
-        int sampleRadius = 2;
-        vec2 pixelSize = 1.0f / textureSize(shadowMap, 0);
-        for (int y = -sampleRadius; y <= sampleRadius; y++) {
-            for (int x = -sampleRadius; x <= sampleRadius; x++) {
-                float closestDepth = texture(shadowMap, lightCoords.xy + vec2(x, y) * pixelSize).r;
-                if (currentDepth > closestDepth + bias) {
-                    shadow += 1.0f;
-                }
-            }
-        }
-        shadow /= pow(sampleRadius * 2.0f + 1.0f, 2.0f);
-        
-        // if (currentDepth > closestDepth + bias) {
-        //     shadow = 1.0f;
-        // }
-    }
-
-    vec4 texColor = /* vec4(1.0f, 1.0f, 1.0f, texture(diffuse0, v_TexCoord).a); */ useTexture ? texture(u_albedoMap, v_TexCoord) : baseColorFactor;
-
-    if (useAlphaCutoff && texColor.a < alphaCutoff) {
-        discard; // Discard fragments below alpha cutoff
-    }
-
-    //vec4 texColor = texture(diffuse0, v_TexCoord);
-    float specMap = texture(u_specularMap, v_TexCoord).g;
-
-    // Combine textures with lighting
-    vec4 finalColor = (texColor * (diffuse * (1.0f - shadow) + ambient) + specMap * specular * (1.0f - shadow)) * lightColor;
-
-    return vec4(finalColor.rgb, texColor.a); // Preserve alpha
-}
-
-vec4 spotLight() {
-    float outerCone = 0.90f;
-    float innerCone = 0.95f;
-
-    // ambient light
-    float ambient = 0.20f;
-    
-    // diffuse light
-    vec3 normal = normalize(v_normal);
-    vec3 lightDirection = normalize(lightPos - crntPos);
-    float diffuse = max(dot(normal, lightDirection), 0.0f);
-
-    //specular light blinn-phong
-    float specular = 0.0f;
-    if (diffuse != 0.0f) // Only calculate if there is diffuse light
-    {
-        vec3 viewDirection = normalize(camPos - crntPos);
-        vec3 reflectionDirection = reflect(-lightDirection, normal);
-        vec3 halfwayVec = normalize(lightDirection + viewDirection);
-        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
-        specular = specAmount * u_SpecularStrength;
-    }
-
-    float angle = dot(vec3(0.0f, -1.0f, 0.0f), -lightDirection);
-    float inten = clamp((angle - outerCone) / (innerCone - outerCone), 0.0f, 1.0f);
-
-    vec4 texColor = useTexture ? texture(u_albedoMap, v_TexCoord) * baseColorFactor : baseColorFactor;
-    //vec4 texColor = texture(diffi)
-    //vec4 texColor = texture(diffuse0, v_TexCoord);
-    float specMap = texture(u_specularMap, v_TexCoord).r;
-    vec4 finalColor = (texColor * (diffuse * inten + ambient) + specMap * specular * inten) * lightColor;
-
-    return vec4(finalColor.rgb, texColor.a); // Preserve alpha
-}
-
-float near = 0.1f;
-float far = 100.0f;
-
-float linearizeDepth(float depth) {
-    return (2.0f * near * far) / (far + near - (depth * 2.0 - 1.0) * (far - near));
-}
-
-float logisticDepth(float depth, float steepness, float offset) {
-    float zVal = linearizeDepth(depth);
-    return (1 / (1 + exp(-steepness * (zVal - offset))));
-}
-
-void main() {
-    if (!u_LightingEnabled) {
-        if (useTexture) {
-            fragColor = texture(u_albedoMap, v_TexCoord);
-        } else {
-            fragColor = baseColorFactor;
-        }
-        return;
-    }
-    
-    float depth = logisticDepth(gl_FragCoord.z, 0.2f, 100.0f);
-    //vec4 directLightColor = directLight();  // Separate color and alpha
-    vec4 pointLightColor = pointLight();
-    vec3 depthColor = (1.0f - depth) + depth * u_BackgroundColor;
-    vec3 finalColor = pointLightColor.rgb * depthColor;//(1.0f - depth) + depth * u_BackgroundColor;
-
-
-    
-    // Preserve the alpha from directLight()
-    //fragColor = vec4(finalColor, directLightColor.a);
-    //test shadowMap
-    //fragColor = vec4(texture(finalColor, v_TexCoord).xyz, 1.0f);
-    fragColor = vec4(finalColor, pointLightColor.a); // fragColor is the fragment in the framebuffer
+#version 330 core
+
+out vec4 fragColor;
+
+in vec3 crntPos;
+in vec3 v_normal;
+in vec4 v_tangent;
+in vec4 v_Color;
+in vec2 v_TexCoord;
+
+uniform sampler2D u_albedoMap;
+uniform sampler2D u_specularMap;
+uniform sampler2D u_normalMap;
+uniform sampler2D u_emissiveMap;
+uniform sampler2D u_occlusionMap;
+
+// a point light, the intensity is divided by constant + linear * d + quadratic * d^2
+struct PointLight {
+    vec3 position;
+    vec4 color;
+    float intensity;
+    float constant;
+    float linear;
+    float quadratic;
+    // distance where the light stops lighting, 0 for no limit
+    float range;
+    float farPlane;
+    int shadowSamples;
+    // 0 hard, 1 grid pcf, 2 rotated disk pcf
+    int shadowQuality;
+    int shadowGrid;
+};
+
+// MAX_POINT_LIGHTS and MAX_POINT_SHADOW_SAMPLES are defined by the engine
+uniform PointLight u_pointLights[MAX_POINT_LIGHTS];
+uniform samplerCube u_pointShadowMaps[MAX_POINT_LIGHTS];
+uniform int u_pointLightCount;
+
+// a directional light, the direction points towards the light
+struct DirectLight {
+    vec3 direction;
+    vec4 color;
+    float intensity;
+    // 0 hard, 1 grid pcf, 2 rotated disk pcf
+    int shadowQuality;
+    int shadowGrid;
+    // the biases are in world units, max(normalBias * (1 - n.l), bias)
+    float shadowBias;
+    float normalBias;
+    // one texel of the shadow map in texture coordinates
+    vec2 texelSize;
+};
+
+// MAX_DIRECT_LIGHTS, SHADOW_CASCADES and SHADOW_CASCADE_GRID are defined by the engine
+uniform DirectLight u_directLights[MAX_DIRECT_LIGHTS];
+// the cascades of a light are the tiles of a SHADOW_CASCADE_GRID wide grid in its shadow map
+uniform sampler2D u_directShadowMaps[MAX_DIRECT_LIGHTS];
+// the cascades of light i start at i * SHADOW_CASCADES
+uniform mat4 u_cascadeMatrices[MAX_DIRECT_LIGHTS * SHADOW_CASCADES];
+// the depth range of each cascade, the biases are scaled by it
+uniform float u_cascadeDepthScales[MAX_DIRECT_LIGHTS * SHADOW_CASCADES];
+uniform int u_directLightCount;
+// the light whose cascades tint the models, -1 for none
+uniform int u_debugCascadeLight;
+
+uniform vec4 baseColorFactor;
+
+uniform bool useTexture;
+uniform bool u_hasNormalMap;
+uniform bool u_hasEmissiveMap;
+// the light the surface gives off, multiplied by the emissive map when there is one
+uniform vec3 u_emissive;
+uniform bool u_hasOcclusionMap;
+// how much the occlusion map darkens the ambient light
+uniform float u_occlusionStrength;
+
+uniform bool useAlphaCutoff;
+uniform float alphaCutoff;
+
+
+uniform vec4 lightColor;
+uniform vec3 lightPos;
+uniform vec3 camPos;
+//uniform float u_farShadowPlane;
+
+//uniform vec3 u_pointLightPosition;
+
+uniform bool u_LightingEnabled;
+
+// poisson disk used by the directional light's disk pcf
+const vec2 POISSON_DISK[16] = vec2[](
+    vec2(-0.94201624f, -0.39906216f), vec2(0.94558609f, -0.76890725f),
+    vec2(-0.09418410f, -0.92938870f), vec2(0.34495938f, 0.29387760f),
+    vec2(-0.91588581f, 0.45771432f), vec2(-0.81544232f, -0.87912464f),
+    vec2(-0.38277543f, 0.27676845f), vec2(0.97484398f, 0.75648379f),
+    vec2(0.44323325f, -0.97511554f), vec2(0.53742981f, -0.47373420f),
+    vec2(-0.26496911f, -0.41893023f), vec2(0.79197514f, 0.19090188f),
+    vec2(-0.24188840f, 0.99706507f), vec2(-0.81409955f, 0.91437590f),
+    vec2(0.19984126f, 0.78641367f), vec2(0.14383161f, -0.14100790f)
+);
+
+uniform float u_SpecularStrength;
+uniform float u_AmbientStrength;
+
+// shadow biases, max(normalBias * (1 - n.l), bias)
+uniform float u_pointShadowBias;
+uniform float u_pointNormalBias;
+
+uniform vec3 u_BackgroundColor;
+// per model color multiply (white when the model has no tint)
+uniform vec4 u_tint;
+
+// hemisphere ambient, surfaces facing up get the sky color and surfaces facing down the ground color
+uniform bool u_hemisphereAmbient;
+uniform vec3 u_ambientSky;
+uniform vec3 u_ambientGround;
+
+// cheap hash used to rotate the shadow sampling pattern per fragment
+float random(vec3 seed) {
+    return fract(sin(dot(seed, vec3(12.9898f, 78.233f, 45.164f))) * 43758.5453f);
+}
+
+// the normal of the surface, bent by the normal map when the mesh has one
+vec3 surfaceNormal() {
+    vec3 normal = normalize(v_normal);
+    // the back faces of double sided meshes are lit from their own side
+    if (!gl_FrontFacing) {
+        normal = -normal;
+    }
+    if (!u_hasNormalMap || dot(v_tangent.xyz, v_tangent.xyz) < 0.000001f) {
+        return normal;
+    }
+    // make the tangent perpendicular to the interpolated normal again
+    vec3 tangent = normalize(v_tangent.xyz - dot(v_tangent.xyz, normal) * normal);
+    // mirrored uvs have a negative handedness
+    vec3 bitangent = cross(normal, tangent) * (v_tangent.w < 0.0f ? -1.0f : 1.0f);
+    vec3 mapped = texture(u_normalMap, v_TexCoord).rgb * 2.0f - 1.0f;
+    return normalize(mat3(tangent, bitangent, normal) * mapped);
+}
+
+// how much of the ambient light reaches the surface, the occlusion is in the red channel
+float ambientOcclusion() {
+    if (!u_hasOcclusionMap) {
+        return 1.0f;
+    }
+    return mix(1.0f, texture(u_occlusionMap, v_TexCoord).r, u_occlusionStrength);
+}
+
+// the light the surface gives off on its own, added without any lighting
+vec3 emission() {
+    if (!u_hasEmissiveMap) {
+        return u_emissive;
+    }
+    return u_emissive * texture(u_emissiveMap, v_TexCoord).rgb;
+}
+
+// the distance to the closest caster the shadow map of a light stores in a direction
+float pointShadowDepth(int light, vec3 direction) {
+    // sampler arrays can only be indexed with constants
+    switch (light) {
+    case 0: return texture(u_pointShadowMaps[0], direction).r * u_pointLights[0].farPlane;
+#if MAX_POINT_LIGHTS > 1
+    case 1: return texture(u_pointShadowMaps[1], direction).r * u_pointLights[1].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 2
+    case 2: return texture(u_pointShadowMaps[2], direction).r * u_pointLights[2].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 3
+    case 3: return texture(u_pointShadowMaps[3], direction).r * u_pointLights[3].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 4
+    case 4: return texture(u_pointShadowMaps[4], direction).r * u_pointLights[4].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 5
+    case 5: return texture(u_pointShadowMaps[5], direction).r * u_pointLights[5].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 6
+    case 6: return texture(u_pointShadowMaps[6], direction).r * u_pointLights[6].farPlane;
+#endif
+#if MAX_POINT_LIGHTS > 7
+    case 7: return texture(u_pointShadowMaps[7], direction).r * u_pointLights[7].farPlane;
+#endif
+    }
+    return 1e30f;
+}
+
+#if MAX_POINT_LIGHTS > 8
+#error pointShadowDepth only samples the shadow maps of 8 point lights
+#endif
+
+// how much of a point light is blocked, 0 fully lit and 1 fully in shadow
+float pointShadow(int light, vec3 fragToLight, float bias) {
+    float currentDepth = length(fragToLight);
+    int quality = u_pointLights[light].shadowQuality;
+    if (quality == 0) {
+        return currentDepth > pointShadowDepth(light, fragToLight) + bias ? 1.0f : 0.0f;
+    }
+
+    // wider when far from the camera
+    float diskRadius = 0.01f * (1.0f + length(camPos - crntPos) / u_pointLights[light].farPlane);
+
+    // sample on the plane facing the light
+    vec3 axis = normalize(fragToLight);
+    vec3 tangent = normalize(cross(axis, abs(axis.y) < 0.99f ? vec3(0.0f, 1.0f, 0.0f) : vec3(1.0f, 0.0f, 0.0f)));
+    vec3 bitangent = cross(axis, tangent);
+
+    float shadow = 0.0f;
+    if (quality == 1) {
+        int grid = clamp(u_pointLights[light].shadowGrid, 1, 8);
+        for (int y = 0; y < grid; y++) {
+            for (int x = 0; x < grid; x++) {
+                vec2 cell = (vec2(x, y) + 0.5f) / float(grid) * 2.0f - 1.0f;
+                vec3 offset = (tangent * cell.x + bitangent * cell.y) * diskRadius * currentDepth;
+                if (currentDepth > pointShadowDepth(light, fragToLight + offset) + bias) {
+                    shadow += 1.0f;
+                }
+            }
+        }
+        return shadow / float(grid * grid);
+    }
+
+    // soften shadows with a vogel disk around the sample direction, rotated per fragment to trade banding for noise
+    int samples = clamp(u_pointLights[light].shadowSamples, 1, MAX_POINT_SHADOW_SAMPLES);
+    float rotation = random(crntPos) * 6.2831853f;
+    for (int i = 0; i < MAX_POINT_SHADOW_SAMPLES; i++) {
+        if (i >= samples) {
+            break;
+        }
+        float r = sqrt((float(i) + 0.5f) / float(samples));
+        float theta = float(i) * 2.3999632f + rotation; // golden angle
+        vec3 offset = (tangent * cos(theta) + bitangent * sin(theta)) * r * diskRadius * currentDepth;
+
+        if (currentDepth > pointShadowDepth(light, fragToLight + offset) + bias) {
+            shadow += 1.0f;
+        }
+    }
+    return shadow / float(samples);
+}
+
+// the first cascade of a light that covers the fragment and its coordinates in that cascade, -1 beyond the shadow
+// distance
+int directCascade(int light, out vec3 lightCoords) {
+    for (int i = 0; i < SHADOW_CASCADES; i++) {
+        vec4 fragPosLight = u_cascadeMatrices[light * SHADOW_CASCADES + i] * vec4(crntPos, 1.0f);
+        lightCoords = fragPosLight.xyz / fragPosLight.w * 0.5f + 0.5f;
+        if (all(greaterThanEqual(lightCoords, vec3(0.0f))) && all(lessThanEqual(lightCoords, vec3(1.0f)))) {
+            return i;
+        }
+    }
+    return -1;
+}
+
+// the depth stored in a cascade's tile, clamped so filtering never reads the neighbouring tile
+float directShadowDepth(int light, int cascade, vec2 coords) {
+    vec2 halfTexel = 0.5f * u_directLights[light].texelSize * float(SHADOW_CASCADE_GRID);
+    vec2 tile = vec2(cascade % SHADOW_CASCADE_GRID, cascade / SHADOW_CASCADE_GRID);
+    vec2 uv = (tile + clamp(coords, halfTexel, 1.0f - halfTexel)) / float(SHADOW_CASCADE_GRID);
+
+    // sampler arrays can only be indexed with constants
+    switch (light) {
+    case 0: return texture(u_directShadowMaps[0], uv).r;
+#if MAX_DIRECT_LIGHTS > 1
+    case 1: return texture(u_directShadowMaps[1], uv).r;
+#endif
+#if MAX_DIRECT_LIGHTS > 2
+    case 2: return texture(u_directShadowMaps[2], uv).r;
+#endif
+    }
+    return 1.0f;
+}
+
+#if MAX_DIRECT_LIGHTS > 3
+#error directShadowDepth only samples the shadow maps of 3 directional lights
+#endif
+
+// how much of a directional light is blocked, 0 fully lit and 1 fully in shadow
+float directShadow(int light, int cascade, vec3 lightCoords, float bias) {
+    float currentDepth = lightCoords.z;
+    int quality = u_directLights[light].shadowQuality;
+    if (quality == 0) {
+        return currentDepth > directShadowDepth(light, cascade, lightCoords.xy) + bias ? 1.0f : 0.0f;
+    }
+
+    // one texel of the cascade's tile
+    vec2 pixelSize = u_directLights[light].texelSize * float(SHADOW_CASCADE_GRID);
+    float shadow = 0.0f;
+    if (quality == 1) {
+        int grid = clamp(u_directLights[light].shadowGrid, 1, 8);
+        for (int y = 0; y < grid; y++) {
+            for (int x = 0; x < grid; x++) {
+                vec2 offset = vec2(x, y) - float(grid - 1) * 0.5f;
+                if (currentDepth > directShadowDepth(light, cascade, lightCoords.xy + offset * pixelSize) + bias) {
+                    shadow += 1.0f;
+                }
+            }
+        }
+        return shadow / float(grid * grid);
+    }
+
+    float rotation = random(crntPos) * 6.2831853f;
+    mat2 spin = mat2(cos(rotation), sin(rotation), -sin(rotation), cos(rotation));
+    for (int i = 0; i < 16; i++) {
+        vec2 offset = spin * POISSON_DISK[i] * 2.0f;
+        if (currentDepth > directShadowDepth(light, cascade, lightCoords.xy + offset * pixelSize) + bias) {
+            shadow += 1.0f;
+        }
+    }
+    return shadow / 16.0f;
+}
+
+vec4 shadowLight() {
+    return texture(u_directShadowMaps[0], v_TexCoord);
+    
+}
+
+// the diffuse and specular light a point light adds to the surface
+vec3 pointLight(int light, vec3 normal, vec4 texColor, float specMap) {
+    vec3 lightVec = u_pointLights[light].position - crntPos;
+    float dist = length(lightVec);
+    float attenuation = u_pointLights[light].constant + u_pointLights[light].linear * dist
+        + u_pointLights[light].quadratic * dist * dist;
+    float inten = u_pointLights[light].intensity / max(attenuation, 0.0001f);
+    float range = u_pointLights[light].range;
+    bool inRange = range <= 0.0f || dist < range;
+    if (range > 0.0f) {
+        // fade to zero at the range instead of cutting off
+        float window = clamp(1.0f - pow(dist / range, 4.0f), 0.0f, 1.0f);
+        inten *= window * window;
+    }
+
+    // diffuse light
+    vec3 lightDirection = normalize(lightVec);
+    float diffuse = max(dot(normal, lightDirection), 0.0f);
+
+    // specular light blinn-phong
+    float specular = 0.0f;
+    if (diffuse != 0.0f) // Only calculate specular if there is diffuse light
+    {
+        vec3 viewDirection = normalize(camPos - crntPos);
+        vec3 halfwayVec = normalize(lightDirection + viewDirection);
+        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
+        specular = specAmount * u_SpecularStrength;
+    }
+
+    float shadow = 0.0;
+    if (inRange) { // beyond the range the fragment is unlit so skip the shadow samples
+        float bias = max(u_pointNormalBias * (1.0f - dot(normalize(v_normal), lightDirection)), u_pointShadowBias);
+        shadow = pointShadow(light, crntPos - u_pointLights[light].position, bias);
+    }
+
+    vec4 lit = (texColor * diffuse * (1.0f - shadow) * inten + specMap * specular * inten) * u_pointLights[light].color;
+    return lit.rgb;
+}
+
+// the diffuse and specular light a directional light adds to the surface
+vec3 directLight(int light, vec3 normal, vec4 texColor, float specMap) {
+    vec3 lightDirection = normalize(u_directLights[light].direction);
+    float diffuse = max(dot(normal, lightDirection), 0.0f);
+
+    // specular light blinn-phong
+    float specular = 0.0f;
+    if (diffuse != 0.0f) // Only calculate specular if there is diffuse light
+    {
+        vec3 viewDirection = normalize(camPos - crntPos);
+        vec3 halfwayVec = normalize(lightDirection + viewDirection);
+        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
+        specular = specAmount * u_SpecularStrength;
+    }
+
+    // fragments beyond the shadow distance are lit
+    float shadow = 0.0f;
+    vec3 lightCoords;
+    int cascade = directCascade(light, lightCoords);
+    if (cascade >= 0) {
+        // bias to prevent shadow acne without detaching the shadow from its caster (peter panning)
+        float bias = max(u_directLights[light].normalBias * (1.0f - dot(normalize(v_normal), lightDirection)),
+            u_directLights[light].shadowBias);
+        bias *= u_cascadeDepthScales[light * SHADOW_CASCADES + cascade];
+        shadow = directShadow(light, cascade, lightCoords, bias);
+    }
+
+    vec4 lit = (texColor * diffuse + specMap * specular) * (1.0f - shadow) * u_directLights[light].intensity
+        * u_directLights[light].color;
+    return lit.rgb;
+}
+
+// the surface lit by the directional lights and the point lights
+vec4 lighting() {
+    vec4 texColor = useTexture ? texture(u_albedoMap, v_TexCoord) : baseColorFactor;
+
+    if (useAlphaCutoff && texColor.a < alphaCutoff) {
+        discard; // Discard fragments below alpha cutoff
+    }
+
+    vec3 normal = surfaceNormal();
+    float specMap = texture(u_specularMap, v_TexCoord).r;
+
+    vec3 lit = vec3(0.0f);
+    int count = clamp(u_pointLightCount, 0, MAX_POINT_LIGHTS);
+    for (int i = 0; i < count; i++) {
+        lit += pointLight(i, normal, texColor, specMap);
+    }
+    int directCount = clamp(u_directLightCount, 0, MAX_DIRECT_LIGHTS);
+    for (int i = 0; i < directCount; i++) {
+        lit += directLight(i, normal, texColor, specMap);
+    }
+
+    if (u_hemisphereAmbient) {
+        // the ambient comes from the sky instead of the lights so it is not tinted by their color
+        vec3 hemisphere = mix(u_ambientGround, u_ambientSky, normal.y * 0.5f + 0.5f);
+        return vec4(lit + texColor.rgb * hemisphere * ambientOcclusion(), texColor.a);
+    }
+
+    // the ambient is tinted by the strongest directional light or else the strongest point light, the engine binds the
+    // lights from the strongest
+    float ambient = 0.20f * ambientOcclusion();
+    vec3 ambientColor = directCount > 0 ? u_directLights[0].color.rgb
+        : count > 0 ? u_pointLights[0].color.rgb : vec3(1.0f);
+    return vec4(lit + texColor.rgb * ambient * ambientColor, texColor.a); // Preserve alpha
+}
+
+vec4 spotLight() {
+    float outerCone = 0.90f;
+    float innerCone = 0.95f;
+
+    // ambient light
+    float ambient = 0.20f;
+    
+    // diffuse light
+    vec3 normal = surfaceNormal();
+    vec3 lightDirection = normalize(lightPos - crntPos);
+    float diffuse = max(dot(normal, lightDirection), 0.0f);
+
+    //specular light blinn-phong
+    float specular = 0.0f;
+    if (diffuse != 0.0f) // Only calculate if there is diffuse light
+    {
+        vec3 viewDirection = normalize(camPos - crntPos);
+        vec3 reflectionDirection = reflect(-lightDirection, normal);
+        vec3 halfwayVec = normalize(lightDirection + viewDirection);
+        float specAmount = pow(max(dot(normal, halfwayVec), 0.0f), 16);
+        specular = specAmount * u_SpecularStrength;
+    }
+
+    float angle = dot(vec3(0.0f, -1.0f, 0.0f), -lightDirection);
+    float inten = clamp((angle - outerCone) / (innerCone - outerCone), 0.0f, 1.0f);
+
+    vec4 texColor = useTexture ? texture(u_albedoMap, v_TexCoord) * baseColorFactor : baseColorFactor;
+    //vec4 texColor = texture(diffi)
+    //vec4 texColor = texture(diffuse0, v_TexCoord);
+    float specMap = texture(u_specularMap, v_TexCoord).r;
+    vec4 finalColor = (texColor * (diffuse * inten + ambient) + specMap * specular * inten) * lightColor;
+
+    return vec4(finalColor.rgb, texColor.a); // Preserve alpha
+}
+
+// the tint of the shadow cascade of a light the fragment samples, blue beyond the shadow distance
+vec3 cascadeDebugColor(int light) {
+    vec3 lightCoords;
+    int cascade = directCascade(light, lightCoords);
+    if (cascade < 0) {
+        return vec3(0.0f, 0.0f, 1.0f);
+    }
+
+    const vec3 CASCADE_COLORS[4] = vec3[](
+        vec3(1.0f, 0.0f, 0.0f), vec3(0.0f, 1.0f, 0.0f), vec3(1.0f, 1.0f, 0.0f), vec3(1.0f, 0.0f, 1.0f)
+    );
+    vec3 color = CASCADE_COLORS[cascade % 4];
+
+    // a checker of 16 by 16 texels shows how many shadow texels cover the surface
+    vec2 tileSize = 1.0f / (u_directLights[light].texelSize * float(SHADOW_CASCADE_GRID));
+    ivec2 cell = ivec2(lightCoords.xy * tileSize / 16.0f);
+    return (cell.x + cell.y) % 2 == 0 ? color : color * 0.6f;
+}
+
+float near = 0.1f;
+float far = 100.0f;
+
+float linearizeDepth(float depth) {
+    return (2.0f * near * far) / (far + near - (depth * 2.0 - 1.0) * (far - near));
+}
+
+float logisticDepth(float depth, float steepness, float offset) {
+    float zVal = linearizeDepth(depth);
+    return (1 / (1 + exp(-steepness * (zVal - offset))));
+}
+
+void main() {
+    if (!u_LightingEnabled) {
+        if (useTexture) {
+            fragColor = texture(u_albedoMap, v_TexCoord) * u_tint;
+        } else {
+            fragColor = baseColorFactor * u_tint;
+        }
+        return;
+    }
+    
+    float depth = logisticDepth(gl_FragCoord.z, 0.2f, 100.0f);
+    vec4 litColor = lighting();
+    vec3 depthColor = (1.0f - depth) + depth * u_BackgroundColor;
+    vec3 finalColor = (litColor.rgb + emission()) * depthColor;//(1.0f - depth) + depth * u_BackgroundColor;
+
+    fragColor = vec4(finalColor, litColor.a) * u_tint; // fragColor is the fragment in the framebuffer
+
+    if (u_debugCascadeLight >= 0 && u_debugCascadeLight < u_directLightCount) {
+        fragColor.rgb = mix(fragColor.rgb, cascadeDebugColor(u_debugCascadeLight), 0.5f);
+    }
 }
\ No newline at end of file
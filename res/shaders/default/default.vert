@@ -1,36 +1,55 @@
-#version 330 core
-
-layout(location = 0) in vec3 position;
-layout(location = 1) in vec3 normal;
-layout(location = 2) in vec4 color;
-layout(location = 3) in vec2 texCoord;
-
-out vec3 crntPos;
-out vec3 v_normal;
-out vec4 v_Color;
-out vec2 v_TexCoord;
-out vec4 fragPosLight;
-
-uniform mat4 u_VP;
-uniform mat4 u_Model;
-
-uniform mat4 u_lightSpaceMatrix;
-
-void main() {
-
-	mat4 normalMatrix = transpose(inverse(u_Model));
-
-	//outputs world position of vertices
-	crntPos = vec3(u_Model * vec4(position, 1.0f));
-	
-	// outputs screen position of vertices
-	gl_Position = u_VP * vec4(crntPos, 1.0) * 0.5; // the 2d screen position in the range of 0 to 1 
-	fragPosLight = u_lightSpaceMatrix * vec4(crntPos, 1.0); // the 2d light position in the range of 0 to 1
-	v_Color = color;
-	v_TexCoord = texCoord;
-
-	//v_normal = normal;
-
-	// apply model matrix to normals to have consistent lighting
-	v_normal = normalize((normalMatrix * vec4(normal, 0.0)).xyz);
+#version 330 core
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec4 color;
+layout(location = 3) in vec2 texCoord;
+layout(location = 4) in vec4 tangent;
+layout(location = 5) in vec4 a_joints;
+layout(location = 6) in vec4 a_weights;
+
+out vec3 crntPos;
+out vec3 v_normal;
+out vec4 v_tangent;
+out vec4 v_Color;
+out vec2 v_TexCoord;
+
+uniform mat4 u_VP;
+uniform mat4 u_Model;
+
+// MAX_JOINTS is defined by the engine
+
+uniform bool u_skinned;
+uniform mat4 u_jointMatrices[MAX_JOINTS];
+
+// blends the joint matrices of a skinned vertex (identity for static meshes)
+mat4 skinMatrix() {
+	if (!u_skinned) {
+		return mat4(1.0);
+	}
+	return a_weights.x * u_jointMatrices[int(a_joints.x)]
+		+ a_weights.y * u_jointMatrices[int(a_joints.y)]
+		+ a_weights.z * u_jointMatrices[int(a_joints.z)]
+		+ a_weights.w * u_jointMatrices[int(a_joints.w)];
+}
+
+void main() {
+
+	mat4 model = u_Model * skinMatrix();
+	mat4 normalMatrix = transpose(inverse(model));
+
+	//outputs world position of vertices
+	crntPos = vec3(model * vec4(position, 1.0f));
+	
+	// outputs screen position of vertices
+	gl_Position = u_VP * vec4(crntPos, 1.0) * 0.5; // the 2d screen position in the range of 0 to 1 
+	v_Color = color;
+	v_TexCoord = texCoord;
+
+	//v_normal = normal;
+
+	// apply model matrix to normals to have consistent lighting
+	v_normal = normalize((normalMatrix * vec4(normal, 0.0)).xyz);
+	// tangents follow the surface so they use the model matrix (zero when the mesh has no tangents), w is the handedness
+	v_tangent = vec4((model * vec4(tangent.xyz, 0.0)).xyz, tangent.w);
 }
\ No newline at end of file
@@ -1,12 +1,30 @@
-#version 330 core
-layout(location = 0) in vec3 position;
-
-uniform mat4 u_lightSpaceMatrix;
-uniform mat4 u_Model;
-
-out vec2 v_texCoords;
-
-void main() {
-    gl_Position = u_lightSpaceMatrix * u_Model * vec4(position, 1.0);
-    v_texCoords = position.xy * 0.5 + 0.5;
+#version 330 core
+layout(location = 0) in vec3 position;
+layout(location = 5) in vec4 a_joints;
+layout(location = 6) in vec4 a_weights;
+
+uniform mat4 u_lightSpaceMatrix;
+uniform mat4 u_Model;
+
+// MAX_JOINTS is defined by the engine
+
+uniform bool u_skinned;
+uniform mat4 u_jointMatrices[MAX_JOINTS];
+
+// blends the joint matrices of a skinned vertex (identity for static meshes)
+mat4 skinMatrix() {
+    if (!u_skinned) {
+        return mat4(1.0);
+    }
+    return a_weights.x * u_jointMatrices[int(a_joints.x)]
+        + a_weights.y * u_jointMatrices[int(a_joints.y)]
+        + a_weights.z * u_jointMatrices[int(a_joints.z)]
+        + a_weights.w * u_jointMatrices[int(a_joints.w)];
+}
+
+out vec2 v_texCoords;
+
+void main() {
+    gl_Position = u_lightSpaceMatrix * u_Model * skinMatrix() * vec4(position, 1.0);
+    v_texCoords = position.xy * 0.5 + 0.5;
 }
\ No newline at end of file
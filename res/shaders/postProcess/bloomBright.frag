@@ -0,0 +1,16 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+uniform float u_threshold;
+
+// keeps the part of the color brighter than the threshold
+void main() {
+    vec3 color = texture(u_screenTexture, v_TexCoord).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    float weight = max(brightness - u_threshold, 0.0f) / max(brightness, 0.0001f);
+    fragColor = vec4(color * weight, 1.0f);
+}
@@ -0,0 +1,16 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+uniform sampler2D u_bloomTexture;
+uniform float u_intensity;
+
+// adds the blurred highlights back on top of the scene
+void main() {
+    vec4 color = texture(u_screenTexture, v_TexCoord);
+    vec3 bloom = texture(u_bloomTexture, v_TexCoord).rgb;
+    fragColor = vec4(color.rgb + bloom * u_intensity, color.a);
+}
@@ -0,0 +1,23 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+uniform vec2 u_texelSize;
+// (1, 0) for the horizontal pass, (0, 1) for the vertical pass
+uniform vec2 u_direction;
+
+const float weights[5] = float[](0.227027f, 0.1945946f, 0.1216216f, 0.054054f, 0.016216f);
+
+// one direction of a separable 9 tap gaussian blur
+void main() {
+    vec2 offset = u_direction * u_texelSize;
+    vec3 color = texture(u_screenTexture, v_TexCoord).rgb * weights[0];
+    for (int i = 1; i < 5; i++) {
+        color += texture(u_screenTexture, v_TexCoord + offset * float(i)).rgb * weights[i];
+        color += texture(u_screenTexture, v_TexCoord - offset * float(i)).rgb * weights[i];
+    }
+    fragColor = vec4(color, 1.0f);
+}
@@ -0,0 +1,14 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+uniform float u_gamma;
+
+// converts linear color to the gamma space of the monitor
+void main() {
+    vec4 color = texture(u_screenTexture, v_TexCoord);
+    fragColor = vec4(pow(max(color.rgb, vec3(0.0f)), vec3(1.0f / u_gamma)), color.a);
+}
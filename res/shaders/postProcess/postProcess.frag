@@ -0,0 +1,12 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+
+// passes the image through unchanged, copy this as a starting point for custom effects
+void main() {
+    fragColor = texture(u_screenTexture, v_TexCoord);
+}
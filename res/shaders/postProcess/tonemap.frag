@@ -0,0 +1,30 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_TexCoord;
+
+uniform sampler2D u_screenTexture;
+uniform float u_exposure;
+// 0 = reinhard, 1 = aces
+uniform int u_operator;
+
+// fitted aces curve by Krzysztof Narkowicz
+vec3 aces(vec3 x) {
+    return clamp((x * (2.51f * x + 0.03f)) / (x * (2.43f * x + 0.59f) + 0.14f), 0.0f, 1.0f);
+}
+
+// maps the hdr scene color into the 0-1 range of the window
+void main() {
+    vec4 color = texture(u_screenTexture, v_TexCoord);
+    vec3 exposed = color.rgb * u_exposure;
+
+    vec3 mapped;
+    if (u_operator == 1) {
+        mapped = aces(exposed);
+    } else {
+        mapped = exposed / (exposed + vec3(1.0f));
+    }
+
+    fragColor = vec4(mapped, color.a);
+}
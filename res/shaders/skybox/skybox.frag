@@ -0,0 +1,12 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec3 v_Direction;
+
+uniform samplerCube u_skybox;
+uniform float u_intensity;
+
+void main() {
+    fragColor = vec4(texture(u_skybox, v_Direction).rgb * u_intensity, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+layout(location = 0) in vec3 position;
+
+out vec3 v_Direction;
+
+// the projection times the view matrix without the translation
+uniform mat4 u_VP;
+
+void main() {
+    v_Direction = position;
+    vec4 pos = u_VP * vec4(position, 1.0);
+    // z = w puts the cube at the far plane after the perspective divide
+    gl_Position = pos.xyww;
+}
@@ -0,0 +1,17 @@
+#version 330 core
+
+out vec4 fragColor;
+
+in vec2 v_UV;
+in vec4 v_Color;
+
+uniform sampler2D u_texture;
+uniform bool u_hasTexture;
+
+void main() {
+    vec4 color = u_hasTexture ? texture(u_texture, v_UV) * v_Color : v_Color;
+    if (color.a <= 0.0) {
+        discard;
+    }
+    fragColor = color;
+}
@@ -0,0 +1,16 @@
+#version 330 core
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 uv;
+layout(location = 2) in vec4 color;
+
+out vec2 v_UV;
+out vec4 v_Color;
+
+uniform mat4 u_VP;
+
+void main() {
+    v_UV = uv;
+    v_Color = color;
+    gl_Position = u_VP * vec4(position, 0.0f, 1.0f);
+}
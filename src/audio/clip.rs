@@ -0,0 +1,213 @@
+//! sound files kept in memory and the source that streams them to the output with the volume of each ear.
+
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rodio::source::UniformSourceIterator;
+use rodio::{Decoder, Source};
+
+use super::AudioError;
+
+/// a decoder reading a clip from memory
+type ClipDecoder = Decoder<Cursor<Arc<[u8]>>>;
+
+/// a WAV or OGG Vorbis sound loaded into memory. cloning is cheap and shares the data, so one clip can be played by
+/// many sources.
+#[derive(Clone)]
+pub struct AudioClip {
+    data: Arc<[u8]>,
+    duration: Option<Duration>,
+}
+
+impl AudioClip {
+    /// loads a sound file
+    ///
+    /// # Arguments
+    /// - `path` - the path of the WAV or OGG file
+    ///
+    /// # Returns
+    /// the clip or the reason it could not be loaded
+    pub fn load(path: impl AsRef<Path>) -> Result<AudioClip, AudioError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// creates a clip from the bytes of a sound file, for example one embedded with `include_bytes!`
+    ///
+    /// # Arguments
+    /// - `data` - the contents of the WAV or OGG file
+    ///
+    /// # Returns
+    /// the clip or the reason it could not be decoded
+    pub fn from_bytes(data: impl Into<Arc<[u8]>>) -> Result<AudioClip, AudioError> {
+        let data = data.into();
+        let decoder = Decoder::new(Cursor::new(data.clone()))?;
+        Ok(AudioClip {
+            duration: decoder.total_duration(),
+            data,
+        })
+    }
+
+    /// get the length of the clip if the format stores it
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// starts decoding the clip from the beginning
+    fn decode(&self) -> Option<ClipDecoder> {
+        Decoder::new(Cursor::new(self.data.clone())).ok()
+    }
+}
+
+/// the settings of a playing clip shared between the [`AudioSource`](crate::nodes::AudioSource) on the main thread
+/// and the [`ClipSource`] on the audio thread
+#[derive(Debug, Default)]
+pub(crate) struct SharedPlayback {
+    /// the volume of the left and right ear as f32 bits
+    left: AtomicU32,
+    right: AtomicU32,
+    looping: AtomicBool,
+}
+
+impl SharedPlayback {
+    /// sets the volume of each ear, used from the next sample
+    pub(crate) fn set_gains(&self, left: f32, right: f32) {
+        self.left.store(left.to_bits(), Ordering::Relaxed);
+        self.right.store(right.to_bits(), Ordering::Relaxed);
+    }
+
+    fn gains(&self) -> (f32, f32) {
+        (
+            f32::from_bits(self.left.load(Ordering::Relaxed)),
+            f32::from_bits(self.right.load(Ordering::Relaxed)),
+        )
+    }
+
+    /// sets if the clip starts over when it ends
+    pub(crate) fn set_looping(&self, looping: bool) {
+        self.looping.store(looping, Ordering::Relaxed);
+    }
+
+    fn looping(&self) -> bool {
+        self.looping.load(Ordering::Relaxed)
+    }
+}
+
+/// plays a clip in stereo with the volumes of the [`SharedPlayback`], starting over while it is looping
+pub(crate) struct ClipSource {
+    clip: AudioClip,
+    samples: UniformSourceIterator<ClipDecoder, f32>,
+    sample_rate: u32,
+    shared: Arc<SharedPlayback>,
+    /// the ear of the next sample, 0 is left and 1 is right
+    channel: u16,
+}
+
+impl ClipSource {
+    /// creates a source at the beginning of the clip, `None` if the clip can't be decoded
+    pub(crate) fn new(clip: AudioClip, shared: Arc<SharedPlayback>) -> Option<ClipSource> {
+        let decoder = clip.decode()?;
+        let sample_rate = decoder.sample_rate();
+        Some(ClipSource {
+            samples: UniformSourceIterator::new(decoder, 2, sample_rate),
+            clip,
+            sample_rate,
+            shared,
+            channel: 0,
+        })
+    }
+}
+
+impl Iterator for ClipSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = match self.samples.next() {
+            Some(sample) => sample,
+            None if self.shared.looping() => {
+                self.samples = UniformSourceIterator::new(self.clip.decode()?, 2, self.sample_rate);
+                self.channel = 0;
+                self.samples.next()?
+            }
+            None => return None,
+        };
+
+        let (left, right) = self.shared.gains();
+        let gain = if self.channel == 0 { left } else { right };
+        self.channel = 1 - self.channel;
+        Some(sample * gain)
+    }
+}
+
+impl Source for ClipSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a mono 16 bit WAV file with the samples
+    fn wav(samples: &[i16]) -> Vec<u8> {
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // pcm
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+        bytes.extend_from_slice(&8000u32.to_le_bytes());
+        bytes.extend_from_slice(&16000u32.to_le_bytes()); // bytes per second
+        bytes.extend_from_slice(&2u16.to_le_bytes()); // bytes per frame
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_clip_source() {
+        assert!(AudioClip::from_bytes(vec![0u8; 16]).is_err());
+
+        let clip = AudioClip::from_bytes(wav(&[16384; 4])).unwrap();
+        let shared = Arc::new(SharedPlayback::default());
+        shared.set_gains(1.0, 0.5);
+
+        // the mono clip plays on both ears with their own volume
+        let source = ClipSource::new(clip.clone(), shared.clone()).unwrap();
+        assert_eq!((source.channels(), source.sample_rate()), (2, 8000));
+        let samples: Vec<f32> = source.collect();
+        assert_eq!(samples.len(), 8);
+        for ears in samples.chunks(2) {
+            assert!((ears[0] - 0.5).abs() < 1e-3);
+            assert!((ears[1] - 0.25).abs() < 1e-3);
+        }
+
+        // a looping clip starts over until looping is turned off
+        shared.set_looping(true);
+        let mut source = ClipSource::new(clip, shared.clone()).unwrap();
+        assert_eq!(source.by_ref().take(20).count(), 20);
+        shared.set_looping(false);
+        assert!(source.count() <= 8);
+    }
+}
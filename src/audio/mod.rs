@@ -0,0 +1,244 @@
+//! sound playback with 3D positioning.
+//!
+//! the audio output lives in the game context as `context.audio`. sounds are played by
+//! [`AudioSource`](crate::nodes::AudioSource) nodes placed in the scene tree, every frame the engine moves the
+//! [`AudioListener`] to the active camera and sets the volume of each ear from the distance and direction of the
+//! source to the listener.
+//!
+//! clips are loaded with [`AudioClip::load`] from WAV or OGG Vorbis files and can be shared between sources.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::audio::AudioClip;
+//! use quaturn::nodes::{AudioSource, NodeBuilder};
+//!
+//! let clip = AudioClip::load("res/sounds/campfire.ogg").unwrap();
+//!
+//! let mut campfire = NodeBuilder::new(AudioSource::new(clip))
+//!     .with_position(glm::vec3(4.0, 0.0, -2.0))
+//!     .build();
+//! campfire.set_looping(true);
+//! campfire.play();
+//!
+//! engine.context.nodes.add("campfire", campfire);
+//! ```
+
+mod clip;
+
+pub use clip::AudioClip;
+pub(crate) use clip::{ClipSource, SharedPlayback};
+
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
+use std::fmt;
+
+use nalgebra_glm as glm;
+use rodio::{OutputStream, OutputStreamHandle};
+
+/// an error while loading or playing a sound
+#[derive(Debug)]
+pub enum AudioError {
+    /// the file could not be read
+    Io(std::io::Error),
+    /// the file is not a supported sound format
+    Decode(rodio::decoder::DecoderError),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::Io(error) => write!(f, "failed to read sound file: {}", error),
+            AudioError::Decode(error) => write!(f, "failed to decode sound: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl From<std::io::Error> for AudioError {
+    fn from(error: std::io::Error) -> Self {
+        AudioError::Io(error)
+    }
+}
+
+impl From<rodio::decoder::DecoderError> for AudioError {
+    fn from(error: rodio::decoder::DecoderError) -> Self {
+        AudioError::Decode(error)
+    }
+}
+
+/// the ears of the scene, placed at the active camera by the engine every frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioListener {
+    /// the world position of the listener
+    pub position: glm::Vec3,
+    /// the direction to the right ear
+    pub right: glm::Vec3,
+}
+
+impl Default for AudioListener {
+    fn default() -> Self {
+        AudioListener {
+            position: glm::Vec3::zeros(),
+            right: glm::Vec3::x(),
+        }
+    }
+}
+
+impl AudioListener {
+    /// creates a listener looking in a direction with +Y up, matching the view of a camera
+    ///
+    /// # Arguments
+    /// - `position` - the world position of the listener
+    /// - `forward` - the direction the listener is looking
+    pub fn looking_at(position: glm::Vec3, forward: glm::Vec3) -> AudioListener {
+        let right = glm::cross(&forward, &glm::Vec3::y());
+        AudioListener {
+            position,
+            // looking straight up or down leaves the ears where they were facing along X
+            right: if glm::length(&right) < 1e-6 {
+                glm::Vec3::x()
+            } else {
+                glm::normalize(&right)
+            },
+        }
+    }
+
+    /// the volume of the left and right ear for a sound at a position
+    ///
+    /// # Arguments
+    /// - `position` - the world position of the sound
+    /// - `min_distance` - the distance up to which the sound plays at full volume
+    /// - `max_distance` - the distance where the sound can't be heard anymore
+    ///
+    /// # Returns
+    /// the left and right volume from 0 to 1
+    pub fn gains(&self, position: &glm::Vec3, min_distance: f32, max_distance: f32) -> (f32, f32) {
+        let offset = position - self.position;
+        let distance = glm::length(&offset);
+        let volume = attenuation(distance, min_distance, max_distance);
+
+        // -1 is fully left and 1 is fully right
+        let pan = if distance > 1e-6 {
+            glm::dot(&(offset / distance), &self.right).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+        // equal power panning scaled so a sound in front plays at full volume on both ears
+        let angle = (pan + 1.0) * FRAC_PI_4;
+        let left = (angle.cos() * SQRT_2).min(1.0);
+        let right = (angle.sin() * SQRT_2).min(1.0);
+        (left * volume, right * volume)
+    }
+}
+
+/// the volume of a sound at a distance from the listener.
+///
+/// the sound plays at full volume up to `min_distance`, then falls off with the inverse distance and fades to
+/// silence at `max_distance`.
+///
+/// # Arguments
+/// - `distance` - the distance from the listener to the sound
+/// - `min_distance` - the distance up to which the sound plays at full volume
+/// - `max_distance` - the distance where the sound can't be heard anymore
+///
+/// # Returns
+/// the volume from 0 to 1
+pub fn attenuation(distance: f32, min_distance: f32, max_distance: f32) -> f32 {
+    if distance <= min_distance {
+        return 1.0;
+    }
+    if distance >= max_distance {
+        return 0.0;
+    }
+    let inverse = min_distance.max(1e-3) / distance;
+    let fade = 1.0 - (distance - min_distance) / (max_distance - min_distance);
+    inverse.min(1.0) * fade
+}
+
+/// the audio output of the game, see the [module docs](self).
+pub struct Audio {
+    /// the output stream has to stay alive while sounds play
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    /// the listener of the scene, moved to the active camera every frame
+    pub listener: AudioListener,
+    /// the volume of every sound
+    pub master_volume: f32,
+}
+
+impl Audio {
+    /// opens the default audio device. without a device the game runs silently.
+    pub fn new() -> Audio {
+        let output = match OutputStream::try_default() {
+            Ok(output) => Some(output),
+            Err(error) => {
+                log::warn!("no audio output available: {}", error);
+                None
+            }
+        };
+        Audio {
+            output,
+            listener: AudioListener::default(),
+            master_volume: 1.0,
+        }
+    }
+
+    /// if an audio device was opened
+    pub fn has_output(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// the handle to play sounds on, `None` without an audio device
+    pub(crate) fn handle(&self) -> Option<&OutputStreamHandle> {
+        self.output.as_ref().map(|(_, handle)| handle)
+    }
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attenuation() {
+        assert_eq!(attenuation(0.5, 1.0, 20.0), 1.0);
+        assert_eq!(attenuation(1.0, 1.0, 20.0), 1.0);
+        assert_eq!(attenuation(20.0, 1.0, 20.0), 0.0);
+        assert_eq!(attenuation(50.0, 1.0, 20.0), 0.0);
+
+        // quieter the further away
+        let near = attenuation(2.0, 1.0, 20.0);
+        let far = attenuation(10.0, 1.0, 20.0);
+        assert!(near < 1.0 && far < near && far > 0.0);
+    }
+
+    #[test]
+    fn test_gains() {
+        // a camera at the origin looking down -Z has +X on its right
+        let listener = AudioListener::looking_at(glm::Vec3::zeros(), -glm::Vec3::z());
+        assert!(glm::distance(&listener.right, &glm::Vec3::x()) < 1e-6);
+
+        let (left, right) = listener.gains(&glm::vec3(0.0, 0.0, -0.5), 1.0, 20.0);
+        assert!((left - 1.0).abs() < 1e-5 && (right - 1.0).abs() < 1e-5);
+
+        let (left, right) = listener.gains(&glm::vec3(5.0, 0.0, 0.0), 1.0, 20.0);
+        assert!(left.abs() < 1e-5 && right > 0.0);
+
+        let (left, right) = listener.gains(&glm::vec3(-5.0, 0.0, 0.0), 1.0, 20.0);
+        assert!(right.abs() < 1e-5 && left > 0.0);
+
+        // turning around swaps the ears
+        let turned = AudioListener::looking_at(glm::Vec3::zeros(), glm::Vec3::z());
+        let (left, right) = turned.gains(&glm::vec3(5.0, 0.0, 0.0), 1.0, 20.0);
+        assert!(right.abs() < 1e-5 && left > 0.0);
+
+        assert_eq!(
+            listener.gains(&glm::vec3(0.0, 0.0, -30.0), 1.0, 20.0),
+            (0.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,578 @@
+//! skeletal animation for skinned gltf models.
+//!
+//! a gltf file stores its nodes as a hierarchy. joints are nodes, skins list the joints that move a mesh and animations key the
+//! translation, rotation and scale of the nodes over time. the [`Animator`] plays one animation at a time, poses the node
+//! hierarchy every frame and computes the joint matrices the vertex shader blends the vertices with.
+//!
+//! models without skins have no animator, so static models are not affected.
+//!
+//! ## Example
+//! ```rust,ignore
+//! engine.context.nodes.add("character", Model::new_gltf("res/models/character.glb")?)
+//!     .define_ready(|model| {
+//!         model.play_animation("Walk");
+//!         model.set_animation_speed(1.5);
+//!     });
+//! ```
+
+use std::collections::BTreeMap;
+
+use nalgebra_glm as glm;
+
+use crate::components::Aabb;
+
+/// the max number of joints a skin can have, the shaders have a joint matrix array of this size.
+///
+/// the matrices are plain uniforms and OpenGL 3.3 only guarantees 1024 vertex uniform components (64 matrices), 48 leaves
+/// room for the other uniforms of the vertex shaders. skins with more joints are loaded without skinning.
+pub const MAX_JOINTS: usize = 48;
+
+/// how the values between two keyframes are computed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// the value of the previous keyframe is held
+    Step,
+    /// the values are blended linearly (spherically for rotations)
+    Linear,
+    /// the values are blended with the tangents of the keyframes
+    CubicSpline,
+}
+
+/// the keyframe values of a channel. cubic spline channels store an in tangent, value and out tangent per keyframe.
+#[derive(Clone, Debug)]
+pub enum ChannelValues {
+    /// keyframes for the translation of the node
+    Translations(Vec<glm::Vec3>),
+    /// keyframes for the rotation of the node
+    Rotations(Vec<glm::Quat>),
+    /// keyframes for the scale of the node
+    Scales(Vec<glm::Vec3>),
+}
+
+/// animates one property of one node
+#[derive(Clone, Debug)]
+pub struct AnimationChannel {
+    /// the index of the animated node in the file
+    pub node: usize,
+    /// the time of every keyframe in seconds
+    pub times: Vec<f32>,
+    /// the value of every keyframe
+    pub values: ChannelValues,
+    /// how the keyframes are blended
+    pub interpolation: Interpolation,
+}
+
+/// a named animation made of channels
+#[derive(Clone, Debug)]
+pub struct Animation {
+    /// the name of the animation
+    pub name: String,
+    /// the channels of the animation
+    pub channels: Vec<AnimationChannel>,
+    /// the time of the last keyframe in seconds
+    pub duration: f32,
+}
+
+impl Animation {
+    /// creates an animation, the duration is the time of the last keyframe
+    ///
+    /// # Arguments
+    /// - `name` - the name of the animation
+    /// - `channels` - the channels of the animation
+    pub fn new(name: &str, channels: Vec<AnimationChannel>) -> Animation {
+        let duration = channels
+            .iter()
+            .filter_map(|channel| channel.times.last().copied())
+            .fold(0.0, f32::max);
+        Animation {
+            name: name.to_string(),
+            channels,
+            duration,
+        }
+    }
+}
+
+/// the joints that move a mesh
+#[derive(Clone, Debug)]
+pub struct Skin {
+    /// the node index of every joint
+    pub joints: Vec<usize>,
+    /// moves a vertex from model space into the space of the matching joint
+    pub inverse_bind_matrices: Vec<glm::Mat4>,
+}
+
+/// a node of the hierarchy with its rest pose
+#[derive(Clone, Debug)]
+pub struct SkeletonNode {
+    /// the index of the parent node, `None` for root nodes
+    pub parent: Option<usize>,
+    /// the translation without an animation
+    pub translation: glm::Vec3,
+    /// the rotation without an animation
+    pub rotation: glm::Quat,
+    /// the scale without an animation
+    pub scale: glm::Vec3,
+}
+
+/// plays the animations of a model and computes the joint matrices of its skins
+#[derive(Clone, Debug)]
+pub struct Animator {
+    nodes: Vec<SkeletonNode>,
+    /// applied on top of the root nodes (converts the file into the engine coordinate system)
+    root_transform: glm::Mat4,
+    skins: Vec<Skin>,
+    animations: Vec<Animation>,
+    /// the world matrix of every node for the current pose
+    pose: Vec<glm::Mat4>,
+    current: Option<usize>,
+    time: f32,
+    speed: f32,
+    playing: bool,
+    looping: bool,
+}
+
+impl Animator {
+    /// creates an animator in the rest pose with no animation playing
+    ///
+    /// # Arguments
+    /// - `nodes` - every node of the file in file order
+    /// - `root_transform` - the transform applied on top of the root nodes
+    /// - `skins` - the skins of the file
+    /// - `animations` - the animations of the file
+    pub fn new(
+        nodes: Vec<SkeletonNode>,
+        root_transform: glm::Mat4,
+        skins: Vec<Skin>,
+        animations: Vec<Animation>,
+    ) -> Animator {
+        let mut animator = Animator {
+            nodes,
+            root_transform,
+            skins,
+            animations,
+            pose: Vec::new(),
+            current: None,
+            time: 0.0,
+            speed: 1.0,
+            playing: false,
+            looping: true,
+        };
+        animator.update_pose();
+        animator
+    }
+
+    /// starts an animation from the beginning
+    ///
+    /// # Arguments
+    /// - `name` - the name of the animation
+    ///
+    /// # Returns
+    /// false if there is no animation with the name
+    pub fn play(&mut self, name: &str) -> bool {
+        let Some(index) = self.animations.iter().position(|a| a.name == name) else {
+            return false;
+        };
+        self.current = Some(index);
+        self.time = 0.0;
+        self.playing = true;
+        self.update_pose();
+        true
+    }
+
+    /// pauses the current animation, keeping the pose
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// resumes the current animation
+    pub fn resume(&mut self) {
+        self.playing = self.current.is_some();
+    }
+
+    /// stops the current animation and goes back to the rest pose
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.playing = false;
+        self.time = 0.0;
+        self.update_pose();
+    }
+
+    /// check if an animation is playing
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// get the name of the current animation
+    pub fn current_animation(&self) -> Option<&str> {
+        self.current.map(|i| self.animations[i].name.as_str())
+    }
+
+    /// get the names of all the animations
+    pub fn animation_names(&self) -> impl Iterator<Item = &str> {
+        self.animations.iter().map(|a| a.name.as_str())
+    }
+
+    /// set the playback speed, 1.0 is normal speed and negative values play backwards
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// get the playback speed
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// set if the animation starts over when it ends (true by default). a non looping animation pauses on its last frame.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// get the time of the current animation in seconds
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// advances the current animation and updates the pose
+    ///
+    /// # Arguments
+    /// - `delta_time` - the time since the last update in seconds
+    pub fn update(&mut self, delta_time: f32) {
+        if !self.playing {
+            return;
+        }
+        let Some(current) = self.current else {
+            return;
+        };
+
+        let duration = self.animations[current].duration;
+        self.time += delta_time * self.speed;
+        if duration <= 0.0 {
+            self.time = 0.0;
+        } else if self.looping {
+            self.time = self.time.rem_euclid(duration);
+        } else if !(0.0..=duration).contains(&self.time) {
+            self.time = self.time.clamp(0.0, duration);
+            self.playing = false;
+        }
+
+        self.update_pose();
+    }
+
+    /// get the joint matrices of a skin for the current pose (at most [`MAX_JOINTS`])
+    ///
+    /// # Arguments
+    /// - `skin` - the index of the skin
+    pub fn joint_matrices(&self, skin: usize) -> Vec<glm::Mat4> {
+        let Some(skin) = self.skins.get(skin) else {
+            return Vec::new();
+        };
+        skin.joints
+            .iter()
+            .take(MAX_JOINTS)
+            .enumerate()
+            .map(|(i, &joint)| {
+                let inverse_bind = skin
+                    .inverse_bind_matrices
+                    .get(i)
+                    .copied()
+                    .unwrap_or_else(glm::Mat4::identity);
+                self.pose
+                    .get(joint)
+                    .copied()
+                    .unwrap_or_else(glm::Mat4::identity)
+                    * inverse_bind
+            })
+            .collect()
+    }
+
+    /// samples the current animation and computes the world matrix of every node
+    fn update_pose(&mut self) {
+        let mut locals: Vec<(glm::Vec3, glm::Quat, glm::Vec3)> = self
+            .nodes
+            .iter()
+            .map(|node| (node.translation, node.rotation, node.scale))
+            .collect();
+
+        if let Some(animation) = self.current.map(|i| &self.animations[i]) {
+            for channel in &animation.channels {
+                let Some(local) = locals.get_mut(channel.node) else {
+                    continue;
+                };
+                match &channel.values {
+                    ChannelValues::Translations(values) => {
+                        if let Some(value) = sample(channel, values, self.time, glm::lerp) {
+                            local.0 = value;
+                        }
+                    }
+                    ChannelValues::Rotations(values) => {
+                        if let Some(value) = sample(channel, values, self.time, glm::quat_slerp) {
+                            local.1 = glm::quat_normalize(&value);
+                        }
+                    }
+                    ChannelValues::Scales(values) => {
+                        if let Some(value) = sample(channel, values, self.time, glm::lerp) {
+                            local.2 = value;
+                        }
+                    }
+                }
+            }
+        }
+
+        // parents can come after their children in the file so resolve them recursively
+        let mut pose: Vec<Option<glm::Mat4>> = vec![None; self.nodes.len()];
+        for i in 0..self.nodes.len() {
+            self.resolve(i, &locals, &mut pose, 0);
+        }
+        self.pose = pose
+            .into_iter()
+            .map(|matrix| matrix.unwrap_or_else(glm::Mat4::identity))
+            .collect();
+    }
+
+    fn resolve(
+        &self,
+        node: usize,
+        locals: &[(glm::Vec3, glm::Quat, glm::Vec3)],
+        pose: &mut [Option<glm::Mat4>],
+        depth: usize,
+    ) -> glm::Mat4 {
+        if let Some(matrix) = pose[node] {
+            return matrix;
+        }
+        let (translation, rotation, scale) = locals[node];
+        let local =
+            glm::translation(&translation) * glm::quat_to_mat4(&rotation) * glm::scaling(&scale);
+
+        let parent = match self.nodes[node].parent {
+            // the depth guard stops broken files with parent cycles
+            Some(parent) if parent < self.nodes.len() && depth < self.nodes.len() => {
+                self.resolve(parent, locals, pose, depth + 1)
+            }
+            _ => self.root_transform,
+        };
+        let matrix = parent * local;
+        pose[node] = Some(matrix);
+        matrix
+    }
+}
+
+/// fits a box around the vertices each joint moves, used to bound a posed mesh without skinning every vertex
+///
+/// # Arguments
+/// - `positions` - the positions of the vertices in the bind pose
+/// - `skin` - the joints and weights of every vertex
+///
+/// # Returns
+/// every joint that moves a vertex with the box around its vertices
+pub fn joint_bounds(
+    positions: impl IntoIterator<Item = glm::Vec3>,
+    skin: &[([u16; 4], [f32; 4])],
+) -> Vec<(usize, Aabb)> {
+    let mut bounds: BTreeMap<usize, Aabb> = BTreeMap::new();
+    for (position, (joints, weights)) in positions.into_iter().zip(skin) {
+        for (&joint, &weight) in joints.iter().zip(weights) {
+            if weight > 0.0 {
+                bounds
+                    .entry(joint as usize)
+                    .or_insert_with(Aabb::empty)
+                    .grow(position);
+            }
+        }
+    }
+    bounds.into_iter().collect()
+}
+
+/// fits a box around a posed mesh. a posed vertex is a weighted blend of its position moved by each of its joints, so it
+/// lies inside the boxes of those joints moved by their matrices.
+///
+/// # Arguments
+/// - `joint_bounds` - the boxes from [`joint_bounds`]
+/// - `joint_matrices` - the joint matrices of the pose, see [`Animator::joint_matrices`]
+///
+/// # Returns
+/// the box around the posed mesh
+pub fn posed_bounds(joint_bounds: &[(usize, Aabb)], joint_matrices: &[glm::Mat4]) -> Aabb {
+    joint_bounds
+        .iter()
+        .filter_map(|(joint, bounds)| Some(bounds.transformed(joint_matrices.get(*joint)?)))
+        .fold(Aabb::empty(), |posed, bounds| posed.merge(&bounds))
+}
+
+/// moves a vertex by its joints the same way the vertex shader does
+///
+/// # Arguments
+/// - `position` - the position in the bind pose
+/// - `joints` - the joints of the vertex
+/// - `weights` - how much every joint moves the vertex
+/// - `joint_matrices` - the joint matrices of the pose
+///
+/// # Returns
+/// the posed position
+pub fn posed_position(
+    position: &glm::Vec3,
+    joints: &[u16; 4],
+    weights: &[f32; 4],
+    joint_matrices: &[glm::Mat4],
+) -> glm::Vec3 {
+    let mut skin = glm::Mat4::zeros();
+    for (&joint, &weight) in joints.iter().zip(weights) {
+        if let Some(matrix) = joint_matrices.get(joint as usize) {
+            skin += matrix * weight;
+        }
+    }
+    (skin * glm::vec4(position.x, position.y, position.z, 1.0)).xyz()
+}
+
+/// samples a channel at a time, `None` if the channel has no keyframes
+fn sample<T>(
+    channel: &AnimationChannel,
+    values: &[T],
+    time: f32,
+    blend: impl Fn(&T, &T, f32) -> T,
+) -> Option<T>
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T>,
+{
+    let times = &channel.times;
+    let cubic = channel.interpolation == Interpolation::CubicSpline;
+    // the value of a keyframe, cubic spline keyframes are stored as [in tangent, value, out tangent]
+    let value = |i: usize| if cubic { values[i * 3 + 1] } else { values[i] };
+
+    let keyframes = if cubic {
+        values.len() / 3
+    } else {
+        values.len()
+    }
+    .min(times.len());
+    if keyframes == 0 {
+        return None;
+    }
+    if keyframes == 1 || time <= times[0] {
+        return Some(value(0));
+    }
+    if time >= times[keyframes - 1] {
+        return Some(value(keyframes - 1));
+    }
+
+    let next = times[..keyframes].partition_point(|&t| t <= time);
+    let previous = next - 1;
+    let delta = times[next] - times[previous];
+    let t = if delta > 0.0 {
+        (time - times[previous]) / delta
+    } else {
+        0.0
+    };
+
+    Some(match channel.interpolation {
+        Interpolation::Step => value(previous),
+        Interpolation::Linear => blend(&value(previous), &value(next), t),
+        Interpolation::CubicSpline => {
+            let out_tangent = values[previous * 3 + 2];
+            let in_tangent = values[next * 3];
+            let (t2, t3) = (t * t, t * t * t);
+            value(previous) * (2.0 * t3 - 3.0 * t2 + 1.0)
+                + out_tangent * ((t3 - 2.0 * t2 + t) * delta)
+                + value(next) * (-2.0 * t3 + 3.0 * t2)
+                + in_tangent * ((t3 - t2) * delta)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a root with one child joint one unit up, the animation moves the root along x over one second
+    fn animator() -> Animator {
+        let rest = |parent, y| SkeletonNode {
+            parent,
+            translation: glm::vec3(0.0, y, 0.0),
+            rotation: glm::quat_identity(),
+            scale: glm::vec3(1.0, 1.0, 1.0),
+        };
+        let channel = AnimationChannel {
+            node: 1,
+            times: vec![0.0, 1.0],
+            values: ChannelValues::Translations(vec![
+                glm::vec3(0.0, 0.0, 0.0),
+                glm::vec3(2.0, 0.0, 0.0),
+            ]),
+            interpolation: Interpolation::Linear,
+        };
+        Animator::new(
+            // the child comes first to check the parents are resolved in any order
+            vec![rest(Some(1), 1.0), rest(None, 0.0)],
+            glm::Mat4::identity(),
+            vec![Skin {
+                joints: vec![0],
+                inverse_bind_matrices: vec![glm::translation(&glm::vec3(0.0, -1.0, 0.0))],
+            }],
+            vec![Animation::new("Move", vec![channel])],
+        )
+    }
+
+    fn joint_position(animator: &Animator) -> glm::Vec3 {
+        let matrix = animator.joint_matrices(0)[0];
+        (matrix * glm::vec4(0.0, 1.0, 0.0, 1.0)).xyz()
+    }
+
+    #[test]
+    fn test_rest_pose_is_identity() {
+        let animator = animator();
+        assert_eq!(animator.joint_matrices(0)[0], glm::Mat4::identity());
+        assert!(animator.joint_matrices(1).is_empty());
+    }
+
+    #[test]
+    fn test_animation_moves_children_and_loops() {
+        let mut animator = animator();
+        assert!(!animator.play("Missing"));
+        assert!(animator.play("Move"));
+
+        animator.update(0.5);
+        assert!((joint_position(&animator) - glm::vec3(1.0, 1.0, 0.0)).magnitude() < 1e-5);
+
+        animator.pause();
+        animator.update(0.25);
+        assert_eq!(animator.get_time(), 0.5);
+
+        animator.resume();
+        animator.set_speed(2.0);
+        animator.update(0.5); // 1.5 seconds wraps to 0.5
+        assert!((animator.get_time() - 0.5).abs() < 1e-5);
+
+        animator.set_looping(false);
+        animator.update(1.0);
+        assert!(!animator.is_playing());
+        assert!((joint_position(&animator) - glm::vec3(2.0, 1.0, 0.0)).magnitude() < 1e-5);
+
+        animator.stop();
+        assert_eq!(animator.joint_matrices(0)[0], glm::Mat4::identity());
+    }
+
+    #[test]
+    fn test_posed_bounds() {
+        // a bar along y, the bottom follows joint 0 and the top joint 1
+        let positions = [glm::vec3(0.0, 0.0, 0.0), glm::vec3(0.0, 2.0, 0.0)];
+        let skin = [
+            ([0, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]),
+            ([0, 1, 0, 0], [0.5, 0.5, 0.0, 0.0]),
+        ];
+        let bounds = joint_bounds(positions, &skin);
+        assert_eq!(bounds.len(), 2);
+        assert_eq!(bounds[1].1, Aabb::new(positions[1], positions[1]));
+
+        // the second joint swings the top over to x = 2
+        let matrices = [
+            glm::Mat4::identity(),
+            glm::translation(&glm::vec3(2.0, 0.0, 0.0)),
+        ];
+        let top = posed_position(&positions[1], &skin[1].0, &skin[1].1, &matrices);
+        assert!((top - glm::vec3(1.0, 2.0, 0.0)).magnitude() < 1e-5);
+
+        let posed = posed_bounds(&bounds, &matrices);
+        assert_eq!(posed.min, glm::vec3(0.0, 0.0, 0.0));
+        assert_eq!(posed.max, glm::vec3(2.0, 2.0, 0.0));
+        assert!(posed.min.x <= top.x && top.x <= posed.max.x);
+    }
+}
@@ -0,0 +1,381 @@
+//! bounding volumes used for culling and picking.
+//!
+//! - [`Aabb`]: an axis aligned bounding box, also used for [`Collider`](crate::nodes::Collider) overlaps.
+//! - [`Frustum`]: the six planes of a camera's view volume.
+//! - [`ray_triangle`]: the distance along a ray to a triangle.
+//!
+//! ## Example
+//! ```rust,ignore
+//! let frustum = camera.frustum(parent_transform);
+//! let bounds = Aabb::from_points([glm::vec3(-1.0, -1.0, -1.0), glm::vec3(1.0, 1.0, 1.0)]);
+//!
+//! if frustum.intersects_aabb(&bounds) {
+//!     // the box is at least partly on screen
+//! }
+//! ```
+
+use nalgebra_glm as glm;
+
+/// an axis aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// the minimum corner of the box
+    pub min: glm::Vec3,
+    /// the maximum corner of the box
+    pub max: glm::Vec3,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Aabb {
+    /// creates a box from its corners
+    ///
+    /// # Arguments
+    /// - `min` - the minimum corner
+    /// - `max` - the maximum corner
+    pub fn new(min: glm::Vec3, max: glm::Vec3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// creates a box that contains nothing. growing it by a point makes a box around just that point.
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: glm::vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: glm::vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// creates the smallest box around the points
+    ///
+    /// # Arguments
+    /// - `points` - the points to fit, no points gives an empty box
+    pub fn from_points(points: impl IntoIterator<Item = glm::Vec3>) -> Aabb {
+        let mut aabb = Aabb::empty();
+        for point in points {
+            aabb.grow(point);
+        }
+        aabb
+    }
+
+    /// check if the box contains nothing
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// grows the box to contain a point
+    ///
+    /// # Arguments
+    /// - `point` - the point to contain
+    pub fn grow(&mut self, point: glm::Vec3) {
+        self.min = glm::min2(&self.min, &point);
+        self.max = glm::max2(&self.max, &point);
+    }
+
+    /// get the smallest box containing both boxes
+    ///
+    /// # Arguments
+    /// - `other` - the box to merge with
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: glm::min2(&self.min, &other.min),
+            max: glm::max2(&self.max, &other.max),
+        }
+    }
+
+    /// check if two boxes overlap, boxes that only touch overlap too
+    ///
+    /// # Arguments
+    /// - `other` - the box to test against
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    /// get the center of the box
+    pub fn center(&self) -> glm::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// get the size of the box on every axis
+    pub fn size(&self) -> glm::Vec3 {
+        self.max - self.min
+    }
+
+    /// get the eight corners of the box
+    pub fn corners(&self) -> [glm::Vec3; 8] {
+        let mut corners = [glm::Vec3::zeros(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            *corner = glm::vec3(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            );
+        }
+        corners
+    }
+
+    /// transforms all eight corners and fits a new box around them, so rotated boxes stay correct
+    ///
+    /// # Arguments
+    /// - `matrix` - the transform to apply
+    pub fn transformed(&self, matrix: &glm::Mat4) -> Aabb {
+        if self.is_empty() {
+            return *self;
+        }
+        Aabb::from_points(
+            self.corners()
+                .iter()
+                .map(|corner| (matrix * glm::vec4(corner.x, corner.y, corner.z, 1.0)).xyz()),
+        )
+    }
+}
+
+impl Aabb {
+    /// intersects a ray with the box
+    ///
+    /// # Arguments
+    /// - `origin` - the start of the ray
+    /// - `direction` - the direction of the ray, does not have to be normalized
+    ///
+    /// # Returns
+    /// the distance along the ray (in multiples of `direction`) where it enters the box, 0 if the origin is inside
+    /// or `None` if the ray misses
+    pub fn intersect_ray(&self, origin: &glm::Vec3, direction: &glm::Vec3) -> Option<f32> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            if direction[axis].abs() < f32::EPSILON {
+                // parallel to the slab, miss unless the origin is between the planes
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.min[axis] - origin[axis]) / direction[axis];
+            let t2 = (self.max[axis] - origin[axis]) / direction[axis];
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+}
+
+/// intersects a ray with a triangle (both sides)
+///
+/// # Arguments
+/// - `origin` - the start of the ray
+/// - `direction` - the direction of the ray, does not have to be normalized
+/// - `triangle` - the corners of the triangle
+///
+/// # Returns
+/// the distance along the ray (in multiples of `direction`) to the hit or `None` if the ray misses
+pub fn ray_triangle(
+    origin: &glm::Vec3,
+    direction: &glm::Vec3,
+    triangle: [&glm::Vec3; 3],
+) -> Option<f32> {
+    let [a, b, c] = triangle;
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(&edge2);
+    let determinant = edge1.dot(&p);
+    if determinant.abs() < 1e-8 {
+        return None; // parallel to the triangle
+    }
+
+    let inverse = 1.0 / determinant;
+    let to_origin = origin - a;
+    let u = to_origin.dot(&p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(&edge1);
+    let v = direction.dot(&q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(&q) * inverse;
+    (t >= 0.0).then_some(t)
+}
+
+/// the six planes of a view volume. every plane is `(normal, distance)` with the normal pointing into the volume.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// the left, right, bottom, top, near and far planes
+    pub planes: [glm::Vec4; 6],
+}
+
+impl Frustum {
+    /// extracts the planes from a view projection matrix
+    ///
+    /// # Arguments
+    /// - `view_projection` - the combined projection and view matrix of a camera
+    pub fn from_matrix(view_projection: &glm::Mat4) -> Frustum {
+        let row = |i: usize| view_projection.row(i).transpose();
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        let mut planes = [w + x, w - x, w + y, w - y, w + z, w - z];
+        for plane in &mut planes {
+            let length = plane.xyz().magnitude();
+            if length > 0.0 {
+                *plane /= length;
+            }
+        }
+        Frustum { planes }
+    }
+
+    /// check if a point is inside the frustum
+    ///
+    /// # Arguments
+    /// - `point` - the point in world space
+    pub fn contains_point(&self, point: &glm::Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.xyz().dot(point) + plane.w >= 0.0)
+    }
+
+    /// check if a box is at least partly inside the frustum.
+    ///
+    /// the test is conservative, big boxes near the corners of the frustum can pass while being outside.
+    ///
+    /// # Arguments
+    /// - `aabb` - the box in world space
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+        self.planes.iter().all(|plane| {
+            // the corner furthest along the normal of the plane
+            let corner = glm::vec3(
+                if plane.x >= 0.0 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if plane.y >= 0.0 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if plane.z >= 0.0 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
+            );
+            plane.xyz().dot(&corner) + plane.w >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transformed_aabb_refits_corners() {
+        let aabb = Aabb::new(glm::vec3(-1.0, -1.0, -1.0), glm::vec3(1.0, 1.0, 1.0));
+        let rotated = aabb.transformed(&glm::rotation(45f32.to_radians(), &glm::Vec3::y()));
+
+        let extent = 2f32.sqrt();
+        assert!((rotated.max.x - extent).abs() < 1e-5);
+        assert!((rotated.min.z + extent).abs() < 1e-5);
+        assert!((rotated.max.y - 1.0).abs() < 1e-5);
+
+        assert!(Aabb::empty().transformed(&glm::Mat4::identity()).is_empty());
+    }
+
+    #[test]
+    fn test_ray_hits() {
+        let aabb = Aabb::new(glm::vec3(-1.0, -1.0, -1.0), glm::vec3(1.0, 1.0, 1.0));
+        let origin = glm::vec3(0.0, 0.0, -5.0);
+        assert_eq!(
+            aabb.intersect_ray(&origin, &glm::vec3(0.0, 0.0, 1.0)),
+            Some(4.0)
+        );
+        assert_eq!(
+            aabb.intersect_ray(&origin, &glm::vec3(0.0, 0.0, -1.0)),
+            None
+        );
+        assert_eq!(
+            aabb.intersect_ray(&glm::Vec3::zeros(), &glm::vec3(1.0, 0.0, 0.0)),
+            Some(0.0)
+        );
+
+        let (a, b, c) = (
+            glm::vec3(-1.0, -1.0, 2.0),
+            glm::vec3(1.0, -1.0, 2.0),
+            glm::vec3(0.0, 1.0, 2.0),
+        );
+        let hit = ray_triangle(&origin, &glm::vec3(0.0, 0.0, 1.0), [&a, &b, &c]);
+        assert!((hit.unwrap() - 7.0).abs() < 1e-5);
+        assert_eq!(
+            ray_triangle(&origin, &glm::vec3(0.0, 0.0, -1.0), [&a, &b, &c]),
+            None
+        );
+        assert_eq!(
+            ray_triangle(
+                &glm::vec3(5.0, 0.0, -5.0),
+                &glm::vec3(0.0, 0.0, 1.0),
+                [&a, &b, &c]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_frustum_culls_boxes() {
+        let view = glm::look_at(
+            &glm::vec3(0.0, 0.0, 0.0),
+            &glm::vec3(0.0, 0.0, 1.0),
+            &glm::Vec3::y(),
+        );
+        let projection = glm::perspective(1.0, 45f32.to_radians(), 0.1, 100.0);
+        let frustum = Frustum::from_matrix(&(projection * view));
+
+        let unit = Aabb::new(glm::vec3(-0.5, -0.5, -0.5), glm::vec3(0.5, 0.5, 0.5));
+        let ahead = unit.transformed(&glm::translation(&glm::vec3(0.0, 0.0, 10.0)));
+        let behind = unit.transformed(&glm::translation(&glm::vec3(0.0, 0.0, -10.0)));
+        let too_far = unit.transformed(&glm::translation(&glm::vec3(0.0, 0.0, 200.0)));
+        let to_the_side = unit.transformed(&glm::translation(&glm::vec3(50.0, 0.0, 10.0)));
+
+        assert!(frustum.intersects_aabb(&ahead));
+        assert!(!frustum.intersects_aabb(&behind));
+        assert!(!frustum.intersects_aabb(&too_far));
+        assert!(!frustum.intersects_aabb(&to_the_side));
+
+        assert!(frustum.contains_point(&glm::vec3(0.0, 0.0, 5.0)));
+        assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -5.0)));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let unit = Aabb::new(glm::vec3(0.0, 0.0, 0.0), glm::vec3(1.0, 1.0, 1.0));
+        let overlapping = Aabb::new(glm::vec3(0.5, 0.5, 0.5), glm::vec3(2.0, 2.0, 2.0));
+        let touching = Aabb::new(glm::vec3(1.0, 0.0, 0.0), glm::vec3(2.0, 1.0, 1.0));
+        let apart = Aabb::new(glm::vec3(0.0, 1.5, 0.0), glm::vec3(1.0, 2.5, 1.0));
+
+        assert!(unit.intersects(&overlapping) && overlapping.intersects(&unit));
+        assert!(unit.intersects(&touching));
+        assert!(!unit.intersects(&apart));
+        assert!(!unit.intersects(&Aabb::empty()));
+    }
+}
@@ -8,27 +8,45 @@ use crate::nodes::model::Vertex;
 use crate::nodes::Camera3D;
 use crate::renderer::buffers::{
     index_buffer::IndexBuffer, vertex_array::VertexArray, vertex_buffer::VertexBuffer,
-    vertex_buffer_layout::VertexBufferLayout,
+    vertex_buffer_layout::VertexBufferLayout, BufferUsage,
 };
 use crate::renderer::{
     shader::Shader,
-    texture::{Texture, TextureType},
+    texture::{FilterMode, Texture, TextureType, WrapMode},
     Renderer,
 };
 
-use crate::components::NodeTransform;
+use crate::components::animation;
+use crate::components::bounds::ray_triangle;
+use crate::components::simplify;
+use crate::components::{Aabb, NodeTransform};
 
 use std::rc::Rc; //reference counted pointer
 
-#[derive(Debug, Clone, PartialEq)]
+/// how the alpha of a material is used, the same modes as glTF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AlphaMode {
+    /// alpha is ignored and the mesh is drawn fully opaque
+    #[default]
     Opaque,
+    /// fragments with an alpha below the alpha cutoff are discarded, the rest are opaque
     Mask,
+    /// the mesh is blended over what is behind it and drawn after the opaque meshes
     Blend,
 }
 
+impl From<gltf::material::AlphaMode> for AlphaMode {
+    fn from(mode: gltf::material::AlphaMode) -> Self {
+        match mode {
+            gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+            gltf::material::AlphaMode::Mask => AlphaMode::Mask,
+            gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+        }
+    }
+}
+
 /// Material properties for the mesh
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MaterialProperties {
     /// Base color factor of the material
     pub base_color_factor: glm::Vec4,
@@ -42,6 +60,10 @@ pub struct MaterialProperties {
     pub alpha_mode: AlphaMode,
     /// Alpha cutoff of the material
     pub alpha_cutoff: f32,
+    /// Emissive factor of the material (already multiplied by the emissive strength)
+    pub emissive_factor: glm::Vec3,
+    /// How much the occlusion texture darkens the ambient light, 0.0 ignores it
+    pub occlusion_strength: f32,
 }
 
 impl MaterialProperties {
@@ -69,6 +91,8 @@ impl MaterialProperties {
             double_sided,
             alpha_mode,
             alpha_cutoff,
+            emissive_factor: glm::vec3(0.0, 0.0, 0.0),
+            occlusion_strength: 1.0,
         }
     }
 
@@ -139,6 +163,50 @@ impl MaterialProperties {
         self.alpha_cutoff = alpha_cutoff;
         self
     }
+
+    /// the light emitted by the material. values above 1.0 are allowed for bright emissive materials
+    ///
+    /// # Arguments
+    /// - `emissive_factor` - The emissive factor of the material
+    ///
+    /// # Returns
+    /// Self
+    pub fn set_emissive_factor(&mut self, emissive_factor: glm::Vec3) -> &mut Self {
+        self.emissive_factor = emissive_factor;
+        self
+    }
+
+    /// makes the material glow with a color, the glow is added on top of the lit surface so it shows in the dark.
+    /// an emissive texture of the mesh is multiplied by it
+    ///
+    /// # Arguments
+    /// - `color` - The color of the glow, black turns it off
+    /// - `strength` - How bright the glow is, above 1.0 for surfaces brighter than white
+    ///
+    /// # Returns
+    /// Self
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut lava = MaterialProperties::default();
+    /// lava.set_emissive(glm::vec3(1.0, 0.3, 0.0), 2.0);
+    /// ```
+    pub fn set_emissive(&mut self, color: glm::Vec3, strength: f32) -> &mut Self {
+        self.emissive_factor = color * strength.max(0.0);
+        self
+    }
+
+    /// how much the occlusion texture of the mesh darkens the ambient light, the lights themselves are not affected
+    ///
+    /// # Arguments
+    /// - `occlusion_strength` - 0.0 ignores the texture, 1.0 applies it fully
+    ///
+    /// # Returns
+    /// Self
+    pub fn set_occlusion_strength(&mut self, occlusion_strength: f32) -> &mut Self {
+        self.occlusion_strength = occlusion_strength.clamp(0.0, 1.0);
+        self
+    }
 }
 
 impl Default for MaterialProperties {
@@ -149,17 +217,30 @@ impl Default for MaterialProperties {
             roughness_factor: 1.0,
             double_sided: false,
             alpha_mode: AlphaMode::Opaque,
-            alpha_cutoff: 0.5, // gltf pipeline default
+            alpha_cutoff: 0.5,                         // gltf pipeline default
+            emissive_factor: glm::vec3(0.0, 0.0, 0.0), // no emission
+            occlusion_strength: 1.0,                   // gltf default
         }
     }
 }
 
+/// the joints and weights of a skinned vertex
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct SkinVertex {
+    joints: glm::Vec4,
+    weights: glm::Vec4,
+}
+
 /// Mesh struct for managing the mesh of a model
 #[derive(Clone, Debug)]
 pub struct Mesh {
     pub center: glm::Vec3,
+    /// bounding box of the vertices
+    bounds: Aabb,
 
-    _vertices: Vec<Vertex>,
+    /// Vertices of the mesh
+    vertices: Vec<Vertex>,
     /// Indices of the mesh
     pub indices: Vec<u32>,
     /// Textures of the mesh
@@ -168,8 +249,16 @@ pub struct Mesh {
     pub material_properties: MaterialProperties,
     /// Vertex array of the mesh
     vertex_array: VertexArray,
+    /// Vertex buffer of the mesh
+    vertex_buffer: VertexBuffer,
     /// Index buffer of the mesh
     index_buffer: IndexBuffer,
+    /// joints and weights of the vertices for skinned meshes (attribute locations 5 and 6)
+    skin_buffer: Option<VertexBuffer>,
+    /// joints and weights of the vertices kept on the cpu to bound and raycast the posed mesh
+    skin: Vec<([u16; 4], [f32; 4])>,
+    /// box around the vertices of every joint in the bind pose, see [`animation::joint_bounds`]
+    joint_bounds: Vec<(usize, Aabb)>,
 }
 
 impl Mesh {
@@ -190,6 +279,56 @@ impl Mesh {
         indices: Vec<u32>,
         textures: Vec<Rc<Texture>>,
         material_properties: MaterialProperties,
+    ) -> Mesh {
+        Self::new_with_usage(
+            vertices,
+            indices,
+            textures,
+            material_properties,
+            BufferUsage::Static,
+        )
+    }
+
+    /// Creates a mesh from vertices built in code without textures
+    ///
+    /// the tangents of the vertices are calculated from the uvs so normal maps set later work.
+    /// use [`Model::from_mesh`](crate::nodes::Model::from_mesh) to add it to the scene.
+    ///
+    /// # Arguments
+    /// - `vertices` - The vertices of the mesh, see [`Vertex::new`]
+    /// - `indices` - The triangle indices of the mesh, empty draws the vertices in order
+    /// - `material` - The material properties of the mesh
+    ///
+    /// # Returns
+    /// The new mesh
+    pub fn from_raw(
+        mut vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        material: MaterialProperties,
+    ) -> Mesh {
+        calculate_tangents(&mut vertices, &indices);
+        Self::new(vertices, indices, Vec::new(), material)
+    }
+
+    /// Creates a new mesh with a hint for how often its vertices and indices change
+    ///
+    /// use [`BufferUsage::Dynamic`] or [`BufferUsage::Stream`] for meshes updated with [`Mesh::update_vertices`].
+    ///
+    /// # Arguments
+    /// - `vertices` - The vertices of the mesh
+    /// - `indices` - The indices of the mesh
+    /// - `textures` - The textures of the mesh
+    /// - `material_properties` - The material properties of the mesh
+    /// - `usage` - how often the mesh data changes
+    ///
+    /// # Returns
+    /// The new mesh
+    pub fn new_with_usage(
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        textures: Vec<Rc<Texture>>,
+        material_properties: MaterialProperties,
+        usage: BufferUsage,
     ) -> Mesh {
         // println!("{:?}", material_properties);
 
@@ -197,16 +336,17 @@ impl Mesh {
 
         va.bind();
 
-        let vb = VertexBuffer::new(&vertices);
+        let vb = VertexBuffer::new_with_usage(&vertices, usage);
 
         let mut layout = VertexBufferLayout::new();
         layout.push::<f32>(3); //positions (x, y, z) (location 0 in the shader)
         layout.push::<f32>(3); //normals (location 1 in the shader)
         layout.push::<f32>(4); //color (r, g, b, a) (location 2 in the shader)
         layout.push::<f32>(2); //texture coordinates (u, v) (location 3 in the shader)
+        layout.push::<f32>(4); //tangent and handedness (location 4 in the shader)
         va.add_buffer(&vb, &layout);
 
-        let ib = IndexBuffer::new(&indices);
+        let ib = IndexBuffer::new_with_usage(&indices, usage);
 
         va.unbind();
         vb.unbind();
@@ -214,19 +354,251 @@ impl Mesh {
 
         Mesh {
             center: calculate_center(&vertices),
-            _vertices: vertices,
+            bounds: Aabb::from_points(vertices.iter().map(|vertex| vertex.position)),
+            vertices,
             indices,
             textures,
             material_properties,
             vertex_array: va,
+            vertex_buffer: vb,
             index_buffer: ib,
+            skin_buffer: None,
+            skin: Vec::new(),
+            joint_bounds: Vec::new(),
+        }
+    }
+
+    /// replaces the vertices of the mesh and uploads them to the existing gpu buffer
+    ///
+    /// # Arguments
+    /// - `vertices` - the new vertices, the count can differ from the old vertices
+    pub fn update_vertices(&mut self, vertices: &[Vertex]) {
+        self.vertices.clear();
+        self.vertices.extend_from_slice(vertices);
+        self.center = calculate_center(&self.vertices);
+        self.bounds = Aabb::from_points(self.vertices.iter().map(|vertex| vertex.position));
+        if self.is_skinned() {
+            self.joint_bounds = animation::joint_bounds(
+                self.vertices.iter().map(|vertex| vertex.position),
+                &self.skin,
+            );
         }
+
+        self.vertex_buffer.set_data(vertices);
+        self.vertex_buffer.unbind();
+    }
+
+    /// replaces the indices of the mesh and uploads them to the existing gpu buffer
+    ///
+    /// # Arguments
+    /// - `indices` - the new indices, an empty slice draws the vertices in order
+    pub fn update_indices(&mut self, indices: &[u32]) {
+        self.indices.clear();
+        self.indices.extend_from_slice(indices);
+
+        // the element buffer binding is stored in the vertex array so it has to be bound while uploading
+        self.vertex_array.bind();
+        self.index_buffer.set_data(indices);
+        self.vertex_array.unbind();
+    }
+
+    /// makes the mesh skinned, every vertex is moved by up to 4 joints of the skin of its mesh node
+    ///
+    /// # Arguments
+    /// - `joints` - the joint indices of every vertex
+    /// - `weights` - how much every joint moves the vertex, should add up to 1
+    pub fn set_skin(&mut self, joints: &[[u16; 4]], weights: &[[f32; 4]]) {
+        let skin: Vec<SkinVertex> = joints
+            .iter()
+            .zip(weights)
+            .map(|(joints, weights)| SkinVertex {
+                joints: glm::vec4(
+                    joints[0] as f32,
+                    joints[1] as f32,
+                    joints[2] as f32,
+                    joints[3] as f32,
+                ),
+                weights: glm::make_vec4(weights),
+            })
+            .collect();
+
+        let buffer = VertexBuffer::new(&skin);
+        let mut layout = VertexBufferLayout::new();
+        layout.push::<f32>(4); // joints (location 5 in the shader)
+        layout.push::<f32>(4); // weights (location 6 in the shader)
+        self.vertex_array.add_buffer_at(&buffer, &layout, 5);
+        self.skin_buffer = Some(buffer);
+
+        self.skin = joints
+            .iter()
+            .copied()
+            .zip(weights.iter().copied())
+            .collect();
+        self.joint_bounds = animation::joint_bounds(
+            self.vertices.iter().map(|vertex| vertex.position),
+            &self.skin,
+        );
+    }
+
+    /// check if the mesh is moved by joints
+    pub fn is_skinned(&self) -> bool {
+        self.skin_buffer.is_some()
+    }
+
+    /// get the bounding box of the vertices in the space of the mesh
+    pub fn get_bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /// get the bounding box of a skinned mesh in a pose, the bind pose bounds for meshes without a skin
+    ///
+    /// # Arguments
+    /// - `joint_matrices` - the joint matrices of the pose, see [`Animator::joint_matrices`](crate::components::animation::Animator::joint_matrices)
+    pub fn get_posed_bounds(&self, joint_matrices: &[glm::Mat4]) -> Aabb {
+        if !self.is_skinned() {
+            return self.bounds;
+        }
+        animation::posed_bounds(&self.joint_bounds, joint_matrices)
+    }
+
+    /// intersects a ray with the triangles of the mesh
+    ///
+    /// # Arguments
+    /// - `origin` - the start of the ray in the space of the mesh
+    /// - `direction` - the direction of the ray in the space of the mesh, does not have to be normalized
+    ///
+    /// # Returns
+    /// the distance along the ray (in multiples of `direction`) to the closest triangle or `None` if the ray misses
+    pub fn raycast(&self, origin: &glm::Vec3, direction: &glm::Vec3) -> Option<f32> {
+        self.bounds.intersect_ray(origin, direction)?;
+
+        self.raycast_triangles(origin, direction, |index| {
+            self.vertices.get(index as usize).map(|v| &v.position)
+        })
+    }
+
+    /// intersects a ray with the triangles of a skinned mesh in a pose, same as [`Mesh::raycast`] for meshes without a skin
+    ///
+    /// # Arguments
+    /// - `origin` - the start of the ray in the space of the mesh
+    /// - `direction` - the direction of the ray in the space of the mesh, does not have to be normalized
+    /// - `joint_matrices` - the joint matrices of the pose, see [`Animator::joint_matrices`](crate::components::animation::Animator::joint_matrices)
+    ///
+    /// # Returns
+    /// the distance along the ray (in multiples of `direction`) to the closest triangle or `None` if the ray misses
+    pub fn raycast_posed(
+        &self,
+        origin: &glm::Vec3,
+        direction: &glm::Vec3,
+        joint_matrices: &[glm::Mat4],
+    ) -> Option<f32> {
+        if !self.is_skinned() {
+            return self.raycast(origin, direction);
+        }
+        self.get_posed_bounds(joint_matrices)
+            .intersect_ray(origin, direction)?;
+
+        let posed: Vec<glm::Vec3> = self
+            .vertices
+            .iter()
+            .zip(&self.skin)
+            .map(|(vertex, (joints, weights))| {
+                animation::posed_position(&vertex.position, joints, weights, joint_matrices)
+            })
+            .collect();
+        self.raycast_triangles(origin, direction, |index| posed.get(index as usize))
+    }
+
+    /// intersects a ray with every triangle, `position` looks up the position of a vertex index
+    fn raycast_triangles<'a>(
+        &self,
+        origin: &glm::Vec3,
+        direction: &glm::Vec3,
+        position: impl Fn(u32) -> Option<&'a glm::Vec3>,
+    ) -> Option<f32> {
+        let triangle = |corners: &[u32]| {
+            Some([
+                position(corners[0])?,
+                position(corners[1])?,
+                position(corners[2])?,
+            ])
+        };
+
+        let mut closest: Option<f32> = None;
+        let mut test = |corners: &[u32]| {
+            if let Some(hit) = triangle(corners).and_then(|t| ray_triangle(origin, direction, t)) {
+                closest = Some(closest.map_or(hit, |closest| closest.min(hit)));
+            }
+        };
+
+        if self.indices.is_empty() {
+            // no indices draws the vertices in order
+            let indices: Vec<u32> = (0..self.vertices.len() as u32).collect();
+            indices.chunks_exact(3).for_each(&mut test);
+        } else {
+            self.indices.chunks_exact(3).for_each(&mut test);
+        }
+        closest
+    }
+
+    /// get the vertices of the mesh
+    pub fn get_vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    /// binds the vertex array and index buffer of the mesh
+    pub fn bind(&self) {
+        self.vertex_array.bind();
+        self.index_buffer.bind();
+    }
+
+    /// get the textures of the mesh
+    pub fn get_textures(&self) -> &[Rc<Texture>] {
+        &self.textures
+    }
+
+    /// get the number of vertices in the mesh
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// creates a lower detail copy of the mesh. the textures and material are shared with the original mesh
+    ///
+    /// # Arguments
+    /// - `target_ratio` - the fraction of triangles to keep (0.0 - 1.0)
+    ///
+    /// # Returns
+    /// the simplified mesh
+    pub fn simplified(&self, target_ratio: f32) -> Mesh {
+        let (vertices, indices) = simplify::simplify(&self.vertices, &self.indices, target_ratio);
+        Mesh::new(
+            vertices,
+            indices,
+            self.textures.clone(),
+            self.material_properties.clone(),
+        )
     }
 
     pub fn set_material(&mut self, material_properties: MaterialProperties) {
         self.material_properties = material_properties;
     }
 
+    /// changes how the textures of the mesh are wrapped and filtered, see [`Texture::set_wrap`] and
+    /// [`Texture::set_filter`].
+    ///
+    /// textures are shared between the meshes of a model file and loads of the same image, this changes them for
+    /// every mesh using them.
+    ///
+    /// # Arguments
+    /// - `wrap` - how the textures are sampled outside of the 0 to 1 uv range
+    /// - `filter` - how the textures are filtered
+    pub fn set_texture_sampling(&self, wrap: WrapMode, filter: FilterMode) {
+        for texture in &self.textures {
+            texture.set_wrap(wrap);
+            texture.set_filter(filter);
+        }
+    }
+
     /// Draw the mesh with the shader uniform and shader binding handled in Model
     ///
     /// # Arguments
@@ -246,6 +618,15 @@ impl Mesh {
                     shader.set_uniform("useTexture", true);
                 }
                 TextureType::Specular => {}
+                TextureType::Normal => {
+                    shader.set_uniform("u_hasNormalMap", true);
+                }
+                TextureType::Emissive => {
+                    shader.set_uniform("u_hasEmissiveMap", true);
+                }
+                TextureType::Occlusion => {
+                    shader.set_uniform("u_hasOcclusionMap", true);
+                }
             }
             let uniform_name = tex_type.get_uniform_name();
 
@@ -266,6 +647,12 @@ impl Mesh {
             self.material_properties.base_color_factor,
         );
 
+        shader.set_uniform("u_emissive", self.material_properties.emissive_factor);
+        shader.set_uniform(
+            "u_occlusionStrength",
+            self.material_properties.occlusion_strength,
+        );
+
         if self.material_properties.alpha_mode == AlphaMode::Mask {
             shader.set_uniform("useAlphaCutoff", true);
             shader.set_uniform("alphaCutoff", self.material_properties.alpha_cutoff);
@@ -278,6 +665,9 @@ impl Mesh {
         // reset stuff
         self.textures.iter().for_each(|t| t.unbind()); //unbind the textures
         shader.set_uniform("useTexture", false); //set the useTexture uniform to false (default)
+        shader.set_uniform("u_hasNormalMap", false); //fall back to the geometric normal (default)
+        shader.set_uniform("u_hasEmissiveMap", false); //the emissive factor alone (default)
+        shader.set_uniform("u_hasOcclusionMap", false); //no ambient occlusion (default)
         shader.set_uniform("useAlphaCutoff", false); //set the useAlphaCutoff uniform to false (default)
     }
 
@@ -299,9 +689,115 @@ impl Mesh {
 
         shader.set_uniform("u_baseColor", base_color);
 
-        Renderer::draw(self);
+        Renderer::draw_shadow(self);
 
         self.textures.iter().for_each(|t| t.unbind());
+        shader.set_uniform("u_hasTexture", false);
+    }
+}
+
+/// gives every triangle its own vertices with the normal of its face, for meshes that come without normals.
+///
+/// the vertices are unwelded so the result is drawn in order without indices. degenerate triangles get a zero normal.
+///
+/// # Arguments
+/// - `vertices` - the vertices of the mesh
+/// - `indices` - the triangle indices, empty if the vertices are drawn in order
+///
+/// # Returns
+/// three vertices per triangle and the index of the vertex each one was copied from, to unweld other per vertex data
+pub fn flat_shade(vertices: &[Vertex], indices: &[u32]) -> (Vec<Vertex>, Vec<usize>) {
+    let corners: Vec<usize> = if indices.is_empty() {
+        (0..vertices.len() / 3 * 3).collect()
+    } else {
+        indices
+            .chunks_exact(3)
+            .flatten()
+            .map(|&index| index as usize)
+            .collect()
+    };
+
+    let mut result = Vec::with_capacity(corners.len());
+    let mut sources = Vec::with_capacity(corners.len());
+    for triangle in corners.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| vertices.get(index));
+        let (Some(a), Some(b), Some(c)) = (a, b, c) else {
+            continue;
+        };
+        sources.extend_from_slice(triangle);
+
+        let normal = (b.position - a.position).cross(&(c.position - a.position));
+        let normal = if normal.norm() > f32::EPSILON {
+            normal.normalize()
+        } else {
+            glm::Vec3::zeros()
+        };
+        for vertex in [a, b, c] {
+            result.push(Vertex {
+                normal,
+                ..vertex.clone()
+            });
+        }
+    }
+    (result, sources)
+}
+
+/// computes the tangent of every vertex from the positions and uvs of its triangles.
+///
+/// the tangents of the triangles sharing a vertex are averaged and w is set to -1 where the uvs are mirrored. vertices
+/// without uvs (or with degenerate uvs) get a zero tangent which makes the shader use the geometric normal.
+///
+/// # Arguments
+/// - `vertices` - the vertices to write the tangents to
+/// - `indices` - the triangle indices, empty if the vertices are drawn in order
+pub fn calculate_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![glm::Vec3::zeros(); vertices.len()];
+    let mut bitangents = vec![glm::Vec3::zeros(); vertices.len()];
+
+    let triangles: Vec<[usize; 3]> = if indices.is_empty() {
+        (0..vertices.len() / 3)
+            .map(|i| [i * 3, i * 3 + 1, i * 3 + 2])
+            .collect()
+    } else {
+        indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect()
+    };
+
+    for [a, b, c] in triangles {
+        if a >= vertices.len() || b >= vertices.len() || c >= vertices.len() {
+            continue;
+        }
+        let edge1 = vertices[b].position - vertices[a].position;
+        let edge2 = vertices[c].position - vertices[a].position;
+        let uv1 = vertices[b].tex_uv - vertices[a].tex_uv;
+        let uv2 = vertices[c].tex_uv - vertices[a].tex_uv;
+
+        let determinant = uv1.x * uv2.y - uv2.x * uv1.y;
+        if determinant.abs() < f32::EPSILON {
+            continue;
+        }
+        let tangent = (edge1 * uv2.y - edge2 * uv1.y) / determinant;
+        let bitangent = (edge2 * uv1.x - edge1 * uv2.x) / determinant;
+        for i in [a, b, c] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+
+    for ((vertex, tangent), bitangent) in vertices.iter_mut().zip(tangents).zip(bitangents) {
+        vertex.tangent = if tangent.magnitude() > 0.0 {
+            // the shader builds the bitangent as cross(normal, tangent), w flips it when the uvs are mirrored
+            let handedness = if vertex.normal.cross(&tangent).dot(&bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            tangent.normalize().push(handedness)
+        } else {
+            glm::Vec4::zeros()
+        };
     }
 }
 
@@ -317,3 +813,52 @@ fn calculate_center(vertices: &[Vertex]) -> glm::Vec3 {
     }
     sum / vertices.len() as f32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(uvs: [glm::Vec2; 3]) -> Vec<Vertex> {
+        let normal = glm::vec3(0.0, 0.0, 1.0);
+        [
+            glm::vec3(0.0, 0.0, 0.0),
+            glm::vec3(1.0, 0.0, 0.0),
+            glm::vec3(0.0, 1.0, 0.0),
+        ]
+        .into_iter()
+        .zip(uvs)
+        .map(|(position, uv)| Vertex::new(position, normal, uv))
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_tangents() {
+        let mut vertices = triangle([
+            glm::vec2(0.0, 0.0),
+            glm::vec2(1.0, 0.0),
+            glm::vec2(0.0, 1.0),
+        ]);
+        calculate_tangents(&mut vertices, &[]);
+        for vertex in &vertices {
+            assert_eq!(vertex.tangent, glm::vec4(1.0, 0.0, 0.0, 1.0));
+        }
+
+        // u is mirrored so the tangent and the handedness flip
+        let mut vertices = triangle([
+            glm::vec2(0.0, 0.0),
+            glm::vec2(-1.0, 0.0),
+            glm::vec2(0.0, 1.0),
+        ]);
+        calculate_tangents(&mut vertices, &[0, 1, 2]);
+        for vertex in &vertices {
+            assert_eq!(vertex.tangent, glm::vec4(-1.0, 0.0, 0.0, -1.0));
+        }
+
+        // without uvs there is no tangent
+        let mut vertices = triangle([glm::Vec2::zeros(); 3]);
+        calculate_tangents(&mut vertices, &[]);
+        for vertex in &vertices {
+            assert_eq!(vertex.tangent, glm::Vec4::zeros());
+        }
+    }
+}
@@ -1,5 +1,11 @@
-pub mod node_transform;
+pub mod animation;
+pub mod bounds;
 pub mod mesh;
+pub mod node_transform;
+pub mod obj;
+pub mod shapes;
+pub mod simplify;
 
-pub use node_transform::NodeTransform;
+pub use bounds::{Aabb, Frustum};
 pub use mesh::Mesh;
+pub use node_transform::NodeTransform;
@@ -1,4 +1,8 @@
 //! represents the current transform of a given node. each node has a transform that can be manipulated to move, rotate, and scale the node in 3D space.
+//!
+//! ## Coordinate System
+//! the engine is right-handed with +Y up and +Z forward (the same as glTF). with no rotation a node faces +Z and +X is on its left.
+//! rotations follow the right hand rule, so a positive rotation around Y turns +Z towards +X.
 
 use glm::{Mat4, Vec3};
 use gltf::Node;
@@ -20,16 +24,23 @@ pub struct NodeTransform {
 impl std::ops::Add for NodeTransform {
     type Output = NodeTransform;
 
+    /// composes `rhs` as a child of `self` by multiplying the parent matrix with the child matrix.
+    ///
+    /// the matrix of the result is the exact product so rendering matches the hierarchy. the position is read back from
+    /// the matrix while the rotation and scale are the parent's multiplied with the child's, which is what decomposing
+    /// the matrix gives unless a non-uniform parent scale shears a rotated child.
     fn add(self, rhs: Self) -> Self::Output {
-        let position = self.position + rhs.position;
+        let matrix = self.matrix * rhs.matrix;
+        let position = glm::vec3(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
         let rotation = glm::quat_normalize(&(self.rotation * rhs.rotation));
-        let scale = glm::vec3(
-            self.scale.x * rhs.scale.x,
-            self.scale.y * rhs.scale.y,
-            self.scale.z * rhs.scale.z,
-        );
+        let scale = self.scale.component_mul(&rhs.scale);
 
-        Self::new(position, rotation, scale)
+        Self {
+            position,
+            rotation,
+            scale,
+            matrix,
+        }
     }
 }
 
@@ -88,6 +99,59 @@ impl NodeTransform {
         transform
     }
 
+    /// composes a child's local transform with this (parent) world transform.
+    ///
+    /// every traversal (main pass, shadow passes, light collection) goes through this function so the rendered
+    /// and shadow casting positions of a node always agree.
+    ///
+    /// # Arguments
+    /// - `local` - the transform of the child relative to this transform.
+    ///
+    /// # Returns
+    /// the world transform of the child.
+    pub fn compose(&self, local: &NodeTransform) -> NodeTransform {
+        *self + *local
+    }
+
+    /// the inverse of [`compose`](NodeTransform::compose), expresses this world transform relative to a parent.
+    ///
+    /// `parent.compose(&world.relative_to(&parent))` gives back `world` so a node can be moved to another parent
+    /// without moving in the world.
+    ///
+    /// # Arguments
+    /// - `parent` - the world transform of the parent, its scale must not be zero on any axis.
+    ///
+    /// # Returns
+    /// the local transform relative to `parent`.
+    pub fn relative_to(&self, parent: &NodeTransform) -> NodeTransform {
+        let inverse_rotation = glm::quat_inverse(&parent.rotation);
+        let offset = glm::quat_rotate_vec3(&inverse_rotation, &(self.position - parent.position));
+        NodeTransform::new(
+            offset.component_div(&parent.scale),
+            glm::quat_normalize(&(inverse_rotation * self.rotation)),
+            self.scale.component_div(&parent.scale),
+        )
+    }
+
+    /// interpolates between this transform and another one, for tweening between two poses.
+    ///
+    /// the position and scale are interpolated linearly and the rotation spherically along the shortest path.
+    ///
+    /// # Arguments
+    /// - `other` - the transform at `t = 1.0`.
+    /// - `t` - how far to go towards `other`, clamped to 0.0..=1.0.
+    ///
+    /// # Returns
+    /// the interpolated transform with its matrix updated.
+    pub fn lerp(&self, other: &NodeTransform, t: f32) -> NodeTransform {
+        let t = t.clamp(0.0, 1.0);
+        NodeTransform::new(
+            glm::lerp(&self.position, &other.position, t),
+            slerp_shortest(&self.rotation, &other.rotation, t),
+            glm::lerp(&self.scale, &other.scale, t),
+        )
+    }
+
     /// updates the model matrix based on the position, rotation, and scale.
     fn update_matrix(&mut self) {
         self.matrix = glm::translation(&self.position)
@@ -246,6 +310,28 @@ impl NodeTransform {
         glm::quat_rotate_vec3(&self.rotation, &glm::vec3(0.0, 1.0, 0.0))
     }
 
+    /// rotates the transform so its forward vector points at a target, keeping the up vector on the side of `up`.
+    ///
+    /// nothing changes when the target is at the position or straight along `up`, since the direction is undefined.
+    ///
+    /// # Arguments
+    /// - `target` - the point to look at, in the same space as the position.
+    /// - `up` - the direction that should stay up, usually the world up.
+    ///
+    /// # Returns
+    /// a mutable reference to the NodeTransform.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) -> &mut Self {
+        let direction = target - self.position;
+        if glm::length2(&direction) < f32::EPSILON
+            || glm::length2(&glm::cross(&direction, &up)) < f32::EPSILON
+        {
+            return self;
+        }
+        // look_at_lh maps the direction onto +z, the inverse turns +z (forward) towards the direction
+        let rotation = glm::quat_inverse(&glm::quat_look_at_lh(&direction, &up));
+        self.set_rotation(glm::quat_normalize(&rotation))
+    }
+
     /// scales the transform by the given scale.
     ///
     /// # Arguments
@@ -318,6 +404,22 @@ impl NodeTransform {
     }
 }
 
+/// spherically interpolates between two rotations along the shortest path.
+///
+/// `glm::quat_slerp` panics when the rotations are almost the same, so those fall back to a normalized lerp.
+fn slerp_shortest(from: &glm::Quat, to: &glm::Quat, t: f32) -> glm::Quat {
+    let from = glm::quat_normalize(from);
+    let mut to = glm::quat_normalize(to);
+    // q and -q are the same rotation, pick the one on the near side
+    if glm::quat_dot(&from, &to) < 0.0 {
+        to = -to;
+    }
+    if glm::quat_dot(&from, &to) > 0.9995 {
+        return glm::quat_normalize(&glm::quat_lerp(&from, &to, t));
+    }
+    glm::quat_slerp(&from, &to, t)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,6 +434,64 @@ mod tests {
         assert_eq!(transform.matrix, glm::Mat4::identity());
     }
 
+    #[test]
+    fn test_look_at() {
+        let mut transform = NodeTransform::default();
+        transform.set_position(vec3(1.0, 0.0, 0.0));
+        transform.look_at(vec3(1.0, 0.0, -5.0), vec3(0.0, 1.0, 0.0));
+        assert!(glm::distance(&transform.get_forward_vector(), &vec3(0.0, 0.0, -1.0)) < 1e-5);
+        assert!(glm::distance(&transform.get_up_vector(), &vec3(0.0, 1.0, 0.0)) < 1e-5);
+
+        // looking straight up is undefined so the rotation is kept
+        let before = transform.rotation;
+        transform.look_at(vec3(1.0, 5.0, 0.0), vec3(0.0, 1.0, 0.0));
+        assert_eq!(transform.rotation, before);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = NodeTransform::new(vec3(0.0, 0.0, 0.0), quat_identity(), vec3(1.0, 1.0, 1.0));
+        let b = NodeTransform::new(
+            vec3(10.0, 0.0, 0.0),
+            glm::quat_angle_axis(90f32.to_radians(), &vec3(0.0, 1.0, 0.0)),
+            vec3(3.0, 3.0, 3.0),
+        );
+
+        let half = a.lerp(&b, 0.5);
+        assert_eq!(half.position, vec3(5.0, 0.0, 0.0));
+        assert_eq!(half.scale, vec3(2.0, 2.0, 2.0));
+        let expected = glm::quat_angle_axis(45f32.to_radians(), &vec3(0.0, 1.0, 0.0));
+        assert!(glm::quat_dot(&half.rotation, &expected).abs() > 0.9999);
+        assert_eq!(
+            half.matrix,
+            NodeTransform::new(half.position, half.rotation, half.scale).matrix
+        );
+
+        // t is clamped
+        assert_eq!(a.lerp(&b, 2.0).position, b.position);
+        assert_eq!(a.lerp(&b, -1.0).position, a.position);
+    }
+
+    #[test]
+    fn test_lerp_takes_shortest_path() {
+        let rotation = glm::quat_angle_axis(10f32.to_radians(), &vec3(0.0, 1.0, 0.0));
+        let a = NodeTransform::new(vec3(0.0, 0.0, 0.0), quat_identity(), vec3(1.0, 1.0, 1.0));
+        // the same rotation written with the opposite sign
+        let b = NodeTransform::new(vec3(0.0, 0.0, 0.0), -rotation, vec3(1.0, 1.0, 1.0));
+
+        let half = a.lerp(&b, 0.5);
+        let expected = glm::quat_angle_axis(5f32.to_radians(), &vec3(0.0, 1.0, 0.0));
+        assert!(glm::quat_dot(&half.rotation, &expected).abs() > 0.9999);
+
+        // nearly identical rotations don't panic
+        let c = NodeTransform::new(
+            vec3(0.0, 0.0, 0.0),
+            glm::quat_angle_axis(1e-4, &vec3(0.0, 1.0, 0.0)),
+            vec3(1.0, 1.0, 1.0),
+        );
+        a.lerp(&c, 0.5);
+    }
+
     #[test]
     fn test_translation() {
         let mut transform = NodeTransform::default();
@@ -387,8 +547,9 @@ mod tests {
 
         let result = transform1 + transform2;
 
-        let expected_position = vec3(1.0, 1.0, 0.0);
-        assert!(result.position == expected_position);
+        // the child offset is scaled by 2 and turning around y leaves it pointing up
+        let expected_position = vec3(1.0, 2.0, 0.0);
+        assert!(glm::distance(&result.position, &expected_position) < 1e-5);
 
         let expected_rotation = glm::quat_normalize(&(transform1.rotation * transform2.rotation));
         assert!(result.rotation == expected_rotation);
@@ -397,6 +558,107 @@ mod tests {
         assert!(result.scale == expected_scale);
     }
 
+    #[test]
+    fn test_compose_nested_parent_first() {
+        // scene node -> child node -> mesh node, the same chain the main and shadow passes walk
+        let parent = NodeTransform::new(
+            vec3(1.0, 0.0, 0.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(90.0)).x, &vec3(0.0, 1.0, 0.0)),
+            vec3(2.0, 2.0, 2.0),
+        );
+        let child = NodeTransform::new(
+            vec3(0.0, 1.0, 0.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(45.0)).x, &vec3(1.0, 0.0, 0.0)),
+            vec3(3.0, 3.0, 3.0),
+        );
+        let mesh = NodeTransform::new(
+            vec3(0.0, 0.0, 1.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(30.0)).x, &vec3(0.0, 0.0, 1.0)),
+            vec3(0.5, 0.5, 2.0),
+        );
+
+        let world = parent.compose(&child).compose(&mesh);
+
+        // the world position and matrix are the product of the chain, which is what both passes upload as u_Model
+        let expected = parent.matrix * child.matrix * mesh.matrix;
+        let origin = expected * glm::vec4(0.0, 0.0, 0.0, 1.0);
+        assert!(glm::distance(&world.position, &origin.xyz()) < 1e-5);
+        for (a, b) in world.matrix.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+
+        // parent rotations are applied before the child rotations
+        let expected_rotation =
+            glm::quat_normalize(&(parent.rotation * child.rotation * mesh.rotation));
+        assert!(glm::length(&(world.rotation.coords - expected_rotation.coords)) < 1e-5);
+
+        // with uniform parent scales composing is associative so it does not matter where a traversal splits the chain
+        let split = parent.compose(&child.compose(&mesh));
+        assert!(glm::length(&(world.position - split.position)) < 1e-5);
+        assert!(glm::length(&(world.scale - split.scale)) < 1e-5);
+    }
+
+    #[test]
+    fn test_compose_matches_matrices() {
+        let parent = NodeTransform::new(
+            vec3(1.0, 2.0, 3.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(90.0)).x, &vec3(0.0, 1.0, 0.0)),
+            vec3(2.0, 2.0, 2.0),
+        );
+        let child = NodeTransform::new(
+            vec3(0.0, 0.0, 1.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(30.0)).x, &vec3(1.0, 0.0, 0.0)),
+            vec3(0.5, 0.5, 0.5),
+        );
+
+        let world = parent.compose(&child);
+        // +z rotated 90 degrees around y is +x, then scaled by the parent
+        assert!(glm::distance(&world.position, &vec3(3.0, 2.0, 3.0)) < 1e-5);
+
+        let expected = parent.matrix * child.matrix;
+        for (a, b) in world.matrix.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_compose_child_on_x() {
+        // translated, rotated 90 degrees around z, and scaled parent with a child 1 unit along +x
+        let parent = NodeTransform::new(
+            vec3(5.0, 0.0, -2.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(90.0)).x, &vec3(0.0, 0.0, 1.0)),
+            vec3(3.0, 3.0, 3.0),
+        );
+        let child = NodeTransform::new(vec3(1.0, 0.0, 0.0), quat_identity(), vec3(1.0, 1.0, 1.0));
+
+        let world = parent + child;
+        let expected = parent.matrix * glm::vec4(1.0, 0.0, 0.0, 1.0);
+        assert!(glm::distance(&world.position, &expected.xyz()) < 1e-5);
+        // +x turned to +y, pushed out by the parent scale
+        assert!(glm::distance(&world.position, &vec3(5.0, 3.0, -2.0)) < 1e-5);
+        assert_eq!(world.matrix, parent.matrix * child.matrix);
+        assert!(glm::distance(&world.scale, &vec3(3.0, 3.0, 3.0)) < 1e-5);
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let parent = NodeTransform::new(
+            vec3(5.0, 1.0, -2.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(60.0)).x, &vec3(0.0, 1.0, 0.0)),
+            vec3(2.0, 2.0, 2.0),
+        );
+        let world = NodeTransform::new(
+            vec3(-1.0, 4.0, 3.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(20.0)).x, &vec3(1.0, 0.0, 0.0)),
+            vec3(1.0, 3.0, 1.0),
+        );
+
+        let back = parent.compose(&world.relative_to(&parent));
+        assert!(glm::distance(&back.position, &world.position) < 1e-5);
+        assert!(glm::distance(&back.scale, &world.scale) < 1e-5);
+        assert!(glm::quat_dot(&back.rotation, &world.rotation).abs() > 0.9999);
+    }
+
     #[test]
     fn test_euler_rotation() {
         let mut transform = NodeTransform::default();
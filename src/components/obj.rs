@@ -0,0 +1,410 @@
+//! parser for wavefront OBJ models and their MTL material libraries.
+//!
+//! the parser only turns the text into vertices, indices and material descriptions, the gpu meshes and textures are created by
+//! [`Model::new_obj`](crate::nodes::Model::new_obj).
+//!
+//! - every `o` starts a new object (a mesh node of the model) and every `usemtl` inside an object starts a new mesh.
+//! - polygons are triangulated as a fan, so quads and convex n-gons work.
+//! - faces without normals get the normal of the face (flat shading).
+//! - negative (relative) indices are supported.
+
+use std::collections::HashMap;
+
+use nalgebra_glm as glm;
+
+use crate::nodes::model::Vertex;
+
+/// a mesh with a single material
+#[derive(Clone, Debug, Default)]
+pub struct ObjMesh {
+    /// the name of the material used by the mesh from `usemtl`
+    pub material: Option<String>,
+    /// the unique vertices of the mesh
+    pub vertices: Vec<Vertex>,
+    /// the triangle indices into the vertices
+    pub indices: Vec<u32>,
+}
+
+/// a named object made of meshes
+#[derive(Clone, Debug, Default)]
+pub struct ObjObject {
+    /// the name of the object from `o` (empty for faces before the first `o`)
+    pub name: String,
+    /// one mesh for every material used by the object
+    pub meshes: Vec<ObjMesh>,
+}
+
+/// the parsed contents of an OBJ file
+#[derive(Clone, Debug, Default)]
+pub struct ObjData {
+    /// the objects of the file, objects without faces are skipped
+    pub objects: Vec<ObjObject>,
+    /// the material libraries referenced by `mtllib`, relative to the OBJ file
+    pub material_libraries: Vec<String>,
+}
+
+/// a material from an MTL file
+#[derive(Clone, Debug)]
+pub struct ObjMaterial {
+    /// the name used by `usemtl`
+    pub name: String,
+    /// the diffuse color (`Kd`)
+    pub diffuse: glm::Vec3,
+    /// the opacity (`d` or `1 - Tr`)
+    pub opacity: f32,
+    /// the specular exponent (`Ns`)
+    pub shininess: f32,
+    /// the emissive color (`Ke`)
+    pub emissive: glm::Vec3,
+    /// the diffuse texture (`map_Kd`) relative to the MTL file
+    pub diffuse_texture: Option<String>,
+    /// the specular texture (`map_Ks`) relative to the MTL file
+    pub specular_texture: Option<String>,
+    /// the tangent space normal map (`norm` or `map_Bump`) relative to the MTL file
+    pub normal_texture: Option<String>,
+}
+
+impl ObjMaterial {
+    fn new(name: &str) -> ObjMaterial {
+        ObjMaterial {
+            name: name.to_string(),
+            diffuse: glm::vec3(1.0, 1.0, 1.0),
+            opacity: 1.0,
+            shininess: 0.0,
+            emissive: glm::vec3(0.0, 0.0, 0.0),
+            diffuse_texture: None,
+            specular_texture: None,
+            normal_texture: None,
+        }
+    }
+}
+
+/// a vertex of a face: position, uv and normal index (already zero based). faces without normals add the face index so
+/// flat shaded vertices are not shared between faces.
+type VertexKey = (usize, Option<usize>, Option<usize>, Option<usize>);
+
+/// builds one mesh, sharing vertices that use the same attributes
+struct MeshBuilder {
+    mesh: ObjMesh,
+    lookup: HashMap<VertexKey, u32>,
+}
+
+impl MeshBuilder {
+    fn new(material: Option<String>) -> MeshBuilder {
+        MeshBuilder {
+            mesh: ObjMesh {
+                material,
+                ..Default::default()
+            },
+            lookup: HashMap::new(),
+        }
+    }
+}
+
+/// parses the text of an OBJ file
+///
+/// # Arguments
+/// - `source` - the contents of the file
+///
+/// # Returns
+/// the objects of the file or a message with the line that could not be parsed
+pub fn parse_obj(source: &str) -> Result<ObjData, String> {
+    let mut positions: Vec<glm::Vec3> = Vec::new();
+    let mut normals: Vec<glm::Vec3> = Vec::new();
+    let mut tex_coords: Vec<glm::Vec2> = Vec::new();
+
+    let mut data = ObjData::default();
+    let mut object = ObjObject::default();
+    let mut builder = MeshBuilder::new(None);
+    let mut face_count = 0;
+
+    // moves the finished mesh into the object if it has faces
+    let finish_mesh = |object: &mut ObjObject, builder: &mut MeshBuilder, material| {
+        let finished = std::mem::replace(builder, MeshBuilder::new(material));
+        if !finished.mesh.indices.is_empty() {
+            object.meshes.push(finished.mesh);
+        }
+    };
+
+    for (line_number, line) in source.lines().enumerate() {
+        let error =
+            |message: &str| format!("line {}: {} ({})", line_number + 1, message, line.trim());
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let mut parts = line.split_whitespace();
+        let Some(keyword) = parts.next() else {
+            continue;
+        };
+        let rest = line[keyword.len()..].trim();
+
+        match keyword {
+            "v" => positions.push(parse_vec3(rest).ok_or_else(|| error("invalid position"))?),
+            "vn" => normals.push(parse_vec3(rest).ok_or_else(|| error("invalid normal"))?),
+            "vt" => {
+                let values = parse_floats(rest).ok_or_else(|| error("invalid uv"))?;
+                tex_coords.push(glm::vec2(
+                    values.first().copied().unwrap_or(0.0),
+                    values.get(1).copied().unwrap_or(0.0),
+                ));
+            }
+            "f" => {
+                let mut keys = Vec::new();
+                for corner in parts {
+                    let mut indices = corner.split('/');
+                    let position = resolve_index(indices.next(), positions.len())
+                        .ok_or_else(|| error("invalid position index"))?;
+                    let uv = match indices.next() {
+                        Some("") | None => None,
+                        index => Some(
+                            resolve_index(index, tex_coords.len())
+                                .ok_or_else(|| error("invalid uv index"))?,
+                        ),
+                    };
+                    let normal = match indices.next() {
+                        Some("") | None => None,
+                        index => Some(
+                            resolve_index(index, normals.len())
+                                .ok_or_else(|| error("invalid normal index"))?,
+                        ),
+                    };
+                    keys.push((position, uv, normal));
+                }
+                if keys.len() < 3 {
+                    return Err(error("face with less than 3 vertices"));
+                }
+
+                let face_normal = {
+                    let (a, b, c) = (
+                        positions[keys[0].0],
+                        positions[keys[1].0],
+                        positions[keys[2].0],
+                    );
+                    let normal = (b - a).cross(&(c - a));
+                    if normal.magnitude() > 0.0 {
+                        normal.normalize()
+                    } else {
+                        glm::vec3(0.0, 1.0, 0.0)
+                    }
+                };
+
+                let mut face_indices = Vec::with_capacity(keys.len());
+                for (position, uv, normal) in keys {
+                    let key = (position, uv, normal, normal.is_none().then_some(face_count));
+                    let mesh = &mut builder.mesh;
+                    let index = *builder.lookup.entry(key).or_insert_with(|| {
+                        mesh.vertices.push(Vertex {
+                            position: positions[position],
+                            normal: normal.map_or(face_normal, |normal| normals[normal]),
+                            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+                            tex_uv: uv.map_or(glm::vec2(0.0, 0.0), |uv| tex_coords[uv]),
+                            tangent: glm::Vec4::zeros(),
+                        });
+                        mesh.vertices.len() as u32 - 1
+                    });
+                    face_indices.push(index);
+                }
+
+                // triangle fan
+                for i in 1..face_indices.len() - 1 {
+                    builder.mesh.indices.extend([
+                        face_indices[0],
+                        face_indices[i],
+                        face_indices[i + 1],
+                    ]);
+                }
+                face_count += 1;
+            }
+            "o" => {
+                let material = builder.mesh.material.clone();
+                finish_mesh(&mut object, &mut builder, material);
+                let finished = std::mem::replace(
+                    &mut object,
+                    ObjObject {
+                        name: rest.to_string(),
+                        meshes: Vec::new(),
+                    },
+                );
+                if !finished.meshes.is_empty() {
+                    data.objects.push(finished);
+                }
+            }
+            "usemtl" => finish_mesh(&mut object, &mut builder, Some(rest.to_string())),
+            "mtllib" => data.material_libraries.push(rest.to_string()),
+            // groups, smoothing groups, lines and points are ignored
+            _ => {}
+        }
+    }
+
+    finish_mesh(&mut object, &mut builder, None);
+    if !object.meshes.is_empty() {
+        data.objects.push(object);
+    }
+
+    Ok(data)
+}
+
+/// parses the text of an MTL file
+///
+/// # Arguments
+/// - `source` - the contents of the file
+///
+/// # Returns
+/// the materials of the file, unknown statements are ignored
+pub fn parse_mtl(source: &str) -> Vec<ObjMaterial> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for line in source.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some(keyword) = line.split_whitespace().next() else {
+            continue;
+        };
+        let rest = line[keyword.len()..].trim();
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(rest));
+            continue;
+        }
+
+        let Some(material) = materials.last_mut() else {
+            continue;
+        };
+        match keyword {
+            "Kd" => material.diffuse = parse_vec3(rest).unwrap_or(material.diffuse),
+            "Ke" => material.emissive = parse_vec3(rest).unwrap_or(material.emissive),
+            "Ns" => material.shininess = rest.parse().unwrap_or(material.shininess),
+            "d" => material.opacity = rest.parse().unwrap_or(material.opacity),
+            "Tr" => material.opacity = rest.parse().map_or(material.opacity, |tr: f32| 1.0 - tr),
+            "map_Kd" => material.diffuse_texture = Some(texture_path(rest)),
+            "map_Ks" => material.specular_texture = Some(texture_path(rest)),
+            "norm" | "map_Bump" | "bump" => material.normal_texture = Some(texture_path(rest)),
+            _ => {}
+        }
+    }
+
+    materials
+}
+
+/// turns a one based (or negative relative) OBJ index into a zero based index
+fn resolve_index(index: Option<&str>, count: usize) -> Option<usize> {
+    let index: i64 = index?.parse().ok()?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    (0..count as i64)
+        .contains(&resolved)
+        .then_some(resolved as usize)
+}
+
+fn parse_floats(text: &str) -> Option<Vec<f32>> {
+    text.split_whitespace()
+        .map(|value| value.parse().ok())
+        .collect()
+}
+
+fn parse_vec3(text: &str) -> Option<glm::Vec3> {
+    match parse_floats(text)?.as_slice() {
+        [x, y, z, ..] => Some(glm::vec3(*x, *y, *z)),
+        _ => None,
+    }
+}
+
+/// the file of a texture statement, skipping options like `-s 1 1 1`
+fn texture_path(text: &str) -> String {
+    if text.starts_with('-') {
+        text.split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_obj_triangulates_and_shares_vertices() {
+        let source = "
+            mtllib box.mtl
+            o Quad
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 1 1
+            vt 0 1
+            vn 0 0 1
+            usemtl red
+            f 1/1/1 2/2/1 3/3/1 4/4/1
+            usemtl blue
+            f -4/1/1 -3/2/1 -2/3/1
+        ";
+        let data = parse_obj(source).unwrap();
+
+        assert_eq!(data.material_libraries, vec!["box.mtl"]);
+        assert_eq!(data.objects.len(), 1);
+        assert_eq!(data.objects[0].name, "Quad");
+
+        let meshes = &data.objects[0].meshes;
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[0].material.as_deref(), Some("red"));
+        assert_eq!(meshes[0].vertices.len(), 4);
+        assert_eq!(meshes[0].indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(meshes[1].material.as_deref(), Some("blue"));
+        assert_eq!(meshes[1].vertices[2].tex_uv, glm::vec2(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_parse_obj_computes_face_normals() {
+        let source = "
+            v 0 0 0
+            v 1 0 0
+            v 0 0 -1
+            v 0 -1 0
+            f 1 2 3
+            f 1 4 2
+        ";
+        let data = parse_obj(source).unwrap();
+        let mesh = &data.objects[0].meshes[0];
+
+        // the shared corners are split because the faces have different normals
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.vertices[0].normal, glm::vec3(0.0, 1.0, 0.0));
+        assert_eq!(mesh.vertices[3].normal, glm::vec3(0.0, 0.0, 1.0));
+
+        assert!(parse_obj("v 0 0 0\nf 1 2 3").is_err());
+    }
+
+    #[test]
+    fn test_parse_mtl() {
+        let source = "
+            newmtl red
+            Kd 1.0 0.0 0.0
+            d 0.5
+            map_Kd -s 1 1 1 textures/red.png
+            newmtl blue
+            Kd 0 0 1
+            Tr 0.25
+            map_Ks blue spec.png
+        ";
+        let materials = parse_mtl(source);
+
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].diffuse, glm::vec3(1.0, 0.0, 0.0));
+        assert_eq!(materials[0].opacity, 0.5);
+        assert_eq!(
+            materials[0].diffuse_texture.as_deref(),
+            Some("textures/red.png")
+        );
+        assert_eq!(materials[1].opacity, 0.75);
+        assert_eq!(
+            materials[1].specular_texture.as_deref(),
+            Some("blue spec.png")
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! procedural shapes with adjustable proportions, used by [`Model::new_torus`](crate::nodes::Model::new_torus) and
+//! [`Model::new_capsule`](crate::nodes::Model::new_capsule).
+//!
+//! the shapes are centered on the origin with +Y up, have smooth normals and uvs that wrap once around the shape.
+//! triangles wind counter clockwise when seen from outside. the tangents are left at zero for
+//! [`calculate_tangents`](super::mesh::calculate_tangents).
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use nalgebra_glm as glm;
+
+use crate::nodes::model::Vertex;
+
+/// connects a grid of `rows` x `columns` quads where every row has `columns + 1` vertices.
+///
+/// the quad `a b c d` goes along the row first (a to b) then to the next row (b to c).
+fn grid_indices(rows: u32, columns: u32) -> Vec<u32> {
+    let stride = columns + 1;
+    let mut indices = Vec::with_capacity((rows * columns * 6) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let a = row * stride + column;
+            let b = a + 1;
+            let c = b + stride;
+            let d = a + stride;
+            indices.extend_from_slice(&[a, b, c, a, c, d]);
+        }
+    }
+    indices
+}
+
+/// generates a torus lying flat in the XZ plane around the Y axis.
+///
+/// # Arguments
+/// - `major_radius` - the distance from the center to the middle of the tube
+/// - `minor_radius` - the radius of the tube
+/// - `major_segments` - the segments around the ring, at least 3
+/// - `minor_segments` - the segments around the tube, at least 3
+///
+/// # Returns
+/// the vertices and triangle indices, `major_segments * minor_segments * 6` indices
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> (Vec<Vertex>, Vec<u32>) {
+    let major_segments = major_segments.max(3);
+    let minor_segments = minor_segments.max(3);
+
+    let mut vertices = Vec::with_capacity(((major_segments + 1) * (minor_segments + 1)) as usize);
+    // rows go around the tube so the quads run along the ring first
+    for j in 0..=minor_segments {
+        let v = j as f32 / minor_segments as f32;
+        // start at the top of the tube and go outwards
+        let phi = FRAC_PI_2 - v * TAU;
+        for i in 0..=major_segments {
+            let u = i as f32 / major_segments as f32;
+            let theta = u * TAU;
+            let ring = glm::vec3(theta.cos(), 0.0, theta.sin());
+            let normal = ring * phi.cos() + glm::vec3(0.0, phi.sin(), 0.0);
+            let position = ring * major_radius + normal * minor_radius;
+            vertices.push(Vertex::new(position, normal, glm::vec2(u, v)));
+        }
+    }
+
+    (vertices, grid_indices(minor_segments, major_segments))
+}
+
+/// generates a capsule standing along the Y axis, a cylinder with a half sphere on each end.
+///
+/// # Arguments
+/// - `radius` - the radius of the cylinder and the half spheres
+/// - `height` - the total height from the bottom to the top, at least `2 * radius`
+/// - `segments` - the segments around the Y axis, at least 3
+/// - `rings` - the rings of each half sphere from the pole to the cylinder, at least 1
+///
+/// # Returns
+/// the vertices and triangle indices, `(2 * rings + 1) * segments * 6` indices
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> (Vec<Vertex>, Vec<u32>) {
+    let segments = segments.max(3);
+    let rings = rings.max(1);
+    let height = height.max(2.0 * radius);
+    let half_cylinder = height / 2.0 - radius;
+
+    // the top half sphere then the bottom one, the gap between their last and first rows is the cylinder
+    let rows: Vec<(f32, f32)> = (0..=rings)
+        .map(|ring| (ring as f32 / rings as f32 * FRAC_PI_2, half_cylinder))
+        .chain((0..=rings).map(|ring| {
+            (
+                FRAC_PI_2 + ring as f32 / rings as f32 * FRAC_PI_2,
+                -half_cylinder,
+            )
+        }))
+        .collect();
+
+    let mut vertices = Vec::with_capacity(rows.len() * (segments + 1) as usize);
+    for (phi, offset) in &rows {
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let theta = u * TAU;
+            let normal = glm::vec3(theta.cos() * phi.sin(), phi.cos(), theta.sin() * phi.sin());
+            let position = normal * radius + glm::vec3(0.0, *offset, 0.0);
+            let v = ((height / 2.0 - position.y) / height).clamp(0.0, 1.0);
+            vertices.push(Vertex::new(position, normal, glm::vec2(u, v)));
+        }
+    }
+
+    (vertices, grid_indices(rows.len() as u32 - 1, segments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// checks that every triangle faces away from the center line of the shape
+    fn assert_faces_outwards(
+        vertices: &[Vertex],
+        indices: &[u32],
+        center: impl Fn(&glm::Vec3) -> glm::Vec3,
+    ) {
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize].position);
+            let normal = glm::cross(&(b - a), &(c - a));
+            // the triangles at the poles collapse to a line
+            if glm::length(&normal) < 1e-6 {
+                continue;
+            }
+            let middle = (a + b + c) / 3.0;
+            assert!(glm::dot(&normal, &(middle - center(&middle))) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_torus() {
+        let (vertices, indices) = torus(1.0, 0.25, 24, 12);
+        assert_eq!(vertices.len(), 25 * 13);
+        assert_eq!(indices.len(), 24 * 12 * 6);
+        assert!(indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.len()));
+
+        for vertex in &vertices {
+            assert!((glm::length(&vertex.normal) - 1.0).abs() < 1e-5);
+            // every vertex is on the surface of the tube
+            let ring = glm::normalize(&glm::vec3(vertex.position.x, 0.0, vertex.position.z));
+            assert!((glm::distance(&vertex.position, &ring) - 0.25).abs() < 1e-5);
+        }
+
+        assert_faces_outwards(&vertices, &indices, |point| {
+            glm::normalize(&glm::vec3(point.x, 0.0, point.z))
+        });
+        // segment counts are clamped
+        assert_eq!(torus(1.0, 0.25, 1, 0).1.len(), 3 * 3 * 6);
+    }
+
+    #[test]
+    fn test_capsule() {
+        let (vertices, indices) = capsule(0.5, 2.0, 16, 4);
+        assert_eq!(vertices.len(), 2 * 5 * 17);
+        assert_eq!(indices.len(), (2 * 4 + 1) * 16 * 6);
+        assert!(indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.len()));
+
+        let top = vertices
+            .iter()
+            .map(|v| v.position.y)
+            .fold(f32::MIN, f32::max);
+        let bottom = vertices
+            .iter()
+            .map(|v| v.position.y)
+            .fold(f32::MAX, f32::min);
+        assert!((top - 1.0).abs() < 1e-5 && (bottom + 1.0).abs() < 1e-5);
+        assert!(vertices
+            .iter()
+            .all(|v| (glm::length(&v.normal) - 1.0).abs() < 1e-5
+                && (0.0..=1.0).contains(&v.tex_uv.y)));
+
+        assert_faces_outwards(&vertices, &indices, |point| {
+            glm::vec3(0.0, point.y.clamp(-0.5, 0.5), 0.0)
+        });
+        // too short capsules become spheres
+        let (sphere, _) = capsule(0.5, 0.2, 16, 4);
+        assert!(sphere
+            .iter()
+            .all(|v| (glm::length(&v.position) - 0.5).abs() < 1e-5));
+    }
+}
@@ -0,0 +1,404 @@
+//! mesh simplification used to generate lower detail versions of meshes (LODs).
+//!
+//! the simplifier collapses edges in the order of the smallest quadric error (Garland & Heckbert). every collapse moves one
+//! vertex onto the other so the remaining vertices keep their original attributes (normals, colors, uvs).
+//!
+//! vertices on uv seams (vertices that share a position with another vertex) and on open borders are never removed, this keeps
+//! textures from tearing and the silhouette of open meshes intact at the cost of less reduction on heavily seamed meshes.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use nalgebra_glm as glm;
+
+use crate::nodes::model::Vertex;
+
+/// symmetric 4x4 error quadric stored as its upper triangle
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// the quadric of the plane ax + by + cz + d = 0
+    fn from_plane(a: f64, b: f64, c: f64, d: f64) -> Quadric {
+        Quadric([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+    }
+
+    /// the squared distance of the point to all the planes in the quadric
+    fn error(&self, p: &glm::Vec3) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (p.x as f64, p.y as f64, p.z as f64);
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+/// a possible collapse of `from` onto `to`
+struct Collapse {
+    cost: f64,
+    from: u32,
+    to: u32,
+    /// the versions of both vertices when the cost was calculated, used to skip outdated entries
+    versions: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed so the binary heap pops the cheapest collapse first
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// simplifies an indexed triangle mesh.
+///
+/// # Arguments
+/// - `vertices` - the vertices of the mesh
+/// - `indices` - the triangle indices of the mesh, empty for a mesh that draws its vertices in order
+/// - `target_ratio` - the fraction of triangles to keep (0.0 - 1.0)
+///
+/// # Returns
+/// the new vertices and indices. the result can have more triangles than requested if the remaining vertices are locked.
+pub fn simplify(
+    vertices: &[Vertex],
+    indices: &[u32],
+    target_ratio: f32,
+) -> (Vec<Vertex>, Vec<u32>) {
+    // a mesh without indices draws its vertices in order
+    let sequential: Vec<u32>;
+    let indices = if indices.is_empty() {
+        sequential = (0..vertices.len() as u32).collect();
+        &sequential
+    } else {
+        indices
+    };
+
+    let triangle_count = indices.len() / 3;
+    let target = (triangle_count as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+
+    let mut triangles: Vec<[u32; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    let mut alive = vec![true; triangles.len()];
+    let mut alive_count = triangles.len();
+
+    let locked = locked_vertices(vertices, &triangles);
+
+    // vertex -> triangles using it
+    let mut vertex_triangles: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for (i, triangle) in triangles.iter().enumerate() {
+        for &v in triangle {
+            vertex_triangles[v as usize].push(i);
+        }
+    }
+
+    // quadric of each vertex from the planes of its triangles
+    let mut quadrics = vec![Quadric::default(); vertices.len()];
+    for triangle in &triangles {
+        let [a, b, c] = triangle.map(|v| vertices[v as usize].position);
+        let cross = glm::cross(&(b - a), &(c - a));
+        let area = glm::length(&cross);
+        if area <= f32::EPSILON {
+            continue;
+        }
+        let n = cross / area;
+        let mut quadric =
+            Quadric::from_plane(n.x as f64, n.y as f64, n.z as f64, -glm::dot(&n, &a) as f64);
+        // weight by area so large faces keep their shape
+        quadric.0.iter_mut().for_each(|q| *q *= area as f64);
+        for &v in triangle {
+            quadrics[v as usize].add(&quadric);
+        }
+    }
+
+    let mut versions = vec![0u32; vertices.len()];
+    let mut removed = vec![false; vertices.len()];
+    let mut heap = BinaryHeap::new();
+
+    let push_edge = |heap: &mut BinaryHeap<Collapse>,
+                     quadrics: &[Quadric],
+                     versions: &[u32],
+                     a: u32,
+                     b: u32| {
+        let mut quadric = quadrics[a as usize];
+        quadric.add(&quadrics[b as usize]);
+
+        for (from, to) in [(a, b), (b, a)] {
+            if locked[from as usize] {
+                continue;
+            }
+            heap.push(Collapse {
+                cost: quadric.error(&vertices[to as usize].position),
+                from,
+                to,
+                versions: (versions[from as usize], versions[to as usize]),
+            });
+        }
+    };
+
+    let mut edges = HashSet::new();
+    for triangle in &triangles {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            if edges.insert((a.min(b), a.max(b))) {
+                push_edge(&mut heap, &quadrics, &versions, a, b);
+            }
+        }
+    }
+
+    while alive_count > target {
+        let Some(collapse) = heap.pop() else {
+            break;
+        };
+        let (from, to) = (collapse.from as usize, collapse.to as usize);
+        if removed[from] || removed[to] || collapse.versions != (versions[from], versions[to]) {
+            continue;
+        }
+
+        if flips_triangle(
+            vertices,
+            &triangles,
+            &alive,
+            &vertex_triangles[from],
+            from,
+            to,
+        ) {
+            continue;
+        }
+
+        // move all the triangles of `from` over to `to`
+        removed[from] = true;
+        let moved = std::mem::take(&mut vertex_triangles[from]);
+        for t in moved {
+            if !alive[t] {
+                continue;
+            }
+            let triangle = &mut triangles[t];
+            if triangle.contains(&(to as u32)) {
+                // the collapsed edge belonged to this triangle so it disappears
+                alive[t] = false;
+                alive_count -= 1;
+            } else {
+                triangle
+                    .iter_mut()
+                    .filter(|v| **v as usize == from)
+                    .for_each(|v| *v = to as u32);
+                vertex_triangles[to].push(t);
+            }
+        }
+        vertex_triangles[to].retain(|&t| alive[t]);
+
+        let quadric = quadrics[from];
+        quadrics[to].add(&quadric);
+        versions[to] += 1;
+
+        // recalculate the cost of the edges around the merged vertex
+        let mut neighbours = HashSet::new();
+        for &t in &vertex_triangles[to] {
+            for &v in &triangles[t] {
+                if v as usize != to {
+                    neighbours.insert(v);
+                }
+            }
+        }
+        for v in neighbours {
+            push_edge(&mut heap, &quadrics, &versions, to as u32, v);
+        }
+    }
+
+    // compact the vertices used by the remaining triangles
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut new_vertices = Vec::new();
+    let mut new_indices = Vec::with_capacity(alive_count * 3);
+    for (triangle, _) in triangles.iter().zip(&alive).filter(|(_, alive)| **alive) {
+        for &v in triangle {
+            let index = *remap.entry(v).or_insert_with(|| {
+                new_vertices.push(vertices[v as usize].clone());
+                new_vertices.len() as u32 - 1
+            });
+            new_indices.push(index);
+        }
+    }
+
+    (new_vertices, new_indices)
+}
+
+/// vertices that can not be removed without tearing uv seams or open borders
+fn locked_vertices(vertices: &[Vertex], triangles: &[[u32; 3]]) -> Vec<bool> {
+    let mut locked = vec![false; vertices.len()];
+
+    // vertices that share a position with another vertex are on a seam
+    let mut positions: HashMap<[u32; 3], u32> = HashMap::new();
+    for (i, vertex) in vertices.iter().enumerate() {
+        let key = vertex.position.map(|p| p.to_bits()).into();
+        if let Some(&other) = positions.get(&key) {
+            locked[i] = true;
+            locked[other as usize] = true;
+        } else {
+            positions.insert(key, i as u32);
+        }
+    }
+
+    // edges used by a single triangle are on the border
+    let mut edge_count: HashMap<(u32, u32), u32> = HashMap::new();
+    for triangle in triangles {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            *edge_count.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    for ((a, b), count) in edge_count {
+        if count == 1 {
+            locked[a as usize] = true;
+            locked[b as usize] = true;
+        }
+    }
+
+    locked
+}
+
+/// checks if moving `from` onto `to` would flip or squash any of the remaining triangles of `from`
+fn flips_triangle(
+    vertices: &[Vertex],
+    triangles: &[[u32; 3]],
+    alive: &[bool],
+    from_triangles: &[usize],
+    from: usize,
+    to: usize,
+) -> bool {
+    for &t in from_triangles {
+        let triangle = triangles[t];
+        if !alive[t] || triangle.contains(&(to as u32)) {
+            continue;
+        }
+
+        let before = triangle.map(|v| vertices[v as usize].position);
+        let after = triangle.map(|v| {
+            if v as usize == from {
+                vertices[to].position
+            } else {
+                vertices[v as usize].position
+            }
+        });
+
+        let normal_before = glm::cross(&(before[1] - before[0]), &(before[2] - before[0]));
+        let normal_after = glm::cross(&(after[1] - after[0]), &(after[2] - after[0]));
+        if glm::dot(&normal_before, &normal_after) <= 0.0 {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a flat grid of `size` x `size` quads
+    fn grid(size: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        for y in 0..=size {
+            for x in 0..=size {
+                vertices.push(Vertex {
+                    position: glm::vec3(x as f32, 0.0, y as f32),
+                    normal: glm::vec3(0.0, 1.0, 0.0),
+                    color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+                    tex_uv: glm::vec2(x as f32 / size as f32, y as f32 / size as f32),
+                    tangent: glm::vec4(1.0, 0.0, 0.0, 1.0),
+                });
+            }
+        }
+
+        let mut indices = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let i = y * (size + 1) + x;
+                indices.extend([i, i + size + 1, i + 1, i + 1, i + size + 1, i + size + 2]);
+            }
+        }
+        (vertices, indices)
+    }
+
+    #[test]
+    fn test_simplify_reduces_flat_grid() {
+        let (vertices, indices) = grid(8);
+        let (new_vertices, new_indices) = simplify(&vertices, &indices, 0.25);
+
+        assert!(new_indices.len() < indices.len());
+        assert!(new_vertices.len() < vertices.len());
+        assert!(new_indices
+            .iter()
+            .all(|&i| (i as usize) < new_vertices.len()));
+
+        // the border is locked so the outline of the grid is kept
+        for corner in [glm::vec3(0.0, 0.0, 0.0), glm::vec3(8.0, 0.0, 8.0)] {
+            assert!(new_vertices.iter().any(|v| v.position == corner));
+        }
+    }
+
+    #[test]
+    fn test_simplify_keeps_everything_at_full_ratio() {
+        let (vertices, indices) = grid(2);
+        let (new_vertices, new_indices) = simplify(&vertices, &indices, 1.0);
+        assert_eq!(new_indices.len(), indices.len());
+        assert_eq!(new_vertices.len(), vertices.len());
+    }
+
+    #[test]
+    fn test_simplify_without_indices() {
+        // every triangle has its own vertices, like a flat shaded mesh
+        let (vertices, indices) = grid(2);
+        let vertices: Vec<Vertex> = indices
+            .iter()
+            .map(|&i| vertices[i as usize].clone())
+            .collect();
+
+        let (new_vertices, new_indices) = simplify(&vertices, &[], 1.0);
+        assert_eq!(new_indices.len(), vertices.len());
+        assert_eq!(new_vertices.len(), vertices.len());
+
+        let (new_vertices, new_indices) = simplify(&vertices, &[], 0.5);
+        assert!(!new_indices.is_empty());
+        assert!(new_indices
+            .iter()
+            .all(|&i| (i as usize) < new_vertices.len()));
+    }
+}
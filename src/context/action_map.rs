@@ -0,0 +1,514 @@
+//! the `action_map` module maps named actions like `"jump"` to the inputs that trigger them so controls can be rebound.
+//!
+//! an action can have any number of [`Binding`]s (keys, mouse buttons, gamepad buttons and gamepad axes). the input manager
+//! keeps an [`ActionMap`] in `context.input.actions` and answers
+//! [`action_pressed`](super::input_manager::InputManager::action_pressed) and
+//! [`action_value`](super::input_manager::InputManager::action_value) from it.
+//!
+//! gamepad bindings read every connected gamepad. stick axes are split into half axes by the `scale` of
+//! [`Binding::GamepadAxis`], so pushing the left stick up can be bound to `"move_forward"` with a scale of -1.0 (glfw reports up as negative).
+//!
+//! ## Saving
+//! the map is plain serde data and is stored as json by [`ActionMap::save`] and [`ActionMap::load`], so a rebinding menu
+//! can edit it and write it back. keys and buttons are stored by their glfw names (`"Space"`, `"ButtonA"`, ...).
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::context::action_map::{ActionMap, Binding};
+//!
+//! context
+//!     .input
+//!     .actions
+//!     .bind("jump", Binding::Key(Key::Space))
+//!     .bind("jump", Binding::GamepadButton(GamepadButton::ButtonA));
+//!
+//! if context.input.action_just_pressed("jump") {
+//!     player.jump();
+//! }
+//! let throttle = context.input.action_value("throttle");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use egui_backend::glfw;
+use egui_gl_glfw as egui_backend;
+use glfw::{GamepadAxis, GamepadButton, Key, MouseButton};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// the actions [`Camera3D::take_input`](crate::nodes::Camera3D::take_input) reads when it uses the action map
+pub mod camera_actions {
+    /// moves along the view direction
+    pub const MOVE_FORWARD: &str = "move_forward";
+    /// moves against the view direction
+    pub const MOVE_BACK: &str = "move_back";
+    /// strafes left
+    pub const MOVE_LEFT: &str = "move_left";
+    /// strafes right
+    pub const MOVE_RIGHT: &str = "move_right";
+    /// moves up in world space
+    pub const MOVE_UP: &str = "move_up";
+    /// moves down in world space
+    pub const MOVE_DOWN: &str = "move_down";
+    /// moves 5 times faster while held
+    pub const SPRINT: &str = "sprint";
+    /// turns left
+    pub const LOOK_LEFT: &str = "look_left";
+    /// turns right
+    pub const LOOK_RIGHT: &str = "look_right";
+    /// looks up
+    pub const LOOK_UP: &str = "look_up";
+    /// looks down
+    pub const LOOK_DOWN: &str = "look_down";
+}
+
+/// how far an action value has to be to count as pressed
+pub const PRESS_THRESHOLD: f32 = 0.5;
+
+/// an input that can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    /// a keyboard key, 1.0 while held
+    Key(#[serde(with = "key_name")] Key),
+    /// a mouse button, 1.0 while held
+    MouseButton(#[serde(with = "mouse_button_name")] MouseButton),
+    /// a gamepad button, 1.0 while held
+    GamepadButton(#[serde(with = "gamepad_button_name")] GamepadButton),
+    /// one direction of a gamepad axis
+    GamepadAxis {
+        /// the axis to read
+        #[serde(with = "gamepad_axis_name")]
+        axis: GamepadAxis,
+        /// the axis is multiplied by this and negative results are ignored. use -1.0 for the negative direction.
+        /// triggers are remapped from -1..1 to 0..1 before scaling.
+        #[serde(default = "default_scale")]
+        scale: f32,
+    },
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// Errors returned when saving or loading an action map.
+#[derive(Debug)]
+pub enum ActionMapError {
+    /// the file could not be read or written.
+    Io(std::io::Error),
+    /// the file is not a valid action map.
+    Format(serde_json::Error),
+}
+
+impl fmt::Display for ActionMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionMapError::Io(error) => write!(f, "failed to access action map: {}", error),
+            ActionMapError::Format(error) => write!(f, "invalid action map: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ActionMapError {}
+
+impl From<std::io::Error> for ActionMapError {
+    fn from(error: std::io::Error) -> Self {
+        ActionMapError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ActionMapError {
+    fn from(error: serde_json::Error) -> Self {
+        ActionMapError::Format(error)
+    }
+}
+
+/// Maps action names to the bindings that trigger them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+}
+
+impl ActionMap {
+    /// creates an empty action map
+    pub fn new() -> ActionMap {
+        ActionMap::default()
+    }
+
+    /// creates an action map with the default free camera controls, see [`camera_actions`].
+    ///
+    /// WASD, space and left control move, left shift sprints, the left stick moves and the right stick looks around.
+    pub fn free_camera() -> ActionMap {
+        use camera_actions::*;
+        let stick = |axis, scale| Binding::GamepadAxis { axis, scale };
+
+        let mut map = ActionMap::new();
+        map.bind(MOVE_FORWARD, Binding::Key(Key::W))
+            .bind(MOVE_FORWARD, stick(GamepadAxis::AxisLeftY, -1.0))
+            .bind(MOVE_BACK, Binding::Key(Key::S))
+            .bind(MOVE_BACK, stick(GamepadAxis::AxisLeftY, 1.0))
+            .bind(MOVE_LEFT, Binding::Key(Key::A))
+            .bind(MOVE_LEFT, stick(GamepadAxis::AxisLeftX, -1.0))
+            .bind(MOVE_RIGHT, Binding::Key(Key::D))
+            .bind(MOVE_RIGHT, stick(GamepadAxis::AxisLeftX, 1.0))
+            .bind(MOVE_UP, Binding::Key(Key::Space))
+            .bind(MOVE_UP, Binding::GamepadButton(GamepadButton::ButtonA))
+            .bind(MOVE_DOWN, Binding::Key(Key::LeftControl))
+            .bind(MOVE_DOWN, Binding::GamepadButton(GamepadButton::ButtonB))
+            .bind(SPRINT, Binding::Key(Key::LeftShift))
+            .bind(
+                SPRINT,
+                Binding::GamepadButton(GamepadButton::ButtonLeftThumb),
+            )
+            .bind(LOOK_LEFT, stick(GamepadAxis::AxisRightX, -1.0))
+            .bind(LOOK_RIGHT, stick(GamepadAxis::AxisRightX, 1.0))
+            .bind(LOOK_UP, stick(GamepadAxis::AxisRightY, -1.0))
+            .bind(LOOK_DOWN, stick(GamepadAxis::AxisRightY, 1.0));
+        map
+    }
+
+    /// adds a binding to an action, creating the action if it does not exist yet. binding the same input twice does nothing.
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    /// - `binding` - the input that triggers the action
+    pub fn bind(&mut self, action: &str, binding: Binding) -> &mut Self {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// removes a binding from an action
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    /// - `binding` - the input to remove
+    pub fn unbind(&mut self, action: &str, binding: &Binding) -> &mut Self {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+        self
+    }
+
+    /// replaces all the bindings of an action, useful for a rebinding menu
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    /// - `bindings` - the new inputs of the action
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) -> &mut Self {
+        self.actions.insert(action.to_string(), bindings);
+        self
+    }
+
+    /// removes an action and all its bindings
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    pub fn remove(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// get the bindings of an action, empty if the action does not exist
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// check if an action exists
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    pub fn contains(&self, action: &str) -> bool {
+        self.actions.contains_key(action)
+    }
+
+    /// get the names of all the actions in alphabetical order
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// reads an action map from a json string
+    ///
+    /// # Arguments
+    /// - `json` - the contents of an action map file
+    pub fn from_json(json: &str) -> Result<ActionMap, ActionMapError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// writes the action map as a pretty printed json string
+    pub fn to_json(&self) -> Result<String, ActionMapError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// writes the action map to a json file
+    ///
+    /// # Arguments
+    /// - `path` - the path of the file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ActionMapError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// reads an action map from a json file
+    ///
+    /// # Arguments
+    /// - `path` - the path of the file
+    pub fn load(path: impl AsRef<Path>) -> Result<ActionMap, ActionMapError> {
+        let json = std::fs::read_to_string(path)?;
+        ActionMap::from_json(&json)
+    }
+}
+
+/// every glfw key, used to read keys back from their names
+const KEYS: &[Key] = &[
+    Key::Space,
+    Key::Apostrophe,
+    Key::Comma,
+    Key::Minus,
+    Key::Period,
+    Key::Slash,
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::Semicolon,
+    Key::Equal,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::LeftBracket,
+    Key::Backslash,
+    Key::RightBracket,
+    Key::GraveAccent,
+    Key::World1,
+    Key::World2,
+    Key::Escape,
+    Key::Enter,
+    Key::Tab,
+    Key::Backspace,
+    Key::Insert,
+    Key::Delete,
+    Key::Right,
+    Key::Left,
+    Key::Down,
+    Key::Up,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Home,
+    Key::End,
+    Key::CapsLock,
+    Key::ScrollLock,
+    Key::NumLock,
+    Key::PrintScreen,
+    Key::Pause,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::F13,
+    Key::F14,
+    Key::F15,
+    Key::F16,
+    Key::F17,
+    Key::F18,
+    Key::F19,
+    Key::F20,
+    Key::F21,
+    Key::F22,
+    Key::F23,
+    Key::F24,
+    Key::F25,
+    Key::Kp0,
+    Key::Kp1,
+    Key::Kp2,
+    Key::Kp3,
+    Key::Kp4,
+    Key::Kp5,
+    Key::Kp6,
+    Key::Kp7,
+    Key::Kp8,
+    Key::Kp9,
+    Key::KpDecimal,
+    Key::KpDivide,
+    Key::KpMultiply,
+    Key::KpSubtract,
+    Key::KpAdd,
+    Key::KpEnter,
+    Key::KpEqual,
+    Key::LeftShift,
+    Key::LeftControl,
+    Key::LeftAlt,
+    Key::LeftSuper,
+    Key::RightShift,
+    Key::RightControl,
+    Key::RightAlt,
+    Key::RightSuper,
+    Key::Menu,
+    Key::Unknown,
+];
+
+/// writes a glfw enum as its variant name
+fn serialize_name<T: fmt::Debug, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:?}", value))
+}
+
+/// reads a glfw enum back from its variant name
+fn deserialize_name<'de, T, D>(
+    deserializer: D,
+    mut candidates: impl Iterator<Item = T>,
+) -> Result<T, D::Error>
+where
+    T: fmt::Debug,
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    candidates
+        .find(|candidate| format!("{:?}", candidate) == name)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown input \"{}\"", name)))
+}
+
+mod key_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(key: &Key, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_name(key, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        deserialize_name(deserializer, KEYS.iter().copied())
+    }
+}
+
+mod mouse_button_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        button: &MouseButton,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_name(button, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<MouseButton, D::Error> {
+        deserialize_name(deserializer, (0..8).filter_map(MouseButton::from_i32))
+    }
+}
+
+mod gamepad_button_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        button: &GamepadButton,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_name(button, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<GamepadButton, D::Error> {
+        deserialize_name(deserializer, (0..15).filter_map(GamepadButton::from_i32))
+    }
+}
+
+mod gamepad_axis_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(axis: &GamepadAxis, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_name(axis, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<GamepadAxis, D::Error> {
+        deserialize_name(deserializer, (0..6).filter_map(GamepadAxis::from_i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_and_unbind() {
+        let mut map = ActionMap::new();
+        map.bind("jump", Binding::Key(Key::Space))
+            .bind("jump", Binding::Key(Key::Space))
+            .bind("jump", Binding::GamepadButton(GamepadButton::ButtonA));
+        assert_eq!(map.bindings("jump").len(), 2);
+
+        map.unbind("jump", &Binding::Key(Key::Space));
+        assert_eq!(
+            map.bindings("jump"),
+            &[Binding::GamepadButton(GamepadButton::ButtonA)]
+        );
+        assert!(map.bindings("missing").is_empty());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let map = ActionMap::free_camera();
+        let json = map.to_json().unwrap();
+        assert!(json.contains("\"LeftShift\""));
+        assert!(json.contains("\"AxisRightX\""));
+        assert_eq!(ActionMap::from_json(&json).unwrap(), map);
+
+        let mut mouse = ActionMap::new();
+        mouse.bind("fire", Binding::MouseButton(MouseButton::Button1));
+        let json = mouse.to_json().unwrap();
+        assert_eq!(ActionMap::from_json(&json).unwrap(), mouse);
+    }
+
+    #[test]
+    fn test_unknown_input_is_an_error() {
+        let json = r#"{ "jump": [{ "Key": "NotAKey" }] }"#;
+        assert!(ActionMap::from_json(json).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! The `asset_loader` module lets assets be loaded with `async`/`.await` instead of blocking the main thread.
+//!
+//! ## How it works
+//! loading is split into two steps:
+//! - **decode**: reading and parsing the file. this runs on a background thread.
+//! - **upload**: creating the OpenGL objects (buffers, textures, ...). OpenGL can only be used from the thread that owns the context
+//!   so this step is sent over a channel to the main thread where the [`AssetLoader`] drains it once per frame.
+//!
+//! the returned [`AssetFuture`] resolves after the upload step ran, so it can be awaited from any executor (tokio, async-std, ...).
+//! without an executor check it every frame with [`AssetFuture::is_ready`] and take the asset with [`AssetFuture::try_take`].
+//!
+//! ## Main thread requirement
+//! the upload step only runs when [`AssetLoader::update`] is called on the main thread. the engine does this every frame in the
+//! render loop, so a future awaited before [`Engine::begin`](crate::Engine::begin) (or while the main thread is blocked) will never
+//! resolve unless you call `context.assets.update()` yourself.
+//!
+//! assets hold OpenGL objects and reference counted handles that can't cross threads, so the uploaded asset stays on the main
+//! thread. the [`AssetFuture`] is not `Send`, create it and await it on the main thread (with a local executor or by polling it
+//! every frame). when no asset loader is running the upload can't happen and the future resolves to [`AssetError::NotUploaded`].
+//!
+//! ## Example
+//! ```rust,ignore
+//! // inside an async task on the main thread
+//! let model = Model::load_async("res/models/model.gltf").await.expect("failed to load model");
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// a job that has to run on the main thread because it touches OpenGL.
+type UploadJob = Box<dyn FnOnce() + Send>;
+
+/// the sender of the active asset loader. background threads use it to queue their upload step.
+static UPLOAD_SENDER: OnceLock<Mutex<Option<Sender<UploadJob>>>> = OnceLock::new();
+
+/// the id of the next load, used to find the uploaded asset of a future.
+static NEXT_LOAD_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// the uploaded assets waiting for their future, kept on the thread that uploaded them.
+    static UPLOADED: RefCell<HashMap<u64, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Drains the upload steps queued by background loads and runs them on the main thread.
+pub struct AssetLoader {
+    receiver: Receiver<UploadJob>,
+}
+
+impl Default for AssetLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetLoader {
+    /// Creates a new asset loader and makes it the target for all new uploads.
+    pub fn new() -> AssetLoader {
+        let (sender, receiver) = mpsc::channel();
+
+        let slot = UPLOAD_SENDER.get_or_init(|| Mutex::new(None));
+        *slot.lock().unwrap() = Some(sender);
+
+        AssetLoader { receiver }
+    }
+
+    /// runs all the uploads that are ready. must be called on the main thread (the engine calls this every frame).
+    pub fn update(&mut self) {
+        while let Ok(job) = self.receiver.try_recv() {
+            job();
+        }
+    }
+}
+
+/// queues a job to be ran on the main thread the next time the asset loader updates. the job is dropped when there is no
+/// loader, which resolves its future with [`AssetError::NotUploaded`].
+fn queue_upload(job: UploadJob) {
+    let sender = UPLOAD_SENDER
+        .get()
+        .and_then(|slot| slot.lock().unwrap().clone());
+
+    match sender {
+        Some(sender) => {
+            if sender.send(job).is_err() {
+                log::warn!("asset loader was dropped before the upload could run");
+            }
+        }
+        None => {
+            log::warn!("no asset loader found, initialize the engine before loading assets")
+        }
+    }
+}
+
+/// Errors an [`AssetFuture`] resolves to when the asset could not be handed over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetError {
+    /// the upload step never ran, no asset loader was running or it was dropped before the upload.
+    NotUploaded,
+    /// the asset was uploaded on another thread than the one that created the future, so it could not be handed over.
+    WrongThread,
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::NotUploaded => write!(
+                f,
+                "the asset was never uploaded, no asset loader is running"
+            ),
+            AssetError::WrongThread => write!(
+                f,
+                "the asset was uploaded on another thread than the one that created the future"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// the state shared between the future and the main thread upload. the asset itself is not in here, it stays in the
+/// [`UPLOADED`] assets of the main thread.
+#[derive(Default)]
+struct LoadState {
+    /// if the upload ran and the asset waits in [`UPLOADED`]
+    uploaded: bool,
+    /// why the asset will never arrive
+    error: Option<AssetError>,
+    /// set when the future is dropped so the upload doesn't keep the asset around
+    abandoned: bool,
+    waker: Option<Waker>,
+}
+
+/// hands the result of the upload to its future, resolves the future with [`AssetError::NotUploaded`] when it is dropped
+/// without running.
+struct Completion<T, E> {
+    id: u64,
+    state: Arc<Mutex<LoadState>>,
+    /// the thread of the future, the only one that can take the asset out of its [`UPLOADED`] assets
+    thread: thread::ThreadId,
+    finished: bool,
+    _result: PhantomData<fn() -> Result<T, E>>,
+}
+
+impl<T: 'static, E: 'static> Completion<T, E> {
+    /// stores the result for the future and wakes it, runs on the main thread.
+    ///
+    /// the result is dropped when this is not the thread of the future, it could never be taken out of this thread's
+    /// [`UPLOADED`] assets and would stay there.
+    fn finish(mut self, result: Result<T, E>) {
+        self.finished = true;
+        let mut state = self.state.lock().unwrap();
+        if thread::current().id() != self.thread {
+            state.error = Some(AssetError::WrongThread);
+        } else if !state.abandoned {
+            UPLOADED.with(|uploaded| uploaded.borrow_mut().insert(self.id, Box::new(result)));
+            state.uploaded = true;
+        }
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T, E> Drop for Completion<T, E> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.error = Some(AssetError::NotUploaded);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A future that resolves to an asset once it is decoded and uploaded to the gpu.
+///
+/// the future is not `Send`, see the [module documentation](self) for where it has to be polled and when it can resolve.
+pub struct AssetFuture<T, E = AssetError> {
+    id: u64,
+    state: Arc<Mutex<LoadState>>,
+    taken: bool,
+    /// the asset can't leave the main thread
+    _result: PhantomData<(*const (), fn() -> Result<T, E>)>,
+}
+
+impl<T: 'static, E: From<AssetError> + 'static> AssetFuture<T, E> {
+    /// starts loading an asset.
+    ///
+    /// # Arguments
+    /// - `decode` - the cpu work ran on a background thread (reading and parsing files).
+    /// - `upload` - the gpu work ran on the main thread with the decoded data.
+    ///
+    /// # Returns
+    /// a future resolving to the output of `upload`, or to [`AssetError::NotUploaded`] when no asset loader runs the upload.
+    pub fn spawn<D, F, U>(decode: F, upload: U) -> AssetFuture<T, E>
+    where
+        D: Send + 'static,
+        F: FnOnce() -> D + Send + 'static,
+        U: FnOnce(D) -> Result<T, E> + Send + 'static,
+    {
+        let (future, completion) = Self::pending();
+
+        thread::spawn(move || {
+            let decoded = decode();
+            queue_upload(Box::new(move || completion.finish(upload(decoded))));
+        });
+
+        future
+    }
+
+    /// creates a future and the completion that resolves it
+    fn pending() -> (AssetFuture<T, E>, Completion<T, E>) {
+        let id = NEXT_LOAD_ID.fetch_add(1, Ordering::Relaxed);
+        let state = Arc::new(Mutex::new(LoadState::default()));
+        let completion = Completion {
+            id,
+            state: state.clone(),
+            thread: thread::current().id(),
+            finished: false,
+            _result: PhantomData,
+        };
+        let future = AssetFuture {
+            id,
+            state,
+            taken: false,
+            _result: PhantomData,
+        };
+        (future, completion)
+    }
+
+    /// checks if the asset finished loading (or failed to) without waiting for it.
+    pub fn is_ready(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        !self.taken && (state.uploaded || state.error.is_some())
+    }
+
+    /// takes the asset out of the future if it finished loading, without waiting for it.
+    ///
+    /// # Returns
+    /// the asset or the error, `None` while it is still loading (or when it was already taken)
+    pub fn try_take(&mut self) -> Option<Result<T, E>> {
+        if self.taken {
+            return None;
+        }
+
+        let state = self.state.lock().unwrap();
+        let result = if state.uploaded {
+            let asset = UPLOADED.with(|uploaded| uploaded.borrow_mut().remove(&self.id));
+            match asset.and_then(|asset| asset.downcast::<Result<T, E>>().ok()) {
+                Some(result) => *result,
+                None => Err(AssetError::WrongThread.into()),
+            }
+        } else {
+            Err(state.error?.into())
+        };
+        drop(state);
+
+        self.taken = true;
+        Some(result)
+    }
+}
+
+impl<T, E> Drop for AssetFuture<T, E> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.abandoned = true;
+        if state.uploaded && !self.taken {
+            UPLOADED.with(|uploaded| uploaded.borrow_mut().remove(&self.id));
+        }
+    }
+}
+
+impl<T: 'static, E: From<AssetError> + 'static> Future for AssetFuture<T, E> {
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, E>> {
+        let future = self.get_mut();
+        // the waker is stored first so an upload finishing in between still wakes the task
+        future.state.lock().unwrap().waker = Some(cx.waker().clone());
+        match future.try_take() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_upload_runs_on_update() {
+        let mut loader = AssetLoader::new();
+        let main_thread = thread::current().id();
+
+        let mut future: AssetFuture<i32> = AssetFuture::spawn(
+            || 21,
+            move |decoded| {
+                assert_eq!(thread::current().id(), main_thread);
+                Ok(decoded * 2)
+            },
+        );
+
+        let start = Instant::now();
+        while !future.is_ready() {
+            assert!(start.elapsed() < Duration::from_secs(5), "upload never ran");
+            loader.update();
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(Ok(42)));
+    }
+
+    #[test]
+    fn test_try_take() {
+        // built by hand, a second loader would steal the uploads of the test above
+        let (mut future, completion) = AssetFuture::<i32>::pending();
+        assert_eq!(future.try_take(), None);
+
+        completion.finish(Ok(7));
+        assert!(future.is_ready());
+        assert_eq!(future.try_take(), Some(Ok(7)));
+        assert_eq!(future.try_take(), None);
+        assert!(!future.is_ready());
+    }
+
+    #[test]
+    fn test_resolves_without_upload() {
+        // the job is dropped when no loader can run it
+        let (mut future, completion) = AssetFuture::<i32>::pending();
+        drop(completion);
+        assert_eq!(future.try_take(), Some(Err(AssetError::NotUploaded)));
+
+        // an asset uploaded on another thread can't be handed over and is not kept there
+        let (mut future, completion) = AssetFuture::<i32>::pending();
+        let id = completion.id;
+        let kept = thread::spawn(move || {
+            completion.finish(Ok(1));
+            UPLOADED.with(|uploaded| uploaded.borrow().contains_key(&id))
+        })
+        .join()
+        .unwrap();
+        assert!(!kept);
+        assert!(future.is_ready());
+        assert_eq!(future.try_take(), Some(Err(AssetError::WrongThread)));
+    }
+}
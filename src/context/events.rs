@@ -0,0 +1,136 @@
+//! custom events sent between nodes without holding references to each other.
+//!
+//! a node subscribes to an event name with [`GameContext::on`], any code with the context can send the event to
+//! every subscriber with [`GameContext::emit`] or to the subscriber at one path with [`GameContext::emit_to`]. the
+//! event can carry any value as its payload.
+//!
+//! events are delivered once per frame after the behaviors and before the scene is drawn. events sent while
+//! delivering are delivered the next frame so two nodes answering each other can't lock up a frame. like in a
+//! behavior, use [`GameContext::spawn`] and [`GameContext::despawn`] to change the node tree from a callback. the
+//! node is taken out of the tree while its callback runs, an empty node is at its path until the callback returns.
+//!
+//! ## Example
+//! ```rust,ignore
+//! // the door opens when any button sends "door_opened"
+//! engine.context.on::<Model, _>("door", "door_opened", |door, event, _context| {
+//!     let speed = event.payload::<f32>().copied().unwrap_or(1.0);
+//!     door.get_transform().rotate_euler_xyz(glm::vec3(0.0, 90.0 * speed, 0.0));
+//! });
+//!
+//! // in the behavior of the button
+//! if pressed {
+//!     context.emit("door_opened", 2.0f32);
+//! }
+//! ```
+
+use std::any::Any;
+
+use super::node_manager::{split_path, Node};
+use super::GameContext;
+
+/// an event sent with [`GameContext::emit`] or [`GameContext::emit_to`]
+pub struct Event {
+    name: String,
+    /// the path of the only subscriber to get the event, `None` for every subscriber
+    target: Option<String>,
+    payload: Box<dyn Any>,
+}
+
+impl Event {
+    /// creates an event for every subscriber of the name
+    ///
+    /// # Arguments
+    /// - `name` - the name the subscribers listen to like `"door_opened"`
+    /// - `payload` - the value sent with the event, `()` for none
+    pub fn new<P: Any>(name: &str, payload: P) -> Event {
+        Event {
+            name: name.to_string(),
+            target: None,
+            payload: Box::new(payload),
+        }
+    }
+
+    /// creates an event for the subscriber of the name at one path
+    ///
+    /// # Arguments
+    /// - `target` - the path of the node like `"level/door"`
+    /// - `name` - the name the subscriber listens to
+    /// - `payload` - the value sent with the event, `()` for none
+    pub fn to<P: Any>(target: &str, name: &str, payload: P) -> Event {
+        Event {
+            target: Some(split_path(target).join("/")),
+            ..Self::new(name, payload)
+        }
+    }
+
+    /// get the name of the event
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// get the path of the node the event was sent to, `None` if it was sent to every subscriber
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// get the payload if it has the type `P`
+    pub fn payload<P: Any>(&self) -> Option<&P> {
+        self.payload.downcast_ref()
+    }
+}
+
+/// the function called with the node of a subscription
+type EventCallback = Box<dyn FnMut(&mut dyn Node, &Event, &mut GameContext)>;
+
+/// a node listening to an event name
+pub(crate) struct Subscription {
+    /// the path of the node like `"level/door"`
+    pub(crate) path: String,
+    name: String,
+    pub(crate) callback: EventCallback,
+}
+
+impl Subscription {
+    /// creates a subscription of the node at a path
+    pub(crate) fn new(path: &str, name: &str, callback: EventCallback) -> Subscription {
+        Subscription {
+            path: split_path(path).join("/"),
+            name: name.to_string(),
+            callback,
+        }
+    }
+
+    /// check if the event is sent to this subscription
+    pub(crate) fn accepts(&self, event: &Event) -> bool {
+        event.name == self.name
+            && event
+                .target
+                .as_ref()
+                .is_none_or(|target| *target == self.path)
+    }
+
+    /// check if this is the subscription of the node at a path to a name
+    pub(crate) fn is(&self, path: &str, name: &str) -> bool {
+        self.path == split_path(path).join("/") && self.name == name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_routing() {
+        let door = Subscription::new("/level/door/", "opened", Box::new(|_, _, _| {}));
+        assert!(door.is("level/door", "opened"));
+
+        let broadcast = Event::new("opened", 2.0f32);
+        assert!(door.accepts(&broadcast));
+        assert_eq!(broadcast.payload::<f32>(), Some(&2.0));
+        assert!(broadcast.payload::<i32>().is_none());
+
+        assert!(door.accepts(&Event::to("level/door", "opened", ())));
+        assert!(!door.accepts(&Event::to("level/gate", "opened", ())));
+        assert!(!door.accepts(&Event::new("closed", ())));
+    }
+}
@@ -14,13 +14,37 @@
 //! }
 //! ```
 //!
+//! ## Statistics
+//! besides [`fps`](FPSManager::fps) the manager keeps the frame times of the last second for an fps counter that
+//! doesn't jump around ([`fps_smoothed`](FPSManager::fps_smoothed)) and the slowest and fastest frames
+//! ([`frame_time_range`](FPSManager::frame_time_range)). use [`every`](FPSManager::every) for code that should run
+//! once per interval like updating the counter.
+//!
+//! ```rust,ignore
+//! if context.frame.every(Duration::from_secs(1)) {
+//!     println!("{:.1} fps, {:.2} ms", context.frame.fps_smoothed(), context.frame.frame_time_ms());
+//! }
+//! ```
+//!
+//! ## Fixed timestep
+//! logic that should run at the same rate on every machine (physics, networking) can implement
+//! [`FixedBehavior`](super::node_manager::FixedBehavior) instead. the engine adds up the frame time and runs
+//! the fixed behavior once for every [`fixed_timestep`](FPSManager::get_fixed_timestep) that passed, which is zero
+//! or more times per frame.
 
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 //use egui_gl_glfw::glfw;
 
+/// the number of frames averaged by [`FPSManager::fps_smoothed`] by default
+const DEFAULT_SMOOTHING_FRAMES: usize = 60;
+
 /// Manages the frame per second of the game
 pub struct FPSManager {
-    frame_count: u32,
+    /// the frames since the fps was last updated
+    frames_this_second: u32,
+    /// the frames since the game started
+    frame_count: u64,
     /// the time when the game started
     pub start_time: Instant,
 
@@ -30,6 +54,23 @@ pub struct FPSManager {
     last_update_time: Instant,
     /// the time between the last frame and the current frame
     pub time_delta: Duration,
+
+    /// the time between fixed updates
+    fixed_timestep: Duration,
+    /// the frame time that has not been used by a fixed update yet
+    accumulator: Duration,
+    /// the most fixed updates run in one frame
+    max_substeps: u32,
+    /// the number of fixed updates to run this frame
+    fixed_steps: u32,
+
+    /// the most frames per second, None for no limit
+    frame_cap: Option<u32>,
+
+    /// when each recent frame ended and how long it took, at least the last second and the smoothing frames
+    history: VecDeque<(Instant, Duration)>,
+    /// the number of frames averaged by fps_smoothed
+    smoothing_frames: usize,
 }
 
 impl Default for FPSManager {
@@ -43,25 +84,293 @@ impl FPSManager {
     /// Creates a new FPSManager
     pub fn new() -> Self {
         FPSManager {
+            frames_this_second: 0,
             frame_count: 0,
             fps: 0,
             start_time: Instant::now(),
             last_frame_time: Instant::now(),
             last_update_time: Instant::now(),
             time_delta: Duration::default(),
+            fixed_timestep: Duration::from_secs(1) / 60,
+            accumulator: Duration::ZERO,
+            max_substeps: 5,
+            fixed_steps: 0,
+            frame_cap: None,
+            history: VecDeque::new(),
+            smoothing_frames: DEFAULT_SMOOTHING_FRAMES,
         }
     }
 
     /// Updates the FPSManager should be called once per frame.
     pub fn update(&mut self) {
+        self.update_with_delta(self.time_since_last_frame());
+    }
+
+    /// Updates the FPSManager with a frame time chosen by the caller instead of the time that passed, for engines
+    /// driven by another loop or stepped in tests.
+    ///
+    /// # Arguments
+    /// - `delta` - the time of the frame, it becomes `time_delta`
+    pub fn update_with_delta(&mut self, delta: Duration) {
+        self.record_frame(self.last_frame_time + delta);
+        self.fixed_steps = self.advance_fixed(self.time_delta);
+    }
+
+    /// get the time since the last update
+    pub fn time_since_last_frame(&self) -> Duration {
+        self.last_frame_time.elapsed()
+    }
+
+    /// counts a frame that ended at the given time and updates the statistics
+    fn record_frame(&mut self, now: Instant) {
+        self.frames_this_second += 1;
         self.frame_count += 1;
-        let now = Instant::now();
         self.time_delta = now.duration_since(self.last_frame_time);
         if now.duration_since(self.last_update_time) >= Duration::from_secs(1) {
-            self.fps = self.frame_count;
-            self.frame_count = 0;
+            self.fps = self.frames_this_second;
+            self.frames_this_second = 0;
             self.last_update_time = now;
         }
         self.last_frame_time = now;
+
+        self.history.push_back((now, self.time_delta));
+        while self.history.len() > self.smoothing_frames
+            && self
+                .history
+                .front()
+                .is_some_and(|(end, _)| now.duration_since(*end) > Duration::from_secs(1))
+        {
+            self.history.pop_front();
+        }
+    }
+
+    /// get the number of frames since the game started
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// get the time the last frame took in milliseconds, the same as `time_delta`
+    pub fn frame_time_ms(&self) -> f32 {
+        self.time_delta.as_secs_f32() * 1000.0
+    }
+
+    /// get the frames per second averaged over the last frames, see
+    /// [`set_smoothing_frames`](FPSManager::set_smoothing_frames). unlike `fps` it changes every frame without
+    /// jumping around, 0 before the first frame.
+    pub fn fps_smoothed(&self) -> f32 {
+        let frames = self.history.iter().rev().take(self.smoothing_frames);
+        let count = frames.len();
+        let total: Duration = frames.map(|(_, delta)| *delta).sum();
+        if total.is_zero() {
+            0.0
+        } else {
+            count as f32 / total.as_secs_f32()
+        }
+    }
+
+    /// set how many frames [`fps_smoothed`](FPSManager::fps_smoothed) averages
+    ///
+    /// # Arguments
+    /// - `frames` - the number of frames, at least 1 (60 by default)
+    pub fn set_smoothing_frames(&mut self, frames: usize) {
+        self.smoothing_frames = frames.max(1);
+    }
+
+    /// get the shortest and longest frame time of the last second, useful to spot stutters that the average hides
+    ///
+    /// # Returns
+    /// the shortest and longest frame time, both zero before the first frame
+    pub fn frame_time_range(&self) -> (Duration, Duration) {
+        let last_second = self
+            .history
+            .iter()
+            .filter(|(end, _)| self.last_frame_time.duration_since(*end) <= Duration::from_secs(1));
+        last_second
+            .map(|(_, delta)| *delta)
+            .fold(None, |range, delta| match range {
+                None => Some((delta, delta)),
+                Some((min, max)) => Some((delta.min(min), delta.max(max))),
+            })
+            .unwrap_or_default()
+    }
+
+    /// check if an interval of time passed during the last frame, counted from the start of the game. returns true
+    /// once per interval so code can run every second without its own timer. use a
+    /// [`Timer`](crate::utils::timer::Timer) for intervals that start later or can be paused.
+    ///
+    /// # Arguments
+    /// - `interval` - how often to return true
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// if context.frame.every(Duration::from_millis(500)) {
+    ///     context.window.set_title(&format!("fps: {:.0}", context.frame.fps_smoothed()));
+    /// }
+    /// ```
+    pub fn every(&self, interval: Duration) -> bool {
+        if interval.is_zero() {
+            return true;
+        }
+        let now = self.last_frame_time.duration_since(self.start_time);
+        let previous = now.saturating_sub(self.time_delta);
+        now.as_nanos() / interval.as_nanos() != previous.as_nanos() / interval.as_nanos()
+    }
+
+    /// set the most frames per second the engine renders.
+    ///
+    /// the engine waits out the rest of the frame after presenting it, which keeps the gpu from drawing frames nobody
+    /// sees. this also works without vsync and on monitors with a different refresh rate.
+    ///
+    /// # Arguments
+    /// - `cap` - the frames per second, None (the default) for no limit
+    pub fn set_frame_cap(&mut self, cap: Option<u32>) {
+        self.frame_cap = cap.filter(|cap| *cap > 0);
+    }
+
+    /// get the most frames per second the engine renders, None for no limit
+    pub fn get_frame_cap(&self) -> Option<u32> {
+        self.frame_cap
+    }
+
+    /// waits until the frame cap allows the next frame to start, returns right away without a cap.
+    pub(crate) fn wait_for_frame_cap(&self) {
+        let Some(cap) = self.frame_cap else {
+            return;
+        };
+        let deadline = self.last_frame_time + Duration::from_secs(1) / cap;
+
+        // sleeping can overshoot by about a millisecond so spin the end of the wait
+        let spin = Duration::from_millis(1);
+        let now = Instant::now();
+        if deadline > now + spin {
+            std::thread::sleep(deadline - now - spin);
+        }
+        while Instant::now() < deadline {
+            std::thread::yield_now();
+        }
+    }
+
+    /// adds frame time to the accumulator and takes the whole fixed steps out of it
+    ///
+    /// # Returns
+    /// the number of fixed updates to run, at most the max substeps
+    fn advance_fixed(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep && steps < self.max_substeps {
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+        }
+        if steps == self.max_substeps {
+            // drop the time we could not catch up on so a slow frame doesn't make the next frames slower
+            self.accumulator = self.accumulator.min(self.fixed_timestep);
+        }
+        steps
+    }
+
+    /// get the number of fixed updates that run this frame
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    /// get the time between fixed updates, use this instead of `time_delta` in fixed behaviors
+    pub fn get_fixed_timestep(&self) -> Duration {
+        self.fixed_timestep
+    }
+
+    /// set how often the fixed updates run
+    ///
+    /// # Arguments
+    /// - `rate` - the number of fixed updates per second (60 by default)
+    pub fn set_fixed_rate(&mut self, rate: f32) {
+        self.fixed_timestep = Duration::from_secs_f64(1.0 / rate.max(1.0) as f64);
+    }
+
+    /// set the most fixed updates that run in a single frame.
+    ///
+    /// after a long frame the remaining time is dropped so the game slows down instead of freezing
+    /// while it tries to catch up.
+    ///
+    /// # Arguments
+    /// - `max_substeps` - the limit, at least 1 (5 by default)
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps.max(1);
+    }
+
+    /// get how far the time is between the last and next fixed update from 0 to 1,
+    /// useful to interpolate what fixed behaviors move when drawing
+    pub fn fixed_alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / self.fixed_timestep.as_secs_f32()).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_statistics() {
+        let mut frame = FPSManager::new();
+        let start = frame.start_time;
+        frame.last_frame_time = start;
+        frame.set_smoothing_frames(4);
+        assert_eq!(frame.fps_smoothed(), 0.0);
+
+        // a 40ms stutter between 10ms frames
+        for (time, ms) in [(10, 10), (20, 10), (60, 40), (70, 10), (80, 10)] {
+            frame.record_frame(start + Duration::from_millis(time));
+            assert_eq!(frame.time_delta, Duration::from_millis(ms));
+        }
+        assert_eq!(frame.frame_count(), 5);
+        assert!((frame.frame_time_ms() - 10.0).abs() < 1e-3);
+        // the last 4 frames took 70ms
+        assert!((frame.fps_smoothed() - 4.0 / 0.07).abs() < 1e-2);
+        assert_eq!(
+            frame.frame_time_range(),
+            (Duration::from_millis(10), Duration::from_millis(40))
+        );
+
+        // the stutter is forgotten a second later
+        frame.record_frame(start + Duration::from_millis(1070));
+        frame.record_frame(start + Duration::from_millis(1080));
+        assert_eq!(
+            frame.frame_time_range(),
+            (Duration::from_millis(10), Duration::from_millis(990))
+        );
+        assert!(frame.history.len() >= 4);
+    }
+
+    #[test]
+    fn test_every() {
+        let mut frame = FPSManager::new();
+        let start = frame.start_time;
+        frame.last_frame_time = start;
+
+        let fired: Vec<u64> = (1..=25)
+            .filter(|time| {
+                frame.record_frame(start + Duration::from_millis(time * 100));
+                frame.every(Duration::from_millis(1000))
+            })
+            .collect();
+        assert_eq!(fired, [10, 20]);
+    }
+
+    #[test]
+    fn test_fixed_steps_accumulate_and_clamp() {
+        let mut frame = FPSManager::new();
+        frame.set_fixed_rate(10.0);
+
+        // 0.25s is two steps with 0.05s left over
+        assert_eq!(frame.advance_fixed(Duration::from_millis(250)), 2);
+        assert!((frame.fixed_alpha() - 0.5).abs() < 1e-3);
+        // the leftover adds up to a third step
+        assert_eq!(frame.advance_fixed(Duration::from_millis(50)), 1);
+        assert_eq!(frame.advance_fixed(Duration::from_millis(20)), 0);
+
+        // a hitch only runs the max substeps and drops the rest
+        frame.set_max_substeps(3);
+        assert_eq!(frame.advance_fixed(Duration::from_secs(5)), 3);
+        assert_eq!(frame.advance_fixed(Duration::ZERO), 1);
+        assert_eq!(frame.advance_fixed(Duration::ZERO), 0);
     }
 }
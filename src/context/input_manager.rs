@@ -4,10 +4,21 @@
 //! - `event-driven`: Uses the `glfw` crate to poll events from the window.
 //! - `key-presses`: Tracks which keys are currently pressed and which were just pressed.
 //! - `mouse-buttons`: Tracks which mouse buttons are currently pressed and which were just pressed.
+//! - `mouse-gestures`: Detects drags and double clicks.
+//! - `scrolling`: Adds up the scroll wheel offset of the frame.
+//! - `actions`: Maps named actions to keys, buttons and axes so controls can be rebound, see [`action_map`](super::action_map).
+//! - `gamepad`: Tracks the buttons and axes of every connected gamepad, see [`InputManager::gamepad`].
 //!
 //! ## Usage
 //! Use this within nodes behavior to have dynamic behavior based on user input.
 //!
+//! ## Consuming input
+//! a handler can consume the mouse or keyboard for the rest of the frame with [`InputManager::consume_mouse`] and
+//! [`InputManager::consume_keyboard`] so later nodes don't also react to it. the `is_*` queries (like
+//! [`InputManager::is_mouse_button_just_pressed`]) return false for consumed input while the raw sets are left untouched.
+//! UI nodes consume the mouse while it is over a window and the keyboard while a text field has focus, so clicking a
+//! button doesn't also click into the world.
+//!
 //! ## Example
 //! ```rust,ignore
 //! impl Behavior for CustomNode {
@@ -19,11 +30,160 @@
 //! }
 //! ```
 
+use super::action_map::{ActionMap, Binding, PRESS_THRESHOLD};
 use egui_backend::glfw;
 use egui_gl_glfw as egui_backend;
-use glfw::{GlfwReceiver, Key, MouseButton};
+use glfw::{GamepadAxis, GamepadButton, GlfwReceiver, JoystickId, Key, MouseButton};
 use nalgebra_glm as glm; // Importing the nalgebra_glm crate for mathematical operations
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// a mouse button that is held down
+struct MousePress {
+    /// where the button was pressed
+    position: glm::Vec2,
+    /// if the mouse moved far enough from the press position to count as a drag
+    dragging: bool,
+}
+
+/// detects drags and double clicks from the mouse button presses
+struct MouseGestures {
+    presses: HashMap<MouseButton, MousePress>,
+    /// the time and position of the last click that could start a double click
+    last_click: HashMap<MouseButton, (f64, glm::Vec2)>,
+    double_clicked: HashSet<MouseButton>,
+    /// the max seconds between the clicks of a double click
+    double_click_time: f64,
+    /// how far in pixels the mouse has to move before a press becomes a drag (also the max distance between double clicks)
+    drag_threshold: f32,
+}
+
+impl MouseGestures {
+    fn new() -> MouseGestures {
+        MouseGestures {
+            presses: HashMap::new(),
+            last_click: HashMap::new(),
+            double_clicked: HashSet::new(),
+            double_click_time: 0.3,
+            drag_threshold: 4.0,
+        }
+    }
+
+    fn press(&mut self, button: MouseButton, position: glm::Vec2, time: f64) {
+        self.presses.insert(
+            button,
+            MousePress {
+                position,
+                dragging: false,
+            },
+        );
+
+        match self.last_click.get(&button) {
+            Some((last_time, last_position))
+                if time - last_time <= self.double_click_time
+                    && glm::distance(last_position, &position) <= self.drag_threshold =>
+            {
+                self.double_clicked.insert(button);
+                // a third click starts a new double click instead of completing another one
+                self.last_click.remove(&button);
+            }
+            _ => {
+                self.last_click.insert(button, (time, position));
+            }
+        }
+    }
+
+    fn release(&mut self, button: MouseButton) {
+        self.presses.remove(&button);
+    }
+
+    /// marks the presses that moved past the threshold as drags
+    fn update(&mut self, position: glm::Vec2) {
+        for press in self.presses.values_mut() {
+            if glm::distance(&press.position, &position) >= self.drag_threshold {
+                press.dragging = true;
+            }
+        }
+    }
+}
+
+/// the number of joysticks glfw can track
+const MAX_GAMEPADS: usize = 16;
+
+/// the state of a single gamepad, updated once per frame by the input manager
+#[derive(Clone, Debug, Default)]
+pub struct Gamepad {
+    connected: bool,
+    buttons: HashSet<GamepadButton>,
+    just_pressed: HashSet<GamepadButton>,
+    /// the axes indexed by [`GamepadAxis`]
+    axes: [f32; 6],
+}
+
+impl Gamepad {
+    /// check if the gamepad is connected and has a gamepad mapping
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// check if a button is held
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn button(&self, button: GamepadButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// check if a button was pressed this frame
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn button_just_pressed(&self, button: GamepadButton) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    /// get the value of an axis. sticks range from -1.0 to 1.0 and triggers from -1.0 (released) to 1.0
+    ///
+    /// # Arguments
+    /// - `axis` - the axis to get
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    /// get the buttons that are held
+    pub fn buttons(&self) -> &HashSet<GamepadButton> {
+        &self.buttons
+    }
+
+    /// get the buttons that were pressed this frame
+    pub fn buttons_just_pressed(&self) -> &HashSet<GamepadButton> {
+        &self.just_pressed
+    }
+
+    /// stores the new button states and axes, buttons that go from released to pressed are marked as just pressed
+    fn set_state(
+        &mut self,
+        pressed: impl IntoIterator<Item = (GamepadButton, bool)>,
+        axes: [f32; 6],
+    ) {
+        self.connected = true;
+        self.just_pressed.clear();
+        for (button, down) in pressed {
+            if down {
+                if self.buttons.insert(button) {
+                    self.just_pressed.insert(button);
+                }
+            } else {
+                self.buttons.remove(&button);
+            }
+        }
+        self.axes = axes;
+    }
+
+    /// releases everything when the gamepad is unplugged
+    fn disconnect(&mut self) {
+        *self = Gamepad::default();
+    }
+}
 
 /// Manages the input from the user
 pub struct InputManager {
@@ -43,8 +203,26 @@ pub struct InputManager {
     pub mouse_position: glm::Vec2,
     /// Stores the mouse position in the last frameq
     pub last_mouse_position: glm::Vec2,
-    /// Stores the change in mouse position since the last frame
+    /// Stores the change in mouse position since the last frame, the sum of the cursor moves of this frame
     pub mouse_delta: glm::Vec2,
+    /// Stores the scroll wheel offset accumulated this frame (x is horizontal scrolling)
+    pub scroll_delta: glm::Vec2,
+    /// Stores the buttons of the first gamepad that are currently pressed
+    pub gamepad_buttons: HashSet<GamepadButton>,
+    /// Stores the buttons of the first gamepad that were just pressed this frame
+    pub gamepad_button_just_pressed: HashSet<GamepadButton>,
+    /// the state of every joystick slot indexed by [`JoystickId`]
+    gamepads: [Gamepad; MAX_GAMEPADS],
+    /// the named actions and the inputs bound to them
+    pub actions: ActionMap,
+    /// ignores the next cursor move so the jump when the cursor is locked or unlocked isn't a delta
+    skip_next_motion: bool,
+    /// if the mouse was consumed this frame
+    mouse_consumed: bool,
+    /// if the keyboard was consumed this frame
+    keyboard_consumed: bool,
+    /// drag and double click detection
+    gestures: MouseGestures,
 }
 
 impl InputManager {
@@ -61,6 +239,15 @@ impl InputManager {
             mouse_position: glm::vec2(0.0, 0.0),
             last_mouse_position: glm::vec2(0.0, 0.0),
             mouse_delta: glm::vec2(0.0, 0.0),
+            scroll_delta: glm::vec2(0.0, 0.0),
+            gamepad_buttons: HashSet::new(),
+            gamepad_button_just_pressed: HashSet::new(),
+            gamepads: Default::default(),
+            actions: ActionMap::new(),
+            skip_next_motion: false,
+            mouse_consumed: false,
+            keyboard_consumed: false,
+            gestures: MouseGestures::new(),
         }
     }
 
@@ -68,16 +255,23 @@ impl InputManager {
     pub fn update(&mut self) {
         self.glfw.poll_events();
 
-        self.mouse_delta = self.mouse_position - self.last_mouse_position;
+        self.mouse_delta = glm::vec2(0.0, 0.0);
         self.last_mouse_position = self.mouse_position;
 
+        self.scroll_delta = glm::vec2(0.0, 0.0);
+
+        self.mouse_consumed = false;
+        self.keyboard_consumed = false;
+
+        self.gestures.double_clicked.clear();
+
         self.key_just_pressed.clear(); //clear previous frame's keys
         self.mouse_button_just_pressed.clear(); //clear previous frame's mouse buttons
 
         self.events.clear(); //clear previous frame's events
         self.events = glfw::flush_messages(&self.event_receiver).collect();
 
-        for (_, event) in self.events.iter() {
+        for (time, event) in self.events.iter() {
             match event {
                 glfw::WindowEvent::Key(key, _, action, _) => {
                     if *action == glfw::Action::Press {
@@ -92,16 +286,385 @@ impl InputManager {
                     if *action == glfw::Action::Press {
                         self.mouse_buttons.insert(*button);
                         self.mouse_button_just_pressed.insert(*button); //add the button to the just pressed set
+                        self.gestures.press(*button, self.mouse_position, *time);
                     } else if *action == glfw::Action::Release {
                         self.mouse_buttons.remove(button);
+                        self.gestures.release(*button);
                     }
                 }
                 glfw::WindowEvent::CursorPos(x, y) => {
-                    self.mouse_position = glm::vec2(*x as f32, *y as f32);
+                    let position = glm::vec2(*x as f32, *y as f32);
+                    // while the cursor is locked glfw reports an unbounded virtual position, built from raw
+                    // deltas when raw motion is on, so adding up the moves gives the motion without screen clamping
+                    if !self.skip_next_motion {
+                        self.mouse_delta += position - self.mouse_position;
+                    }
+                    self.skip_next_motion = false;
+                    self.mouse_position = position;
+                    self.gestures.update(self.mouse_position);
                     //println!("Mouse position: {:?}", self.mouse_position);
                 }
+                glfw::WindowEvent::Scroll(x, y) => {
+                    self.scroll_delta += glm::vec2(*x as f32, *y as f32);
+                }
                 _ => {}
             }
         }
+
+        self.update_gamepad();
+    }
+
+    /// ignores the next cursor move when computing [`InputManager::mouse_delta`], used when the cursor mode changes
+    pub(crate) fn skip_next_motion(&mut self) {
+        self.skip_next_motion = true;
+    }
+
+    /// consumes the mouse for the rest of the frame so later handlers ignore its buttons
+    pub fn consume_mouse(&mut self) {
+        self.mouse_consumed = true;
+    }
+
+    /// consumes the keyboard for the rest of the frame so later handlers ignore its keys
+    pub fn consume_keyboard(&mut self) {
+        self.keyboard_consumed = true;
+    }
+
+    /// check if the mouse was consumed this frame
+    pub fn is_mouse_consumed(&self) -> bool {
+        self.mouse_consumed
+    }
+
+    /// check if the keyboard was consumed this frame
+    pub fn is_keyboard_consumed(&self) -> bool {
+        self.keyboard_consumed
+    }
+
+    /// check if a key is held and the keyboard was not consumed
+    ///
+    /// # Arguments
+    /// - `key` - the key to check
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        !self.keyboard_consumed && self.keys.contains(&key)
+    }
+
+    /// check if a key was pressed this frame and the keyboard was not consumed
+    ///
+    /// # Arguments
+    /// - `key` - the key to check
+    pub fn is_key_just_pressed(&self, key: Key) -> bool {
+        !self.keyboard_consumed && self.key_just_pressed.contains(&key)
+    }
+
+    /// check if a mouse button is held and the mouse was not consumed
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        !self.mouse_consumed && self.mouse_buttons.contains(&button)
+    }
+
+    /// check if a mouse button was pressed this frame and the mouse was not consumed
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn is_mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        !self.mouse_consumed && self.mouse_button_just_pressed.contains(&button)
+    }
+
+    /// get how far the scroll wheel moved vertically this frame, positive is away from the user.
+    /// returns 0.0 when the mouse was consumed.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // dolly the camera along its view direction
+    /// let forward = camera.transform.get_forward_vector();
+    /// camera.move_camera(forward * context.input.scroll_y());
+    /// ```
+    pub fn scroll_y(&self) -> f32 {
+        if self.mouse_consumed {
+            0.0
+        } else {
+            self.scroll_delta.y
+        }
+    }
+
+    /// check if a mouse button is held and the mouse moved past the drag threshold since it was pressed
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn is_dragging(&self, button: MouseButton) -> bool {
+        !self.mouse_consumed
+            && self
+                .gestures
+                .presses
+                .get(&button)
+                .is_some_and(|press| press.dragging)
+    }
+
+    /// get where the current drag of a button started
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    ///
+    /// # Returns
+    /// the mouse position when the button was pressed or `None` if the button is not dragging
+    pub fn drag_start(&self, button: MouseButton) -> Option<glm::Vec2> {
+        if !self.is_dragging(button) {
+            return None;
+        }
+        self.gestures
+            .presses
+            .get(&button)
+            .map(|press| press.position)
+    }
+
+    /// get how far the mouse moved since the current drag of a button started
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    ///
+    /// # Returns
+    /// the offset from the drag start or zero if the button is not dragging
+    pub fn drag_delta(&self, button: MouseButton) -> glm::Vec2 {
+        self.drag_start(button)
+            .map(|start| self.mouse_position - start)
+            .unwrap_or_else(glm::Vec2::zeros)
+    }
+
+    /// check if a mouse button was double clicked this frame
+    ///
+    /// # Arguments
+    /// - `button` - the button to check
+    pub fn mouse_double_clicked(&self, button: MouseButton) -> bool {
+        !self.mouse_consumed && self.gestures.double_clicked.contains(&button)
+    }
+
+    /// set the max time between the two clicks of a double click
+    ///
+    /// # Arguments
+    /// - `seconds` - the max time in seconds (0.3 by default)
+    pub fn set_double_click_time(&mut self, seconds: f64) {
+        self.gestures.double_click_time = seconds.max(0.0);
+    }
+
+    /// set how far the mouse has to move while a button is held before it counts as a drag.
+    /// the two clicks of a double click also have to be closer than this.
+    ///
+    /// # Arguments
+    /// - `pixels` - the distance in pixels (4.0 by default)
+    pub fn set_drag_threshold(&mut self, pixels: f32) {
+        self.gestures.drag_threshold = pixels.max(0.0);
+    }
+
+    /// get the value of an axis of the first gamepad. sticks range from -1.0 to 1.0 and triggers from -1.0 (released) to 1.0
+    ///
+    /// # Arguments
+    /// - `axis` - the axis to get
+    pub fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad(JoystickId::Joystick1).axis(axis)
+    }
+
+    /// get the state of a gamepad. a gamepad that is not connected has no buttons held and all axes at 0.
+    ///
+    /// # Arguments
+    /// - `id` - the joystick slot of the gamepad
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let pad = context.input.gamepad(JoystickId::Joystick2);
+    /// if pad.button_just_pressed(GamepadButton::ButtonA) {
+    ///     println!("player 2 jumped");
+    /// }
+    /// let steer = pad.axis(GamepadAxis::AxisLeftX);
+    /// ```
+    pub fn gamepad(&self, id: JoystickId) -> &Gamepad {
+        &self.gamepads[id as usize]
+    }
+
+    /// get the joystick slots that have a gamepad connected
+    pub fn connected_gamepads(&self) -> impl Iterator<Item = JoystickId> + '_ {
+        (0..MAX_GAMEPADS as i32)
+            .filter_map(JoystickId::from_i32)
+            .filter(|id| self.gamepads[*id as usize].connected)
+    }
+
+    /// get how strongly an action is triggered, the strongest of its bindings.
+    ///
+    /// keys and buttons are 0.0 or 1.0, gamepad axes go from 0.0 to 1.0 (or the scale of the binding).
+    /// consumed keyboard or mouse input counts as released.
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    ///
+    /// # Returns
+    /// the value of the action or 0.0 if it has no bindings
+    pub fn action_value(&self, action: &str) -> f32 {
+        self.actions
+            .bindings(action)
+            .iter()
+            .map(|binding| self.binding_value(binding))
+            .fold(0.0, f32::max)
+    }
+
+    /// check if an action is held, its value is at least [`PRESS_THRESHOLD`]
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.action_value(action) >= PRESS_THRESHOLD
+    }
+
+    /// check if a key or button bound to an action was pressed this frame. axis bindings are ignored.
+    ///
+    /// # Arguments
+    /// - `action` - the name of the action
+    pub fn action_just_pressed(&self, action: &str) -> bool {
+        self.actions
+            .bindings(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self.is_key_just_pressed(*key),
+                Binding::MouseButton(button) => self.is_mouse_button_just_pressed(*button),
+                Binding::GamepadButton(button) => self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.button_just_pressed(*button)),
+                Binding::GamepadAxis { .. } => false,
+            })
+    }
+
+    /// get the value of a single binding, reading every connected gamepad for gamepad bindings
+    fn binding_value(&self, binding: &Binding) -> f32 {
+        let pressed = |down: bool| if down { 1.0 } else { 0.0 };
+        match binding {
+            Binding::Key(key) => pressed(self.is_key_pressed(*key)),
+            Binding::MouseButton(button) => pressed(self.is_mouse_button_pressed(*button)),
+            Binding::GamepadButton(button) => {
+                pressed(self.gamepads.iter().any(|gamepad| gamepad.button(*button)))
+            }
+            Binding::GamepadAxis { axis, scale } => self
+                .gamepads
+                .iter()
+                .filter(|gamepad| gamepad.connected)
+                .map(|gamepad| {
+                    let mut value = gamepad.axis(*axis);
+                    if matches!(
+                        axis,
+                        GamepadAxis::AxisLeftTrigger | GamepadAxis::AxisRightTrigger
+                    ) {
+                        // triggers rest at -1.0
+                        value = (value + 1.0) * 0.5;
+                    }
+                    (value * scale).max(0.0)
+                })
+                .fold(0.0, f32::max),
+        }
+    }
+
+    /// reads the state of every gamepad. unplugged gamepads are released instead of read
+    fn update_gamepad(&mut self) {
+        for (i, gamepad) in self.gamepads.iter_mut().enumerate() {
+            let Some(id) = JoystickId::from_i32(i as i32) else {
+                continue;
+            };
+
+            let state = self.glfw.get_joystick(id).get_gamepad_state();
+            let Some(state) = state else {
+                // no gamepad connected (or no mapping for it)
+                if gamepad.connected {
+                    log::info!("gamepad {} disconnected", i + 1);
+                    gamepad.disconnect();
+                }
+                continue;
+            };
+            if !gamepad.connected {
+                log::info!("gamepad {} connected", i + 1);
+            }
+
+            let buttons = (0..15).filter_map(GamepadButton::from_i32).map(|button| {
+                (
+                    button,
+                    state.get_button_state(button) == glfw::Action::Press,
+                )
+            });
+            let mut axes = [0.0; 6];
+            for (i, value) in axes.iter_mut().enumerate() {
+                if let Some(axis) = GamepadAxis::from_i32(i as i32) {
+                    *value = state.get_axis(axis);
+                }
+            }
+            gamepad.set_state(buttons, axes);
+        }
+
+        let first = &self.gamepads[JoystickId::Joystick1 as usize];
+        self.gamepad_buttons.clone_from(&first.buttons);
+        self.gamepad_button_just_pressed
+            .clone_from(&first.just_pressed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_click() {
+        let mut gestures = MouseGestures::new();
+        let position = glm::vec2(10.0, 10.0);
+
+        gestures.press(MouseButton::Button1, position, 1.0);
+        gestures.release(MouseButton::Button1);
+        assert!(gestures.double_clicked.is_empty());
+
+        gestures.press(MouseButton::Button1, position, 1.2);
+        assert!(gestures.double_clicked.contains(&MouseButton::Button1));
+
+        // too slow
+        gestures.double_clicked.clear();
+        gestures.press(MouseButton::Button1, position, 2.0);
+        gestures.press(MouseButton::Button1, position, 2.5);
+        assert!(gestures.double_clicked.is_empty());
+    }
+
+    #[test]
+    fn test_gamepad_just_pressed_and_disconnect() {
+        let mut gamepad = Gamepad::default();
+        let mut axes = [0.0; 6];
+        axes[GamepadAxis::AxisRightX as usize] = 0.5;
+
+        gamepad.set_state([(GamepadButton::ButtonA, true)], axes);
+        assert!(gamepad.is_connected());
+        assert!(gamepad.button(GamepadButton::ButtonA));
+        assert!(gamepad.button_just_pressed(GamepadButton::ButtonA));
+        assert_eq!(gamepad.axis(GamepadAxis::AxisRightX), 0.5);
+
+        // still held, no longer just pressed
+        gamepad.set_state([(GamepadButton::ButtonA, true)], axes);
+        assert!(gamepad.button(GamepadButton::ButtonA));
+        assert!(!gamepad.button_just_pressed(GamepadButton::ButtonA));
+
+        gamepad.disconnect();
+        assert!(!gamepad.is_connected());
+        assert!(!gamepad.button(GamepadButton::ButtonA));
+        assert_eq!(gamepad.axis(GamepadAxis::AxisRightX), 0.0);
+    }
+
+    #[test]
+    fn test_drag() {
+        let mut gestures = MouseGestures::new();
+        gestures.press(MouseButton::Button1, glm::vec2(0.0, 0.0), 0.0);
+
+        gestures.update(glm::vec2(2.0, 0.0));
+        assert!(!gestures.presses[&MouseButton::Button1].dragging);
+
+        gestures.update(glm::vec2(10.0, 0.0));
+        assert!(gestures.presses[&MouseButton::Button1].dragging);
+
+        // moving back keeps the drag going
+        gestures.update(glm::vec2(0.0, 0.0));
+        assert!(gestures.presses[&MouseButton::Button1].dragging);
+
+        gestures.release(MouseButton::Button1);
+        assert!(gestures.presses.is_empty());
     }
 }
@@ -2,27 +2,58 @@
 //!
 //! This includes the window, the nodes, the frame manager, the input manager, and the shadow distance.
 
+use asset_loader::AssetLoader;
 use fps_manager::*;
 use input_manager::*;
 use node_manager::NodeManager;
 
+pub mod action_map;
+pub mod asset_loader;
+pub mod events;
 pub mod fps_manager;
 pub mod input_manager;
 pub mod node_manager;
+pub mod scene_file;
 
 use egui_backend::glfw;
 use egui_gl_glfw as egui_backend;
 use glfw::GlfwReceiver;
 
-use crate::{components::NodeTransform, nodes::Camera3D};
+use crate::audio::Audio;
+use crate::renderer::debug_draw::DebugDraw;
+use crate::renderer::shader::Shader;
+use crate::renderer::skybox::Skybox;
+use crate::renderer::viewport::{Viewport, ViewportRect};
+use crate::utils::color::Color;
+use crate::{
+    components::NodeTransform,
+    nodes::{Camera2D, Camera3D, Empty},
+};
+use nalgebra_glm as glm;
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 
-use node_manager::Node;
+use events::{Event, Subscription};
+use node_manager::{split_path, Node};
+use std::any::Any;
 
 // use fps_manager::FPSManager;
 // use input_manager::InputManager;
 // use node_manager::NodeManager;
 
+/// How the window is shown on the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WindowMode {
+    /// a normal window with a title bar (the default)
+    #[default]
+    Windowed,
+    /// exclusive fullscreen on the primary monitor using its current video mode
+    Fullscreen,
+    /// a window without a title bar covering the primary monitor, switches faster than fullscreen and
+    /// keeps other windows usable
+    BorderlessFullscreen,
+}
+
 /// The main game context, containing all the necessary information for the game to run.
 /// This includes the window, the nodes, the frame manager, the input manager, and the shadow distance.
 pub struct GameContext {
@@ -34,10 +65,73 @@ pub struct GameContext {
     pub frame: FPSManager,
     /// The input manager of the game.
     pub input: InputManager,
+    /// The asset loader that finishes async loads on the main thread.
+    pub assets: AssetLoader,
     /// The shadow distance of the game.
     pub shadow_distance: f32,
     /// path to the active camera
     pub active_camera_path: Vec<String>,
+    /// immediate mode debug lines drawn after the scene
+    pub debug: DebugDraw,
+    /// the audio output and the listener
+    pub audio: Audio,
+    /// the depth texture of the main pass, set by the engine after the models are drawn
+    pub(crate) depth_texture: Option<u32>,
+    /// the sky and ground ambient colors, `None` uses the flat ambient of the shader
+    hemisphere_ambient: Option<(Color, Color)>,
+    /// the environment drawn behind the scene
+    pub(crate) skybox: Option<Skybox>,
+    /// the camera the sprites are drawn through
+    pub(crate) camera_2d: Option<Camera2D>,
+    /// the cameras drawn into parts of the window, empty to draw the active camera full screen
+    pub(crate) viewports: Vec<Viewport>,
+    /// how the window is shown
+    window_mode: WindowMode,
+    /// the position and size of the window to go back to when leaving fullscreen
+    windowed_rect: (i32, i32, i32, i32),
+    /// the path of the screenshot to save at the end of the frame and if the UI overlay is included
+    pub(crate) pending_screenshot: Option<(PathBuf, bool)>,
+    /// the anti-aliasing samples of the window, set by the engine
+    pub(crate) msaa_samples: u32,
+    /// nodes to add or remove at the end of the frame
+    node_commands: Vec<NodeCommand>,
+    /// the events to deliver after the behaviors
+    events: Vec<Event>,
+    /// the nodes listening to events
+    subscriptions: Vec<Subscription>,
+}
+
+/// called with a node taken out of the tree by [`GameContext::take`], `None` if there was no node at the path
+type TakeCallback = Box<dyn FnOnce(Option<Box<dyn Node>>, &mut GameContext)>;
+
+/// called with the result of a move made with [`GameContext::reparent_then`]
+type ReparentCallback = Box<dyn FnOnce(Result<(), node_manager::NodeError>, &mut GameContext)>;
+
+/// a change to the node tree that waits until the end of the frame, see [`GameContext::spawn`].
+enum NodeCommand {
+    Spawn {
+        path: String,
+        node: Box<dyn Node>,
+    },
+    Despawn(String),
+    Take {
+        path: String,
+        callback: TakeCallback,
+    },
+    Reparent {
+        from: String,
+        to: String,
+        callback: Option<ReparentCallback>,
+    },
+    ClearScene,
+    ReplaceScene(Box<NodeManager>),
+}
+
+impl Drop for GameContext {
+    fn drop(&mut self) {
+        // the window and its OpenGL context go first, the gpu objects dropped after it are freed with the context
+        crate::renderer::gl_object::set_context_alive(false);
+    }
 }
 
 impl GameContext {
@@ -55,26 +149,798 @@ impl GameContext {
         glfw: glfw::Glfw,
         window: glfw::PWindow,
     ) -> GameContext {
+        let (x, y) = window.get_pos();
+        let (width, height) = window.get_size();
         GameContext {
             window,
 
             nodes: NodeManager::new(),
             frame: FPSManager::new(),
             input: InputManager::new(events, glfw),
+            assets: AssetLoader::new(),
             shadow_distance: 100.0,
             active_camera_path: Vec::new(),
+            debug: DebugDraw::new(),
+            audio: Audio::new(),
+            depth_texture: None,
+            hemisphere_ambient: None,
+            skybox: None,
+            camera_2d: None,
+            viewports: Vec::new(),
+            window_mode: WindowMode::Windowed,
+            windowed_rect: (x, y, width, height),
+            pending_screenshot: None,
+            msaa_samples: 0,
+            node_commands: Vec::new(),
+            events: Vec::new(),
+            subscriptions: Vec::new(),
         }
     }
 
+    /// changes how the window is shown.
+    ///
+    /// the position and size of the window are restored when going back to [`WindowMode::Windowed`].
+    /// the fullscreen modes use the primary monitor, the mode is not changed when there is none.
+    ///
+    /// # Arguments
+    /// - `mode` - the new window mode
+    pub fn set_window_mode(&mut self, mode: WindowMode) {
+        if mode == self.window_mode {
+            return;
+        }
+        if self.window_mode == WindowMode::Windowed {
+            let (x, y) = self.window.get_pos();
+            let (width, height) = self.window.get_size();
+            self.windowed_rect = (x, y, width, height);
+        }
+
+        if mode == WindowMode::Windowed {
+            let (x, y, width, height) = self.windowed_rect;
+            self.window.set_decorated(true);
+            self.window.set_monitor(
+                glfw::WindowMode::Windowed,
+                x,
+                y,
+                width as u32,
+                height as u32,
+                None,
+            );
+        } else {
+            let window = &mut self.window;
+            let mut glfw = window.glfw.clone();
+            let found = glfw.with_primary_monitor(|_, monitor| {
+                let Some(monitor) = monitor else {
+                    return false;
+                };
+                let Some(video_mode) = monitor.get_video_mode() else {
+                    return false;
+                };
+                if mode == WindowMode::Fullscreen {
+                    window.set_monitor(
+                        glfw::WindowMode::FullScreen(monitor),
+                        0,
+                        0,
+                        video_mode.width,
+                        video_mode.height,
+                        Some(video_mode.refresh_rate),
+                    );
+                } else {
+                    let (x, y) = monitor.get_pos();
+                    window.set_decorated(false);
+                    window.set_monitor(
+                        glfw::WindowMode::Windowed,
+                        x,
+                        y,
+                        video_mode.width,
+                        video_mode.height,
+                        None,
+                    );
+                }
+                true
+            });
+            if !found {
+                log::warn!("no monitor found, the window mode was not changed");
+                return;
+            }
+        }
+        self.window_mode = mode;
+    }
+
+    /// get how the window is shown
+    pub fn get_window_mode(&self) -> WindowMode {
+        self.window_mode
+    }
+
+    /// get the anti-aliasing samples per pixel that are used, 0 when anti-aliasing is off.
+    ///
+    /// this can be lower than what was asked for in [`Engine::init_with_samples`](crate::Engine::init_with_samples)
+    /// when the gpu does not support that many.
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa_samples
+    }
+
     /// lock the cursor inside the window.
     ///
     /// # Arguments
     /// - `lock` - Whether to lock the cursor or not.
     pub fn lock_cursor(&mut self, lock: bool) {
+        if lock == self.cursor_locked() {
+            return;
+        }
         if lock {
             self.window.set_cursor_mode(glfw::CursorMode::Disabled);
         } else {
             self.window.set_cursor_mode(glfw::CursorMode::Normal);
+            self.window.set_raw_mouse_motion(false);
+        }
+        // the cursor jumps when the mode changes, don't count that as mouse movement
+        self.input.skip_next_motion();
+    }
+
+    /// lock the cursor inside the window and read the mouse without the acceleration of the os.
+    ///
+    /// raw motion makes aiming consistent in first person games. it is only used while the cursor is locked and falls
+    /// back to [`lock_cursor`](GameContext::lock_cursor) when the platform does not support it.
+    ///
+    /// # Arguments
+    /// - `lock` - Whether to lock the cursor or not.
+    pub fn lock_cursor_raw(&mut self, lock: bool) {
+        self.lock_cursor(lock);
+        if lock && self.window.glfw.supports_raw_motion() {
+            self.window.set_raw_mouse_motion(true);
+        }
+    }
+
+    /// check if the cursor is locked inside the window
+    pub fn cursor_locked(&self) -> bool {
+        self.window.get_cursor_mode() == glfw::CursorMode::Disabled
+    }
+
+    /// check if the locked cursor reads raw mouse motion, see [`lock_cursor_raw`](GameContext::lock_cursor_raw)
+    pub fn uses_raw_mouse_motion(&self) -> bool {
+        self.cursor_locked() && self.window.uses_raw_mouse_motion()
+    }
+
+    /// saves the next rendered frame as a png.
+    ///
+    /// the frame is captured at the end of the frame, just before it is shown. if the file can't be written the error
+    /// is printed and the game keeps running.
+    ///
+    /// # Arguments
+    /// - `path` - where the png is written
+    /// - `include_ui` - if the overlay UI is in the screenshot, background UIs are always included
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// if context.input.is_key_just_pressed(Key::F12) {
+    ///     context.save_screenshot("screenshot.png", false);
+    /// }
+    /// ```
+    pub fn save_screenshot(&mut self, path: impl AsRef<Path>, include_ui: bool) {
+        self.pending_screenshot = Some((path.as_ref().to_path_buf(), include_ui));
+    }
+
+    /// captures and writes the pending screenshot. called by the engine after the UI layer it was asked for is drawn
+    pub(crate) fn take_screenshot(&mut self, with_ui: bool) {
+        match self.pending_screenshot.take() {
+            Some((path, include_ui)) if include_ui == with_ui => {
+                if let Err(error) = crate::renderer::Renderer::capture_frame().save(&path) {
+                    log::error!("failed to save screenshot to {}: {}", path.display(), error);
+                }
+            }
+            pending => self.pending_screenshot = pending,
+        }
+    }
+
+    /// get the depth of the main pass as a texture that can be sampled (GL_TEXTURE_2D, depth24 stencil8).
+    ///
+    /// only available when enabled with [`Engine::set_depth_texture`](crate::Engine::set_depth_texture) or when post process
+    /// passes are added. the texture holds the current frame after the models are drawn (in post process passes and UI)
+    /// and the previous frame during behavior.
+    ///
+    /// # Returns
+    /// the gl texture id or `None` if the depth is not captured
+    pub fn depth_texture(&self) -> Option<u32> {
+        self.depth_texture
+    }
+
+    /// lights the scene with a hemisphere ambient instead of the flat ambient.
+    ///
+    /// surfaces facing up get the sky color, surfaces facing down get the ground color, and everything in between
+    /// blends between the two. pair this with a directional light as the sun for natural outdoor lighting.
+    ///
+    /// # Arguments
+    /// - `sky_color` - the ambient light coming from above
+    /// - `ground_color` - the ambient light bounced up from the ground
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// context.set_hemisphere_ambient(
+    ///     Color::from_normalized(0.35, 0.45, 0.6, 1.0),
+    ///     Color::from_normalized(0.15, 0.12, 0.1, 1.0),
+    /// );
+    /// ```
+    pub fn set_hemisphere_ambient(&mut self, sky_color: Color, ground_color: Color) {
+        self.hemisphere_ambient = Some((sky_color, ground_color));
+    }
+
+    /// goes back to the flat ambient of the shader
+    pub fn clear_hemisphere_ambient(&mut self) {
+        self.hemisphere_ambient = None;
+    }
+
+    /// get the sky and ground ambient colors if the hemisphere ambient is used
+    pub fn get_hemisphere_ambient(&self) -> Option<(Color, Color)> {
+        self.hemisphere_ambient
+    }
+
+    /// sets the environment drawn behind the scene, replacing the previous one.
+    ///
+    /// the skybox is not part of the node tree and stays when the scene is cleared.
+    ///
+    /// # Arguments
+    /// - `skybox` - the skybox to draw
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let skybox = Skybox::from_faces([
+    ///     "res/sky/right.png",
+    ///     "res/sky/left.png",
+    ///     "res/sky/top.png",
+    ///     "res/sky/bottom.png",
+    ///     "res/sky/front.png",
+    ///     "res/sky/back.png",
+    /// ])
+    /// .unwrap();
+    /// context.set_skybox(skybox);
+    /// ```
+    pub fn set_skybox(&mut self, skybox: Skybox) {
+        self.skybox = Some(skybox);
+    }
+
+    /// removes the skybox so the clear color is shown behind the scene again
+    ///
+    /// # Returns
+    /// the removed skybox if there was one
+    pub fn remove_skybox(&mut self) -> Option<Skybox> {
+        self.skybox.take()
+    }
+
+    /// get the skybox, for example to sample its [`cubemap`](Skybox::cubemap)
+    pub fn skybox(&self) -> Option<&Skybox> {
+        self.skybox.as_ref()
+    }
+
+    /// get the skybox mutably to change its intensity
+    pub fn skybox_mut(&mut self) -> Option<&mut Skybox> {
+        self.skybox.as_mut()
+    }
+
+    /// sets the camera the [`Sprite`](crate::nodes::Sprite) nodes are drawn through, replacing the previous one.
+    ///
+    /// the sprites are drawn on top of the 3D scene. like the skybox the camera is not part of the node tree.
+    ///
+    /// # Arguments
+    /// - `camera` - the 2D camera
+    pub fn set_camera_2d(&mut self, camera: Camera2D) {
+        self.camera_2d = Some(camera);
+    }
+
+    /// removes the 2D camera so no sprites are drawn
+    ///
+    /// # Returns
+    /// the removed camera if there was one
+    pub fn remove_camera_2d(&mut self) -> Option<Camera2D> {
+        self.camera_2d.take()
+    }
+
+    /// get the camera the sprites are drawn through
+    pub fn camera_2d(&self) -> Option<&Camera2D> {
+        self.camera_2d.as_ref()
+    }
+
+    /// get the 2D camera mutably to move or zoom it
+    pub fn camera_2d_mut(&mut self) -> Option<&mut Camera2D> {
+        self.camera_2d.as_mut()
+    }
+
+    /// draws a camera into a part of the window, see the [viewport](crate::renderer::viewport) module.
+    ///
+    /// while there are viewports the active camera is only drawn if it has one too. viewports are drawn in the order
+    /// they were added so later ones cover earlier ones, adding a camera that already has a viewport moves it.
+    ///
+    /// the camera is drawn with the aspect ratio of its viewport and keeps its own aspect ratio outside of it, the
+    /// directional light shadows are fitted to each viewport camera before it is drawn.
+    ///
+    /// # Arguments
+    /// - `camera_path` - the path of the camera like `"player_1/camera"`
+    /// - `rect` - the part of the window in fractions of its size
+    pub fn add_viewport(&mut self, camera_path: &str, rect: ViewportRect) {
+        let camera_path = split_path(camera_path).join("/");
+        self.viewports
+            .retain(|viewport| viewport.camera_path() != camera_path);
+        self.viewports.push(Viewport::new(&camera_path, rect));
+    }
+
+    /// stops drawing a camera into its viewport
+    ///
+    /// # Arguments
+    /// - `camera_path` - the path of the camera
+    ///
+    /// # Returns
+    /// the removed viewport if the camera had one
+    pub fn remove_viewport(&mut self, camera_path: &str) -> Option<Viewport> {
+        let camera_path = split_path(camera_path).join("/");
+        let index = self
+            .viewports
+            .iter()
+            .position(|viewport| viewport.camera_path() == camera_path)?;
+        Some(self.viewports.remove(index))
+    }
+
+    /// removes every viewport so the active camera is drawn full screen again
+    pub fn clear_viewports(&mut self) {
+        self.viewports.clear();
+    }
+
+    /// get the viewports in the order they are drawn
+    pub fn viewports(&self) -> &[Viewport] {
+        &self.viewports
+    }
+
+    /// get a viewport to change its rectangle
+    ///
+    /// # Arguments
+    /// - `camera_path` - the path of the camera
+    pub fn viewport_mut(&mut self, camera_path: &str) -> Option<&mut Viewport> {
+        let camera_path = split_path(camera_path).join("/");
+        self.viewports
+            .iter_mut()
+            .find(|viewport| viewport.camera_path() == camera_path)
+    }
+
+    /// uploads the ambient lighting to the shader
+    pub(crate) fn bind_ambient_uniforms(&self, shader: &mut Shader) {
+        shader.bind();
+        shader.set_uniform("u_hemisphereAmbient", self.hemisphere_ambient.is_some());
+        if let Some((sky, ground)) = self.hemisphere_ambient {
+            shader.set_uniform("u_ambientSky", glm::vec3(sky.r, sky.g, sky.b));
+            shader.set_uniform("u_ambientGround", glm::vec3(ground.r, ground.g, ground.b));
+        }
+    }
+
+    /// removes every node and shader from the scene and resets the active camera, shader and camera path at the end of
+    /// the frame.
+    ///
+    /// use this between levels to tear down the current scene before adding the next one, the clear waits like
+    /// [`despawn`](GameContext::despawn) and nodes spawned after this call are added to the empty scene.
+    /// the next camera has to be made active with [`set_main_camera`](GameContext::set_main_camera).
+    pub fn clear_scene(&mut self) {
+        self.node_commands.push(NodeCommand::ClearScene);
+    }
+
+    /// removes every node and shader right away, see [`clear_scene`](GameContext::clear_scene)
+    fn clear_scene_now(&mut self) {
+        self.nodes.clear();
+        self.active_camera_path.clear();
+        self.debug.clear();
+    }
+
+    /// removes a nested node from the scene right away, see [`NodeManager::remove`]. used by
+    /// [`despawn`](GameContext::despawn) at the end of the frame.
+    ///
+    /// if the active camera was the node or one of its children the camera path is cleared and the root's active camera
+    /// (if there is still one) becomes the main camera. without a camera nothing is drawn until one is set with
+    /// [`set_main_camera`](GameContext::set_main_camera).
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the node separated by `/` like `"enemies/goblin"`
+    ///
+    /// # Returns
+    /// the removed node, or `None` if there is no node at the path
+    pub(crate) fn remove_node(&mut self, path: &str) -> Option<Box<dyn Node>> {
+        let node = self.nodes.remove(path)?;
+
+        let names = node_manager::split_path(path);
+        if self
+            .active_camera_path
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .starts_with(&names)
+        {
+            self.active_camera_path.clear();
+            let active_camera = self.nodes.active_camera.clone();
+            if let Some(camera) = self.nodes.get::<Camera3D>(&active_camera) {
+                let camera = camera.as_ptr();
+                self.set_main_camera(camera);
+            }
+        }
+        Some(node)
+    }
+
+    /// moves a nested node under another node right away without changing where it is in the world, see
+    /// [`NodeManager::reparent`]. used by [`reparent`](GameContext::reparent) at the end of the frame.
+    ///
+    /// the active camera path follows the node if the camera was moved.
+    ///
+    /// # Arguments
+    /// - `from` - the path of the node to move like `"items/sword"`
+    /// - `to` - the path of the new parent like `"player/hand"`, an empty path moves the node to the root
+    pub(crate) fn reparent_node(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<(), node_manager::NodeError> {
+        self.nodes.reparent(from, to)?;
+
+        let from_names = node_manager::split_path(from);
+        let camera_path: Vec<&str> = self.active_camera_path.iter().map(String::as_str).collect();
+        if camera_path.starts_with(&from_names) {
+            let mut path: Vec<String> = node_manager::split_path(to)
+                .into_iter()
+                .map(String::from)
+                .collect();
+            path.extend(
+                camera_path[from_names.len() - 1..]
+                    .iter()
+                    .map(|name| name.to_string()),
+            );
+            self.active_camera_path = path;
+        }
+        Ok(())
+    }
+
+    /// adds a node at the end of the frame.
+    ///
+    /// the engine holds pointers into the node tree while it runs behaviors and renders so nodes can't be added from
+    /// a behavior or callback directly. spawned nodes are added once every pass of the frame is done, so there is one
+    /// frame of latency: the node first updates and renders the frame after it was spawned. its ready method runs
+    /// when it is added.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the new node like `"bullets/bullet_12"`, the parent has to exist when the node is added
+    /// - `node` - the node to add, it is not added (with an error in the log) if the path already has a node
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// if context.input.is_mouse_button_just_pressed(MouseButton::Button1) {
+    ///     let muzzle = context.nodes.world_transform("player/gun/muzzle").unwrap();
+    ///     let mut bullet = bullet_model.clone();
+    ///     *bullet.get_transform() = muzzle;
+    ///     context.spawn(&format!("bullets/{}", id), bullet);
+    /// }
+    /// ```
+    pub fn spawn<T: Node + 'static>(&mut self, path: &str, node: T) {
+        self.node_commands.push(NodeCommand::Spawn {
+            path: path.to_string(),
+            node: Box::new(node),
+        });
+    }
+
+    /// adds a boxed node at the end of the frame like [`spawn`](GameContext::spawn), for nodes that were taken out of
+    /// the tree with [`take`](GameContext::take).
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node like `"player/back/sword"`, the parent has to exist when the node is added
+    /// - `node` - the node to add, it is not added (with an error in the log) if the path already has a node
+    pub fn spawn_boxed(&mut self, path: &str, node: Box<dyn Node>) {
+        self.node_commands.push(NodeCommand::Spawn {
+            path: path.to_string(),
+            node,
+        });
+    }
+
+    /// removes a node and its children at the end of the frame.
+    ///
+    /// the node keeps updating and rendering until the frame is done, see [`spawn`](GameContext::spawn). if the active
+    /// camera was the node or one of its children the root's active camera (if there is still one) becomes the main
+    /// camera, without a camera nothing is drawn until one is set with [`set_main_camera`](GameContext::set_main_camera).
+    /// the node is dropped, use [`take`](GameContext::take) to keep it.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node to remove like `"enemies/goblin"`
+    pub fn despawn(&mut self, path: &str) {
+        self.node_commands
+            .push(NodeCommand::Despawn(path.to_string()));
+    }
+
+    /// removes a node and its children at the end of the frame and hands it to a callback instead of dropping it.
+    ///
+    /// the node is removed like with [`despawn`](GameContext::despawn). the callback runs during the flush so it can
+    /// keep the node, spawn it somewhere else or queue more changes.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node to remove like `"inventory/sword"`
+    /// - `callback` - called with the removed node and the context, the node is `None` if there was no node at the path
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // the sword keeps its state while it is out of the scene
+    /// let stash = Rc::clone(&self.stash);
+    /// context.take("player/hand/sword", move |sword, _context| {
+    ///     *stash.borrow_mut() = sword;
+    /// });
+    ///
+    /// // later
+    /// if let Some(sword) = self.stash.borrow_mut().take() {
+    ///     context.spawn_boxed("player/back/sword", sword);
+    /// }
+    /// ```
+    pub fn take<F>(&mut self, path: &str, callback: F)
+    where
+        F: FnOnce(Option<Box<dyn Node>>, &mut GameContext) + 'static,
+    {
+        self.node_commands.push(NodeCommand::Take {
+            path: path.to_string(),
+            callback: Box::new(callback),
+        });
+    }
+
+    /// moves a node (with its children) under another node at the end of the frame without changing where it is in
+    /// the world.
+    ///
+    /// the move waits like [`spawn`](GameContext::spawn), the node keeps its name and id and the active camera path
+    /// follows the node if the camera was moved. the move fails with an error in the log when a path is missing, the new
+    /// parent already has a child with the same name or the new parent is the node or one of its children.
+    ///
+    /// # Arguments
+    /// - `from` - the path of the node to move like `"items/sword"`
+    /// - `to` - the path of the new parent like `"player/hand"`, an empty path moves the node to the root
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // pick up the sword, it stays where it is until the hand moves
+    /// context.reparent("items/sword", "player/hand");
+    /// ```
+    pub fn reparent(&mut self, from: &str, to: &str) {
+        self.node_commands.push(NodeCommand::Reparent {
+            from: from.to_string(),
+            to: to.to_string(),
+            callback: None,
+        });
+    }
+
+    /// moves a node under another node at the end of the frame like [`reparent`](GameContext::reparent) and calls a
+    /// callback with the result instead of logging failures.
+    ///
+    /// # Arguments
+    /// - `from` - the path of the node to move like `"items/sword"`
+    /// - `to` - the path of the new parent like `"player/hand"`, an empty path moves the node to the root
+    /// - `callback` - called with the result of the move and the context
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// context.reparent_then("items/sword", "player/hand", |result, context| {
+    ///     if result.is_err() {
+    ///         context.emit("pickup_failed", ());
+    ///     }
+    /// });
+    /// ```
+    pub fn reparent_then<F>(&mut self, from: &str, to: &str, callback: F)
+    where
+        F: FnOnce(Result<(), node_manager::NodeError>, &mut GameContext) + 'static,
+    {
+        self.node_commands.push(NodeCommand::Reparent {
+            from: from.to_string(),
+            to: to.to_string(),
+            callback: Some(Box::new(callback)),
+        });
+    }
+
+    /// subscribes the node at a path to an event, see the [`events`] module.
+    ///
+    /// the callback is called with the node when an event with the name is sent to every subscriber or to the path.
+    /// the subscription lasts until [`off`](GameContext::off), events are skipped while there is no node at the path or it is inactive.
+    /// subscribing the same node to the same name again adds another callback.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node like `"level/door"`, it does not have to exist yet
+    /// - `name` - the name of the event like `"door_opened"`
+    /// - `callback` - called with the node, the event and the context. nodes of another type than `T` are skipped
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// context.on::<PointLight, _>("lamp", "switch", |lamp, event, _context| {
+    ///     if let Some(on) = event.payload::<bool>() {
+    ///         lamp.set_intensity(if *on { 10.0 } else { 0.0 });
+    ///     }
+    /// });
+    /// ```
+    pub fn on<T, F>(&mut self, path: &str, name: &str, mut callback: F)
+    where
+        T: Node + 'static,
+        F: FnMut(&mut T, &Event, &mut GameContext) + 'static,
+    {
+        self.subscriptions.push(Subscription::new(
+            path,
+            name,
+            Box::new(move |node, event, context| {
+                if let Some(node) = node.as_any_mut().downcast_mut::<T>() {
+                    callback(node, event, context);
+                }
+            }),
+        ));
+    }
+
+    /// ends the subscriptions of the node at a path to an event name
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node like `"level/door"`
+    /// - `name` - the name of the event
+    pub fn off(&mut self, path: &str, name: &str) {
+        self.subscriptions
+            .retain(|subscription| !subscription.is(path, name));
+    }
+
+    /// sends an event to every node subscribed to the name, delivered after the behaviors of the frame
+    ///
+    /// # Arguments
+    /// - `name` - the name of the event like `"door_opened"`
+    /// - `payload` - the value sent with the event, `()` for none
+    pub fn emit<P: Any>(&mut self, name: &str, payload: P) {
+        self.events.push(Event::new(name, payload));
+    }
+
+    /// sends an event to the node at a path if it is subscribed to the name, delivered after the behaviors of the frame
+    ///
+    /// # Arguments
+    /// - `path` - the path of the node like `"level/door"`
+    /// - `name` - the name of the event
+    /// - `payload` - the value sent with the event, `()` for none
+    pub fn emit_to<P: Any>(&mut self, path: &str, name: &str, payload: P) {
+        self.events.push(Event::to(path, name, payload));
+    }
+
+    /// delivers the events sent since the last flush to their subscribers, called by the engine after the behaviors.
+    pub(crate) fn flush_events(&mut self) {
+        let events = std::mem::take(&mut self.events);
+        if events.is_empty() {
+            return;
+        }
+
+        // the callbacks get the context, subscriptions made while delivering are added after
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+        for event in &events {
+            for subscription in subscriptions.iter_mut() {
+                if !subscription.accepts(event) {
+                    continue;
+                }
+                let Some(slot) = self.nodes.get_active_at(&subscription.path) else {
+                    continue;
+                };
+                // the node leaves the tree while its callback runs so the callback can use the whole context, an
+                // empty node holds its place (and its name, id and update order) until it is put back
+                let placeholder: Box<dyn Node> = Box::new(Empty::new());
+                let placeholder_ptr = &*placeholder as *const dyn Node as *const ();
+                let mut node = std::mem::replace(slot, placeholder);
+
+                (subscription.callback)(&mut *node, event, self);
+
+                match self.nodes.get_at(&subscription.path) {
+                    Some(slot)
+                        if std::ptr::eq(
+                            &**slot as *const dyn Node as *const (),
+                            placeholder_ptr,
+                        ) =>
+                    {
+                        *slot = node;
+                    }
+                    _ => log::warn!(
+                        "\"{}\" was replaced or removed while handling \"{}\", the node is dropped",
+                        subscription.path,
+                        event.name()
+                    ),
+                }
+            }
+        }
+
+        subscriptions.append(&mut self.subscriptions);
+        self.subscriptions = subscriptions;
+    }
+
+    /// applies the queued spawns, despawns and moves in the order they were made, called by the engine after the frame.
+    pub(crate) fn flush_node_commands(&mut self) {
+        // commands queued by a ready method run in the next flush
+        for command in std::mem::take(&mut self.node_commands) {
+            match command {
+                NodeCommand::Spawn { path, node } => match self.nodes.insert_at(&path, node) {
+                    Ok(node) => {
+                        let node = &mut **node as *mut dyn Node;
+                        // SAFETY: the node stays in the tree while ready runs, ready can only queue more commands
+                        unsafe {
+                            if let Some(ready) = (*node).as_ready() {
+                                ready.ready();
+                            }
+                            (*node).get_children().ready(self);
+                        }
+                    }
+                    Err(err) => log::error!("failed to spawn \"{}\": {}", path, err),
+                },
+                NodeCommand::Despawn(path) => {
+                    if self.remove_node(&path).is_none() {
+                        log::warn!("failed to despawn \"{}\": no node at that path", path);
+                    }
+                }
+                NodeCommand::Take { path, callback } => {
+                    let node = self.remove_node(&path);
+                    callback(node, self);
+                }
+                NodeCommand::Reparent { from, to, callback } => {
+                    let result = self.reparent_node(&from, &to);
+                    match callback {
+                        Some(callback) => callback(result, self),
+                        None => {
+                            if let Err(err) = result {
+                                log::error!("failed to move \"{}\" to \"{}\": {}", from, to, err);
+                            }
+                        }
+                    }
+                }
+                NodeCommand::ClearScene => self.clear_scene_now(),
+                NodeCommand::ReplaceScene(nodes) => {
+                    self.replace_scene_now(*nodes);
+                    let nodes = &mut self.nodes as *mut NodeManager;
+                    // SAFETY: the tree stays in place while ready runs, ready can only queue more commands
+                    unsafe {
+                        (*nodes).ready(self);
+                    }
+                }
+            }
+        }
+    }
+
+    /// replaces the nodes with the nodes of a scene file at the end of the frame, see [`scene_file`].
+    ///
+    /// the file is read right away and the scene is replaced like [`replace_scene`](GameContext::replace_scene).
+    /// callbacks are not saved in scene files, load the file with [`NodeManager::load_from_file`] and pass it to
+    /// `replace_scene` to define them on the loaded nodes first.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the scene file
+    ///
+    /// # Returns
+    /// the error if the file could not be loaded, the current scene is left unchanged in that case
+    pub fn load_scene(&mut self, path: &str) -> Result<(), scene_file::SceneFileError> {
+        let nodes = NodeManager::load_from_file(path)?;
+        self.replace_scene(nodes);
+        Ok(())
+    }
+
+    /// replaces the nodes of the scene at the end of the frame.
+    ///
+    /// the change waits like [`clear_scene`](GameContext::clear_scene). the shaders of the current scene are kept, the
+    /// active camera of the new nodes becomes the main camera and the ready methods of the new nodes run.
+    ///
+    /// # Arguments
+    /// - `nodes` - the new nodes, usually loaded with [`NodeManager::load_from_file`]
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut level = NodeManager::load_from_file("res/scenes/level2.json")?;
+    /// level
+    ///     .get_mut_expect::<Model>("player")
+    ///     .define_behavior(|player, context| { /* ... */ });
+    /// context.replace_scene(level);
+    /// ```
+    pub fn replace_scene(&mut self, nodes: NodeManager) {
+        self.node_commands
+            .push(NodeCommand::ReplaceScene(Box::new(nodes)));
+    }
+
+    /// replaces the nodes right away, see [`replace_scene`](GameContext::replace_scene)
+    fn replace_scene_now(&mut self, mut nodes: NodeManager) {
+        nodes.shaders = std::mem::take(&mut self.nodes.shaders);
+        nodes.shadow_shader = self.nodes.shadow_shader.take();
+        nodes.active_shader = std::mem::take(&mut self.nodes.active_shader);
+
+        self.clear_scene_now();
+        self.nodes = nodes;
+
+        let active_camera = self.nodes.active_camera.clone();
+        if let Some(camera) = self.nodes.get::<Camera3D>(&active_camera) {
+            let camera = camera.as_ptr();
+            self.set_main_camera(camera);
         }
     }
 
@@ -90,9 +956,9 @@ impl GameContext {
         }
 
         if search_path.is_empty() {
-            println!("no matching result");
+            log::warn!("the main camera is not in the scene");
         } else {
-            println!("camera found at path: {:?}", search_path);
+            log::debug!("camera found at path: {:?}", search_path);
             self.active_camera_path = search_path;
         }
     }
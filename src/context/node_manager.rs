@@ -27,6 +27,10 @@
 //!         &mut self.children
 //!     }
 //!
+//!     fn get_children_ref(&self) -> &NodeManager {
+//!         &self.children
+//!     }
+//!
 //!     // nodes that implement the Ready trait need to have a as_ready method to
 //!     // cast to the dyn Ready object so the engine can dynamically dispatch the ready method
 //!     fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
@@ -68,7 +72,9 @@
 //! engine.context.nodes.add("custom", CustomNode::new());
 //! ```
 
+use super::scene_file::{self, SceneFileError};
 use crate::components::NodeTransform;
+use crate::nodes::collider::{self, Collider};
 use crate::nodes::{Camera3D, Model};
 use crate::renderer::shader::Shader;
 use dyn_clone::DynClone;
@@ -78,6 +84,7 @@ use std::collections::HashMap;
 
 use std::fmt;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// The Ready trait is used to define that has behavior that is called when the node is ready.
@@ -104,6 +111,10 @@ use std::sync::{Arc, Mutex};
 ///         &mut self.children
 ///     }
 ///
+///     fn get_children_ref(&self) -> &NodeManager {
+///         &self.children
+///     }
+///
 ///     // nodes that implement the Ready trait need to have a as_ready method to
 ///     // cast to the dyn Ready object so the engine can dynamically dispatch the ready method
 ///     fn as_ready(&mut self) -> Option<&mut (dyn Ready)> {
@@ -158,6 +169,10 @@ pub type ReadyCallback<T> = Option<Arc<Mutex<dyn FnMut(&mut T) + Send + Sync>>>;
 ///         &mut self.children
 ///     }
 ///
+///     fn get_children_ref(&self) -> &NodeManager {
+///         &self.children
+///     }
+///
 ///     // nodes that implement the Behavior trait need to have a as_behavior method to
 ///     // cast to the dyn Behavior object so the engine can dynamically dispatch the ready method
 ///     fn as_behavior(&mut self) -> Option<&mut (dyn Behavior)> {
@@ -187,6 +202,18 @@ pub trait Behavior: Node {
 
 pub type BehaviorCallback<T, U> = Option<Arc<Mutex<dyn FnMut(&mut T, &mut U) + Send + Sync>>>;
 
+/// The FixedBehavior trait is used to define that has behavior that is called at a fixed rate.
+///
+/// unlike [`Behavior`] it is called zero or more times per frame so that it runs the same number of times per second
+/// on every machine, which is what physics and other simulation logic needs. use
+/// [`FPSManager::get_fixed_timestep`](super::fps_manager::FPSManager::get_fixed_timestep) as the time step.
+///
+/// the node has to return itself from [`Node::as_fixed_behavior`] like it does with [`Node::as_behavior`].
+pub trait FixedBehavior: Node {
+    /// the fixed behavior method is called once for every fixed timestep that passed.
+    fn fixed_behavior(&mut self, context: &mut super::GameContext);
+}
+
 // pub trait Casts: Any {
 //     fn as_any(&self) -> &dyn Any;
 //     fn as_any_mut(&mut self) -> &mut dyn Any;
@@ -320,7 +347,6 @@ where
     // for child in node.get_children().get_all_mut().values_mut() {
     //     let child_node: &mut dyn Node = &mut **child;
     //     apply_transform(child_node, operation);
-    //     //println!("processing children");
     // }
 }
 
@@ -367,6 +393,12 @@ pub trait Node: Any + Casting + DynClone {
     /// a mutable reference to the children of the node.
     fn get_children(&mut self) -> &mut NodeManager;
 
+    /// gets the children of the node without borrowing it mutably.
+    ///
+    /// # Returns
+    /// a reference to the children of the node.
+    fn get_children_ref(&self) -> &NodeManager;
+
     /// cast to Ready trait if it implements it
     ///
     /// A node that implements the Ready trait need to have a as_ready method to cast to the dyn Ready object so the engine can dynamically dispatch the ready method
@@ -380,6 +412,46 @@ pub trait Node: Any + Casting + DynClone {
     fn as_behavior(&mut self) -> Option<&mut dyn Behavior> {
         None
     }
+
+    /// cast to FixedBehavior trait if it implements it
+    ///
+    /// A node that implements the FixedBehavior trait need to have a as_fixed_behavior method to cast to the dyn FixedBehavior object so the engine can dynamically dispatch the fixed behavior method
+    fn as_fixed_behavior(&mut self) -> Option<&mut dyn FixedBehavior> {
+        None
+    }
+
+    /// check if the node is drawn, see [`set_enabled`](Node::set_enabled).
+    fn is_enabled(&self) -> bool {
+        self.get_children_ref().enabled
+    }
+
+    /// sets if the node and its children are drawn, use it to keep a pool of nodes in the tree and show them when
+    /// they are needed instead of adding and removing them.
+    ///
+    /// disabled nodes are skipped by the main pass, the shadow passes and the UI passes, disabled lights don't light
+    /// the scene and disabled audio sources are no longer updated. a disabled node keeps running its behavior, use
+    /// [`set_active`](Node::set_active) to freeze it.
+    ///
+    /// # Arguments
+    /// - `enabled` - if the node is drawn, true by default
+    fn set_enabled(&mut self, enabled: bool) {
+        self.get_children().enabled = enabled;
+    }
+
+    /// check if the node runs its behavior, see [`set_active`](Node::set_active).
+    fn is_active(&self) -> bool {
+        self.get_children_ref().active
+    }
+
+    /// sets if the node and its children run their behaviors. inactive nodes skip the behavior and fixed behavior,
+    /// don't get events and their colliders aren't checked. an inactive node is still drawn, use
+    /// [`set_enabled`](Node::set_enabled) to hide it.
+    ///
+    /// # Arguments
+    /// - `active` - if the node runs its behavior, true by default
+    fn set_active(&mut self, active: bool) {
+        self.get_children().active = active;
+    }
 }
 
 impl fmt::Debug for dyn Node {
@@ -455,11 +527,98 @@ pub trait Drawable {
     fn draw_shadow(&mut self, shader: &mut Shader, parent_transform: NodeTransform);
 }
 
+/// Errors returned when looking up or adding nodes by name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeError {
+    /// there is no node with the given name.
+    NotFound(String),
+    /// a node with the given name exists but it is not of the requested type.
+    WrongType {
+        /// the name of the node.
+        name: String,
+        /// the type that was requested.
+        expected: &'static str,
+    },
+    /// a node with the given name already exists.
+    AlreadyExists(String),
+    /// a node can't be moved under itself or one of its children.
+    InvalidParent(String),
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeError::NotFound(name) => write!(f, "no node named \"{}\"", name),
+            NodeError::WrongType { name, expected } => {
+                write!(f, "node \"{}\" is not of type {}", name, expected)
+            }
+            NodeError::AlreadyExists(name) => write!(f, "a node named \"{}\" already exists", name),
+            NodeError::InvalidParent(path) => {
+                write!(f, "a node can't be moved under itself (\"{}\")", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+/// the next id handed out to a node, shared by all node managers so ids are never reused
+static NEXT_NODE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A stable handle to a node returned when it is added.
+///
+/// the id stays the same when the node is renamed or replaced and can be stored instead of the name to
+/// access nodes that are used every frame. ids are unique across all node managers and are plain integers so
+/// they can be saved and restored with [`NodeId::as_u64`] and [`NodeId::from_u64`]. scene files keep the ids of
+/// their nodes, loading the same file twice gives both copies the same ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u64);
+
+impl NodeId {
+    /// creates a new unique id
+    fn next() -> NodeId {
+        NodeId(NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// get the raw value of the id
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// creates an id from a raw value (for example one that was saved with [`NodeId::as_u64`])
+    pub fn from_u64(id: u64) -> NodeId {
+        NodeId(id)
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// splits a `/` separated node path like `"player/gun/muzzle"` into node names, empty segments are ignored.
+pub(crate) fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|name| !name.is_empty()).collect()
+}
+
 /// The NodeManager struct is used to manage all the nodes in the scene tree.
+///
+/// ## Update order
+/// `ready` and `behavior` run in a deterministic order: nodes are visited in the order they were added
+/// and each node runs before its children (depth first). replacing a node with the same name keeps its
+/// original position. nodes inserted directly through [`get_all_mut`](NodeManager::get_all_mut) are run
+/// after the added nodes sorted by name.
 #[derive(Clone)]
 pub struct NodeManager {
     /// A hashmap of all the nodes in the scene tree.
     nodes: HashMap<String, Box<dyn Node>>,
+    /// the names of the nodes in the order they were added (used for the update order)
+    order: Vec<String>,
+    /// the id of every node by name
+    ids: HashMap<String, NodeId>,
+    /// the name of every node by id
+    names: HashMap<NodeId, String>,
     /// A hashmap of all the shaders in the scene.
     pub shaders: HashMap<String, Box<Shader>>,
     /// The shadow shader used to render depth maps.
@@ -468,6 +627,10 @@ pub struct NodeManager {
     pub active_camera: String,
     /// The active shader in the scene.
     pub active_shader: String,
+    /// if the node owning these children is drawn, see [`Node::set_enabled`]
+    enabled: bool,
+    /// if the node owning these children runs its behavior, see [`Node::set_active`]
+    active: bool,
 }
 
 impl Default for NodeManager {
@@ -511,10 +674,15 @@ impl NodeManager {
     pub fn new() -> NodeManager {
         NodeManager {
             nodes: HashMap::new(),
+            order: Vec::new(),
+            ids: HashMap::new(),
+            names: HashMap::new(),
             shaders: HashMap::new(),
             active_camera: String::new(),
             active_shader: String::new(),
             shadow_shader: None,
+            enabled: true,
+            active: true,
         }
     }
 
@@ -542,7 +710,14 @@ impl NodeManager {
     /// ```
     pub fn add<T: Node + 'static>(&mut self, name: &str, node: T) -> &mut T {
         // Insert the node into the map
-        self.nodes.insert(name.to_string(), Box::new(node));
+        if self
+            .nodes
+            .insert(name.to_string(), Box::new(node))
+            .is_none()
+        {
+            self.order.push(name.to_string());
+            self.assign_id(name);
+        }
 
         // If it's the first camera added, set it as the active camera
         if std::any::type_name::<T>() == std::any::type_name::<Camera3D>()
@@ -558,9 +733,605 @@ impl NodeManager {
             .expect("Failed to downcast the node")
     }
 
+    /// adds a node to the scene tree without replacing an existing node.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    /// - `node` - the node to add to the scene tree.
+    ///
+    /// # Returns
+    /// a mutable reference to the node or [`NodeError::AlreadyExists`] if the name is taken.
+    pub fn try_add<T: Node + 'static>(&mut self, name: &str, node: T) -> Result<&mut T, NodeError> {
+        if self.nodes.contains_key(name) {
+            return Err(NodeError::AlreadyExists(name.to_string()));
+        }
+
+        self.add(name, node);
+        self.get_mut_or_err(name)
+    }
+
+    /// removes all the nodes and shaders and resets the active camera and shader.
+    ///
+    /// the removed nodes and shaders are dropped so their resources are released. like [`remove`](NodeManager::remove)
+    /// this only runs at the end of the frame, use [`GameContext::clear_scene`](super::GameContext::clear_scene).
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.order.clear();
+        self.ids.clear();
+        self.names.clear();
+        self.shaders.clear();
+        self.shadow_shader = None;
+        self.active_camera.clear();
+        self.active_shader.clear();
+    }
+
+    /// merges the meshes of every model in the scene tree that share a material, see [`Model::bake_static`].
+    ///
+    /// models marked with [`Model::mark_dynamic`] are skipped. call it once after the level is loaded.
+    ///
+    /// # Returns
+    /// the number of draw calls saved
+    pub fn bake_static(&mut self) -> usize {
+        let mut saved = 0;
+        for node in self.nodes.values_mut() {
+            if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
+                saved += model.bake_static();
+            }
+            saved += node.get_children().bake_static();
+        }
+        saved
+    }
+
+    /// casts a ray through the scene tree and finds the closest model it hits.
+    ///
+    /// the ray is moved into the space of every model and tested against the triangles of its meshes.
+    ///
+    /// # Arguments
+    /// - `origin` - the start of the ray in world space
+    /// - `direction` - the direction of the ray in world space
+    ///
+    /// # Returns
+    /// the path of the hit model (like the active camera path) and the distance to the hit, or `None` if nothing was hit
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// if context.input.is_mouse_button_just_pressed(MouseButton::Button1) {
+    ///     let (width, height) = context.window.get_size();
+    ///     let (origin, direction) = camera.screen_ray(
+    ///         NodeTransform::default(),
+    ///         context.input.mouse_position,
+    ///         (width as u32, height as u32),
+    ///     );
+    ///     if let Some((path, distance)) = context.nodes.raycast(origin, direction) {
+    ///         println!("clicked {:?} at {}", path, distance);
+    ///     }
+    /// }
+    /// ```
+    pub fn raycast(
+        &mut self,
+        origin: glm::Vec3,
+        direction: glm::Vec3,
+    ) -> Option<(Vec<String>, f32)> {
+        if direction == glm::Vec3::zeros() {
+            return None;
+        }
+        let direction = direction.normalize();
+
+        let mut closest: Option<(Vec<String>, f32)> = None;
+        for (name, node) in self {
+            Self::raycast_node(
+                &mut **node,
+                vec![name.clone()],
+                NodeTransform::default(),
+                &origin,
+                &direction,
+                &mut closest,
+            );
+        }
+        closest
+    }
+
+    fn raycast_node(
+        node: &mut dyn Node,
+        path: Vec<String>,
+        parent_transform: NodeTransform,
+        origin: &glm::Vec3,
+        direction: &glm::Vec3,
+        closest: &mut Option<(Vec<String>, f32)>,
+    ) {
+        let world_transform = parent_transform.compose(node.get_transform());
+
+        if let Some(model) = node.as_any().downcast_ref::<Model>() {
+            if let Some(distance) = model.raycast(&world_transform, origin, direction) {
+                if closest
+                    .as_ref()
+                    .is_none_or(|(_, closest)| distance < *closest)
+                {
+                    *closest = Some((path.clone(), distance));
+                }
+            }
+        }
+
+        for (name, child) in node.get_children() {
+            let mut child_path = path.clone();
+            child_path.push(name.clone());
+            Self::raycast_node(
+                &mut **child,
+                child_path,
+                world_transform,
+                origin,
+                direction,
+                closest,
+            );
+        }
+    }
+
+    /// gets the paths of the colliders whose world boxes overlap the collider at a path right now.
+    ///
+    /// the boxes are axis aligned in world space, rotated colliders use the box fitted around their rotated corners.
+    /// see [`Collider`] for the overlaps found by the engine every frame.
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the collider separated by `/` like `"player/collider"`
+    ///
+    /// # Returns
+    /// the paths of the other colliders, empty if there is no collider at the path
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// for other in context.nodes.overlapping("player/collider") {
+    ///     if other.starts_with("coins/") {
+    ///         context.despawn(other.trim_end_matches("/collider"));
+    ///     }
+    /// }
+    /// ```
+    pub fn overlapping(&mut self, path: &str) -> Vec<String> {
+        let path = split_path(path).join("/");
+        let mut colliders = Vec::new();
+        Self::visit_colliders(
+            self,
+            "",
+            NodeTransform::default(),
+            &mut |other, world, collider| {
+                colliders.push((other.to_string(), collider.get_world_aabb(world)));
+            },
+        );
+
+        let Some((_, bounds)) = colliders.iter().find(|(other, _)| *other == path) else {
+            return Vec::new();
+        };
+        colliders
+            .iter()
+            .filter(|(other, other_bounds)| *other != path && bounds.intersects(other_bounds))
+            .map(|(other, _)| other.clone())
+            .collect()
+    }
+
+    /// checks every collider against the others and records the overlaps that began or ended, called by the engine
+    /// every frame
+    pub(crate) fn update_collisions(&mut self) {
+        let mut colliders = Vec::new();
+        Self::visit_colliders(
+            self,
+            "",
+            NodeTransform::default(),
+            &mut |path, world, collider| {
+                colliders.push((path.to_string(), collider.get_world_aabb(world)));
+            },
+        );
+
+        let mut overlaps = collider::find_overlaps(&colliders).into_iter();
+        Self::visit_colliders(self, "", NodeTransform::default(), &mut |_, _, collider| {
+            // the tree is visited in the same order both times
+            collider.set_overlaps(overlaps.next().unwrap_or_default());
+        });
+    }
+
+    /// calls a function with the path and world transform of every collider in the tree
+    fn visit_colliders<F>(nodes: &mut NodeManager, prefix: &str, parent: NodeTransform, f: &mut F)
+    where
+        F: FnMut(&str, &NodeTransform, &mut Collider),
+    {
+        for (name, node) in nodes {
+            if !node.is_active() {
+                continue;
+            }
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let world_transform = parent.compose(node.get_transform());
+            if let Some(collider) = node.as_any_mut().downcast_mut::<Collider>() {
+                f(&path, &world_transform, collider);
+            }
+            Self::visit_colliders(node.get_children(), &path, world_transform, f);
+        }
+    }
+
+    /// gets the world transform of a nested node by composing the transforms of every node along its path.
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the node separated by `/` like `"player/gun/muzzle"`
+    ///
+    /// # Returns
+    /// the transform of the node in world space, or `None` if a node along the path doesn't exist
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// if let Some(muzzle) = context.nodes.world_transform("player/gun/muzzle") {
+    ///     spawn_bullet(context, muzzle.position, muzzle.get_forward_vector());
+    /// }
+    /// ```
+    pub fn world_transform(&mut self, path: &str) -> Option<NodeTransform> {
+        let mut names = split_path(path).into_iter();
+        let mut node = self.get_dyn(names.next()?)?;
+        let mut transform = NodeTransform::default().compose(node.get_transform());
+
+        for name in names {
+            node = node.get_children().get_dyn(name)?;
+            transform = transform.compose(node.get_transform());
+        }
+
+        Some(transform)
+    }
+
+    /// removes a nested node and all of its children from the scene tree.
+    ///
+    /// if the node was the active camera of its manager the active camera is cleared. the engine holds pointers into the
+    /// tree during a frame so this only runs at the end of the frame, use [`GameContext::despawn`](super::GameContext::despawn).
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the node separated by `/` like `"enemies/goblin"`
+    ///
+    /// # Returns
+    /// the removed node, or `None` if there is no node at the path
+    pub(crate) fn remove(&mut self, path: &str) -> Option<Box<dyn Node>> {
+        let names = split_path(path);
+        let (name, parents) = names.split_last()?;
+        self.children_at(parents)?.take(name).map(|(node, _)| node)
+    }
+
+    /// moves a nested node (with its children) under another node without changing where it is in the world.
+    ///
+    /// the local transform of the node is recomputed relative to the new parent and the node keeps its name and id.
+    /// like [`remove`](NodeManager::remove) this only runs at the end of the frame, use
+    /// [`GameContext::reparent`](super::GameContext::reparent).
+    ///
+    /// # Arguments
+    /// - `from` - the path of the node to move like `"items/sword"`
+    /// - `to` - the path of the new parent like `"player/hand"`, an empty path moves the node to the root
+    ///
+    /// # Returns
+    /// [`NodeError::NotFound`] if either path doesn't exist, [`NodeError::AlreadyExists`] if the new parent already
+    /// has a child with the same name, or [`NodeError::InvalidParent`] if `to` is the node or one of its children.
+    pub(crate) fn reparent(&mut self, from: &str, to: &str) -> Result<(), NodeError> {
+        let from_names = split_path(from);
+        let to_names = split_path(to);
+        let Some((name, from_parents)) = from_names.split_last() else {
+            return Err(NodeError::NotFound(from.to_string()));
+        };
+        if to_names.starts_with(&from_names) {
+            return Err(NodeError::InvalidParent(to.to_string()));
+        }
+        if from_parents == to_names.as_slice() {
+            return Ok(());
+        }
+
+        let world = self
+            .world_transform(from)
+            .ok_or_else(|| NodeError::NotFound(from.to_string()))?;
+        let parent_world = if to_names.is_empty() {
+            NodeTransform::default()
+        } else {
+            self.world_transform(to)
+                .ok_or_else(|| NodeError::NotFound(to.to_string()))?
+        };
+        if self
+            .children_at(&to_names)
+            .is_some_and(|parent| parent.nodes.contains_key(*name))
+        {
+            return Err(NodeError::AlreadyExists(name.to_string()));
+        }
+
+        let (mut node, id) = self
+            .children_at(from_parents)
+            .and_then(|parent| parent.take(name))
+            .ok_or_else(|| NodeError::NotFound(from.to_string()))?;
+        *node.get_transform() = world.relative_to(&parent_world);
+
+        let parent = self
+            .children_at(&to_names)
+            .expect("the new parent was checked above");
+        parent.insert_boxed(name, node, id);
+        Ok(())
+    }
+
+    /// inserts a boxed node at a nested path with a new id.
+    ///
+    /// # Returns
+    /// the inserted node, [`NodeError::NotFound`] if the parent doesn't exist or [`NodeError::AlreadyExists`] if the
+    /// parent already has a node with the same name (the old node is kept)
+    pub(crate) fn insert_at(
+        &mut self,
+        path: &str,
+        node: Box<dyn Node>,
+    ) -> Result<&mut Box<dyn Node>, NodeError> {
+        let names = split_path(path);
+        let Some((name, parents)) = names.split_last() else {
+            return Err(NodeError::NotFound(path.to_string()));
+        };
+        let parent = self
+            .children_at(parents)
+            .ok_or_else(|| NodeError::NotFound(parents.join("/")))?;
+
+        if parent.nodes.contains_key(*name) {
+            return Err(NodeError::AlreadyExists(name.to_string()));
+        }
+        parent.insert_boxed(name, node, None);
+        Ok(parent
+            .nodes
+            .get_mut(*name)
+            .expect("the node was just inserted"))
+    }
+
+    /// gets the children of the node at the path, or this manager for an empty path.
+    fn children_at(&mut self, names: &[&str]) -> Option<&mut NodeManager> {
+        let mut manager = self;
+        for name in names {
+            manager = manager.get_dyn(name)?.get_children();
+        }
+        Some(manager)
+    }
+
+    /// removes a direct child and its bookkeeping, returning the node and its id.
+    fn take(&mut self, name: &str) -> Option<(Box<dyn Node>, Option<NodeId>)> {
+        let node = self.nodes.remove(name)?;
+        self.order.retain(|entry| entry != name);
+        let id = self.ids.remove(name);
+        if let Some(id) = id {
+            self.names.remove(&id);
+        }
+        if self.active_camera == name {
+            self.active_camera.clear();
+        }
+        Some((node, id))
+    }
+
+    /// inserts a node taken from another manager, keeping its id if it had one.
+    fn insert_boxed(&mut self, name: &str, node: Box<dyn Node>, id: Option<NodeId>) {
+        let is_camera = node.as_any().is::<Camera3D>();
+        self.nodes.insert(name.to_string(), node);
+        self.order.push(name.to_string());
+        match id {
+            Some(id) => {
+                self.ids.insert(name.to_string(), id);
+                self.names.insert(id, name.to_string());
+            }
+            None => self.assign_id(name),
+        }
+
+        if is_camera && self.active_camera.is_empty() {
+            self.active_camera = name.to_string();
+        }
+    }
+
+    /// adds a node to the scene tree and returns its id.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    /// - `node` - the node to add to the scene tree.
+    ///
+    /// # Returns
+    /// the id of the node, replacing a node with the same name keeps its id.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let camera_id = engine.context.nodes.add_with_id("camera", camera);
+    ///
+    /// // later, every frame
+    /// if let Some(camera) = context.nodes.get_by_id_mut::<Camera3D>(camera_id) {
+    ///     camera.move_camera(offset);
+    /// }
+    /// ```
+    pub fn add_with_id<T: Node + 'static>(&mut self, name: &str, node: T) -> NodeId {
+        self.add(name, node);
+        self.ids[name]
+    }
+
+    /// get the id of a node
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    ///
+    /// # Returns
+    /// the id or `None` if there is no node with the name.
+    pub fn get_id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    /// get the name of a node from its id
+    ///
+    /// # Arguments
+    /// - `id` - the id of the node.
+    pub fn get_name(&self, id: NodeId) -> Option<&str> {
+        self.names.get(&id).map(|name| name.as_str())
+    }
+
+    /// get a node by its id
+    ///
+    /// # Arguments
+    /// - `id` - the id of the node.
+    ///
+    /// # Returns
+    /// the node anywhere in the tree of this manager or `None` if no node has the id or the node is not of the given
+    /// type.
+    pub fn get_by_id<T: Node>(&self, id: NodeId) -> Option<&T> {
+        self.find_by_id(id)?.as_any().downcast_ref::<T>()
+    }
+
+    /// get a mutable node by its id
+    ///
+    /// # Arguments
+    /// - `id` - the id of the node.
+    ///
+    /// # Returns
+    /// the node anywhere in the tree of this manager or `None` if no node has the id or the node is not of the given
+    /// type.
+    pub fn get_by_id_mut<T: Node>(&mut self, id: NodeId) -> Option<&mut T> {
+        self.find_by_id_mut(id)?.as_any_mut().downcast_mut::<T>()
+    }
+
+    /// finds a node by its id in this manager first and then in the children of every node
+    fn find_by_id(&self, id: NodeId) -> Option<&dyn Node> {
+        if let Some(name) = self.names.get(&id) {
+            return self.nodes.get(name).map(|node| &**node);
+        }
+        self.nodes
+            .values()
+            .find_map(|node| node.get_children_ref().find_by_id(id))
+    }
+
+    /// finds a mutable node by its id, see [`find_by_id`](NodeManager::find_by_id)
+    fn find_by_id_mut(&mut self, id: NodeId) -> Option<&mut dyn Node> {
+        if let Some(name) = self.names.get(&id) {
+            return self.nodes.get_mut(name).map(|node| &mut **node);
+        }
+        self.nodes
+            .values_mut()
+            .find_map(|node| node.get_children().find_by_id_mut(id))
+    }
+
+    /// gives a node the id it was saved with, ids handed out later are always larger.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    /// - `id` - the saved id.
+    pub(crate) fn restore_id(&mut self, name: &str, id: NodeId) {
+        if !self.nodes.contains_key(name) {
+            return;
+        }
+        if let Some(old) = self.ids.insert(name.to_string(), id) {
+            self.names.remove(&old);
+        }
+        self.names.insert(id, name.to_string());
+        NEXT_NODE_ID.fetch_max(id.0.saturating_add(1), Ordering::Relaxed);
+    }
+
+    /// renames a node while keeping its id and its place in the update order.
+    ///
+    /// # Arguments
+    /// - `name` - the current name of the node.
+    /// - `new_name` - the new name of the node.
+    ///
+    /// # Returns
+    /// [`NodeError::NotFound`] if there is no node named `name` or [`NodeError::AlreadyExists`] if `new_name` is taken.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), NodeError> {
+        if name == new_name {
+            return Ok(());
+        }
+        if self.nodes.contains_key(new_name) {
+            return Err(NodeError::AlreadyExists(new_name.to_string()));
+        }
+        let node = self
+            .nodes
+            .remove(name)
+            .ok_or_else(|| NodeError::NotFound(name.to_string()))?;
+        self.nodes.insert(new_name.to_string(), node);
+
+        if let Some(entry) = self.order.iter_mut().find(|entry| *entry == name) {
+            *entry = new_name.to_string();
+        }
+        if let Some(id) = self.ids.remove(name) {
+            self.ids.insert(new_name.to_string(), id);
+            self.names.insert(id, new_name.to_string());
+        }
+        if self.active_camera == name {
+            self.active_camera = new_name.to_string();
+        }
+        Ok(())
+    }
+
+    /// saves the nodes to a json scene file, see [`scene_file`](super::scene_file) for what is saved.
+    ///
+    /// nodes that can't be saved (custom node types, models built in code) are skipped with a warning.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the file to write
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// engine.context.nodes.save("res/scenes/level1.json")?;
+    /// ```
+    pub fn save(&mut self, path: &str) -> Result<(), SceneFileError> {
+        scene_file::save(self, path)
+    }
+
+    /// loads the nodes of a json scene file written by [`save`](NodeManager::save).
+    ///
+    /// the callbacks of the nodes are not saved and have to be defined again after loading.
+    /// use [`GameContext::load_scene`](super::GameContext::load_scene) to replace the running scene.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the scene file
+    ///
+    /// # Returns
+    /// the loaded nodes or the error if the file could not be read or a model file is missing
+    pub fn load_from_file(path: &str) -> Result<NodeManager, SceneFileError> {
+        scene_file::load(path)
+    }
+
+    /// get the names of the nodes in update order
+    pub(crate) fn ordered_names(&mut self) -> Vec<String> {
+        self.sync_order();
+        self.order.clone()
+    }
+
+    /// gives a new id to a node that does not have one yet
+    fn assign_id(&mut self, name: &str) {
+        if !self.ids.contains_key(name) {
+            let id = NodeId::next();
+            self.ids.insert(name.to_string(), id);
+            self.names.insert(id, name.to_string());
+        }
+    }
+
+    /// brings the update order in sync with the nodes in case the map was modified directly.
+    fn sync_order(&mut self) {
+        if self.order.len() == self.nodes.len()
+            && self.order.iter().all(|name| self.nodes.contains_key(name))
+        {
+            return;
+        }
+
+        let nodes = &self.nodes;
+        self.order.retain(|name| nodes.contains_key(name));
+
+        let mut missing: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|name| !self.order.contains(name))
+            .cloned()
+            .collect();
+        missing.sort();
+        for name in &missing {
+            self.assign_id(name);
+        }
+        self.order.extend(missing);
+
+        let nodes = &self.nodes;
+        self.ids.retain(|name, _| nodes.contains_key(name));
+        let ids = &self.ids;
+        self.names.retain(|_, name| ids.contains_key(name));
+    }
+
     /// runs the ready method if the node implements the Ready trait and reruns this method for children.
+    ///
+    /// nodes are visited in the order they were added, see [update order](NodeManager#update-order).
     pub fn ready(&mut self, context: &mut super::GameContext) {
-        for node in self.nodes.values_mut() {
+        self.sync_order();
+        for name in &self.order {
+            let node = self.nodes.get_mut(name).unwrap();
             if let Some(camera) = node.as_any_mut().downcast_mut::<Camera3D>() {
                 if context.active_camera_path.is_empty() {
                     let camera_ptr = camera.as_ptr();
@@ -577,8 +1348,15 @@ impl NodeManager {
     }
 
     /// runs the behavior method if the node implements the Behavior trait and reruns this method for children.
+    ///
+    /// nodes are visited in the order they were added, see [update order](NodeManager#update-order).
     pub fn behavior(&mut self, context: &mut super::GameContext) {
-        for node in self.nodes.values_mut() {
+        self.sync_order();
+        for name in &self.order {
+            let node = self.nodes.get_mut(name).unwrap();
+            if !node.is_active() {
+                continue;
+            }
             if let Some(node) = node.as_behavior() {
                 node.behavior(context);
             }
@@ -587,6 +1365,22 @@ impl NodeManager {
         }
     }
 
+    /// calls the fixed behavior method on all nodes in the scene tree.
+    pub fn fixed_behavior(&mut self, context: &mut super::GameContext) {
+        self.sync_order();
+        for name in &self.order {
+            let node = self.nodes.get_mut(name).unwrap();
+            if !node.is_active() {
+                continue;
+            }
+            if let Some(node) = node.as_fixed_behavior() {
+                node.fixed_behavior(context);
+            }
+            // recursively call fixed behavior on all children
+            node.get_children().fixed_behavior(context);
+        }
+    }
+
     /// get a node but without a specific type
     ///
     /// # Arguments
@@ -598,6 +1392,35 @@ impl NodeManager {
         self.nodes.get_mut(name).map(|node| &mut **node)
     }
 
+    /// get the box of a nested node, active or not
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the node separated by `/` like `"level/door"`
+    pub(crate) fn get_at(&mut self, path: &str) -> Option<&mut Box<dyn Node>> {
+        let names = split_path(path);
+        let (name, parents) = names.split_last()?;
+        self.children_at(parents)?.nodes.get_mut(*name)
+    }
+
+    /// get a nested node if it and all of its parents are active
+    ///
+    /// # Arguments
+    /// - `path` - the names of the nodes from the root to the node separated by `/` like `"level/door"`
+    pub(crate) fn get_active_at(&mut self, path: &str) -> Option<&mut Box<dyn Node>> {
+        let names = split_path(path);
+        let (name, parents) = names.split_last()?;
+        let mut manager = self;
+        for parent in parents {
+            // the children of a node hold its flags
+            manager = manager.get_dyn(parent)?.get_children();
+            if !manager.active {
+                return None;
+            }
+        }
+        let node = manager.nodes.get_mut(*name)?;
+        node.is_active().then_some(node)
+    }
+
     /// get all the nodes in the scene tree.
     ///
     /// # Returns
@@ -608,6 +1431,10 @@ impl NodeManager {
 
     /// get all the nodes in the scene tree as a mutable reference.
     ///
+    /// the engine holds pointers into the tree during a frame, so don't remove or replace nodes through the map from a
+    /// behavior or callback. use [`GameContext::despawn`](super::GameContext::despawn) and
+    /// [`GameContext::spawn`](super::GameContext::spawn) instead.
+    ///
     /// # Returns
     /// a mutable hashmap of all the nodes in the scene tree.
     pub fn get_all_mut(&mut self) -> &mut HashMap<String, Box<dyn Node>> {
@@ -640,7 +1467,76 @@ impl NodeManager {
             .and_then(|node| node.as_any_mut().downcast_mut::<T>())
     }
 
-    /// get all nodes of a specific type as an iterator
+    /// get a node by name.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    ///
+    /// # Returns
+    /// a reference to the node, or an error telling if the node is missing or of a different type.
+    pub fn get_or_err<T: Node>(&self, name: &str) -> Result<&T, NodeError> {
+        self.nodes
+            .get(name)
+            .ok_or_else(|| NodeError::NotFound(name.to_string()))?
+            .as_any()
+            .downcast_ref::<T>()
+            .ok_or_else(|| NodeError::WrongType {
+                name: name.to_string(),
+                expected: std::any::type_name::<T>(),
+            })
+    }
+
+    /// get a mutable reference to a node by name.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    ///
+    /// # Returns
+    /// a mutable reference to the node, or an error telling if the node is missing or of a different type.
+    pub fn get_mut_or_err<T: Node>(&mut self, name: &str) -> Result<&mut T, NodeError> {
+        self.nodes
+            .get_mut(name)
+            .ok_or_else(|| NodeError::NotFound(name.to_string()))?
+            .as_any_mut()
+            .downcast_mut::<T>()
+            .ok_or_else(|| NodeError::WrongType {
+                name: name.to_string(),
+                expected: std::any::type_name::<T>(),
+            })
+    }
+
+    /// get a node by name.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    ///
+    /// # Returns
+    /// a reference to the node.
+    ///
+    /// # Panics
+    /// if the node does not exist or is not of the given type. the message tells which one.
+    pub fn get_expect<T: Node>(&self, name: &str) -> &T {
+        self.get_or_err(name)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// get a mutable reference to a node by name.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the node.
+    ///
+    /// # Returns
+    /// a mutable reference to the node.
+    ///
+    /// # Panics
+    /// if the node does not exist or is not of the given type. the message tells which one.
+    pub fn get_mut_expect<T: Node>(&mut self, name: &str) -> &mut T {
+        self.get_mut_or_err(name)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// get all nodes of a specific type as an iterator, only the nodes of this manager are checked (not their
+    /// children), use [`visit`](NodeManager::visit) to search the whole tree
     ///
     /// # Returns
     /// an iterator of mutable references to all nodes of the given type.
@@ -650,7 +1546,8 @@ impl NodeManager {
             .filter_map(|node| node.as_any_mut().downcast_mut::<T>())
     }
 
-    /// get all nodes of a specific type as a vector
+    /// get all nodes of a specific type as a vector, only the nodes of this manager are checked (not their
+    /// children), use [`visit`](NodeManager::visit) to search the whole tree
     ///
     /// # Returns
     /// a vector of mutable references to all nodes of the given type.
@@ -661,6 +1558,50 @@ impl NodeManager {
             .collect()
     }
 
+    /// calls a function with every node of a specific type in the whole tree and its world transform.
+    ///
+    /// nodes are visited in the order they were added with parents before their children, see
+    /// [update order](NodeManager#update-order). disabled nodes and their children are skipped like when drawing.
+    /// a node owns its children so the references can't be collected into a list, each node is only borrowed during
+    /// the call.
+    ///
+    /// # Arguments
+    /// - `visit` - called with the node and its world transform (relative to the owner of this manager if it holds
+    ///   children)
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // the models nested under the camera are found too
+    /// engine.context.nodes.visit::<Model>(|model, world_transform| {
+    ///     println!("model at {}", world_transform.position);
+    /// });
+    /// ```
+    pub fn visit<T: Node>(&mut self, mut visit: impl FnMut(&mut T, NodeTransform)) {
+        self.visit_from(NodeTransform::default(), &mut visit);
+    }
+
+    /// the recursion of [`visit`](NodeManager::visit)
+    fn visit_from<T: Node>(
+        &mut self,
+        parent_transform: NodeTransform,
+        visit: &mut dyn FnMut(&mut T, NodeTransform),
+    ) {
+        self.sync_order();
+        for name in &self.order {
+            let node = self.nodes.get_mut(name).unwrap();
+            // disabled nodes hide their children too
+            if !node.is_enabled() {
+                continue;
+            }
+
+            let world_transform = parent_transform.compose(node.get_transform());
+            if let Some(target) = node.as_any_mut().downcast_mut::<T>() {
+                visit(target, world_transform);
+            }
+            node.get_children().visit_from(world_transform, visit);
+        }
+    }
+
     /// add a shader to the scene.
     ///
     /// # Arguments
@@ -720,6 +1661,10 @@ mod test {
                 &mut self.children
             }
 
+            fn get_children_ref(&self) -> &super::NodeManager {
+                &self.children
+            }
+
             fn as_behavior(&mut self) -> Option<&mut (dyn super::Behavior)> {
                 Some(self)
             }
@@ -763,6 +1708,10 @@ mod test {
             fn get_children(&mut self) -> &mut super::NodeManager {
                 &mut self.children
             }
+
+            fn get_children_ref(&self) -> &super::NodeManager {
+                &self.children
+            }
         }
 
         impl Node {
@@ -778,4 +1727,400 @@ mod test {
         let node_dyn = &mut node_no_behavior as &mut dyn super::Node;
         assert_eq!(node_dyn.as_behavior().is_none(), true);
     }
+
+    #[test]
+    fn update_order_test() {
+        #[derive(Clone)]
+        struct Node {
+            transform: super::NodeTransform,
+            children: super::NodeManager,
+        }
+
+        impl super::Node for Node {
+            fn get_transform(&mut self) -> &mut super::NodeTransform {
+                &mut self.transform
+            }
+
+            fn get_children(&mut self) -> &mut super::NodeManager {
+                &mut self.children
+            }
+
+            fn get_children_ref(&self) -> &super::NodeManager {
+                &self.children
+            }
+        }
+
+        impl Node {
+            pub fn new() -> Self {
+                Self {
+                    transform: super::NodeTransform::default(),
+                    children: super::NodeManager::new(),
+                }
+            }
+        }
+
+        let mut nodes = super::NodeManager::new();
+        nodes.add("b", Node::new());
+        nodes.add("a", Node::new());
+        nodes.add("c", Node::new());
+
+        // replacing a node keeps its position
+        nodes.add("a", Node::new());
+        assert_eq!(nodes.order, vec!["b", "a", "c"]);
+
+        // nodes inserted into the map directly are appended sorted by name
+        nodes.get_all_mut().remove("b");
+        nodes
+            .get_all_mut()
+            .insert("e".to_string(), Box::new(Node::new()));
+        nodes
+            .get_all_mut()
+            .insert("d".to_string(), Box::new(Node::new()));
+        nodes.sync_order();
+        assert_eq!(nodes.order, vec!["a", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn get_or_err_test() {
+        #[derive(Clone)]
+        struct Node {
+            transform: super::NodeTransform,
+            children: super::NodeManager,
+        }
+
+        impl super::Node for Node {
+            fn get_transform(&mut self) -> &mut super::NodeTransform {
+                &mut self.transform
+            }
+
+            fn get_children(&mut self) -> &mut super::NodeManager {
+                &mut self.children
+            }
+
+            fn get_children_ref(&self) -> &super::NodeManager {
+                &self.children
+            }
+        }
+
+        let mut nodes = super::NodeManager::new();
+        let node = Node {
+            transform: super::NodeTransform::default(),
+            children: super::NodeManager::new(),
+        };
+
+        assert!(nodes.try_add("node", node.clone()).is_ok());
+        assert_eq!(
+            nodes.try_add("node", node).err(),
+            Some(super::NodeError::AlreadyExists("node".to_string()))
+        );
+
+        assert!(nodes.get_or_err::<Node>("node").is_ok());
+        assert_eq!(
+            nodes.get_or_err::<Node>("missing").err(),
+            Some(super::NodeError::NotFound("missing".to_string()))
+        );
+        assert!(matches!(
+            nodes.get_mut_or_err::<crate::nodes::Empty>("node"),
+            Err(super::NodeError::WrongType { .. })
+        ));
+    }
+
+    #[test]
+    fn clear_test() {
+        let mut nodes = super::NodeManager::new();
+        nodes.add("camera", crate::nodes::Camera3D::new(45.0, 1.0, 0.1, 100.0));
+        nodes.add("empty", crate::nodes::Empty::new());
+        assert_eq!(nodes.active_camera, "camera");
+
+        nodes.clear();
+        assert!(nodes.get_all().is_empty());
+        assert!(nodes.active_camera.is_empty());
+        assert!(nodes.active_shader.is_empty());
+
+        // the scene can be filled again after clearing
+        nodes.add("empty", crate::nodes::Empty::new());
+        assert!(nodes.get::<crate::nodes::Empty>("empty").is_some());
+    }
+
+    #[test]
+    fn world_transform_test() {
+        use nalgebra_glm as glm;
+
+        let mut nodes = super::NodeManager::new();
+        let player = nodes.add("player", crate::nodes::Empty::new());
+        player.transform = super::NodeTransform::new(
+            glm::vec3(10.0, 0.0, 0.0),
+            glm::quat_angle_axis(90f32.to_radians(), &glm::vec3(0.0, 1.0, 0.0)),
+            glm::vec3(1.0, 1.0, 1.0),
+        );
+        let mut muzzle = crate::nodes::Empty::new();
+        muzzle.transform.set_position(glm::vec3(0.0, 0.0, 2.0));
+        player.children.add("muzzle", muzzle);
+
+        // the muzzle is 2 in front of the player which faces +x
+        let world = nodes.world_transform("player/muzzle").unwrap();
+        assert!(glm::distance(&world.position, &glm::vec3(12.0, 0.0, 0.0)) < 1e-5);
+        assert!(glm::distance(&world.get_forward_vector(), &glm::vec3(1.0, 0.0, 0.0)) < 1e-5);
+
+        assert_eq!(
+            nodes.world_transform("player").unwrap().position,
+            glm::vec3(10.0, 0.0, 0.0)
+        );
+        assert!(nodes.world_transform("player/missing").is_none());
+        assert!(nodes.world_transform("").is_none());
+    }
+
+    #[test]
+    fn overlapping_test() {
+        use super::Node;
+        use crate::nodes::{Collider, CollisionEvent, Empty};
+        use nalgebra_glm as glm;
+
+        let mut nodes = super::NodeManager::new();
+        let player = nodes.add("player", Empty::new());
+        player
+            .children
+            .add("collider", Collider::new(glm::vec3(0.5, 1.0, 0.5)));
+        let coin = nodes.add("coin", Collider::new(glm::vec3(0.25, 0.25, 0.25)));
+        coin.get_transform().set_position(glm::vec3(3.0, 0.0, 0.0));
+
+        assert!(nodes.overlapping("player/collider").is_empty());
+        nodes.update_collisions();
+        assert!(nodes.get::<Collider>("coin").unwrap().events().is_empty());
+
+        // the player walks into the coin
+        nodes
+            .get_mut::<Empty>("player")
+            .unwrap()
+            .transform
+            .set_position(glm::vec3(2.5, 0.0, 0.0));
+        assert_eq!(nodes.overlapping("player/collider"), ["coin"]);
+        assert_eq!(nodes.overlapping("coin"), ["player/collider"]);
+        assert!(nodes.overlapping("player").is_empty());
+
+        nodes.update_collisions();
+        assert_eq!(
+            nodes.get::<Collider>("coin").unwrap().events(),
+            [CollisionEvent::Begin {
+                other: "player/collider".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn active_test() {
+        use super::Node;
+        use crate::nodes::{Collider, Empty};
+        use nalgebra_glm as glm;
+
+        let mut nodes = super::NodeManager::new();
+        let pool = nodes.add("pool", Empty::new());
+        assert!(pool.is_enabled() && pool.is_active());
+        pool.children
+            .add("bullet", Collider::new(glm::vec3(1.0, 1.0, 1.0)));
+        nodes.add("wall", Collider::new(glm::vec3(1.0, 1.0, 1.0)));
+        assert!(nodes.get_active_at("pool/bullet").is_some());
+
+        // an inactive parent freezes its children
+        nodes.get_dyn("pool").unwrap().set_active(false);
+        assert!(nodes.get_active_at("pool/bullet").is_none());
+        assert!(nodes.get_active_at("pool").is_none());
+        nodes.update_collisions();
+        assert!(!nodes
+            .get::<Collider>("wall")
+            .unwrap()
+            .is_overlapping("pool/bullet"));
+
+        nodes.get_dyn("pool").unwrap().set_active(true);
+        nodes.update_collisions();
+        assert!(nodes
+            .get::<Collider>("wall")
+            .unwrap()
+            .is_overlapping("pool/bullet"));
+
+        // the flags are kept when the node is cloned
+        let pool = nodes.get_dyn("pool").unwrap();
+        pool.set_enabled(false);
+        assert!(!dyn_clone::clone_box(&*pool).is_enabled());
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut nodes = super::NodeManager::new();
+        let id = nodes.add_with_id("camera", crate::nodes::Camera3D::new(45.0, 1.0, 0.1, 100.0));
+        let enemies = nodes.add("enemies", crate::nodes::Empty::new());
+        enemies.children.add("goblin", crate::nodes::Empty::new());
+
+        assert!(nodes.remove("enemies/goblin").is_some());
+        assert!(nodes.remove("enemies/goblin").is_none());
+        assert!(nodes.get::<crate::nodes::Empty>("enemies").is_some());
+
+        // removing the active camera clears it
+        assert!(nodes.remove("camera").is_some());
+        assert!(nodes.active_camera.is_empty());
+        assert!(nodes.get_by_id::<crate::nodes::Camera3D>(id).is_none());
+        assert!(nodes.remove("").is_none());
+    }
+
+    #[test]
+    fn insert_at_test() {
+        let mut nodes = super::NodeManager::new();
+        nodes.add("bullets", crate::nodes::Empty::new());
+
+        assert!(nodes
+            .insert_at("bullets/bullet_1", Box::new(crate::nodes::Empty::new()))
+            .is_ok());
+        assert!(nodes.world_transform("bullets/bullet_1").is_some());
+        assert_eq!(
+            nodes
+                .insert_at("missing/bullet_1", Box::new(crate::nodes::Empty::new()))
+                .err(),
+            Some(super::NodeError::NotFound("missing".to_string()))
+        );
+
+        // a taken path keeps the old node and its id
+        let bullets = nodes.get_dyn("bullets").unwrap().get_children();
+        let id = bullets.get_id("bullet_1");
+        assert!(id.is_some());
+        assert_eq!(
+            nodes
+                .insert_at("bullets/bullet_1", Box::new(crate::nodes::Empty::new()))
+                .err(),
+            Some(super::NodeError::AlreadyExists("bullet_1".to_string()))
+        );
+        let bullets = nodes.get_dyn("bullets").unwrap().get_children();
+        assert_eq!(bullets.get_id("bullet_1"), id);
+
+        // spawning again after a removal gives a new id
+        nodes.remove("bullets/bullet_1");
+        nodes
+            .insert_at("bullets/bullet_1", Box::new(crate::nodes::Empty::new()))
+            .unwrap();
+        let bullets = nodes.get_dyn("bullets").unwrap().get_children();
+        assert!(bullets.get_id("bullet_1").is_some());
+        assert_ne!(bullets.get_id("bullet_1"), id);
+    }
+
+    #[test]
+    fn reparent_test() {
+        use nalgebra_glm as glm;
+
+        let mut nodes = super::NodeManager::new();
+        let player = nodes.add("player", crate::nodes::Empty::new());
+        player.transform = super::NodeTransform::new(
+            glm::vec3(10.0, 0.0, 0.0),
+            glm::quat_angle_axis(90f32.to_radians(), &glm::vec3(0.0, 1.0, 0.0)),
+            glm::vec3(2.0, 2.0, 2.0),
+        );
+        player.children.add("hand", crate::nodes::Empty::new());
+        let sword = nodes.add_with_id("sword", crate::nodes::Empty::new());
+        nodes
+            .get_mut::<crate::nodes::Empty>("sword")
+            .unwrap()
+            .transform
+            .set_position(glm::vec3(4.0, 1.0, 3.0));
+
+        nodes.reparent("sword", "player/hand").unwrap();
+        assert!(nodes.get::<crate::nodes::Empty>("sword").is_none());
+        // the id moved with the node into the hand
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(sword).is_none());
+        let world = nodes.world_transform("player/hand/sword").unwrap();
+        assert!(glm::distance(&world.position, &glm::vec3(4.0, 1.0, 3.0)) < 1e-5);
+        assert!(glm::distance(&world.scale, &glm::vec3(1.0, 1.0, 1.0)) < 1e-5);
+
+        // and back to the root
+        nodes.reparent("player/hand/sword", "").unwrap();
+        let world = nodes.world_transform("sword").unwrap();
+        assert!(glm::distance(&world.position, &glm::vec3(4.0, 1.0, 3.0)) < 1e-5);
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(sword).is_some());
+
+        assert_eq!(
+            nodes.reparent("player", "player/hand"),
+            Err(super::NodeError::InvalidParent("player/hand".to_string()))
+        );
+        assert_eq!(
+            nodes.reparent("missing", "player"),
+            Err(super::NodeError::NotFound("missing".to_string()))
+        );
+        nodes
+            .get_mut::<crate::nodes::Empty>("player")
+            .unwrap()
+            .children
+            .add("sword", crate::nodes::Empty::new());
+        assert_eq!(
+            nodes.reparent("sword", "player"),
+            Err(super::NodeError::AlreadyExists("sword".to_string()))
+        );
+    }
+
+    #[test]
+    fn node_id_test() {
+        let mut nodes = super::NodeManager::new();
+        let id = nodes.add_with_id("empty", crate::nodes::Empty::new());
+        let other = nodes.add_with_id("other", crate::nodes::Empty::new());
+        assert_ne!(id, other);
+
+        // replacing keeps the id
+        assert_eq!(nodes.add_with_id("empty", crate::nodes::Empty::new()), id);
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(id).is_some());
+        assert!(nodes.get_by_id::<crate::nodes::Camera3D>(id).is_none());
+
+        // renaming keeps the id
+        nodes.rename("empty", "renamed").unwrap();
+        assert_eq!(nodes.get_id("renamed"), Some(id));
+        assert_eq!(nodes.get_name(id), Some("renamed"));
+        assert!(nodes.get_by_id_mut::<crate::nodes::Empty>(id).is_some());
+        assert_eq!(
+            nodes.rename("renamed", "other"),
+            Err(super::NodeError::AlreadyExists("other".to_string()))
+        );
+
+        // nested nodes are found from the root
+        let child = nodes
+            .get_mut::<crate::nodes::Empty>("other")
+            .unwrap()
+            .children
+            .add_with_id("child", crate::nodes::Empty::new());
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(child).is_some());
+        assert!(nodes.get_by_id_mut::<crate::nodes::Empty>(child).is_some());
+
+        // restored ids are kept and new ids come after them
+        let saved = super::NodeId::from_u64(child.as_u64() + 1000);
+        nodes.restore_id("renamed", saved);
+        assert_eq!(nodes.get_id("renamed"), Some(saved));
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(id).is_none());
+        assert!(nodes.add_with_id("new", crate::nodes::Empty::new()) > saved);
+
+        nodes.clear();
+        assert!(nodes.get_by_id::<crate::nodes::Empty>(saved).is_none());
+    }
+
+    #[test]
+    fn visit_test() {
+        use super::Node;
+        use crate::nodes::Empty;
+        use nalgebra_glm as glm;
+
+        let mut nodes = super::NodeManager::new();
+        let camera = nodes.add("camera", crate::nodes::Camera3D::new(45.0, 1.0, 0.1, 100.0));
+        camera.transform.set_position(glm::vec3(0.0, 2.0, 0.0));
+        let arm = camera.children.add("arm", Empty::new());
+        arm.transform.set_position(glm::vec3(1.0, 0.0, 0.0));
+        arm.children.add("hand", Empty::new());
+        let hidden = nodes.add("hidden", Empty::new());
+        hidden.children.add("child", Empty::new());
+        hidden.set_enabled(false);
+        nodes.add("root", Empty::new());
+
+        let mut found = Vec::new();
+        nodes.visit::<Empty>(|_, world_transform| found.push(world_transform.position));
+        // the nested nodes are found with their world position, the disabled subtree is skipped
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[2], glm::vec3(0.0, 0.0, 0.0));
+        assert!(glm::distance(&found[0], &glm::vec3(1.0, 2.0, 0.0)) < 1e-5);
+        assert!(glm::distance(&found[1], &glm::vec3(1.0, 2.0, 0.0)) < 1e-5);
+
+        // get_iter only checks the top level
+        assert_eq!(nodes.get_iter::<Empty>().count(), 2);
+    }
 }
@@ -0,0 +1,597 @@
+//! scene files store the node tree as json so scenes can be built in an editor and shipped as data.
+//!
+//! every node is saved with its name, [`NodeId`], type, [`NodeTransform`], enabled and active flags, children and
+//! the settings of its type:
+//! - [`Empty`]: nothing extra.
+//! - [`Camera3D`]: the projection and movement settings.
+//! - [`PointLight`] and [`DirectionalLight`]: the color, intensity and shadow settings.
+//! - [`Model`]: the primitive or file it was loaded from ([`ModelSource`]) and its render flags.
+//!
+//! callbacks are code and can't be saved, attach them again after loading. nodes of other types
+//! (including your own) and models built in code are skipped with a warning.
+//!
+//! ## Usage
+//! use [`NodeManager::save`] and [`NodeManager::load_from_file`] or [`GameContext::load_scene`](super::GameContext::load_scene)
+//! to replace the running scene at the end of the frame. a [`SceneData`] can also be built or edited directly.
+//!
+//! ## Example
+//! ```rust,ignore
+//! engine.context.nodes.save("res/scenes/level1.json")?;
+//!
+//! let mut level = NodeManager::load_from_file("res/scenes/level1.json")?;
+//! level
+//!     .get_mut_expect::<Model>("player")
+//!     .define_behavior(|player, context| { /* ... */ });
+//! engine.context.replace_scene(level);
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use nalgebra_glm as glm;
+use serde::{Deserialize, Serialize};
+
+use crate::components::NodeTransform;
+use crate::context::node_manager::{Node, NodeId, NodeManager};
+use crate::nodes::{Camera3D, DirectionalLight, Empty, Model, ModelError, ModelSource, PointLight};
+use crate::renderer::shadow_map::{self, ShadowQuality};
+use crate::utils::color::Color;
+
+/// Errors returned when saving or loading a scene file.
+#[derive(Debug)]
+pub enum SceneFileError {
+    /// the file could not be read or written.
+    Io(std::io::Error),
+    /// the file is not a valid scene.
+    Format(serde_json::Error),
+    /// a model in the scene points to a file that does not exist.
+    MissingModel(String),
+    /// a model file of the scene exists but could not be loaded.
+    Model {
+        /// the path of the model file.
+        path: String,
+        /// why it could not be loaded.
+        error: ModelError,
+    },
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(error) => write!(f, "failed to access scene file: {}", error),
+            SceneFileError::Format(error) => write!(f, "invalid scene file: {}", error),
+            SceneFileError::MissingModel(path) => write!(f, "model file \"{}\" not found", path),
+            SceneFileError::Model { path, error } => {
+                write!(f, "failed to load model \"{}\": {}", path, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl From<std::io::Error> for SceneFileError {
+    fn from(error: std::io::Error) -> Self {
+        SceneFileError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SceneFileError {
+    fn from(error: serde_json::Error) -> Self {
+        SceneFileError::Format(error)
+    }
+}
+
+/// the contents of a scene file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneData {
+    /// the name of the active camera (empty uses the first camera)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub active_camera: String,
+    /// the root nodes in update order
+    #[serde(default)]
+    pub nodes: Vec<NodeData>,
+}
+
+/// a saved node and its children
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeData {
+    /// the name of the node in its parent
+    pub name: String,
+    /// the [`NodeId`] of the node, loading gives it back so saved ids keep pointing at the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// the type of the node and its settings
+    #[serde(flatten)]
+    pub kind: NodeKind,
+    /// the local transform of the node
+    #[serde(default)]
+    pub transform: TransformData,
+    /// if the node is drawn, see [`Node::set_enabled`]
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// if the node runs its behavior, see [`Node::set_active`]
+    #[serde(default = "default_true")]
+    pub active: bool,
+    /// the children in update order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<NodeData>,
+}
+
+/// a [`NodeTransform`] without the cached matrix
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransformData {
+    /// the position
+    pub position: [f32; 3],
+    /// the rotation quaternion as `[x, y, z, w]`
+    pub rotation: [f32; 4],
+    /// the scale
+    pub scale: [f32; 3],
+}
+
+impl Default for TransformData {
+    fn default() -> Self {
+        TransformData {
+            position: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+impl From<&NodeTransform> for TransformData {
+    fn from(transform: &NodeTransform) -> Self {
+        let rotation = transform.rotation.coords;
+        TransformData {
+            position: transform.position.into(),
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+impl From<TransformData> for NodeTransform {
+    fn from(data: TransformData) -> Self {
+        let [x, y, z, w] = data.rotation;
+        NodeTransform::new(
+            data.position.into(),
+            glm::quat_normalize(&glm::Quat::new(w, x, y, z)),
+            data.scale.into(),
+        )
+    }
+}
+
+/// the type of a saved node and the settings needed to create it again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NodeKind {
+    /// an [`Empty`] node
+    Empty,
+    /// a [`Camera3D`]
+    Camera3D {
+        /// the field of view in radians
+        fov: f32,
+        /// the aspect ratio
+        aspect_ratio: f32,
+        /// the near plane
+        near: f32,
+        /// the far plane
+        far: f32,
+        /// if the camera can be moved with the input
+        movement_enabled: bool,
+        /// the look sensitivity
+        look_sensitivity: f32,
+        /// the movement speed
+        move_speed: f32,
+    },
+    /// a [`PointLight`]
+    PointLight {
+        /// the color of the light
+        color: Color,
+        /// the shader intensity
+        intensity: f32,
+        /// the near plane of the shadow
+        near_plane: f32,
+        /// the far plane of the shadow
+        far_plane: f32,
+        /// the resolution of every face of the shadow cube map
+        shadow_resolution: u32,
+        /// the samples of the soft shadow
+        shadow_samples: u32,
+        /// the filtering of the shadow
+        shadow_quality: ShadowQuality,
+        /// the `[constant, linear, quadratic]` falloff
+        attenuation: [f32; 3],
+        /// the range, 0 for no limit
+        range: f32,
+        /// the depth bias, `None` uses the engine default
+        #[serde(default)]
+        shadow_bias: Option<f32>,
+        /// the normal bias, `None` uses the engine default
+        #[serde(default)]
+        normal_bias: Option<f32>,
+    },
+    /// a [`DirectionalLight`], the direction is the rotation of the transform
+    DirectionalLight {
+        /// the color of the light
+        color: Color,
+        /// the intensity of the light
+        intensity: f32,
+        /// the distance covered by the shadow
+        shadow_distance: f32,
+        /// the resolution of the shadow map
+        shadow_resolution: u32,
+        /// the filtering of the shadow
+        shadow_quality: ShadowQuality,
+        /// the depth bias, `None` uses the engine default
+        #[serde(default)]
+        shadow_bias: Option<f32>,
+        /// the normal bias, `None` uses the engine default
+        #[serde(default)]
+        normal_bias: Option<f32>,
+        /// how the shadow distance is split into cascades
+        #[serde(default = "default_cascade_lambda")]
+        cascade_lambda: f32,
+    },
+    /// a [`Model`]
+    Model {
+        /// what the model is loaded from
+        source: ModelSource,
+        /// if the model casts shadows
+        cast_shadows: bool,
+        /// if the model is lit
+        has_lighting: bool,
+        /// the color multiplied into the model
+        #[serde(default)]
+        tint: Option<Color>,
+    },
+}
+
+fn default_cascade_lambda() -> f32 {
+    shadow_map::DEFAULT_CASCADE_LAMBDA
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SceneData {
+    /// collects the nodes of a node manager, nodes that can't be saved are skipped with a warning
+    ///
+    /// # Arguments
+    /// - `nodes` - the node manager to save
+    pub fn from_nodes(nodes: &mut NodeManager) -> SceneData {
+        SceneData {
+            active_camera: nodes.active_camera.clone(),
+            nodes: collect_nodes(nodes),
+        }
+    }
+
+    /// creates the nodes of the scene. needs an opengl context for the lights and models.
+    ///
+    /// # Returns
+    /// the node manager with the scene, [`SceneFileError::MissingModel`] if a model file does not exist or
+    /// [`SceneFileError::Model`] if it can't be loaded
+    pub fn into_nodes(self) -> Result<NodeManager, SceneFileError> {
+        let mut nodes = NodeManager::new();
+        add_nodes(&mut nodes, self.nodes)?;
+        if nodes.get::<Camera3D>(&self.active_camera).is_some() {
+            nodes.active_camera = self.active_camera;
+        }
+        Ok(nodes)
+    }
+
+    /// reads a scene from a json string
+    ///
+    /// # Arguments
+    /// - `json` - the contents of a scene file
+    pub fn from_json(json: &str) -> Result<SceneData, SceneFileError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// writes the scene as a pretty printed json string
+    pub fn to_json(&self) -> Result<String, SceneFileError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// saves the nodes of a node manager to a file, see [`NodeManager::save`]
+pub(crate) fn save(nodes: &mut NodeManager, path: &str) -> Result<(), SceneFileError> {
+    let json = SceneData::from_nodes(nodes).to_json()?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// loads the nodes from a file, see [`NodeManager::load_from_file`]
+pub(crate) fn load(path: &str) -> Result<NodeManager, SceneFileError> {
+    let json = std::fs::read_to_string(path)?;
+    SceneData::from_json(&json)?.into_nodes()
+}
+
+fn collect_nodes(nodes: &mut NodeManager) -> Vec<NodeData> {
+    let mut data = Vec::new();
+    for name in nodes.ordered_names() {
+        let id = nodes.get_id(&name).map(|id| id.as_u64());
+        let Some(node) = nodes.get_dyn(&name) else {
+            continue;
+        };
+        match node_kind(node) {
+            Ok(kind) => data.push(NodeData {
+                transform: TransformData::from(&*node.get_transform()),
+                enabled: node.is_enabled(),
+                active: node.is_active(),
+                children: collect_nodes(node.get_children()),
+                name,
+                id,
+                kind,
+            }),
+            Err(reason) => log::warn!(
+                "skipped node \"{}\" while saving the scene: {}",
+                name,
+                reason
+            ),
+        }
+    }
+    data
+}
+
+fn node_kind(node: &mut dyn Node) -> Result<NodeKind, &'static str> {
+    let node = node.as_any_mut();
+    if node.is::<Empty>() {
+        return Ok(NodeKind::Empty);
+    }
+    if let Some(camera) = node.downcast_ref::<Camera3D>() {
+        return Ok(NodeKind::Camera3D {
+            fov: camera.fov,
+            aspect_ratio: camera.get_aspect_ratio(),
+            near: camera.get_near(),
+            far: camera.get_far(),
+            movement_enabled: camera.movement_enabled,
+            look_sensitivity: camera.look_sensitivity,
+            move_speed: camera.move_speed,
+        });
+    }
+    if let Some(light) = node.downcast_ref::<PointLight>() {
+        let (near_plane, far_plane) = light.get_shadow_planes();
+        let (constant, linear, quadratic) = light.get_attenuation();
+        let (shadow_bias, normal_bias) = light.get_shadow_bias_override();
+        return Ok(NodeKind::PointLight {
+            color: light.get_color().into(),
+            intensity: light.get_intensity(),
+            near_plane,
+            far_plane,
+            shadow_resolution: light.get_shadow_resolution(),
+            shadow_samples: light.get_shadow_samples(),
+            shadow_quality: light.get_shadow_quality(),
+            attenuation: [constant, linear, quadratic],
+            range: light.get_range(),
+            shadow_bias,
+            normal_bias,
+        });
+    }
+    if let Some(light) = node.downcast_ref::<DirectionalLight>() {
+        let (shadow_bias, normal_bias) = light.get_shadow_bias_override();
+        return Ok(NodeKind::DirectionalLight {
+            color: light.color.into(),
+            intensity: light.intensity,
+            shadow_distance: light.get_far_plane(),
+            shadow_resolution: light.get_shadow_resolution(),
+            shadow_quality: light.get_shadow_quality(),
+            shadow_bias,
+            normal_bias,
+            cascade_lambda: light.get_cascade_lambda(),
+        });
+    }
+    if let Some(model) = node.downcast_ref::<Model>() {
+        let source = model
+            .get_source()
+            .ok_or("the model was not loaded from a primitive or file")?;
+        return Ok(NodeKind::Model {
+            source: source.clone(),
+            cast_shadows: model.get_casts_shadows(),
+            has_lighting: model.get_has_lighting(),
+            tint: model.get_tint().map(Color::from),
+        });
+    }
+    Err("its type can't be saved")
+}
+
+fn add_nodes(nodes: &mut NodeManager, data: Vec<NodeData>) -> Result<(), SceneFileError> {
+    for node in data {
+        let NodeData {
+            name,
+            id,
+            kind,
+            transform,
+            enabled,
+            active,
+            children,
+        } = node;
+        let transform = NodeTransform::from(transform);
+
+        match kind {
+            NodeKind::Empty => insert(nodes, &name, Empty::new(), transform, children)?,
+            NodeKind::Camera3D {
+                fov,
+                aspect_ratio,
+                near,
+                far,
+                movement_enabled,
+                look_sensitivity,
+                move_speed,
+            } => {
+                let mut camera = Camera3D::new(fov, aspect_ratio, near, far);
+                camera.movement_enabled = movement_enabled;
+                camera.look_sensitivity = look_sensitivity;
+                camera.move_speed = move_speed;
+                insert(nodes, &name, camera, transform, children)?;
+            }
+            NodeKind::PointLight {
+                color,
+                intensity,
+                near_plane,
+                far_plane,
+                shadow_resolution,
+                shadow_samples,
+                shadow_quality,
+                attenuation: [constant, linear, quadratic],
+                range,
+                shadow_bias,
+                normal_bias,
+            } => {
+                let mut light = PointLight::new(near_plane, far_plane, shadow_resolution);
+                light
+                    .set_color(color.into())
+                    .set_intensity(intensity)
+                    .set_shadow_samples(shadow_samples)
+                    .set_shadow_quality(shadow_quality)
+                    .set_attenuation(constant, linear, quadratic)
+                    .set_range(range);
+                if let Some(bias) = shadow_bias {
+                    light.set_shadow_bias(bias);
+                }
+                if let Some(bias) = normal_bias {
+                    light.set_normal_bias(bias);
+                }
+                insert(nodes, &name, light, transform, children)?;
+            }
+            NodeKind::DirectionalLight {
+                color,
+                intensity,
+                shadow_distance,
+                shadow_resolution,
+                shadow_quality,
+                shadow_bias,
+                normal_bias,
+                cascade_lambda,
+            } => {
+                let mut light =
+                    DirectionalLight::new(shadow_distance, shadow_resolution, cascade_lambda);
+                light
+                    .set_color(color)
+                    .set_intensity(intensity)
+                    .set_shadow_quality(shadow_quality);
+                if let Some(bias) = shadow_bias {
+                    light.set_shadow_bias(bias);
+                }
+                if let Some(bias) = normal_bias {
+                    light.set_normal_bias(bias);
+                }
+                *light.get_transform() = transform;
+                // rebuild the shadow projection from the saved rotation
+                let direction = light.get_direction();
+                light.set_direction(direction);
+                insert(nodes, &name, light, transform, children)?;
+            }
+            NodeKind::Model {
+                source,
+                cast_shadows,
+                has_lighting,
+                tint,
+            } => {
+                let mut model = match source {
+                    ModelSource::Primitive(primitive) => Model::new_primitive(primitive),
+                    ModelSource::File { path, up_axis } => {
+                        if !Path::new(&path).exists() {
+                            return Err(SceneFileError::MissingModel(path));
+                        }
+                        let model = if up_axis == Default::default() {
+                            Model::load(&path)
+                        } else {
+                            Model::new_gltf_with_options(
+                                &path,
+                                crate::nodes::ImportOptions {
+                                    up_axis,
+                                    ..Default::default()
+                                },
+                            )
+                        };
+                        model.map_err(|error| SceneFileError::Model { path, error })?
+                    }
+                };
+                model
+                    .casts_shadows(cast_shadows)
+                    .has_lighting(has_lighting)
+                    .set_tint(tint.map(Into::into));
+                insert(nodes, &name, model, transform, children)?;
+            }
+        }
+
+        if let Some(node) = nodes.get_dyn(&name) {
+            node.set_enabled(enabled);
+            node.set_active(active);
+        }
+        if let Some(id) = id {
+            nodes.restore_id(&name, NodeId::from_u64(id));
+        }
+    }
+    Ok(())
+}
+
+fn insert<T: Node + 'static>(
+    nodes: &mut NodeManager,
+    name: &str,
+    mut node: T,
+    transform: NodeTransform,
+    children: Vec<NodeData>,
+) -> Result<(), SceneFileError> {
+    *node.get_transform() = transform;
+    add_nodes(node.get_children(), children)?;
+    nodes.add(name, node);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::model::Primitive;
+
+    #[test]
+    fn test_scene_json_round_trip() {
+        let mut transform = NodeTransform::default();
+        transform
+            .set_position(glm::vec3(1.0, 2.0, 3.0))
+            .rotate(glm::vec3(0.0, 1.0, 0.0), 90.0);
+
+        let scene = SceneData {
+            active_camera: "camera".to_string(),
+            nodes: vec![NodeData {
+                name: "crate".to_string(),
+                id: Some(7),
+                kind: NodeKind::Model {
+                    source: ModelSource::Primitive(Primitive::Cube),
+                    cast_shadows: true,
+                    has_lighting: false,
+                    tint: None,
+                },
+                transform: TransformData::from(&transform),
+                enabled: true,
+                active: false,
+                children: vec![NodeData {
+                    name: "pivot".to_string(),
+                    id: None,
+                    kind: NodeKind::Empty,
+                    transform: TransformData::default(),
+                    enabled: false,
+                    active: true,
+                    children: Vec::new(),
+                }],
+            }],
+        };
+
+        let json = scene.to_json().unwrap();
+        assert!(json.contains("\"type\": \"Model\""));
+        assert_eq!(SceneData::from_json(&json).unwrap(), scene);
+
+        let restored = NodeTransform::from(scene.nodes[0].transform);
+        assert!((restored.position - transform.position).magnitude() < 1e-6);
+        assert!((restored.rotation.coords - transform.rotation.coords).magnitude() < 1e-6);
+
+        // missing transforms and children fall back to the defaults
+        let minimal =
+            SceneData::from_json(r#"{"nodes": [{"name": "a", "type": "Empty"}]}"#).unwrap();
+        assert_eq!(minimal.nodes[0].transform, TransformData::default());
+        assert!(minimal.nodes[0].enabled && minimal.nodes[0].active);
+        assert_eq!(minimal.nodes[0].id, None);
+    }
+}
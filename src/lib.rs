@@ -1,454 +1,1357 @@
-#![doc = include_str!("../README.md")]
-#![warn(missing_docs)]
-pub use nalgebra_glm as glm; // Importing the nalgebra_glm crate for mathematical operations
-
-//re-exporting the engine module
-pub use egui_gl_glfw::egui;
-pub use egui_gl_glfw::glfw;
-
-use egui_gl_glfw::glfw::Context;
-use renderer::shader;
-
-use crate::nodes::{Camera3D, DirectionalLight, Model, PointLight, UI};
-use context::node_manager::{Drawable, Node, NodeManager};
-use renderer::shader::Shader;
-use renderer::Renderer;
-
-use components::NodeTransform;
-
-pub mod components;
-pub mod context;
-pub mod nodes;
-pub mod renderer;
-pub mod utils;
-
-use context::GameContext;
-
-/// Represents the main game engine.
-///
-/// The Enigne is responsible for managing the game loop and rendering the scene.
-pub struct Engine {
-    /// The game context such as the frame, input, nodes, and shaders.
-    pub context: GameContext,
-    // /// The shadow map used for rendering shadows.
-    //pub shadow_map: Option<renderer::shadow_map::ShadowMap>,
-}
-
-/// The number of samples for anti-aliasing.
-const SAMPLES: u32 = 8;
-
-impl Engine {
-    /// Initializes the game engine.
-    ///
-    /// # Arguments
-    /// - `window_title`: The title of the window.
-    /// - `window_width`: The width of the window.
-    /// - `window_height`: The height of the window.
-    ///
-    /// # Returns
-    /// A new instance of the Engine.
-    ///
-    /// # Example
-    /// ```rust
-    /// use quaturn::Engine;
-    /// let mut engine = Engine::init("My Game", 800, 600);
-    /// ```
-    pub fn init(window_title: &str, window_width: u32, window_height: u32) -> Engine {
-        use glfw::fail_on_errors;
-        let mut glfw = glfw::init(fail_on_errors!()).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-            glfw::OpenGlProfileHint::Core,
-        ));
-        glfw.window_hint(glfw::WindowHint::DoubleBuffer(true));
-        glfw.window_hint(glfw::WindowHint::Resizable(false));
-        glfw.window_hint(glfw::WindowHint::Samples(Some(SAMPLES)));
-
-        let (mut window, events) = glfw
-            .create_window(
-                window_width,
-                window_height,
-                window_title,
-                glfw::WindowMode::Windowed,
-            )
-            .expect("Failed to create GLFW window.");
-
-        //set up input polling
-        window.set_key_polling(true);
-        window.set_cursor_pos_polling(true);
-        window.set_mouse_button_polling(true);
-        window.set_scroll_polling(true);
-        window.set_framebuffer_size_polling(true);
-        window.make_current();
-
-        //load grahpics api
-        Renderer::context(&mut window);
-
-        glfw.set_swap_interval(glfw::SwapInterval::None);
-
-        Renderer::init();
-
-        Engine {
-            context: GameContext::new(events, glfw, window),
-            //shadow_map: None,
-        }
-    }
-
-    pub fn set_window_title(&mut self, title: &str) {
-        self.context.window.set_title(title);
-    }
-
-    /// sets the clear color of the window.
-    ///
-    /// the renderer clears the screen before rendering the next frame with the color set here.
-    /// # Arguments
-    /// - `r`: The red value of the color.
-    /// - `g`: The green value of the color.
-    /// - `b`: The blue value of the color.
-    /// - `a`: The alpha value of the color.
-    ///
-    /// # Example
-    /// ```rust
-    /// use quaturn::Engine;
-    /// let mut engine = Engine::init("My Game", 800, 600);
-    /// engine.set_clear_color(0.1, 0.1, 0.1, 1.0);
-    /// ```
-    pub fn set_clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
-        Renderer::set_clear_color([r, g, b, a]);
-    }
-
-    /// starts the gamme/render loop.
-    ///
-    /// this function is responsible for rendering the scene and updating the game context.
-    ///
-    /// # Example
-    /// ```rust
-    /// use quaturn::Engine;
-    /// let mut engine = Engine::init("My Game", 800, 600);
-    ///
-    /// //set up the scene
-    ///
-    /// engine.begin();
-    /// ```
-    pub fn begin(&mut self) {
-        {
-            let nodes = &mut self.context.nodes as *mut NodeManager;
-            unsafe {
-                (*nodes).ready(&mut self.context);
-            }
-        }
-
-        if self.context.nodes.active_camera.is_empty() {
-            eprintln!("Warning: No camera found in the scene");
-        }
-
-        if self.context.nodes.active_shader.is_empty() {
-            eprintln!("Warning: No shader found in the scene");
-            self.context.nodes.add_shader("default", Shader::default());
-        }
-
-        // self.shadow_map = Some(renderer::shadow_map::ShadowMap::gen_map(
-        //     8192,
-        //     8192,
-        //     Shader::from_slice(
-        //         "res/shaders/depthShader/depthShader.vert",
-        //         "res/shaders/depthShader/depthShader.frag",
-        //         None,
-        //     ),
-        // ));
-
-        //render loop
-        self.render_loop();
-    }
-
-    /// The main render loop.
-    /// This function is responsible for rendering the scene and updating the game context.
-    /// It is called by the `begin` function.
-    fn render_loop(&mut self) {
-        while !self.context.window.should_close() {
-            Renderer::clear();
-
-            // Update frame and input
-            {
-                let context = &mut self.context;
-                context.frame.update();
-                context.input.update();
-            }
-
-            //note if a node is removed while in these scope it can cause a dangling pointer
-
-            // Update UIs
-            {
-                let nodes = self.context.nodes.get_iter::<UI>();
-
-                //map nodes to raw pointer to borrowed twice
-                let nodes: Vec<*mut UI> = nodes.map(|node| node as *const UI as *mut UI).collect();
-
-                for ui in nodes {
-                    unsafe {
-                        (*ui).update(&mut self.context);
-                    }
-                }
-            }
-
-            {
-                let nodes = &mut self.context.nodes as *mut NodeManager;
-                // SAFETY: we are using raw pointers here because we guarantee
-                // that the nodes vector will not be modified (no adding/removing nodes)
-                // during this iteration instead that is needs to be handled through a queue system
-                unsafe { (*nodes).behavior(&mut self.context) };
-            }
-
-            // Render shadow map
-            {
-                let context = &mut self.context;
-                // let lights: Vec<*mut PointLight> = context
-                //     .nodes
-                //     .get_iter::<PointLight>()
-                //     .map(|light| light as *const PointLight as *mut PointLight)
-                //     .collect();
-
-                let lights: &mut Vec<(*mut PointLight, NodeTransform)> = &mut Vec::new();
-                for node in context.nodes.get_all_mut().values_mut() {
-                    collect_items::<PointLight, *mut PointLight>(
-                        &mut **node,
-                        lights,
-                        NodeTransform::default(),
-                    );
-                }
-
-                //println!("{:?}", lights);
-
-                for (light, transform) in lights {
-                    unsafe {
-                        // SAFETY: we are using raw pointers here because we guarantee
-                        // that the nodes vector will not be modified (no adding/removing nodes)
-                        // during this iteration instead that is needs to be handled through a queue system
-                        let nodes = context.nodes.get_all_mut();
-
-                        //println!("{:?}, {:?}", light, transform);
-
-                        let nodes = nodes.values_mut().collect::<Vec<&mut Box<dyn Node>>>();
-
-                        //println!("{:?}", nodes);
-
-                        // Render shadow map
-                        (**light).render_shadow_map(nodes, *transform);
-
-                        // Bind uniforms
-                        let active_shader = context.nodes.active_shader.clone();
-                        if let Some(shader) = context.nodes.shaders.get_mut(&active_shader) {
-                            (**light).bind_uniforms(shader);
-                        }
-                    }
-                }
-            }
-
-            //reset viewport
-            Renderer::viewport(
-                self.context.window.get_framebuffer_size().0,
-                self.context.window.get_framebuffer_size().1,
-            );
-
-            // Draw models
-            {
-                let context = &mut self.context;
-
-                let active_shader = context.nodes.active_shader.clone();
-                let active_camera = context.nodes.active_camera.clone();
-
-                // // collect all the models
-                // let nodes: &mut Vec<*mut Model> = &mut Vec::new();
-                // for node in context.nodes.get_all_mut().values_mut() {
-                //     collect_items::<Model, *mut Model>(&mut **node, nodes);
-                // }
-
-                let camera_path = context.active_camera_path.clone();
-                let camera = traverse_camera_path(context, camera_path);
-
-                // if let Some(camera) = camera {
-                //     // sort models by distance to camera so that they are drawn in the correct order
-                //     nodes.sort_by(|a, b| {
-                //         let a_distance: f32;
-                //         let b_distance: f32;
-                //         unsafe {
-                //             a_distance = glm::distance2(
-                //                 (**a).transform.get_position(),
-                //                 &camera.get_position(),
-                //             ); // Using squared distance for efficiency
-                //             b_distance = glm::distance2(
-                //                 (**b).transform.get_position(),
-                //                 &camera.get_position(),
-                //             ); // Using squared distance for efficiency
-                //         }
-                //         b_distance
-                //             .partial_cmp(&a_distance)
-                //             .unwrap_or(std::cmp::Ordering::Equal)
-                //     });
-                // }
-
-                // Draw the model
-                // we use raw pointers here because taking ownership means we need to allocate memory which takes longer and in realtime rendering every ns counts
-                if let Some((camera, parent_transform)) = camera {
-                    let camera_ptr = camera as *const Camera3D as *mut Camera3D;
-                    let shader_ptr = context
-                        .nodes
-                        .shaders
-                        .get_mut(&active_shader)
-                        .map(|s| &mut **s as *mut Shader);
-
-                    if let Some(shader_ptr) = shader_ptr {
-                        for node in self.context.nodes.get_all_mut() {
-                            draw_node(
-                                &mut **node.1,
-                                NodeTransform::default(),
-                                shader_ptr,
-                                (camera_ptr, parent_transform),
-                            );
-                        }
-                    }
-                }
-            }
-
-            // Render UIs
-            {
-                let nodes = self.context.nodes.get_iter::<UI>();
-
-                //map nodes to raw pointer to borrowed twice
-                let nodes: Vec<*mut UI> = nodes.map(|node| node as *const UI as *mut UI).collect();
-
-                for ui in nodes {
-                    unsafe {
-                        // SAFETY: we are using raw pointers here because we guarantee
-                        // that the nodes vector will not be modified (no adding/removing nodes)
-                        // during this iteration instead that is needs to be handled through a queue system
-                        (*ui).render(&mut self.context)
-                    }
-                }
-            }
-
-            self.context.window.swap_buffers();
-            //std::thread::sleep(std::time::Duration::from_millis(10)); //sleep for 1ms
-        }
-    }
-}
-
-/// Collects all the models in the scene for rendering.
-fn collect_models<T>(node: &mut dyn Node, models: &mut Vec<T>)
-where
-    T: From<&'static mut Model>,
-{
-    // Check if the current node is a Model
-    if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
-        models.push(T::from(unsafe { &mut *(model as *mut _) }));
-    }
-
-    // Recursively collect models from children
-    for child in node.get_children().get_all_mut().values_mut() {
-        let child_node: &mut dyn Node = &mut **child;
-        collect_models(child_node, models);
-    }
-}
-
-fn collect_lights<T>(
-    node: &mut dyn Node,
-    lights: &mut Vec<(T, NodeTransform)>,
-    parent_transform: NodeTransform,
-) where
-    T: From<&'static mut PointLight>,
-{
-    let world_transform = parent_transform + *node.get_transform();
-    if let Some(light) = node.as_any_mut().downcast_mut::<PointLight>() {
-        lights.push((T::from(unsafe { &mut *(light as *mut _) }), world_transform));
-    }
-
-    for child in node.get_children().get_all_mut().values_mut() {
-        let child_node: &mut dyn Node = &mut **child;
-        collect_lights(child_node, lights, world_transform);
-    }
-}
-
-fn collect_items<N, T>(
-    node: &mut dyn Node,
-    items: &mut Vec<(T, NodeTransform)>,
-    parent_transform: NodeTransform,
-) where
-    T: From<&'static mut N>,
-    N: 'static,
-{
-    let world_transform = parent_transform + *node.get_transform();
-    // Check if the current node matches the target type `N`
-    if let Some(target) = node.as_any_mut().downcast_mut::<N>() {
-        // Use `unsafe` to extend the lifetime as static (assuming safe usage)
-        items.push((
-            T::from(unsafe { &mut *(target as *mut _) }),
-            world_transform,
-        ));
-    }
-
-    // Recursively collect items from children
-    for child in node.get_children().get_all_mut().values_mut() {
-        let child_node: &mut dyn Node = &mut **child;
-        collect_items::<N, T>(child_node, items, world_transform);
-    }
-}
-
-/// Converts a mutable reference to a Model to a raw pointer.
-impl From<&'static mut Model> for *mut Model {
-    fn from(model: &'static mut Model) -> Self {
-        model as *mut Model
-    }
-}
-
-impl From<&'static mut PointLight> for *mut PointLight {
-    fn from(light: &'static mut PointLight) -> Self {
-        light as *mut PointLight
-    }
-}
-
-fn draw_node(
-    node: &mut dyn Node,
-    parent_transform: NodeTransform,
-    shader_ptr: *mut Shader,
-    camera_ptr: (*mut Camera3D, NodeTransform),
-) {
-    let world_transform = parent_transform + *node.get_transform();
-
-    if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
-        unsafe {
-            model.draw(
-                &mut *shader_ptr,
-                (&*(camera_ptr.0), camera_ptr.1),
-                world_transform,
-            );
-        }
-    }
-
-    for child in node.get_children() {
-        draw_node(&mut **child.1, world_transform, shader_ptr, camera_ptr);
-    }
-}
-
-fn traverse_camera_path(
-    context: &mut GameContext,
-    camera_path: Vec<String>,
-) -> Option<(&mut Camera3D, NodeTransform)> {
-    // Early return if path is empty
-    if camera_path.is_empty() {
-        return None;
-    }
-
-    let mut current_node = context.nodes.get_dyn(&camera_path[0])?;
-    let mut current_transform = NodeTransform::default();
-
-    for index in &camera_path[1..] {
-        current_transform = current_transform + *current_node.get_transform();
-        current_node = current_node.get_children().get_dyn(&index)?;
-    }
-
-    if let Some(camera) = current_node.as_any_mut().downcast_mut::<Camera3D>() {
-        Some((camera, current_transform))
-    } else {
-        None
-    }
-}
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+pub use nalgebra_glm as glm; // Importing the nalgebra_glm crate for mathematical operations
+
+// re-exported so uniform buffer types can implement Pod
+pub use bytemuck;
+
+//re-exporting the engine module
+pub use egui_gl_glfw::egui;
+pub use egui_gl_glfw::glfw;
+
+use egui_gl_glfw::glfw::Context;
+use renderer::shader;
+
+use crate::audio::AudioListener;
+use crate::nodes::directional_light::{self, DirectionalLightPass};
+use crate::nodes::model::MeshDraw;
+use crate::nodes::point_light::{self, PointLightPass};
+use crate::nodes::{
+    AudioSource, Camera3D, DirectionalLight, Model, PointLight, Sprite, Text, UILayer, UI,
+};
+use context::node_manager::{Node, NodeManager};
+use renderer::draw_queue::DrawQueue;
+use renderer::post_process::PostProcessChain;
+use renderer::shader::Shader;
+use renderer::sprite_batch::SpriteBatch;
+use renderer::Renderer;
+
+use components::{Frustum, NodeTransform};
+
+pub mod audio;
+pub mod components;
+pub mod context;
+pub mod nodes;
+pub mod renderer;
+pub mod utils;
+
+use context::GameContext;
+
+/// Represents the main game engine.
+///
+/// The Enigne is responsible for managing the game loop and rendering the scene.
+pub struct Engine {
+    /// The game context such as the frame, input, nodes, and shaders.
+    pub context: GameContext,
+    /// The post process passes ran after the scene is rendered.
+    post_process: PostProcessChain,
+    /// Draws the sprites through the 2D camera.
+    sprite_batch: SpriteBatch,
+    /// If watched shaders are reloaded when their files change (debug builds only).
+    shader_hot_reload: bool,
+    /// How the models are rasterized.
+    render_mode: renderer::PolygonMode,
+    /// If the ready methods ran and the scene is set up for rendering.
+    started: bool,
+    /// The layer a frame capture waits for, `Some(true)` to capture after the overlay UI.
+    capture_request: Option<bool>,
+    /// The frame captured for [`Engine::capture_frame`].
+    captured_frame: Option<image::RgbaImage>,
+    /// The point lights skipped last frame because of [`MAX_POINT_LIGHTS`](point_light::MAX_POINT_LIGHTS), to warn
+    /// only when it changes.
+    dropped_point_lights: usize,
+    /// The directional lights skipped last frame because of
+    /// [`MAX_DIRECT_LIGHTS`](directional_light::MAX_DIRECT_LIGHTS), to warn only when it changes.
+    dropped_direct_lights: usize,
+    // /// The shadow map used for rendering shadows.
+    //pub shadow_map: Option<renderer::shadow_map::ShadowMap>,
+}
+
+/// The default number of samples for anti-aliasing.
+const DEFAULT_SAMPLES: u32 = 8;
+
+/// The settings the engine window is created with, see [`Engine::init_with_config`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineConfig {
+    /// The title of the window.
+    pub title: String,
+    /// The width of the window.
+    pub width: u32,
+    /// The height of the window.
+    pub height: u32,
+    /// If the window can be resized by the user, see [`Engine::set_resizable`].
+    pub resizable: bool,
+    /// The anti-aliasing samples, see [`Engine::init_with_samples`].
+    pub msaa_samples: u32,
+    /// Creates a hidden window for tests and tools, the frames are rendered with [`Engine::render_frames`] instead of
+    /// [`Engine::begin`] and read back with [`Engine::capture_frame`].
+    pub headless: bool,
+    /// The fixed updates per second, see [`Engine::set_fixed_timestep`].
+    pub fixed_rate: f32,
+    /// The most fixed updates in one frame, see [`Engine::set_fixed_timestep`].
+    pub max_substeps: u32,
+    /// If presenting a frame waits for the monitor, see [`Engine::set_vsync`].
+    pub vsync: bool,
+    /// The most frames per second, see [`Engine::set_frame_cap`].
+    pub frame_cap: Option<u32>,
+    /// If watched shaders are reloaded when their files change, see [`Engine::set_shader_hot_reload`].
+    pub shader_hot_reload: bool,
+    /// The mipmap lod bias of the loaded textures, see [`Engine::set_texture_lod_bias`].
+    pub texture_lod_bias: f32,
+}
+
+impl Default for EngineConfig {
+    /// an 800x600 visible window with 8x anti-aliasing, 60 fixed updates per second and no frame limit
+    fn default() -> Self {
+        EngineConfig {
+            title: "Quaturn".to_string(),
+            width: 800,
+            height: 600,
+            resizable: false,
+            msaa_samples: DEFAULT_SAMPLES,
+            headless: false,
+            fixed_rate: 60.0,
+            max_substeps: 5,
+            vsync: false,
+            frame_cap: None,
+            shader_hot_reload: false,
+            texture_lod_bias: 0.0,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// creates the config of a hidden window without anti-aliasing, so captured frames are the same on every run.
+    ///
+    /// # Arguments
+    /// - `width`: The width of the frames.
+    /// - `height`: The height of the frames.
+    pub fn headless(width: u32, height: u32) -> EngineConfig {
+        EngineConfig {
+            width,
+            height,
+            msaa_samples: 0,
+            headless: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Engine {
+    /// Initializes the game engine with 8x anti-aliasing.
+    ///
+    /// # Arguments
+    /// - `window_title`: The title of the window.
+    /// - `window_width`: The width of the window.
+    /// - `window_height`: The height of the window.
+    ///
+    /// # Returns
+    /// A new instance of the Engine.
+    ///
+    /// # Example
+    /// ```rust
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// ```
+    pub fn init(window_title: &str, window_width: u32, window_height: u32) -> Engine {
+        Self::init_with_samples(window_title, window_width, window_height, DEFAULT_SAMPLES)
+    }
+
+    /// Initializes the game engine with the given number of anti-aliasing (MSAA) samples.
+    ///
+    /// the window is created with this many samples so it can't be changed afterwards. counts above what the gpu supports
+    /// (`GL_MAX_SAMPLES`) are only known once the window exists, the window keeps the count the driver picked for it and
+    /// the render targets are clamped to the supported count with a warning. use [`GameContext::msaa_samples`] to get
+    /// the count that is used.
+    ///
+    /// # Arguments
+    /// - `window_title`: The title of the window.
+    /// - `window_width`: The width of the window.
+    /// - `window_height`: The height of the window.
+    /// - `samples`: The samples per pixel, 0 disables anti-aliasing. lower counts are faster on integrated gpus.
+    ///
+    /// # Returns
+    /// A new instance of the Engine.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init_with_samples("My Game", 800, 600, 4);
+    /// ```
+    pub fn init_with_samples(
+        window_title: &str,
+        window_width: u32,
+        window_height: u32,
+        samples: u32,
+    ) -> Engine {
+        Self::init_with_config(EngineConfig {
+            title: window_title.to_string(),
+            width: window_width,
+            height: window_height,
+            msaa_samples: samples,
+            ..Default::default()
+        })
+    }
+
+    /// Initializes the game engine with the given window settings.
+    ///
+    /// with [`EngineConfig::headless`] the window is never shown, so the renderer can run on CI machines and in
+    /// golden image tests. the scene is set up like usual and the frames are advanced with [`Engine::render_frames`].
+    /// the other settings can be changed later with their setters, like [`Engine::set_vsync`].
+    ///
+    /// # Arguments
+    /// - `config`: The settings of the window.
+    ///
+    /// # Returns
+    /// A new instance of the Engine.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::{Engine, EngineConfig};
+    ///
+    /// let mut engine = Engine::init_with_config(EngineConfig::headless(320, 240));
+    /// // set up the scene
+    /// let frame = engine.capture_frame(false).unwrap();
+    /// frame.save("golden/pyramid.png").unwrap();
+    /// ```
+    pub fn init_with_config(config: EngineConfig) -> Engine {
+        let EngineConfig {
+            title: window_title,
+            width: window_width,
+            height: window_height,
+            resizable,
+            msaa_samples: samples,
+            headless,
+            fixed_rate,
+            max_substeps,
+            vsync,
+            frame_cap,
+            shader_hot_reload,
+            texture_lod_bias,
+        } = config;
+        let window_title = window_title.as_str();
+
+        use glfw::fail_on_errors;
+        let mut glfw = glfw::init(fail_on_errors!()).unwrap();
+        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
+        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+            glfw::OpenGlProfileHint::Core,
+        ));
+        glfw.window_hint(glfw::WindowHint::DoubleBuffer(true));
+        glfw.window_hint(glfw::WindowHint::Resizable(resizable));
+        glfw.window_hint(glfw::WindowHint::Samples(Some(samples)));
+        glfw.window_hint(glfw::WindowHint::Visible(!headless));
+
+        let mut created = glfw.create_window(
+            window_width,
+            window_height,
+            window_title,
+            glfw::WindowMode::Windowed,
+        );
+        if created.is_none() && samples > 0 {
+            // the driver can refuse sample counts it does not support, try again without anti-aliasing
+            log::warn!(
+                "failed to create a window with {} samples, anti-aliasing is disabled",
+                samples
+            );
+            glfw.window_hint(glfw::WindowHint::Samples(Some(0)));
+            created = glfw.create_window(
+                window_width,
+                window_height,
+                window_title,
+                glfw::WindowMode::Windowed,
+            );
+        }
+        let (mut window, events) = created.expect("Failed to create GLFW window.");
+
+        //set up input polling
+        window.set_key_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_scroll_polling(true);
+        window.set_framebuffer_size_polling(true);
+        window.make_current();
+
+        //load grahpics api
+        Renderer::context(&mut window);
+
+        Renderer::init();
+
+        // the limit needs a context so the window is already created, clamp the samples of the render targets
+        let max_samples = Renderer::max_samples();
+        let msaa_samples = Renderer::window_samples().min(max_samples);
+        if samples > max_samples {
+            log::warn!(
+                "{} samples requested but the gpu supports at most {}, using {}",
+                samples,
+                max_samples,
+                msaa_samples
+            );
+        }
+
+        let mut post_process = PostProcessChain::new();
+        post_process.set_samples(msaa_samples);
+
+        let mut context = GameContext::new(events, glfw, window);
+        context.msaa_samples = msaa_samples;
+
+        let mut engine = Engine {
+            context,
+            post_process,
+            shader_hot_reload: false,
+            render_mode: renderer::PolygonMode::Fill,
+            started: false,
+            capture_request: None,
+            captured_frame: None,
+            dropped_point_lights: 0,
+            dropped_direct_lights: 0,
+            sprite_batch: SpriteBatch::new(),
+            //shadow_map: None,
+        };
+        engine.set_fixed_timestep(fixed_rate, max_substeps);
+        engine.set_vsync(vsync);
+        engine.set_frame_cap(frame_cap);
+        engine.set_shader_hot_reload(shader_hot_reload);
+        engine.set_texture_lod_bias(texture_lod_bias);
+        engine
+    }
+
+    pub fn set_window_title(&mut self, title: &str) {
+        self.context.window.set_title(title);
+    }
+
+    /// sets the clear color of the window.
+    ///
+    /// the renderer clears the screen before rendering the next frame with the color set here.
+    /// # Arguments
+    /// - `r`: The red value of the color.
+    /// - `g`: The green value of the color.
+    /// - `b`: The blue value of the color.
+    /// - `a`: The alpha value of the color.
+    ///
+    /// # Example
+    /// ```rust
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_clear_color(0.1, 0.1, 0.1, 1.0);
+    /// ```
+    pub fn set_clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        Renderer::set_clear_color([r, g, b, a]);
+    }
+
+    /// sets the default mipmap lod bias of textures loaded after this call.
+    ///
+    /// positive values make distant textures softer, negative values make them sharper.
+    /// the bias of a single texture can be changed with [`Texture::set_lod_bias`](renderer::texture::Texture::set_lod_bias).
+    /// # Arguments
+    /// - `bias`: The bias added to the mip level (0.0 by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_texture_lod_bias(-0.5);
+    /// ```
+    pub fn set_texture_lod_bias(&self, bias: f32) {
+        renderer::texture::Texture::set_default_lod_bias(bias);
+    }
+
+    /// sets the shadow bias of lights that don't set their own.
+    ///
+    /// a single light can override it with `set_shadow_bias` and `set_normal_bias`.
+    /// see [`set_default_shadow_bias`](renderer::shadow_map::set_default_shadow_bias) for how the values are used.
+    /// # Arguments
+    /// - `bias`: The smallest depth offset in world units (0.0005 by default).
+    /// - `normal_bias`: The extra offset at grazing angles in world units (0.5 by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_shadow_bias(0.001, 0.2);
+    /// ```
+    pub fn set_shadow_bias(&self, bias: f32, normal_bias: f32) {
+        renderer::shadow_map::set_default_shadow_bias(bias, normal_bias);
+    }
+
+    /// switches between windowed, fullscreen and borderless fullscreen.
+    ///
+    /// see [`GameContext::set_window_mode`]. the viewport and the aspect ratio of the main camera follow the new size.
+    /// # Arguments
+    /// - `mode`: The new window mode (windowed by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// use quaturn::context::WindowMode;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_window_mode(WindowMode::BorderlessFullscreen);
+    /// ```
+    pub fn set_window_mode(&mut self, mode: context::WindowMode) {
+        self.context.set_window_mode(mode);
+    }
+
+    /// draws a camera into a part of the window, for split screen or a picture in picture view.
+    ///
+    /// see [`GameContext::add_viewport`]. the aspect ratio of the camera follows the size of its viewport.
+    /// # Arguments
+    /// - `camera_path`: The path of the camera like `"player_1/camera"`.
+    /// - `rect`: The part of the window in fractions of its size.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// use quaturn::renderer::viewport::ViewportRect;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.add_viewport("player_1/camera", ViewportRect::left_half());
+    /// engine.add_viewport("player_2/camera", ViewportRect::right_half());
+    /// ```
+    pub fn add_viewport(&mut self, camera_path: &str, rect: renderer::viewport::ViewportRect) {
+        self.context.add_viewport(camera_path, rect);
+    }
+
+    /// lets the user resize the window by dragging its edges.
+    ///
+    /// the viewport, the aspect ratio of the main camera, the post process targets and the UI follow the new size.
+    /// # Arguments
+    /// - `resizable`: If the window can be resized (false by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_resizable(true);
+    /// ```
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.context.window.set_resizable(resizable);
+    }
+
+    /// sets how many times per second fixed behaviors are called.
+    ///
+    /// nodes that implement [`FixedBehavior`](context::node_manager::FixedBehavior) are called zero or more times
+    /// per frame to keep up with this rate. after a slow frame at most `max_substeps` updates run and the rest of the time
+    /// is dropped so the game does not fall further behind trying to catch up.
+    /// # Arguments
+    /// - `rate`: The fixed updates per second (60 by default).
+    /// - `max_substeps`: The most fixed updates in one frame (5 by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_fixed_timestep(50.0, 4);
+    /// ```
+    pub fn set_fixed_timestep(&mut self, rate: f32, max_substeps: u32) {
+        self.context.frame.set_fixed_rate(rate);
+        self.context.frame.set_max_substeps(max_substeps);
+    }
+
+    /// turns vsync on or off.
+    ///
+    /// with vsync on, presenting a frame waits for the monitor to refresh, so the frame rate matches the refresh rate
+    /// and there is no tearing. it is off by default so the frame rate is not limited.
+    /// # Arguments
+    /// - `enabled`: If vsync is used.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_vsync(true);
+    /// ```
+    pub fn set_vsync(&mut self, enabled: bool) {
+        self.context.window.glfw.set_swap_interval(if enabled {
+            glfw::SwapInterval::Sync(1)
+        } else {
+            glfw::SwapInterval::None
+        });
+    }
+
+    /// limits the frames per second.
+    ///
+    /// the engine sleeps for the rest of each frame, which saves power on laptops where the uncapped loop keeps the gpu busy.
+    /// see [`FPSManager::set_frame_cap`](context::fps_manager::FPSManager::set_frame_cap).
+    /// # Arguments
+    /// - `cap`: The most frames per second, None for no limit (None by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_frame_cap(Some(60));
+    /// ```
+    pub fn set_frame_cap(&mut self, cap: Option<u32>) {
+        self.context.frame.set_frame_cap(cap);
+    }
+
+    /// saves the next rendered frame as a png.
+    ///
+    /// see [`GameContext::save_screenshot`], use that one to take screenshots while the game is running.
+    /// # Arguments
+    /// - `path`: Where the png is written.
+    /// - `include_ui`: If the overlay UI is in the screenshot.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.save_screenshot("thumbnail.png", false);
+    /// ```
+    pub fn save_screenshot(&mut self, path: impl AsRef<std::path::Path>, include_ui: bool) {
+        self.context.save_screenshot(path, include_ui);
+    }
+
+    /// sets how the models are rasterized, e.g. [`PolygonMode::Line`](renderer::PolygonMode::Line) for wireframe.
+    ///
+    /// only the models use the mode. shadows, debug lines, post processing and UI are always filled.
+    /// # Arguments
+    /// - `mode`: The polygon mode of the models (fill by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// use quaturn::renderer::PolygonMode;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_render_mode(PolygonMode::Line);
+    /// ```
+    pub fn set_render_mode(&mut self, mode: renderer::PolygonMode) {
+        self.render_mode = mode;
+    }
+
+    /// reload the scene shaders every frame when their source files change.
+    ///
+    /// only shaders that are watched with [`Shader::watch`](renderer::shader::Shader::watch) or
+    /// [`Shader::watch_files`](renderer::shader::Shader::watch_files) are checked. this only works in debug builds
+    /// so shipped games don't check the disk every frame, in release builds the call does nothing.
+    /// # Arguments
+    /// - `enabled`: If shaders are reloaded (false by default).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    /// engine.set_shader_hot_reload(true);
+    /// ```
+    pub fn set_shader_hot_reload(&mut self, enabled: bool) {
+        self.shader_hot_reload = enabled && cfg!(debug_assertions);
+    }
+
+    /// adds a full screen post process pass to the end of the post process chain.
+    ///
+    /// each pass reads the output of the previous effect (`u_screenTexture`) and the scene depth (`u_depthTexture`).
+    /// see the [post_process](renderer::post_process) module for the details.
+    ///
+    /// # Arguments
+    /// - `shader`: The shader of the pass, usually created with [`post_process_shader`](renderer::post_process::post_process_shader).
+    /// - `uniforms`: Called every frame before the pass is drawn to set custom uniforms.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::renderer::post_process::post_process_shader;
+    ///
+    /// let grayscale = post_process_shader(include_str!("grayscale.frag"));
+    /// engine.add_post_process(grayscale, |shader, _context| {
+    ///     shader.set_uniform("u_strength", 0.8);
+    /// });
+    /// ```
+    pub fn add_post_process<F>(&mut self, shader: Shader, uniforms: F) -> &mut Self
+    where
+        F: FnMut(&mut Shader, &GameContext) + 'static,
+    {
+        self.post_process.add(shader, uniforms);
+        self
+    }
+
+    /// adds an effect to the end of the post process chain.
+    ///
+    /// the scene is rendered into an hdr frame buffer while there are effects, so effects like
+    /// [`Bloom`](renderer::post_process::Bloom) and [`Tonemap`](renderer::post_process::Tonemap) see colors above 1.0.
+    /// the UI is drawn on top of the final image. see the [post_process](renderer::post_process) module for the details.
+    ///
+    /// # Arguments
+    /// - `effect`: The effect, a built in one or your own [`PostProcess`](renderer::post_process::PostProcess).
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use quaturn::renderer::post_process::{Bloom, Gamma, Tonemap};
+    ///
+    /// engine
+    ///     .add_post_effect(Box::new(Bloom::default()))
+    ///     .add_post_effect(Box::new(Tonemap::aces(1.0)))
+    ///     .add_post_effect(Box::new(Gamma::new(2.2)));
+    /// ```
+    pub fn add_post_effect(
+        &mut self,
+        effect: Box<dyn renderer::post_process::PostProcess>,
+    ) -> &mut Self {
+        self.post_process.add_effect(effect);
+        self
+    }
+
+    /// keeps the depth of the main pass in a texture that can be sampled by effects like SSAO, soft particles, or decals.
+    ///
+    /// the scene is rendered offscreen while enabled, into a multisampled frame buffer when the window is anti-aliased.
+    /// # Arguments
+    /// - `enabled`: If the depth texture should be rendered.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// engine.set_depth_texture(true);
+    /// // later, after the first frame
+    /// let depth = engine.depth_texture();
+    /// ```
+    pub fn set_depth_texture(&mut self, enabled: bool) -> &mut Self {
+        self.post_process.set_capture_depth(enabled);
+        self
+    }
+
+    /// gets the depth texture of the last main pass.
+    ///
+    /// # Returns
+    /// the gl texture id or `None` if the depth is not captured, see [`Engine::set_depth_texture`].
+    pub fn depth_texture(&self) -> Option<u32> {
+        self.post_process.depth_texture()
+    }
+
+    /// starts the gamme/render loop.
+    ///
+    /// this function is responsible for rendering the scene and updating the game context.
+    ///
+    /// # Example
+    /// ```rust
+    /// use quaturn::Engine;
+    /// let mut engine = Engine::init("My Game", 800, 600);
+    ///
+    /// //set up the scene
+    ///
+    /// engine.begin();
+    /// ```
+    pub fn begin(&mut self) {
+        self.start();
+
+        //render loop
+        self.render_loop();
+    }
+
+    /// advances the game by a number of frames without waiting for the window to close, then returns.
+    ///
+    /// the ready methods run before the first frame like with [`Engine::begin`]. this is meant for headless engines
+    /// (see [`EngineConfig::headless`]) in tests and tools, a visible window is not shown by the calls. every frame
+    /// advances the game by one [fixed timestep](context::fps_manager::FPSManager::get_fixed_timestep) instead of the
+    /// time that passed and skips the frame cap, so the frames are the same on every run however fast the machine is.
+    ///
+    /// # Arguments
+    /// - `frames`: The number of frames to update and render.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut engine = Engine::init_with_config(EngineConfig::headless(320, 240));
+    /// engine.context.nodes.add("camera", Camera3D::new(45.0, 320.0 / 240.0, 0.1, 100.0));
+    /// engine.render_frames(10);
+    /// ```
+    pub fn render_frames(&mut self, frames: u32) {
+        self.start();
+        for _ in 0..frames {
+            self.poll_events();
+            let delta = self.context.frame.get_fixed_timestep();
+            self.update(delta);
+            self.render();
+        }
+    }
+
+    /// renders a frame and reads it back as an image, for golden image tests of the renderer.
+    ///
+    /// # Arguments
+    /// - `include_ui`: If the overlay UI is in the image.
+    ///
+    /// # Returns
+    /// the frame with the first row at the top, `None` if nothing was drawn because the window is minimized.
+    pub fn capture_frame(&mut self, include_ui: bool) -> Option<image::RgbaImage> {
+        self.capture_request = Some(include_ui);
+        self.render_frames(1);
+        self.capture_request = None;
+        self.captured_frame.take()
+    }
+
+    /// captures the frame for [`Engine::capture_frame`] after the UI layer it was asked for is drawn
+    fn capture(&mut self, with_ui: bool) {
+        if self.capture_request == Some(with_ui) {
+            self.captured_frame = Some(Renderer::capture_frame());
+        }
+    }
+
+    /// runs the ready methods and checks the scene before the first frame, does nothing after that.
+    fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+
+        {
+            let nodes = &mut self.context.nodes as *mut NodeManager;
+            unsafe {
+                (*nodes).ready(&mut self.context);
+            }
+        }
+
+        // changes queued while the scene was set up are applied before the first frame
+        self.context.flush_node_commands();
+
+        if self.context.nodes.active_camera.is_empty() {
+            log::warn!("no camera found in the scene");
+        }
+
+        if self.context.nodes.active_shader.is_empty() {
+            log::warn!("no shader found in the scene, using the default shader");
+            self.context.nodes.add_shader("default", Shader::default());
+        }
+
+        // self.shadow_map = Some(renderer::shadow_map::ShadowMap::gen_map(
+        //     8192,
+        //     8192,
+        //     Shader::from_slice(
+        //         "res/shaders/depthShader/depthShader.vert",
+        //         "res/shaders/depthShader/depthShader.frag",
+        //         None,
+        //     ),
+        // ));
+    }
+
+    /// The main render loop.
+    /// This function is responsible for rendering the scene and updating the game context.
+    /// It is called by the `begin` function.
+    fn render_loop(&mut self) {
+        while !self.context.window.should_close() {
+            self.render_frame();
+        }
+    }
+
+    /// updates the game and renders one frame, skipping the rendering while the window is minimized.
+    fn render_frame(&mut self) {
+        self.poll_events();
+        let delta = self.context.frame.time_since_last_frame();
+        self.update(delta);
+        self.render();
+
+        if self.is_minimized() {
+            // idle instead of spinning while nothing is drawn
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        self.context.frame.wait_for_frame_cap();
+    }
+
+    /// check if the window is minimized, the framebuffer is 0x0 then
+    fn is_minimized(&self) -> bool {
+        let (width, height) = self.context.window.get_framebuffer_size();
+        width <= 0 || height <= 0
+    }
+
+    /// reads the window events into [`GameContext::input`] and passes them to the UIs.
+    ///
+    /// together with [`update`](Engine::update) and [`render`](Engine::render) this is one frame of
+    /// [`begin`](Engine::begin), call the three once per frame in this order to drive the engine from your own loop.
+    /// the ready methods run before the first call of any of them. the UIs start their frame here and finish it in
+    /// `render`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut last_frame = Instant::now();
+    /// while !engine.context.window.should_close() {
+    ///     engine.poll_events();
+    ///     engine.update(last_frame.elapsed());
+    ///     last_frame = Instant::now();
+    ///     engine.render();
+    /// }
+    /// ```
+    pub fn poll_events(&mut self) {
+        self.start();
+
+        {
+            let context = &mut self.context;
+            context.input.update();
+
+            // keep the main camera from stretching when the framebuffer changes size
+            let resized = context
+                .input
+                .events
+                .iter()
+                .rev()
+                .find_map(|(_, event)| match event {
+                    glfw::WindowEvent::FramebufferSize(width, height)
+                        if *width > 0 && *height > 0 =>
+                    {
+                        Some((*width, *height))
+                    }
+                    _ => None,
+                });
+            if let Some((width, height)) = resized {
+                let camera_path = context.active_camera_path.clone();
+                if let Some((camera, _)) = traverse_camera_path(context, camera_path) {
+                    camera.set_aspect_ratio(width as f32 / height as f32);
+                }
+            }
+        }
+
+        //note if a node is removed while in these scope it can cause a dangling pointer, use GameContext::spawn and despawn
+
+        // Update UIs (skipped while minimized since the ui frame is only finished when rendering)
+        if !self.is_minimized() {
+            let nodes = self.context.nodes.get_iter::<UI>();
+
+            //map nodes to raw pointer to borrowed twice
+            let nodes: Vec<*mut UI> = nodes
+                .filter_map(|node| node.is_enabled().then_some(node as *mut UI))
+                .collect();
+
+            for ui in nodes {
+                unsafe {
+                    (*ui).update(&mut self.context);
+                }
+            }
+        }
+    }
+
+    /// advances the game by a time step: runs the fixed behaviors and behaviors, checks the colliders, delivers the
+    /// events and updates the sounds. the nodes spawned and despawned meanwhile are applied at the end.
+    ///
+    /// see [`poll_events`](Engine::poll_events) for driving the engine from your own loop.
+    ///
+    /// # Arguments
+    /// - `delta`: The time since the last update, it becomes `context.frame.time_delta`.
+    pub fn update(&mut self, delta: std::time::Duration) {
+        self.start();
+
+        self.context.frame.update_with_delta(delta);
+        self.context.assets.update();
+
+        {
+            let nodes = &mut self.context.nodes as *mut NodeManager;
+            // SAFETY: we are using raw pointers here because we guarantee
+            // that the nodes vector will not be modified (no adding/removing nodes)
+            // during this iteration instead that is needs to be handled through a queue system
+            for _ in 0..self.context.frame.fixed_steps() {
+                unsafe { (*nodes).fixed_behavior(&mut self.context) };
+            }
+            unsafe { (*nodes).behavior(&mut self.context) };
+        }
+
+        // Find the colliders that started or stopped overlapping
+        self.context.nodes.update_collisions();
+
+        // Deliver the events sent by the behaviors
+        self.context.flush_events();
+
+        // Move the listener to the camera and update the sounds, also while minimized so they keep playing
+        {
+            let context = &mut self.context;
+
+            let camera_path = context.active_camera_path.clone();
+            if let Some((camera, parent_transform)) = traverse_camera_path(context, camera_path) {
+                let camera_transform = parent_transform.compose(camera.get_transform());
+                context.audio.listener = AudioListener::looking_at(
+                    camera_transform.position,
+                    camera_transform.get_forward_vector(),
+                );
+            }
+
+            let audio = &context.audio;
+            context.nodes.visit::<AudioSource>(|source, transform| {
+                source.update_audio(audio, transform.position)
+            });
+        }
+
+        // nothing points into the node tree anymore so queued nodes can be added and removed
+        self.context.flush_node_commands();
+    }
+
+    /// draws the scene, the debug lines and the UIs and presents the frame. nothing is drawn while the window is
+    /// minimized, the logic keeps running in [`update`](Engine::update).
+    ///
+    /// see [`poll_events`](Engine::poll_events) for driving the engine from your own loop.
+    pub fn render(&mut self) {
+        self.start();
+
+        // the framebuffer is 0x0 while the window is minimized, keep the logic running but skip rendering
+        if self.is_minimized() {
+            self.context.debug.clear();
+            return;
+        }
+
+        Renderer::clear();
+
+        if self.shader_hot_reload {
+            for shader in self.context.nodes.shaders.values_mut() {
+                shader.reload_if_changed();
+            }
+        }
+
+        // Render the point light shadow maps
+        {
+            let context = &mut self.context;
+
+            // the lights are copied out of the tree so drawing the tree into their shadow maps doesn't alias them
+            let mut passes: Vec<PointLightPass> = Vec::new();
+            context.nodes.visit::<PointLight>(|light, world_transform| {
+                passes.push(light.shadow_pass(world_transform))
+            });
+
+            // the shader has room for a fixed number of lights, the weakest ones are skipped
+            let dropped =
+                point_light::keep_strongest(&mut passes, point_light::MAX_POINT_LIGHTS, |pass| {
+                    pass.get_intensity()
+                });
+            if dropped != self.dropped_point_lights && dropped > 0 {
+                log::warn!(
+                    "the scene has {} point lights but only the {} strongest are rendered",
+                    dropped + point_light::MAX_POINT_LIGHTS,
+                    point_light::MAX_POINT_LIGHTS
+                );
+            }
+            self.dropped_point_lights = dropped;
+
+            for pass in &mut passes {
+                pass.render(context.nodes.get_all_mut().values_mut());
+            }
+
+            let active_shader = context.nodes.active_shader.clone();
+            if let Some(shader) = context.nodes.shaders.get_mut(&active_shader) {
+                point_light::bind_point_lights(shader, &mut passes);
+            }
+        }
+
+        // Render the directional light shadow maps, split screen renders them for each viewport instead
+        if self.context.viewports.is_empty() {
+            let camera_path = self.context.active_camera_path.clone();
+            self.render_direct_shadows(camera_path);
+        }
+
+        //reset viewport
+        Renderer::viewport(
+            self.context.window.get_framebuffer_size().0,
+            self.context.window.get_framebuffer_size().1,
+        );
+
+        // Render the scene offscreen when there are post process passes
+        {
+            let (width, height) = self.context.window.get_framebuffer_size();
+            self.post_process.begin(width, height);
+        }
+
+        // Render background UIs behind the models
+        render_ui_layer(&mut self.context, UILayer::Background);
+
+        // Draw the scene from the active camera or from every viewport
+        let viewports = self.context.viewports.clone();
+        if viewports.is_empty() {
+            let camera_path = self.context.active_camera_path.clone();
+            self.draw_camera(camera_path);
+        } else {
+            let (width, height) = self.context.window.get_framebuffer_size();
+            for viewport in &viewports {
+                let rect = viewport.rect().to_pixels(width, height);
+                if rect[2] == 0 || rect[3] == 0 {
+                    continue;
+                }
+
+                let camera_path: Vec<String> = viewport
+                    .camera_path()
+                    .split('/')
+                    .map(String::from)
+                    .collect();
+
+                // the camera draws with the aspect of the viewport and gets its own back after
+                let previous_aspect = traverse_camera_path(&mut self.context, camera_path.clone())
+                    .map(|(camera, _)| {
+                        let previous = camera.get_aspect_ratio();
+                        camera.set_aspect_ratio(rect[2] as f32 / rect[3] as f32);
+                        previous
+                    });
+
+                // the shadows of the directional lights are fitted to the view of every camera
+                self.render_direct_shadows(camera_path.clone());
+                self.post_process.rebind();
+
+                // overlapping viewports draw over the earlier ones, keep the background UI
+                Renderer::viewport_rect(Some(rect), width, height);
+                Renderer::clear_depth();
+
+                self.draw_camera(camera_path.clone());
+                Renderer::viewport_rect(None, width, height);
+
+                if let Some(previous) = previous_aspect {
+                    if let Some((camera, _)) = traverse_camera_path(&mut self.context, camera_path)
+                    {
+                        camera.set_aspect_ratio(previous);
+                    }
+                }
+            }
+        }
+        self.context.debug.clear();
+
+        // Run the post process passes
+        self.context.depth_texture = self.post_process.depth_texture();
+        self.post_process.end(&self.context);
+
+        // Draw the sprites and texts on top of the scene
+        if let Some(camera_2d) = self.context.camera_2d {
+            let sprite_batch = &mut self.sprite_batch;
+            self.context
+                .nodes
+                .visit::<Sprite>(|sprite, world_transform| {
+                    sprite_batch.push(sprite, &world_transform)
+                });
+            self.context.nodes.visit::<Text>(|text, world_transform| {
+                text.queue(sprite_batch, &world_transform, &camera_2d)
+            });
+            sprite_batch.render(camera_2d.get_vp_matrix());
+        }
+
+        self.context.take_screenshot(false);
+        self.capture(false);
+
+        // Render overlay UIs
+        render_ui_layer(&mut self.context, UILayer::Overlay);
+
+        self.context.take_screenshot(true);
+        self.capture(true);
+
+        // the UIs can queue nodes too
+        self.context.flush_node_commands();
+
+        self.context.window.swap_buffers();
+    }
+
+    /// draws the models, the skybox and the debug lines from a camera into the current viewport
+    ///
+    /// # Arguments
+    /// - `camera_path` - the names of the nodes from the root to the camera
+    /// fits the directional light shadows to a camera, renders their shadow maps and binds them to the active shader
+    ///
+    /// # Arguments
+    /// - `camera_path` - the path of the camera the shadows are fitted to
+    fn render_direct_shadows(&mut self, camera_path: Vec<String>) {
+        let context = &mut self.context;
+
+        // the camera is copied so the lights can be borrowed from the same tree
+        if let Some((camera, camera_parent)) = traverse_camera_path(context, camera_path)
+            .map(|(camera, parent)| (camera.detached(), parent))
+        {
+            context
+                .nodes
+                .visit::<DirectionalLight>(|light, _| light.fit_to_camera(&camera, camera_parent));
+        }
+
+        // copied out of the tree like the point lights
+        let mut passes: Vec<DirectionalLightPass> = Vec::new();
+        context
+            .nodes
+            .visit::<DirectionalLight>(|light, _| passes.push(light.shadow_pass()));
+
+        // the shader has room for a fixed number of lights, the weakest ones are skipped
+        let dropped = point_light::keep_strongest(
+            &mut passes,
+            directional_light::MAX_DIRECT_LIGHTS,
+            |pass| pass.get_intensity(),
+        );
+        if dropped != self.dropped_direct_lights && dropped > 0 {
+            log::warn!(
+                "the scene has {} directional lights but only the {} strongest are rendered",
+                dropped + directional_light::MAX_DIRECT_LIGHTS,
+                directional_light::MAX_DIRECT_LIGHTS
+            );
+        }
+        self.dropped_direct_lights = dropped;
+
+        for pass in &mut passes {
+            pass.render(context.nodes.get_all_mut().values_mut());
+        }
+
+        let active_shader = context.nodes.active_shader.clone();
+        if let Some(shader) = context.nodes.shaders.get_mut(&active_shader) {
+            directional_light::bind_direct_lights(shader, &passes);
+        }
+    }
+
+    fn draw_camera(&mut self, camera_path: Vec<String>) {
+        // Draw models
+        {
+            let context = &mut self.context;
+
+            let active_shader = context.nodes.active_shader.clone();
+            let active_camera = context.nodes.active_camera.clone();
+
+            let camera = traverse_camera_path(context, camera_path.clone());
+
+            // Draw the model
+            // we use raw pointers here because taking ownership means we need to allocate memory which takes longer and in realtime rendering every ns counts
+            if let Some((camera, parent_transform)) = camera {
+                let frustum = camera.frustum(parent_transform);
+                let camera_position = camera.get_position(parent_transform);
+                let camera_ptr = camera as *const Camera3D as *mut Camera3D;
+                let shader_ptr = context
+                    .nodes
+                    .shaders
+                    .get_mut(&active_shader)
+                    .map(|s| &mut **s as *mut Shader);
+
+                if let Some(shader_ptr) = shader_ptr {
+                    unsafe { self.context.bind_ambient_uniforms(&mut *shader_ptr) };
+
+                    // order the meshes of every model together so blended meshes from different models
+                    // are drawn back to front after all the opaque meshes of their layer. the nodes are queued
+                    // by name so meshes that tie are drawn in the same order every frame
+                    let mut queue = DrawQueue::new();
+                    let mut nodes: Vec<_> = self.context.nodes.get_all_mut().iter_mut().collect();
+                    nodes.sort_by(|a, b| a.0.cmp(b.0));
+                    for node in nodes {
+                        queue_node(
+                            &mut **node.1,
+                            NodeTransform::default(),
+                            &camera_position,
+                            &frustum,
+                            &mut queue,
+                        );
+                    }
+
+                    let mut sky_drawn = false;
+                    let mut depth_ignored = false;
+                    Renderer::set_polygon_mode(self.render_mode);
+                    for queued in queue.drain() {
+                        // the sky fills the pixels the opaque meshes left empty and is seen through blended ones
+                        if !sky_drawn && queued.after_sky() {
+                            sky_drawn = true;
+                            self.draw_skybox(camera_ptr, parent_transform);
+                        }
+
+                        // the layers come in order so once the overlay starts every later mesh ignores depth
+                        if !depth_ignored && queued.layer >= Model::OVERLAY_LAYER {
+                            depth_ignored = true;
+                            unsafe { gl::Disable(gl::DEPTH_TEST) };
+                        }
+
+                        // SAFETY: the node tree is not modified while the queue is drawn
+                        unsafe {
+                            queued.item.model().draw_mesh(
+                                &mut *shader_ptr,
+                                (&*camera_ptr, parent_transform),
+                                &queued.item,
+                            )
+                        };
+                    }
+
+                    if depth_ignored {
+                        unsafe { gl::Enable(gl::DEPTH_TEST) };
+                    }
+                    if !sky_drawn {
+                        self.draw_skybox(camera_ptr, parent_transform);
+                    }
+                    Renderer::set_polygon_mode(renderer::PolygonMode::Fill);
+                }
+            }
+        }
+
+        // Draw debug lines on top of the models
+        let view_projection = traverse_camera_path(&mut self.context, camera_path)
+            .map(|(camera, parent_transform)| camera.get_vp_matrix(parent_transform));
+        if let Some(view_projection) = view_projection {
+            self.context.debug.draw(view_projection);
+        }
+    }
+
+    /// draws the skybox behind what was drawn so far, the polygon mode is set back to the render mode after
+    ///
+    /// # Arguments
+    /// - `camera` - the camera of the viewport
+    /// - `parent_transform` - the world transform of the parent of the camera
+    fn draw_skybox(&mut self, camera: *const Camera3D, parent_transform: NodeTransform) {
+        if let Some(skybox) = self.context.skybox.as_mut() {
+            // SAFETY: the camera is part of the node tree which the skybox does not touch
+            let camera = unsafe { &*camera };
+            Renderer::set_polygon_mode(renderer::PolygonMode::Fill);
+            skybox.render(
+                &camera.get_view_matrix(parent_transform),
+                &camera.get_projection_matrix(),
+            );
+            Renderer::set_polygon_mode(self.render_mode);
+        }
+    }
+}
+
+/// renders the UIs on the given layer
+fn render_ui_layer(context: &mut GameContext, layer: UILayer) {
+    let nodes = context.nodes.get_iter::<UI>();
+
+    //map nodes to raw pointer to borrowed twice
+    let nodes: Vec<*mut UI> = nodes
+        .filter(|node| node.get_layer() == layer)
+        .filter_map(|node| node.is_enabled().then_some(node as *mut UI))
+        .collect();
+
+    for ui in nodes {
+        unsafe {
+            // SAFETY: we are using raw pointers here because we guarantee
+            // that the nodes vector will not be modified (no adding/removing nodes)
+            // during this iteration instead that is needs to be handled through a queue system
+            (*ui).render(context)
+        }
+    }
+}
+
+/// Collects all the models in the scene for rendering.
+fn collect_models<T>(node: &mut dyn Node, models: &mut Vec<T>)
+where
+    T: From<&'static mut Model>,
+{
+    // Check if the current node is a Model
+    if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
+        models.push(T::from(unsafe { &mut *(model as *mut _) }));
+    }
+
+    // Recursively collect models from children
+    for child in node.get_children().get_all_mut().values_mut() {
+        let child_node: &mut dyn Node = &mut **child;
+        collect_models(child_node, models);
+    }
+}
+
+/// Converts a mutable reference to a Model to a raw pointer.
+impl From<&'static mut Model> for *mut Model {
+    fn from(model: &'static mut Model) -> Self {
+        model as *mut Model
+    }
+}
+
+/// queues the meshes of the visible models in the tree for the main pass
+fn queue_node(
+    node: &mut dyn Node,
+    parent_transform: NodeTransform,
+    camera_position: &glm::Vec3,
+    frustum: &Frustum,
+    queue: &mut DrawQueue<MeshDraw>,
+) {
+    if !node.is_enabled() {
+        return;
+    }
+
+    let mut world_transform = parent_transform.compose(node.get_transform());
+
+    if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
+        world_transform = model
+            .get_billboard_mode()
+            .apply(world_transform, camera_position);
+
+        // skip models that are completely off screen (their children are still checked)
+        if frustum.intersects_aabb(&model.get_world_aabb(&world_transform)) {
+            model.queue_meshes(world_transform, camera_position, queue);
+        }
+    }
+
+    for child in node.get_children() {
+        queue_node(
+            &mut **child.1,
+            world_transform,
+            camera_position,
+            frustum,
+            queue,
+        );
+    }
+}
+
+fn traverse_camera_path(
+    context: &mut GameContext,
+    camera_path: Vec<String>,
+) -> Option<(&mut Camera3D, NodeTransform)> {
+    // Early return if path is empty
+    if camera_path.is_empty() {
+        return None;
+    }
+
+    let mut current_node = context.nodes.get_dyn(&camera_path[0])?;
+    let mut current_transform = NodeTransform::default();
+
+    for index in &camera_path[1..] {
+        current_transform = current_transform.compose(current_node.get_transform());
+        current_node = current_node.get_children().get_dyn(&index)?;
+    }
+
+    if let Some(camera) = current_node.as_any_mut().downcast_mut::<Camera3D>() {
+        Some((camera, current_transform))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::Empty;
+    use crate::renderer::depth_cube_map::DepthCubeMap;
+    use crate::renderer::shadow_map::ShadowMap;
+
+    #[test]
+    fn test_light_passes_outlive_tree_changes() {
+        let mut nodes = NodeManager::new();
+        nodes
+            .add("arm", Empty::new())
+            .transform
+            .set_position(glm::vec3(0.0, 3.0, 0.0));
+        let mut lamp = PointLight::with_shadow_map(0.1, 50.0, DepthCubeMap::unloaded(64, 64));
+        lamp.set_intensity(4.0);
+        nodes
+            .get_mut::<Empty>("arm")
+            .unwrap()
+            .children
+            .add("lamp", lamp);
+        let mut sun = DirectionalLight::with_shadow_map(50.0, ShadowMap::unloaded(64, 64), 0.7);
+        sun.set_direction(glm::vec3(1.0, 1.0, 0.0))
+            .set_intensity(2.0);
+        let sun_cascades = sun.shadow_pass().get_cascade_matrices();
+        nodes.add("sun", sun);
+
+        // the engine copies the lights out of the tree before the shadow passes
+        let mut point_passes = Vec::new();
+        nodes.visit::<PointLight>(|light, world_transform| {
+            point_passes.push(light.shadow_pass(world_transform))
+        });
+        let mut direct_passes = Vec::new();
+        nodes.visit::<DirectionalLight>(|light, _| direct_passes.push(light.shadow_pass()));
+
+        // the tree changes while the copies are alive
+        nodes.add("crate", Empty::new());
+        nodes
+            .get_mut::<Empty>("arm")
+            .unwrap()
+            .transform
+            .set_position(glm::vec3(5.0, 0.0, 0.0));
+        nodes.reparent("arm/lamp", "crate").unwrap();
+        nodes
+            .get_mut::<Empty>("crate")
+            .unwrap()
+            .children
+            .get_mut::<PointLight>("lamp")
+            .unwrap()
+            .set_intensity(1.0);
+        let sun = nodes.get_mut::<DirectionalLight>("sun").unwrap();
+        sun.set_direction(glm::vec3(0.0, 1.0, 1.0))
+            .set_intensity(0.5);
+        assert_ne!(sun.shadow_pass().get_cascade_matrices(), sun_cascades);
+        assert!(nodes.remove("sun").is_some());
+
+        // the copies keep the light as it was when they were made
+        assert_eq!(point_passes.len(), 1);
+        assert_eq!(point_passes[0].get_position(), glm::vec3(0.0, 3.0, 0.0));
+        assert_eq!(point_passes[0].get_intensity(), 4.0);
+        assert_eq!(direct_passes.len(), 1);
+        assert_eq!(direct_passes[0].get_intensity(), 2.0);
+        assert_eq!(direct_passes[0].get_cascade_matrices(), sun_cascades);
+
+        // the next frame copies the lights where they are now
+        let mut point_passes = Vec::new();
+        nodes.visit::<PointLight>(|light, world_transform| {
+            point_passes.push(light.shadow_pass(world_transform))
+        });
+        assert!(glm::distance(&point_passes[0].get_position(), &glm::vec3(5.0, 0.0, 0.0)) < 1e-5);
+        assert_eq!(point_passes[0].get_intensity(), 1.0);
+        assert_eq!(nodes.get_iter::<DirectionalLight>().count(), 0);
+    }
+}
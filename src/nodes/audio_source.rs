@@ -0,0 +1,342 @@
+//! AudioSource is a node that plays an [`AudioClip`] at its position in the scene.
+//!
+//! the volume of each ear follows the distance and direction from the [`AudioListener`](crate::audio::AudioListener)
+//! at the active camera, updated by the engine every frame. see the [`audio`](crate::audio) module.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::audio::AudioClip;
+//! use quaturn::nodes::AudioSource;
+//!
+//! let step = AudioClip::load("res/sounds/step.wav").unwrap();
+//!
+//! let mut footsteps = AudioSource::new(step);
+//! footsteps.set_distances(0.5, 15.0);
+//! footsteps.define_behavior(|source, context| {
+//!     if context.input.is_key_pressed(Key::W) && !source.is_playing() {
+//!         source.play();
+//!     }
+//! });
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm as glm;
+use rodio::Sink;
+
+use crate::audio::{Audio, AudioClip, ClipSource, SharedPlayback};
+use crate::components::NodeTransform;
+use crate::context::node_manager::{
+    Behavior, BehaviorCallback, Node, NodeManager, Ready, ReadyCallback,
+};
+use crate::context::GameContext;
+
+use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+
+/// a change to the playback that waits for the next audio update of the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlaybackRequest {
+    Play,
+    Stop,
+}
+
+/// plays a sound at its position in the scene, see the [module docs](self).
+pub struct AudioSource {
+    transform: NodeTransform,
+    children: NodeManager,
+
+    /// the ready callback
+    pub ready_callback: ReadyCallback<AudioSource>,
+    /// the behavior callback
+    pub behavior_callback: BehaviorCallback<AudioSource, GameContext>,
+
+    clip: AudioClip,
+    volume: f32,
+    looping: bool,
+    /// if the sound is positioned in 3D or plays at the same volume everywhere like music
+    spatial: bool,
+    min_distance: f32,
+    max_distance: f32,
+
+    request: Option<PlaybackRequest>,
+    /// the playing sound, dropping it stops the sound
+    sink: Option<Sink>,
+    /// the volumes and looping read by the audio thread
+    shared: Arc<SharedPlayback>,
+}
+
+impl Clone for AudioSource {
+    /// clones the settings of the source, the clone does not share the sound that is playing
+    fn clone(&self) -> Self {
+        let shared = Arc::new(SharedPlayback::default());
+        shared.set_looping(self.looping);
+        AudioSource {
+            transform: self.transform,
+            children: self.children.clone(),
+            ready_callback: self.ready_callback.clone(),
+            behavior_callback: self.behavior_callback.clone(),
+            clip: self.clip.clone(),
+            volume: self.volume,
+            looping: self.looping,
+            spatial: self.spatial,
+            min_distance: self.min_distance,
+            max_distance: self.max_distance,
+            request: self.request,
+            sink: None,
+            shared,
+        }
+    }
+}
+
+impl Ready for AudioSource {
+    fn ready(&mut self) {
+        if let Some(callback) = self.ready_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self);
+            drop(guard);
+            self.ready_callback = Some(callback)
+        }
+    }
+}
+
+impl Behavior for AudioSource {
+    fn behavior(&mut self, context: &mut GameContext) {
+        // take callback out of self so we can use self later
+        if let Some(callback) = self.behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.behavior_callback = Some(callback);
+        }
+    }
+}
+
+impl Node for AudioSource {
+    fn get_transform(&mut self) -> &mut NodeTransform {
+        &mut self.transform
+    }
+
+    fn get_children(&mut self) -> &mut NodeManager {
+        &mut self.children
+    }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
+    fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
+        Some(self)
+    }
+
+    fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
+        Some(self)
+    }
+}
+
+impl AudioSource {
+    /// creates a stopped spatial source that plays at full volume within 1 unit and can be heard up to 50 units away
+    ///
+    /// # Arguments
+    /// - `clip` - the sound to play
+    ///
+    /// # Returns
+    /// the new audio source
+    pub fn new(clip: AudioClip) -> AudioSource {
+        AudioSource {
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            clip,
+            volume: 1.0,
+            looping: false,
+            spatial: true,
+            min_distance: 1.0,
+            max_distance: 50.0,
+            request: None,
+            sink: None,
+            shared: Arc::new(SharedPlayback::default()),
+        }
+    }
+
+    /// plays the clip from the beginning, restarting it if it is already playing. the sound starts at the end of
+    /// the frame.
+    pub fn play(&mut self) {
+        self.request = Some(PlaybackRequest::Play);
+    }
+
+    /// stops the clip at the end of the frame
+    pub fn stop(&mut self) {
+        self.request = Some(PlaybackRequest::Stop);
+    }
+
+    /// if the clip is playing or about to start
+    pub fn is_playing(&self) -> bool {
+        match self.request {
+            Some(PlaybackRequest::Play) => true,
+            Some(PlaybackRequest::Stop) => false,
+            None => self.sink.as_ref().is_some_and(|sink| !sink.empty()),
+        }
+    }
+
+    /// sets if the clip starts over when it ends, also changes the clip that is playing
+    ///
+    /// # Arguments
+    /// - `looping` - if the clip loops
+    pub fn set_looping(&mut self, looping: bool) -> &mut Self {
+        self.looping = looping;
+        self.shared.set_looping(looping);
+        self
+    }
+
+    /// if the clip starts over when it ends
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// sets the volume of the source before the distance falloff
+    ///
+    /// # Arguments
+    /// - `volume` - the volume, 1 plays the clip as it is
+    pub fn set_volume(&mut self, volume: f32) -> &mut Self {
+        self.volume = volume.max(0.0);
+        self
+    }
+
+    /// get the volume of the source before the distance falloff
+    pub fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// sets if the sound comes from the position of the node. non spatial sources play at the same volume on both
+    /// ears wherever the listener is, use them for music and interface sounds.
+    ///
+    /// # Arguments
+    /// - `spatial` - if the sound is positioned in 3D
+    pub fn set_spatial(&mut self, spatial: bool) -> &mut Self {
+        self.spatial = spatial;
+        self
+    }
+
+    /// if the sound comes from the position of the node
+    pub fn is_spatial(&self) -> bool {
+        self.spatial
+    }
+
+    /// sets how the volume falls off with the distance to the listener, see
+    /// [`attenuation`](crate::audio::attenuation)
+    ///
+    /// # Arguments
+    /// - `min_distance` - the distance up to which the sound plays at full volume
+    /// - `max_distance` - the distance where the sound can't be heard anymore
+    pub fn set_distances(&mut self, min_distance: f32, max_distance: f32) -> &mut Self {
+        self.min_distance = min_distance.max(0.0);
+        self.max_distance = max_distance.max(self.min_distance);
+        self
+    }
+
+    /// get the distance up to which the sound plays at full volume and the distance where it can't be heard anymore
+    pub fn get_distances(&self) -> (f32, f32) {
+        (self.min_distance, self.max_distance)
+    }
+
+    /// replaces the clip, the new clip is used from the next [`play`](AudioSource::play)
+    ///
+    /// # Arguments
+    /// - `clip` - the new sound
+    pub fn set_clip(&mut self, clip: AudioClip) -> &mut Self {
+        self.clip = clip;
+        self
+    }
+
+    /// get the clip of the source
+    pub fn get_clip(&self) -> &AudioClip {
+        &self.clip
+    }
+
+    /// updates the volume of each ear from the listener and starts or stops the sound, called by the engine every
+    /// frame.
+    ///
+    /// # Arguments
+    /// - `audio` - the audio output with the listener
+    /// - `position` - the world position of the source
+    pub(crate) fn update_audio(&mut self, audio: &Audio, position: glm::Vec3) {
+        let (left, right) = if self.spatial {
+            audio
+                .listener
+                .gains(&position, self.min_distance, self.max_distance)
+        } else {
+            (1.0, 1.0)
+        };
+        let volume = self.volume * audio.master_volume;
+        self.shared.set_gains(left * volume, right * volume);
+
+        match self.request.take() {
+            Some(PlaybackRequest::Play) => {
+                self.sink = None;
+                let source = ClipSource::new(self.clip.clone(), self.shared.clone());
+                if let (Some(handle), Some(source)) = (audio.handle(), source) {
+                    if let Ok(sink) = Sink::try_new(handle) {
+                        sink.append(source);
+                        self.sink = Some(sink);
+                    }
+                }
+            }
+            Some(PlaybackRequest::Stop) => self.sink = None,
+            None => {}
+        }
+
+        // let go of sounds that played to the end
+        if self.sink.as_ref().is_some_and(|sink| sink.empty()) {
+            self.sink = None;
+        }
+    }
+
+    /// define the ready callback for the node
+    ///
+    /// # Arguments
+    /// - `ready_function` - The function to be called when the node is ready.
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self) + Sync + Send,
+    {
+        self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
+        self
+    }
+
+    /// define the behavior callback for the node
+    ///
+    /// # Arguments
+    /// - `behavior_function` - The function to be called every frame.
+    pub fn define_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
+        self
+    }
+}
+
+impl UseReadyCallback for NodeBuilder<AudioSource> {
+    type Node = AudioSource;
+
+    fn with_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut AudioSource) + Send + Sync,
+    {
+        self.node.define_ready(ready_function);
+        self
+    }
+}
+
+impl UseBehaviorCallback for NodeBuilder<AudioSource> {
+    type Node = AudioSource;
+
+    fn with_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut AudioSource, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_behavior(behavior_function);
+        self
+    }
+}
@@ -27,15 +27,29 @@
 //!
 //! //engine.begin();
 //! ```
+//!
+//! ## Camera shake
+//! [`Camera3D::add_shake`] adds trauma (0 to 1) that decays over time. while there is trauma the view is offset and rotated
+//! by smooth noise scaled by trauma squared, so small hits barely move the camera and big ones stack into a strong shake.
+//! the shake is only applied to the view matrix, the transform of the camera is never changed.
+//!
+//! ```rust,ignore
+//! .define_behavior(|camera, context| {
+//!     if context.input.is_key_just_pressed(Key::E) {
+//!         camera.add_shake(0.5); // explosion
+//!     }
+//! });
+//! ```
 
 extern crate nalgebra_glm as glm;
 use egui_gl_glfw::glfw;
 
-use glfw::Key;
+use glfw::{GamepadAxis, JoystickId, Key};
 
 use std::sync::{Arc, Mutex};
 
-use crate::components::NodeTransform;
+use crate::components::{Frustum, NodeTransform};
+use crate::context::action_map::camera_actions;
 use crate::context::{
     node_manager::{Behavior, Node, NodeManager, Ready},
     GameContext,
@@ -45,7 +59,10 @@ use crate::context::node_manager::{BehaviorCallback, ReadyCallback};
 
 use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
 
-/// A 2D camera that can be used to move around the screen. **Currently work in progress**.
+/// A 2D camera that can be used to move around the screen, the [`Sprite`](crate::nodes::Sprite) nodes are drawn through it.
+///
+/// set it with [`GameContext::set_camera_2d`].
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Camera2D {
     height: f32,
     width: f32,
@@ -94,11 +111,29 @@ impl Camera2D {
         self.width
     }
 
-    /// get the zoom of the camera
+    /// get the position of the camera
     pub fn get_position(&self) -> glm::Vec2 {
         self.position
     }
 
+    /// get the zoom of the camera, bigger values show more of the world
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// get the point of the view at fractions of its size
+    ///
+    /// # Arguments
+    /// - `fraction` - 0 to 1 from the left and from the top of the view
+    pub fn view_point(&self, fraction: glm::Vec2) -> glm::Vec2 {
+        let half_size = glm::vec2(self.width, self.height) * (self.zoom / 2.0);
+        self.position
+            + glm::vec2(
+                (fraction.x * 2.0 - 1.0) * half_size.x,
+                (1.0 - fraction.y * 2.0) * half_size.y,
+            )
+    }
+
     /// set the position of the camera
     pub fn set_position(&mut self, position: glm::Vec2) {
         self.position = position;
@@ -128,6 +163,105 @@ impl Camera2D {
     }
 }
 
+/// the trauma based shake of a camera
+#[derive(Clone, Debug)]
+struct CameraShake {
+    /// the current trauma from 0 to 1
+    trauma: f32,
+    /// how much trauma is removed per second
+    decay: f32,
+    /// the max offset of the view in world units
+    max_offset: f32,
+    /// the max rotation of the view in radians
+    max_angle: f32,
+    /// how fast the noise is sampled (roughly the shakes per second)
+    frequency: f32,
+    /// the time used to sample the noise, only advances while shaking
+    time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        CameraShake {
+            trauma: 0.0,
+            decay: 1.0,
+            max_offset: 0.3,
+            max_angle: 3f32.to_radians(),
+            frequency: 15.0,
+            time: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    fn update(&mut self, delta_time: f32) {
+        if self.trauma <= 0.0 {
+            return;
+        }
+        self.time += delta_time * self.frequency;
+        self.trauma = (self.trauma - self.decay * delta_time).max(0.0);
+    }
+
+    /// the view space offset of the shake (applied after the view matrix)
+    fn matrix(&self) -> glm::Mat4 {
+        if self.trauma <= 0.0 {
+            return glm::Mat4::identity();
+        }
+        let amount = self.trauma * self.trauma;
+        // every axis samples the noise with a different seed so they move independently
+        let offset = glm::vec3(
+            noise(0, self.time),
+            noise(1, self.time),
+            noise(2, self.time),
+        ) * self.max_offset
+            * amount;
+        let yaw = noise(3, self.time) * self.max_angle * amount;
+        let pitch = noise(4, self.time) * self.max_angle * amount;
+        let roll = noise(5, self.time) * self.max_angle * amount;
+
+        glm::translation(&offset)
+            * glm::rotation(roll, &glm::Vec3::z())
+            * glm::rotation(pitch, &glm::Vec3::x())
+            * glm::rotation(yaw, &glm::Vec3::y())
+    }
+}
+
+/// smooth 1d gradient noise from -1 to 1
+fn noise(seed: u32, t: f32) -> f32 {
+    // random gradient from -1 to 1 for each integer point
+    let gradient = |i: i32| {
+        let mut h = (i as u32)
+            .wrapping_mul(0x27d4_eb2d)
+            .wrapping_add(seed.wrapping_mul(0x9e37_79b9));
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        (h & 0xffff) as f32 / 32767.5 - 1.0
+    };
+
+    let i = t.floor();
+    let f = t - i;
+    let a = gradient(i as i32) * f;
+    let b = gradient(i as i32 + 1) * (f - 1.0);
+    let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    // 1d gradient noise peaks at 0.5 so scale it to -1 to 1
+    (a + (b - a) * fade) * 2.0
+}
+
+/// stick values below this are ignored so a worn stick doesn't drift the camera
+const GAMEPAD_DEADZONE: f32 = 0.15;
+/// how fast a fully tilted right stick turns the camera, as the mouse movement it stands in for
+const GAMEPAD_LOOK_SPEED: f32 = 6.0;
+
+/// rescales a stick axis so it starts at 0 at the edge of the deadzone
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < GAMEPAD_DEADZONE {
+        0.0
+    } else {
+        value.signum() * (value.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)
+    }
+}
+
 // pub struct CameraTransform {
 //     pub position: glm::Vec3,
 //     pub orientation: glm::Vec3,
@@ -143,6 +277,8 @@ pub struct Camera3D {
     pub look_sensitivity: f32,
     /// The speed of the camera
     pub move_speed: f32,
+    /// If [`Camera3D::take_input`] reads the actions in [`camera_actions`] instead of the fixed keys
+    pub use_actions: bool,
     /// the NodeTransform of the camera (every node has this)
     pub transform: NodeTransform,
     /// the children of the camera (every node has this)
@@ -159,6 +295,8 @@ pub struct Camera3D {
     pub ready_callback: ReadyCallback<Camera3D>,
     /// the behavior callback
     pub behavior_callback: BehaviorCallback<Camera3D, GameContext>,
+    /// the screen shake applied to the view matrix
+    shake: CameraShake,
 }
 
 impl Ready for Camera3D {
@@ -183,6 +321,8 @@ impl Behavior for Camera3D {
             drop(guard); // delete stupid fucking guard because its stupid and dumb
             self.behavior_callback = Some(callback);
         }
+
+        self.shake.update(context.frame.time_delta.as_secs_f32());
     }
 }
 
@@ -195,6 +335,10 @@ impl Node for Camera3D {
         &mut self.children
     }
 
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
     fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
         Some(self)
     }
@@ -218,24 +362,11 @@ impl Camera3D {
     /// # Returns
     /// A new Camera3D
     pub fn new(fov: f32, aspect_ratio: f32, near: f32, far: f32) -> Camera3D {
-        // println!("Camera created");
-        // println!("Position: {:?}", position);
-        // println!("Orientation: {:?}", orientation);
-        // println!("FOV: {:?}", fov);
-        // println!("Aspect Ratio: {:?}", aspect_ratio);
-        // println!("Near: {:?}", near);
-        // println!("Far: {:?}", far);
-
-        // calculate the rotation quaternion from the orientation vector
-
-        // println!("Rotation Quaternion: {:?}", rotation_quat);
-        // println!("Rotation Axis: {:?}", rotation_axis);
-        // println!("Rotation Angle: {:?}", rotation_angle);
-
         Camera3D {
             movement_enabled: true,
             look_sensitivity: 0.5,
             move_speed: 10.0,
+            use_actions: false,
 
             transform: NodeTransform::default(),
             children: NodeManager::new(),
@@ -247,6 +378,8 @@ impl Camera3D {
 
             ready_callback: None,
             behavior_callback: None,
+
+            shake: CameraShake::default(),
         }
     }
 
@@ -317,6 +450,57 @@ impl Camera3D {
         self.transform.set_rotation(new_rotation.normalize());
     }
 
+    /// turns the camera to look at a point, without any roll
+    ///
+    /// # Arguments
+    /// - `point` - the point to look at, in the space of the camera's parent
+    pub fn look_at(&mut self, point: glm::Vec3) -> &mut Self {
+        self.transform.look_at(point, glm::vec3(0.0, 1.0, 0.0));
+        self
+    }
+
+    /// smoothly moves the camera towards `target + offset` and looks at the target.
+    ///
+    /// the camera closes the same fraction of the distance every second no matter the frame rate, so it eases in
+    /// instead of snapping. call this every frame.
+    ///
+    /// # Arguments
+    /// - `target` - the point to follow, in the space of the camera's parent
+    /// - `offset` - where the camera should sit relative to the target
+    /// - `stiffness` - how fast the camera catches up, higher is tighter (around 5.0 feels like a chase camera)
+    /// - `delta_time` - the time since the last frame in seconds
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // chase the light
+    /// NodeBuilder::new(Camera3D::new(0.78, 16.0 / 9.0, 0.1, 1000.0))
+    ///     .with_behavior(|camera, context| {
+    ///         let Some(light) = context.nodes.get_mut::<PointLight>("light") else {
+    ///             return;
+    ///         };
+    ///         let target = *light.get_transform().get_position();
+    ///         camera.follow(
+    ///             target,
+    ///             glm::vec3(0.0, 3.0, -8.0),
+    ///             5.0,
+    ///             context.frame.time_delta.as_secs_f32(),
+    ///         );
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn follow(
+        &mut self,
+        target: glm::Vec3,
+        offset: glm::Vec3,
+        stiffness: f32,
+        delta_time: f32,
+    ) -> &mut Self {
+        let t = 1.0 - (-stiffness.max(0.0) * delta_time).exp();
+        let position = glm::lerp(&self.transform.position, &(target + offset), t);
+        self.transform.set_position(position);
+        self.look_at(target)
+    }
+
     /// set the position of the camera
     ///
     /// # Arguments
@@ -330,7 +514,7 @@ impl Camera3D {
     /// # Returns
     /// The position of the camera
     pub fn get_position(&self, parent_transform: NodeTransform) -> glm::Vec3 {
-        (self.transform + parent_transform).position
+        parent_transform.compose(&self.transform).position
     }
 
     pub fn as_ptr(&self) -> *const Camera3D {
@@ -395,14 +579,64 @@ impl Camera3D {
     /// # Returns
     /// The view matrix of the camera
     pub fn get_view_matrix(&self, parent_transform: NodeTransform) -> glm::Mat4 {
-        let world_position = self.transform + parent_transform;
+        let world_position = parent_transform.compose(&self.transform);
 
-        let target = world_position.position + self.transform.get_forward_vector();
-        glm::look_at(
+        let target = world_position.position + world_position.get_forward_vector();
+        let view = glm::look_at(
             &world_position.position,
             &target,
             &glm::vec3(0.0, 1.0, 0.0), //up vector
-        )
+        );
+        self.shake.matrix() * view
+    }
+
+    /// shake the camera. the trauma stacks with the current shake and decays over time.
+    ///
+    /// the strength of the shake is trauma squared so 0.2 is a light bump and 1.0 is the strongest shake.
+    ///
+    /// # Arguments
+    /// - `trauma` - the amount of trauma to add, the total is clamped from 0 to 1
+    pub fn add_shake(&mut self, trauma: f32) {
+        self.shake.trauma = (self.shake.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// get the current trauma of the shake from 0 to 1
+    pub fn get_shake(&self) -> f32 {
+        self.shake.trauma
+    }
+
+    /// stop the shake immediately
+    pub fn clear_shake(&mut self) {
+        self.shake.trauma = 0.0;
+    }
+
+    /// set how fast the shake fades out
+    ///
+    /// # Arguments
+    /// - `decay` - the trauma removed per second (1.0 by default so a full shake lasts a second)
+    pub fn set_shake_decay(&mut self, decay: f32) -> &mut Self {
+        self.shake.decay = decay.max(0.0);
+        self
+    }
+
+    /// set how far the shake can move and rotate the view at full trauma
+    ///
+    /// # Arguments
+    /// - `max_offset` - the max offset in world units (0.3 by default)
+    /// - `max_angle` - the max rotation in degrees (3.0 by default)
+    pub fn set_shake_strength(&mut self, max_offset: f32, max_angle: f32) -> &mut Self {
+        self.shake.max_offset = max_offset;
+        self.shake.max_angle = max_angle.to_radians();
+        self
+    }
+
+    /// set how fast the camera shakes
+    ///
+    /// # Arguments
+    /// - `frequency` - roughly the shakes per second (15.0 by default)
+    pub fn set_shake_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.shake.frequency = frequency.max(0.0);
+        self
     }
 
     /// get the projection matrix of the camera
@@ -413,6 +647,90 @@ impl Camera3D {
         glm::perspective(self.aspect_ratio, self.fov, self.near, self.far)
     }
 
+    /// get the vertical field of view of the camera in radians
+    pub fn get_fov(&self) -> f32 {
+        self.fov
+    }
+
+    /// get the aspect ratio (width / height) of the camera
+    pub fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    /// set the aspect ratio (width / height) of the camera.
+    ///
+    /// the engine keeps the aspect ratio of the main camera in sync with the window, this is for cameras that
+    /// render to something else.
+    ///
+    /// # Arguments
+    /// - `aspect_ratio` - the width divided by the height of the target
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        if aspect_ratio.is_finite() && aspect_ratio > 0.0 {
+            self.aspect_ratio = aspect_ratio;
+        }
+    }
+
+    /// get the near plane of the camera
+    pub fn get_near(&self) -> f32 {
+        self.near
+    }
+
+    /// get the far plane of the camera
+    pub fn get_far(&self) -> f32 {
+        self.far
+    }
+
+    /// copies the camera without its children and callbacks, for passes that need the camera while the scene tree
+    /// it is in is borrowed
+    pub(crate) fn detached(&self) -> Camera3D {
+        Camera3D {
+            movement_enabled: self.movement_enabled,
+            look_sensitivity: self.look_sensitivity,
+            move_speed: self.move_speed,
+            use_actions: self.use_actions,
+            transform: self.transform,
+            children: NodeManager::new(),
+            fov: self.fov,
+            aspect_ratio: self.aspect_ratio,
+            near: self.near,
+            far: self.far,
+            ready_callback: None,
+            behavior_callback: None,
+            shake: self.shake.clone(),
+        }
+    }
+
+    /// get the corners of the view frustum in world space
+    ///
+    /// # Arguments
+    /// - `parent_transform` - The world transform of the camera's parent
+    /// - `max_distance` - cuts the frustum off at this distance if it is closer than the far plane
+    ///
+    /// # Returns
+    /// the 4 near plane corners followed by the 4 far plane corners
+    pub fn get_frustum_corners(
+        &self,
+        parent_transform: NodeTransform,
+        max_distance: f32,
+    ) -> [glm::Vec3; 8] {
+        let far = self.far.min(max_distance).max(self.near + 0.001);
+        let projection = glm::perspective(self.aspect_ratio, self.fov, self.near, far);
+        let inverse_vp = glm::inverse(&(projection * self.get_view_matrix(parent_transform)));
+
+        let mut corners = [glm::Vec3::zeros(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let ndc = glm::vec4(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i < 4 { -1.0 } else { 1.0 },
+                1.0,
+            );
+            let world = inverse_vp * ndc;
+            *corner = world.xyz() / world.w;
+        }
+        corners
+    }
+
     /// get the view projection matrix of the camera
     ///
     /// # Returns
@@ -421,6 +739,48 @@ impl Camera3D {
         self.get_projection_matrix() * self.get_view_matrix(parent_transform)
     }
 
+    /// get the six planes of the view volume, used to skip models that are off screen
+    ///
+    /// # Arguments
+    /// - `parent_transform` - The world transform of the camera's parent
+    ///
+    /// # Returns
+    /// the frustum in world space
+    pub fn frustum(&self, parent_transform: NodeTransform) -> Frustum {
+        Frustum::from_matrix(&self.get_vp_matrix(parent_transform))
+    }
+
+    /// get the ray going from the camera through a point on the screen, for example to pick the model under the mouse
+    ///
+    /// # Arguments
+    /// - `parent_transform` - The world transform of the camera's parent
+    /// - `mouse` - the point on the screen in pixels from the top left (like `InputManager::mouse_position`)
+    /// - `viewport` - the width and height of the screen in the same units as `mouse`
+    ///
+    /// # Returns
+    /// the origin on the near plane and the normalized direction of the ray in world space
+    pub fn screen_ray(
+        &self,
+        parent_transform: NodeTransform,
+        mouse: glm::Vec2,
+        viewport: (u32, u32),
+    ) -> (glm::Vec3, glm::Vec3) {
+        let width = viewport.0.max(1) as f32;
+        let height = viewport.1.max(1) as f32;
+        let x = mouse.x / width * 2.0 - 1.0;
+        let y = 1.0 - mouse.y / height * 2.0;
+
+        let inverse_vp = glm::inverse(&self.get_vp_matrix(parent_transform));
+        let unproject = |z: f32| {
+            let world = inverse_vp * glm::vec4(x, y, z, 1.0);
+            world.xyz() / world.w
+        };
+
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        (near, (far - near).normalize())
+    }
+
     /// define the ready callback that is called when ready
     ///
     /// # Arguments
@@ -453,6 +813,11 @@ impl Camera3D {
 
     /// take input for the camera and implement basic free cam movement
     ///
+    /// WASD, space and control (or the left stick of the first gamepad) move, the mouse (or the right stick) looks around.
+    /// when [`Camera3D::use_actions`] is set the movement and stick look come from the actions in [`camera_actions`]
+    /// instead, see [`ActionMap::free_camera`](crate::context::action_map::ActionMap::free_camera) for the default bindings.
+    /// the mouse always looks around.
+    ///
     /// # Arguments
     /// - `input_manager` - The input manager to get input from
     /// - `delta_time` - The time between frames
@@ -466,66 +831,137 @@ impl Camera3D {
             return;
         }
 
-        let key = &input_manager.keys;
-
-        let mut speed = self.move_speed * delta_time;
         let sensitivity = self.look_sensitivity;
 
-        let mut movement_offset = glm::vec3(0.0, 0.0, 0.0);
-
         // the current right vector of the camera so that we know what direction to move diaganoly
         let right = glm::normalize(&glm::cross(
             &self.transform.get_forward_vector(),
             &glm::vec3(0.0, 1.0, 0.0),
         ));
 
+        if self.use_actions {
+            self.take_action_input(input_manager, right, delta_time);
+        } else {
+            self.take_raw_input(input_manager, right, delta_time);
+        }
+
+        // a ui under the cursor consumes the mouse so dragging over it doesn't turn the camera
+        let mouse_offset = input_manager.mouse_delta;
+        if !input_manager.is_mouse_consumed() && mouse_offset != glm::vec2(0.0, 0.0) {
+            self.rotate_camera(
+                glm::vec3(mouse_offset.x, mouse_offset.y, 0.0),
+                sensitivity * delta_time,
+            );
+        }
+
+        // handle mouse movement for rotation
+        // if input_manager.mouse_buttons.contains(&MouseButton::Button3) {
+        //     let mouse_offset: glm::Vec2 =
+        //         input_manager.mouse_position - input_manager.last_mouse_position;
+        //     if mouse_offset != glm::vec2(0.0, 0.0) {
+        //         self.rotate_camera(
+        //             glm::vec3(mouse_offset.x, mouse_offset.y, 0.0),
+        //             sensitivity * delta_time,
+        //         );
+        //     }
+        // }
+    }
+
+    /// moves the camera with the fixed keys and the sticks of the first gamepad
+    fn take_raw_input(
+        &mut self,
+        input_manager: &crate::context::input_manager::InputManager,
+        right: glm::Vec3,
+        delta_time: f32,
+    ) {
+        let sensitivity = self.look_sensitivity;
+        // keys typed into a ui text field are consumed and don't move the camera
+        let key = |key| input_manager.is_key_pressed(key);
+
+        let mut speed = self.move_speed * delta_time;
+
+        let mut movement_offset = glm::vec3(0.0, 0.0, 0.0);
+
         // handle keys
-        // if key.contains(&Key::LeftControl) {
+        // if key(Key::LeftControl) {
         //     speed /= 5.0;
         // }
-        if key.contains(&Key::LeftShift) {
+        if key(Key::LeftShift) {
             speed *= 5.0;
         }
-        if key.contains(&Key::W) {
+        if key(Key::W) {
             movement_offset += self.transform.get_forward_vector() * speed;
         }
-        if key.contains(&Key::A) {
+        if key(Key::A) {
             movement_offset -= right * speed;
         }
-        if key.contains(&Key::S) {
+        if key(Key::S) {
             movement_offset -= self.transform.get_forward_vector() * speed;
         }
-        if key.contains(&Key::D) {
+        if key(Key::D) {
             movement_offset += right * speed;
         }
-        if key.contains(&Key::Space) {
+        if key(Key::Space) {
             movement_offset += glm::vec3(0.0, 1.0, 0.0) * speed;
         }
-        if key.contains(&Key::LeftControl) {
+        if key(Key::LeftControl) {
             movement_offset -= glm::vec3(0.0, 1.0, 0.0) * speed;
         }
 
+        // the left stick moves and the right stick looks around
+        let pad = input_manager.gamepad(JoystickId::Joystick1);
+        if pad.is_connected() {
+            let stick = |axis| apply_deadzone(pad.axis(axis));
+            movement_offset +=
+                self.transform.get_forward_vector() * -stick(GamepadAxis::AxisLeftY) * speed;
+            movement_offset += right * stick(GamepadAxis::AxisLeftX) * speed;
+
+            let look = glm::vec3(
+                stick(GamepadAxis::AxisRightX),
+                stick(GamepadAxis::AxisRightY),
+                0.0,
+            );
+            if look != glm::Vec3::zeros() {
+                self.rotate_camera(look * GAMEPAD_LOOK_SPEED, sensitivity * delta_time);
+            }
+        }
+
         self.move_camera(movement_offset);
+    }
 
-        let mouse_offset = input_manager.mouse_delta;
-        if mouse_offset != glm::vec2(0.0, 0.0) {
+    /// moves the camera with the actions in [`camera_actions`]
+    fn take_action_input(
+        &mut self,
+        input_manager: &crate::context::input_manager::InputManager,
+        right: glm::Vec3,
+        delta_time: f32,
+    ) {
+        let action = |name| apply_deadzone(input_manager.action_value(name));
+
+        let mut speed = self.move_speed * delta_time;
+        if input_manager.action_pressed(camera_actions::SPRINT) {
+            speed *= 5.0;
+        }
+
+        let forward = self.transform.get_forward_vector();
+        let up = glm::vec3(0.0, 1.0, 0.0);
+        let movement_offset = forward
+            * (action(camera_actions::MOVE_FORWARD) - action(camera_actions::MOVE_BACK))
+            + right * (action(camera_actions::MOVE_RIGHT) - action(camera_actions::MOVE_LEFT))
+            + up * (action(camera_actions::MOVE_UP) - action(camera_actions::MOVE_DOWN));
+        self.move_camera(movement_offset * speed);
+
+        let look = glm::vec3(
+            action(camera_actions::LOOK_RIGHT) - action(camera_actions::LOOK_LEFT),
+            action(camera_actions::LOOK_DOWN) - action(camera_actions::LOOK_UP),
+            0.0,
+        );
+        if look != glm::Vec3::zeros() {
             self.rotate_camera(
-                glm::vec3(mouse_offset.x, mouse_offset.y, 0.0),
-                sensitivity * delta_time,
+                look * GAMEPAD_LOOK_SPEED,
+                self.look_sensitivity * delta_time,
             );
         }
-
-        // handle mouse movement for rotation
-        // if input_manager.mouse_buttons.contains(&MouseButton::Button3) {
-        //     let mouse_offset: glm::Vec2 =
-        //         input_manager.mouse_position - input_manager.last_mouse_position;
-        //     if mouse_offset != glm::vec2(0.0, 0.0) {
-        //         self.rotate_camera(
-        //             glm::vec3(mouse_offset.x, mouse_offset.y, 0.0),
-        //             sensitivity * delta_time,
-        //         );
-        //     }
-        // }
     }
 }
 
@@ -575,3 +1011,68 @@ impl Into<*mut Camera3D> for &mut Camera3D {
         self as *mut Camera3D
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shake_decays() {
+        let mut shake = CameraShake::default();
+        assert_eq!(shake.matrix(), glm::Mat4::identity());
+
+        shake.trauma = 1.0;
+        shake.update(0.25);
+        assert!((shake.trauma - 0.75).abs() < 1e-5);
+
+        // the same time split into more frames decays the same amount
+        let mut split = CameraShake {
+            trauma: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..5 {
+            split.update(0.05);
+        }
+        assert!((split.trauma - 0.75).abs() < 1e-5);
+
+        shake.update(10.0);
+        assert_eq!(shake.trauma, 0.0);
+        assert_eq!(shake.matrix(), glm::Mat4::identity());
+    }
+
+    #[test]
+    fn test_deadzone() {
+        assert_eq!(apply_deadzone(0.1), 0.0);
+        assert_eq!(apply_deadzone(-0.1), 0.0);
+        assert_eq!(apply_deadzone(1.0), 1.0);
+        assert_eq!(apply_deadzone(-1.0), -1.0);
+        assert!(apply_deadzone(0.2) > 0.0 && apply_deadzone(0.2) < 0.1);
+    }
+
+    #[test]
+    fn test_follow_eases_towards_target() {
+        let mut camera = Camera3D::new(1.0, 1.0, 0.1, 100.0);
+        let target = glm::vec3(0.0, 0.0, 10.0);
+        let offset = glm::vec3(0.0, 0.0, -5.0);
+
+        camera.follow(target, offset, 5.0, 0.1);
+        let position = camera.transform.position;
+        assert!(position.z > 0.0 && position.z < 5.0);
+        assert!(glm::distance(&camera.get_orientation_vector(), &glm::vec3(0.0, 0.0, 1.0)) < 1e-5);
+
+        for _ in 0..200 {
+            camera.follow(target, offset, 5.0, 0.1);
+        }
+        assert!(glm::distance(&camera.transform.position, &(target + offset)) < 1e-3);
+    }
+
+    #[test]
+    fn test_noise_range() {
+        for i in 0..1000 {
+            let value = noise(3, i as f32 * 0.037);
+            assert!((-1.0..=1.0).contains(&value));
+        }
+        // gradient noise is zero on the integer points
+        assert_eq!(noise(0, 4.0), 0.0);
+    }
+}
@@ -0,0 +1,351 @@
+//! Collider is a node that detects when its box overlaps the box of another collider.
+//!
+//! the check is broadphase only: every collider is an axis aligned box in world space, there is no physics response.
+//! use it for trigger volumes, pickups and simple hit checks. the local box of the collider is transformed by its
+//! world transform and a new axis aligned box is fitted around it, so rotated colliders grow to cover their corners
+//! instead of rotating with the node.
+//!
+//! the engine checks the colliders every frame after the behaviors. the overlaps that began or ended are kept in
+//! [`Collider::events`] until the next check, so read them in the behavior of the collider or its parent.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::nodes::{Collider, CollisionEvent};
+//!
+//! let mut trigger = Collider::new(glm::vec3(2.0, 1.0, 2.0));
+//! trigger.define_behavior(|collider, context| {
+//!     for event in collider.events() {
+//!         if let CollisionEvent::Begin { other } = event {
+//!             println!("{} entered the trigger", other);
+//!         }
+//!     }
+//! });
+//! engine.context.nodes.add("door_trigger", trigger);
+//!
+//! // the player carries a collider fitted around its model
+//! let player = engine.context.nodes.add("player", Model::new_gltf("res/models/player.gltf")?);
+//! let collider = Collider::from_model(player);
+//! player.get_children().add("collider", collider);
+//! ```
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm as glm;
+
+use crate::components::{Aabb, NodeTransform};
+use crate::context::node_manager::{
+    Behavior, BehaviorCallback, Node, NodeManager, Ready, ReadyCallback,
+};
+use crate::context::GameContext;
+use crate::nodes::Model;
+
+use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+
+/// an overlap between two colliders that began or ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollisionEvent {
+    /// the collider at the path started to overlap this collider
+    Begin {
+        /// the path of the other collider like `"player/collider"`
+        other: String,
+    },
+    /// the collider at the path stopped overlapping this collider or was removed
+    End {
+        /// the path of the other collider like `"player/collider"`
+        other: String,
+    },
+}
+
+/// an axis aligned box that reports overlaps with other colliders, see the [module docs](self).
+#[derive(Clone)]
+pub struct Collider {
+    transform: NodeTransform,
+    children: NodeManager,
+
+    /// the ready callback
+    pub ready_callback: ReadyCallback<Collider>,
+    /// the behavior callback
+    pub behavior_callback: BehaviorCallback<Collider, GameContext>,
+
+    /// the box relative to the node
+    bounds: Aabb,
+    /// the paths of the colliders overlapping at the last check
+    overlaps: BTreeSet<String>,
+    /// the overlaps that began or ended at the last check
+    events: Vec<CollisionEvent>,
+}
+
+impl Ready for Collider {
+    fn ready(&mut self) {
+        if let Some(callback) = self.ready_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self);
+            drop(guard);
+            self.ready_callback = Some(callback)
+        }
+    }
+}
+
+impl Behavior for Collider {
+    fn behavior(&mut self, context: &mut GameContext) {
+        // take callback out of self so we can use self later
+        if let Some(callback) = self.behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.behavior_callback = Some(callback);
+        }
+    }
+}
+
+impl Node for Collider {
+    fn get_transform(&mut self) -> &mut NodeTransform {
+        &mut self.transform
+    }
+
+    fn get_children(&mut self) -> &mut NodeManager {
+        &mut self.children
+    }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
+    fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
+        Some(self)
+    }
+
+    fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
+        Some(self)
+    }
+}
+
+impl Collider {
+    /// creates a box collider centered on the node
+    ///
+    /// # Arguments
+    /// - `half_extents` - half the size of the box on every axis
+    ///
+    /// # Returns
+    /// the new collider
+    pub fn new(half_extents: glm::Vec3) -> Collider {
+        let half_extents = half_extents.abs();
+        Self::from_aabb(Aabb::new(-half_extents, half_extents))
+    }
+
+    /// creates a collider from a box relative to the node
+    ///
+    /// # Arguments
+    /// - `bounds` - the box in the space of the node
+    ///
+    /// # Returns
+    /// the new collider
+    pub fn from_aabb(bounds: Aabb) -> Collider {
+        Collider {
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            bounds,
+            overlaps: BTreeSet::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// creates a collider around the meshes of a model, add it as a child of the model so it follows the model
+    ///
+    /// # Arguments
+    /// - `model` - the model to fit
+    ///
+    /// # Returns
+    /// the new collider
+    pub fn from_model(model: &Model) -> Collider {
+        Self::from_aabb(model.get_local_aabb())
+    }
+
+    /// get the box relative to the node
+    pub fn get_bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /// sets the box relative to the node
+    ///
+    /// # Arguments
+    /// - `bounds` - the box in the space of the node
+    pub fn set_bounds(&mut self, bounds: Aabb) -> &mut Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// get the box in world space, fitted around the transformed box
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the collider (its own transform composed with its parents)
+    pub fn get_world_aabb(&self, world_transform: &NodeTransform) -> Aabb {
+        self.bounds.transformed(&world_transform.matrix)
+    }
+
+    /// get the paths of the colliders that overlapped this collider at the last check
+    pub fn overlaps(&self) -> impl Iterator<Item = &str> {
+        self.overlaps.iter().map(String::as_str)
+    }
+
+    /// check if the collider at a path overlapped this collider at the last check
+    ///
+    /// # Arguments
+    /// - `path` - the path of the other collider like `"player/collider"`
+    pub fn is_overlapping(&self, path: &str) -> bool {
+        self.overlaps.contains(path)
+    }
+
+    /// get the overlaps that began or ended at the last check, replaced at the next check
+    pub fn events(&self) -> &[CollisionEvent] {
+        &self.events
+    }
+
+    /// replaces the overlaps with the result of a new check and records which began and ended
+    ///
+    /// # Arguments
+    /// - `overlaps` - the paths of the colliders overlapping now
+    pub(crate) fn set_overlaps(&mut self, overlaps: BTreeSet<String>) {
+        self.events.clear();
+        for other in self.overlaps.difference(&overlaps) {
+            self.events.push(CollisionEvent::End {
+                other: other.clone(),
+            });
+        }
+        for other in overlaps.difference(&self.overlaps) {
+            self.events.push(CollisionEvent::Begin {
+                other: other.clone(),
+            });
+        }
+        self.overlaps = overlaps;
+    }
+
+    /// define the ready callback for the node
+    ///
+    /// # Arguments
+    /// - `ready_function` - The function to be called when the node is ready.
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self) + Sync + Send,
+    {
+        self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
+        self
+    }
+
+    /// define the behavior callback for the node
+    ///
+    /// # Arguments
+    /// - `behavior_function` - The function to be called every frame.
+    pub fn define_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
+        self
+    }
+}
+
+/// finds the colliders overlapping each collider
+///
+/// # Arguments
+/// - `colliders` - the path and world box of every collider
+///
+/// # Returns
+/// the paths of the other colliders overlapping each collider, in the same order
+pub(crate) fn find_overlaps(colliders: &[(String, Aabb)]) -> Vec<BTreeSet<String>> {
+    let mut overlaps = vec![BTreeSet::new(); colliders.len()];
+    for (i, (path, bounds)) in colliders.iter().enumerate() {
+        for (j, (other_path, other_bounds)) in colliders.iter().enumerate().skip(i + 1) {
+            if bounds.intersects(other_bounds) {
+                overlaps[i].insert(other_path.clone());
+                overlaps[j].insert(path.clone());
+            }
+        }
+    }
+    overlaps
+}
+
+impl UseReadyCallback for NodeBuilder<Collider> {
+    type Node = Collider;
+
+    fn with_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Collider) + Send + Sync,
+    {
+        self.node.define_ready(ready_function);
+        self
+    }
+}
+
+impl UseBehaviorCallback for NodeBuilder<Collider> {
+    type Node = Collider;
+
+    fn with_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Collider, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_behavior(behavior_function);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_overlaps() {
+        let unit = Aabb::new(glm::vec3(0.0, 0.0, 0.0), glm::vec3(1.0, 1.0, 1.0));
+        let colliders = vec![
+            ("a".to_string(), unit),
+            (
+                "b".to_string(),
+                unit.transformed(&glm::translation(&glm::vec3(0.5, 0.0, 0.0))),
+            ),
+            (
+                "c".to_string(),
+                unit.transformed(&glm::translation(&glm::vec3(5.0, 0.0, 0.0))),
+            ),
+        ];
+
+        let overlaps = find_overlaps(&colliders);
+        assert_eq!(overlaps[0], BTreeSet::from(["b".to_string()]));
+        assert_eq!(overlaps[1], BTreeSet::from(["a".to_string()]));
+        assert!(overlaps[2].is_empty());
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let mut collider = Collider::new(glm::vec3(1.0, 1.0, 1.0));
+
+        collider.set_overlaps(BTreeSet::from(["coin".to_string()]));
+        assert_eq!(
+            collider.events(),
+            [CollisionEvent::Begin {
+                other: "coin".to_string()
+            }]
+        );
+        assert!(collider.is_overlapping("coin"));
+
+        // an overlap that continues doesn't fire again
+        collider.set_overlaps(BTreeSet::from(["coin".to_string()]));
+        assert!(collider.events().is_empty());
+
+        collider.set_overlaps(BTreeSet::from(["wall".to_string()]));
+        assert_eq!(
+            collider.events(),
+            [
+                CollisionEvent::End {
+                    other: "coin".to_string()
+                },
+                CollisionEvent::Begin {
+                    other: "wall".to_string()
+                }
+            ]
+        );
+        assert_eq!(collider.overlaps().collect::<Vec<_>>(), ["wall"]);
+    }
+}
@@ -40,4 +40,8 @@ where
     fn get_children(&mut self) -> &mut NodeManager {
         &mut self.children
     }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
 }
@@ -1,23 +1,20 @@
 //! Directional light casts light on a scene from a single direction, like the sun. It is used to simulate sunlight in a scene. It is a type of light that is infinitely far away and has no attenuation. It is defined by a direction and a color. It can also cast shadows using a shadow map.
 //!
 //! ## Usage
-//! add this to the node tree to add a directional light to the scene.
+//! add this to the node tree to add a directional light to the scene. up to [`MAX_DIRECT_LIGHTS`] enabled directional
+//! lights light the scene and cast their shadows, the weakest ones past that are skipped with a warning.
 //!
 //! ## Example
-//! ```rust
+//! ```rust,ignore
 //! use quaturn::Engine;
 //! use quaturn::glm;
-//! use quaturn::game_context::nodes::directional_light::DirectionalLight;
+//! use quaturn::nodes::DirectionalLight;
 //!
 //! let mut engine = Engine::init("Example", 800, 600);
 //!
-//! engine.context.nodes.add("directional_light", DirectionalLight::new(
-//!     glm::vec3(1.0, 1.0, 1.0),
-//!     glm::vec3(1.0, 1.0, 1.0),
-//!     1.0,
-//!     100.0,
-//!     1024,
-//! ));
+//! let mut light = DirectionalLight::new(100.0, 2048, 0.7);
+//! light.set_direction(glm::vec3(1.0, 1.0, 1.0));
+//! engine.context.nodes.add("directional_light", light);
 //!
 //! //engine.begin();
 //! ```
@@ -25,10 +22,11 @@
 use crate::components::NodeTransform;
 use crate::context::node_manager::{Behavior, Drawable, Node, NodeManager, Ready};
 use crate::context::GameContext;
-use crate::nodes::Model;
+use crate::nodes::{Camera3D, Model};
 use crate::renderer::shader::Shader;
-use crate::renderer::shadow_map::ShadowMap;
+use crate::renderer::shadow_map::{self, ShadowMap, ShadowQuality};
 use crate::utils::color::Color;
+use crate::utils::light_units;
 use egui_gl_glfw::egui::Direction;
 use nalgebra_glm as glm;
 
@@ -38,6 +36,168 @@ use crate::context::node_manager::{BehaviorCallback, ReadyCallback};
 
 use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
 
+/// the number of shadow cascades a directional light renders, shared with the shaders as `SHADOW_CASCADES`
+pub const CASCADE_COUNT: usize = 4;
+
+/// the cascades are the tiles of a grid this wide in the one shadow map, shared with the shaders as
+/// `SHADOW_CASCADE_GRID`. each cascade gets the shadow resolution divided by this on both axes.
+pub const CASCADE_GRID: usize = 2;
+
+const _: () = assert!(
+    CASCADE_COUNT <= CASCADE_GRID * CASCADE_GRID,
+    "every cascade needs its own tile in the shadow map grid"
+);
+
+/// the most directional lights that light a frame, the shaders get it as `MAX_DIRECT_LIGHTS`.
+///
+/// when the scene has more enabled lights the strongest ones (by intensity) are used and the rest are skipped with a
+/// warning. each light binds its shadow map to its own texture unit, so the limit is the units left between the
+/// textures of the meshes and the point light shadow maps. raising it needs the shader changes listed in
+/// [`shader_defines`](crate::renderer::shader::shader_defines).
+pub const MAX_DIRECT_LIGHTS: usize = 3;
+
+/// the texture unit of the shadow map of the first directional light, light `i` uses `DIRECT_SHADOW_UNIT + i`. the
+/// textures of the meshes use one unit per texture type (0 to 4) and the point light shadow maps start after the last
+/// directional light.
+pub(crate) const DIRECT_SHADOW_UNIT: u32 = 5;
+
+/// an owned copy of a directional light for the shadow pass and the main pass.
+///
+/// the engine copies the light out of the scene tree before the pass, so drawing the tree into the shadow map never
+/// aliases the light inside the tree. the shadow map is shared with the light it was copied from.
+#[derive(Clone)]
+pub(crate) struct DirectionalLightPass {
+    direction: glm::Vec3,
+    light_space_matrices: [glm::Mat4; CASCADE_COUNT],
+    /// the shadow map stores depth from 0 to 1 over the depth range of each cascade, the world biases are scaled by this
+    depth_scales: [f32; CASCADE_COUNT],
+    shadow_map: ShadowMap,
+    color: glm::Vec4,
+    intensity: f32,
+    shadow_quality: ShadowQuality,
+    shadow_bias: f32,
+    normal_bias: f32,
+    debug_cascades: bool,
+}
+
+impl DirectionalLightPass {
+    /// renders the models of the tree into the shadow map of the light, each cascade into its own tile
+    ///
+    /// # Arguments
+    /// - `root_nodes` - the top level nodes of the scene tree
+    pub(crate) fn render<'a>(
+        &mut self,
+        root_nodes: impl IntoIterator<Item = &'a mut Box<dyn Node>>,
+    ) {
+        let mut root_nodes: Vec<&mut Box<dyn Node>> = root_nodes.into_iter().collect();
+        let tile_width = self.shadow_map.width / CASCADE_GRID as i32;
+        let tile_height = self.shadow_map.height / CASCADE_GRID as i32;
+
+        let depth_shader = self.shadow_map.prepare_shadow_map();
+        for (cascade, matrix) in self.light_space_matrices.iter().enumerate() {
+            let (column, row) = (cascade % CASCADE_GRID, cascade / CASCADE_GRID);
+            unsafe {
+                gl::Viewport(
+                    column as i32 * tile_width,
+                    row as i32 * tile_height,
+                    tile_width,
+                    tile_height,
+                );
+            }
+            depth_shader.set_uniform("u_lightSpaceMatrix", *matrix);
+
+            for node in root_nodes.iter_mut() {
+                DirectionalLight::draw_node_shadow(depth_shader, node, NodeTransform::default());
+            }
+        }
+
+        self.shadow_map.finish_shadow_map();
+    }
+
+    /// get the intensity the lights are ranked by when there are more than [`MAX_DIRECT_LIGHTS`]
+    pub(crate) fn get_intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// get the light space matrix of each cascade
+    pub(crate) fn get_cascade_matrices(&self) -> [glm::Mat4; CASCADE_COUNT] {
+        self.light_space_matrices
+    }
+
+    /// binds the light and its shadow map to a slot of the `u_directLights` array of a shader
+    ///
+    /// # Arguments
+    /// - `shader` - the shader to bind to
+    /// - `index` - the slot, below [`MAX_DIRECT_LIGHTS`]
+    pub(crate) fn bind_uniforms(&self, shader: &mut Shader, index: usize) {
+        let field = |name: &str| format!("u_directLights[{}].{}", index, name);
+        // the cascades of every light follow each other in one array
+        let cascade = |name: &str| format!("{}[{}]", name, index * CASCADE_COUNT);
+
+        shader.bind();
+        shader.set_uniform(&field("direction"), self.direction);
+        shader.set_uniform(&field("color"), self.color);
+        shader.set_uniform(&field("intensity"), self.intensity);
+        shader.set_uniform(&field("shadowQuality"), self.shadow_quality.mode());
+        shader.set_uniform(
+            &field("shadowGrid"),
+            self.shadow_quality.grid_width() as i32,
+        );
+        shader.set_uniform(&field("shadowBias"), self.shadow_bias);
+        shader.set_uniform(&field("normalBias"), self.normal_bias);
+        shader.set_uniform(
+            &field("texelSize"),
+            glm::vec2(
+                1.0 / self.shadow_map.width as f32,
+                1.0 / self.shadow_map.height as f32,
+            ),
+        );
+        shader.set_uniform(
+            &cascade("u_cascadeMatrices"),
+            &self.light_space_matrices[..],
+        );
+        shader.set_uniform(&cascade("u_cascadeDepthScales"), &self.depth_scales[..]);
+
+        self.shadow_map.bind_shadow_map(
+            shader,
+            &format!("u_directShadowMaps[{}]", index),
+            DIRECT_SHADOW_UNIT + index as u32,
+        );
+    }
+}
+
+/// binds the lights to the `u_directLights` array of a shader and sets how many are used
+///
+/// every shadow map sampler gets its own unit even when its slot is empty, so no sampler shares a unit with the
+/// textures of the meshes.
+///
+/// # Arguments
+/// - `shader` - the shader to bind to
+/// - `passes` - the lights, at most [`MAX_DIRECT_LIGHTS`]
+pub(crate) fn bind_direct_lights(shader: &mut Shader, passes: &[DirectionalLightPass]) {
+    let passes = &passes[..passes.len().min(MAX_DIRECT_LIGHTS)];
+
+    shader.bind();
+    shader.set_uniform("u_directLightCount", passes.len() as i32);
+    for index in passes.len()..MAX_DIRECT_LIGHTS {
+        shader.set_uniform(
+            &format!("u_directShadowMaps[{}]", index),
+            (DIRECT_SHADOW_UNIT + index as u32) as i32,
+        );
+    }
+
+    // the first light asking for the cascade tint gets it
+    let debug_light = passes.iter().position(|pass| pass.debug_cascades);
+    shader.set_uniform(
+        "u_debugCascadeLight",
+        debug_light.map_or(-1, |index| index as i32),
+    );
+
+    for (index, pass) in passes.iter().enumerate() {
+        pass.bind_uniforms(shader, index);
+    }
+}
+
 /// Directional light casts light on a scene from a single direction, like the sun. It is used to simulate sunlight in a scene. It is a type of light that is infinitely far away and has no attenuation. It is defined by a direction and a color. It can also cast shadows using a shadow map.
 ///
 /// ## Usage
@@ -54,12 +214,28 @@ pub struct DirectionalLight {
     pub intensity: f32,
     /// The distance of the shadow cast by the directional light.
     shadow_distance: f32,
-    /// The projection matrix of the shadow cast by the directional light.
-    shadow_projections: glm::Mat4,
-    /// The light space matrix of the shadow cast by the directional light.
-    light_space_matrix: glm::Mat4,
+    /// The light space matrix of each shadow cascade.
+    light_space_matrices: [glm::Mat4; CASCADE_COUNT],
+    /// The depth range of each shadow cascade, see [`DirectionalLightPass`].
+    depth_scales: [f32; CASCADE_COUNT],
     /// The shadow map of the directional light.
     shadow_map: ShadowMap,
+    /// How the edges of the shadow are filtered.
+    shadow_quality: ShadowQuality,
+    /// The depth bias of the shadow in world units, `None` uses the engine default.
+    shadow_bias: Option<f32>,
+    /// The normal bias of the shadow in world units, `None` uses the engine default.
+    normal_bias: Option<f32>,
+    /// If the lit models are tinted by the cascade of the shadow map they sample.
+    debug_cascades: bool,
+    /// The blend between logarithmic and uniform cascade splits.
+    cascade_lambda: f32,
+    /// The near plane of the camera the shadow was last fitted to.
+    camera_near: f32,
+    /// The far plane of the camera the shadow was last fitted to.
+    camera_far: f32,
+    /// The corners of the view the cascades were last fitted to, `None` covers a box around the origin.
+    view_corners: Option<[glm::Vec3; 8]>,
     /// The ready callback of the directional light.
     ready_callback: ReadyCallback<DirectionalLight>,
     /// The behavior callback of the directional light.
@@ -107,6 +283,10 @@ impl Node for DirectionalLight {
         &mut self.children
     }
 
+    fn get_children_ref(&self) -> &crate::context::node_manager::NodeManager {
+        &self.children
+    }
+
     fn as_ready(&mut self) -> Option<&mut (dyn Ready)> {
         Some(self)
     }
@@ -117,42 +297,20 @@ impl Node for DirectionalLight {
 }
 
 impl DirectionalLight {
-    /// creates a new directional light with the given direction, color, intensity, shadow distance, and shadow resolution.
+    /// creates a new directional light with the given shadow distance, shadow resolution and cascade split.
     ///
     /// # Arguments
-    /// - `direction` - The direction of the directional light.
-    /// - `color` - The color of the directional light.
-    /// - `intensity` - The intensity of the directional light.
     /// - `shadow_distance` - The distance of the shadow cast by the directional light.
-    /// - `shadow_resolution` - The resolution of the shadow map of the directional light.
+    /// - `shadow_resolution` - The resolution of the shadow map of the directional light, split between the cascades.
+    /// - `cascade_lambda` - How the shadow distance is split into cascades, see [`DirectionalLight::set_cascade_lambda`].
     ///
     /// # Returns
     /// The new directional light.
     pub fn new(
-        // direction: glm::Vec3,
-        // color: glm::Vec3,
         shadow_distance: f32,
         shadow_resolution: u32,
+        cascade_lambda: f32,
     ) -> DirectionalLight {
-        let shadow_projections = glm::ortho(
-            -shadow_distance / 2.0,
-            shadow_distance / 2.0,
-            -shadow_distance / 2.0,
-            shadow_distance / 2.0,
-            0.1,
-            shadow_distance,
-        );
-
-        let direction = glm::vec3(0.0, 0.0, 1.0);
-        let light_direction = glm::normalize(&direction);
-        let light_position = light_direction * (shadow_distance / 2.0);
-        let light_view = glm::look_at(
-            &light_position,
-            &glm::vec3(0.0, 0.0, 0.0),
-            &glm::vec3(0.0, 1.0, 0.0),
-        );
-        let light_space_matrix = shadow_projections * light_view;
-
         let shadow_shader = Shader::from_slice(
             include_str!("../../res/shaders/depthShader/depthShader.vert"),
             include_str!("../../res/shaders/depthShader/depthShader.frag"),
@@ -165,6 +323,17 @@ impl DirectionalLight {
             shadow_shader,
         );
 
+        Self::with_shadow_map(shadow_distance, shadow_map, cascade_lambda)
+    }
+
+    /// builds the light around a shadow map, [`DirectionalLight::new`] generates the map
+    pub(crate) fn with_shadow_map(
+        shadow_distance: f32,
+        shadow_map: ShadowMap,
+        cascade_lambda: f32,
+    ) -> DirectionalLight {
+        let direction = glm::vec3(0.0, 0.0, 1.0);
+
         // calculate the rotation quaternion from the orientation vector
         let direction = glm::normalize(&direction);
         let reference = glm::vec3(0.0, 0.0, 1.0);
@@ -183,16 +352,12 @@ impl DirectionalLight {
             glm::quat_angle_axis(rotation_angle, &rotation_axis)
         };
 
-        // println!("Directional Light Rotation: {:?}", rotation_quat);
-        // println!("Directional Light Direction: {:?}", direction);
-
         let check_direction = glm::quat_rotate_vec3(&rotation_quat, &reference);
-        // println!("Directional Light Check Direction: {:?}", check_direction);
 
         // Use a tolerance-based assertion for floating-point comparisons
         assert!((check_direction - direction).magnitude() < 1e-5);
 
-        DirectionalLight {
+        let mut light = DirectionalLight {
             transform: NodeTransform::new(
                 glm::vec3(0.0, 0.0, 0.0),
                 rotation_quat,
@@ -202,26 +367,27 @@ impl DirectionalLight {
             intensity: 1.0,
             color: Color::from_normalized(1.0, 1.0, 1.0, 1.0).into(),
             shadow_distance,
-            shadow_projections,
-            light_space_matrix,
+            light_space_matrices: [glm::Mat4::identity(); CASCADE_COUNT],
+            depth_scales: [1.0; CASCADE_COUNT],
             shadow_map,
+            shadow_quality: ShadowQuality::Hard,
+            shadow_bias: None,
+            normal_bias: None,
+            debug_cascades: false,
+            cascade_lambda: cascade_lambda.clamp(0.0, 1.0),
+            camera_near: 0.1,
+            camera_far: f32::INFINITY,
+            view_corners: None,
             ready_callback: None,
             behavior_callback: None,
-        }
+        };
+        light.update_cascades();
+        light
     }
 
     /// direction the lights coming from
     pub fn set_direction(&mut self, direction: glm::Vec3) -> &mut Self {
-        // update projection
-        let light_direction = glm::normalize(&direction);
-        let light_position = light_direction * (self.shadow_distance / 2.0);
-        let light_view = glm::look_at(
-            &light_position,
-            &glm::vec3(0.0, 0.0, 0.0),
-            &glm::vec3(0.0, 1.0, 0.0),
-        );
-        self.light_space_matrix = self.shadow_projections * light_view;
-
+        let direction = glm::normalize(&direction);
         let reference = glm::vec3(0.0, 0.0, 1.0);
 
         // update rotation
@@ -240,6 +406,9 @@ impl DirectionalLight {
 
         self.transform.set_rotation(rotation_quat);
 
+        // update projection
+        self.update_cascades();
+
         self
     }
 
@@ -253,21 +422,96 @@ impl DirectionalLight {
         self.intensity = intensity;
         self
     }
-    /// renders the shadow map of the directional light
+
+    /// sets the intensity of the light from the illuminance it gives a surface facing it.
+    ///
+    /// uses the same reference as [`PointLight::set_intensity_lumens`](super::PointLight::set_intensity_lumens)
+    /// so both lights can be balanced against each other, see [`light_units`](crate::utils::light_units).
     ///
     /// # Arguments
-    /// - `models` - The models to render the shadow map for.
-    pub fn render_shadow_map(&mut self, root_nodes: Vec<&mut Box<dyn Node>>) {
-        //let nodes = *root_nodes;
+    /// - `lux` - the illuminance of the light (direct sunlight is around 100 000 lux)
+    pub fn set_intensity_lux(&mut self, lux: f32) -> &mut Self {
+        self.set_intensity(light_units::lux_to_intensity(lux))
+    }
+    /// fits the shadow cascades around the part of the camera's view that is within the shadow distance.
+    ///
+    /// the engine calls this every frame with the active camera so the shadow stays tight when the camera moves,
+    /// zooms (fov), or changes its far plane.
+    ///
+    /// # Arguments
+    /// - `camera` - the camera the shadow should cover.
+    /// - `camera_parent` - the world transform of the camera's parent.
+    pub fn fit_to_camera(&mut self, camera: &Camera3D, camera_parent: NodeTransform) {
+        self.view_corners = Some(camera.get_frustum_corners(camera_parent, self.shadow_distance));
+        self.camera_near = camera.get_near();
+        self.camera_far = camera.get_far();
+        self.update_cascades();
+    }
 
-        let depth_shader = self.shadow_map.prepare_shadow_map();
-        depth_shader.set_uniform("u_lightSpaceMatrix", self.light_space_matrix);
+    /// recomputes the light space matrix of each cascade from the splits and the view they were last fitted to
+    fn update_cascades(&mut self) {
+        let direction = self.get_direction();
+
+        let Some(corners) = self.view_corners else {
+            // not fitted to a camera yet, every cascade covers a box around the origin
+            let half = self.shadow_distance / 2.0;
+            let projection = glm::ortho(-half, half, -half, half, 0.1, self.shadow_distance);
+            let light_view =
+                Self::light_view(&direction, &(direction * half), &glm::vec3(0.0, 0.0, 0.0));
+            self.light_space_matrices = [projection * light_view; CASCADE_COUNT];
+            self.depth_scales = [projection[(2, 2)].abs() / 2.0; CASCADE_COUNT];
+            return;
+        };
 
-        for node in root_nodes {
-            Self::draw_node_shadow(depth_shader, node, NodeTransform::default());
+        // the frustum corners run from the near plane to the end of the shadow distance
+        let near = self.camera_near;
+        let far = self.shadow_distance.min(self.camera_far);
+        let fraction =
+            |distance: f32| ((distance - near) / (far - near).max(0.0001)).clamp(0.0, 1.0);
+
+        let mut start = near;
+        for (cascade, end) in self.get_cascade_splits().into_iter().enumerate() {
+            let (start_fraction, end_fraction) = (fraction(start), fraction(end));
+            let slice: [glm::Vec3; 8] = std::array::from_fn(|i| {
+                let t = if i < 4 { start_fraction } else { end_fraction };
+                glm::lerp(&corners[i % 4], &corners[i % 4 + 4], t)
+            });
+
+            let center = slice.iter().sum::<glm::Vec3>() / slice.len() as f32;
+            // a bounding sphere keeps the projection size stable while the camera rotates
+            let radius = slice
+                .iter()
+                .map(|corner| glm::distance(corner, &center))
+                .fold(0.0, f32::max)
+                .max(0.001);
+
+            // move the light back so casters between the light and the view still cast shadows
+            let light_position = center + direction * radius * 2.0;
+            let light_view = Self::light_view(&direction, &light_position, &center);
+
+            let projection = glm::ortho(-radius, radius, -radius, radius, 0.1, radius * 4.0);
+            self.light_space_matrices[cascade] = projection * light_view;
+            self.depth_scales[cascade] = projection[(2, 2)].abs() / 2.0;
+            start = end;
         }
+    }
 
-        self.shadow_map.finish_shadow_map();
+    /// the view matrix of the light looking along its direction at a target
+    fn light_view(direction: &glm::Vec3, position: &glm::Vec3, target: &glm::Vec3) -> glm::Mat4 {
+        let up = if direction.y.abs() > 0.99 {
+            glm::vec3(0.0, 0.0, 1.0)
+        } else {
+            glm::vec3(0.0, 1.0, 0.0)
+        };
+        glm::look_at(position, target, &up)
+    }
+
+    /// renders the shadow map of the directional light
+    ///
+    /// # Arguments
+    /// - `models` - The models to render the shadow map for.
+    pub fn render_shadow_map(&mut self, root_nodes: Vec<&mut Box<dyn Node>>) {
+        self.shadow_pass().render(root_nodes);
     }
 
     fn draw_node_shadow(
@@ -275,7 +519,11 @@ impl DirectionalLight {
         node: &mut Box<dyn Node>,
         parent_transform: NodeTransform,
     ) {
-        let world_transfrom = parent_transform + *node.get_transform();
+        if !node.is_enabled() {
+            return;
+        }
+
+        let world_transfrom = parent_transform.compose(node.get_transform());
         if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
             model.draw_shadow(shader, world_transfrom);
         }
@@ -285,19 +533,141 @@ impl DirectionalLight {
         }
     }
 
-    /// binds the shadow map and light space matrix to the active shader for shaders that need shadow mapping
+    /// copies what the shadow pass and the main pass need out of the light, see [`DirectionalLightPass`]
+    pub(crate) fn shadow_pass(&self) -> DirectionalLightPass {
+        let (shadow_bias, normal_bias) = self.get_shadow_bias();
+        DirectionalLightPass {
+            direction: self.get_direction(),
+            light_space_matrices: self.light_space_matrices,
+            depth_scales: self.depth_scales,
+            shadow_map: self.shadow_map.clone(),
+            color: self.color,
+            intensity: self.intensity,
+            shadow_quality: self.shadow_quality,
+            shadow_bias,
+            normal_bias,
+            debug_cascades: self.debug_cascades,
+        }
+    }
+
+    /// binds the light as the only directional light of a shader, with its shadow map and cascades
     ///
     /// # Arguments
-    /// - `shader` - The shader to bind the shadow map and light space matrix to.
+    /// - `shader` - The shader to bind the light to.
     pub fn bind_uniforms(&self, shader: &mut Shader) {
-        let direction = glm::quat_rotate_vec3(&self.transform.rotation, &glm::vec3(0.0, 0.0, 1.0));
-        // Bind shadow map and light space matrix to the active shader
-        shader.bind();
-        shader.set_uniform("u_lightSpaceMatrix", self.light_space_matrix);
-        //shader.set_uniform1f("u_farShadowPlane", self.shadow_distance);
-        shader.set_uniform("u_directLightDirection", direction);
-        // Bind the shadow map texture to texture unit 2 (example)
-        self.shadow_map.bind_shadow_map(shader, "shadowMap", 2);
+        bind_direct_lights(shader, &[self.shadow_pass()]);
+    }
+
+    /// set how the shadow edges are filtered (default [`ShadowQuality::Hard`])
+    ///
+    /// # Arguments
+    /// - `quality` - the filtering of the shadow map
+    pub fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self {
+        self.shadow_quality = quality;
+        self
+    }
+
+    /// get how the shadow edges are filtered
+    pub fn get_shadow_quality(&self) -> ShadowQuality {
+        self.shadow_quality
+    }
+
+    /// set the smallest depth offset of this light's shadow in world units, raise it to remove shadow acne.
+    ///
+    /// lights that don't set it use the engine default, see [`Engine::set_shadow_bias`](crate::Engine::set_shadow_bias).
+    ///
+    /// # Arguments
+    /// - `bias` - the depth offset
+    pub fn set_shadow_bias(&mut self, bias: f32) -> &mut Self {
+        self.shadow_bias = Some(bias.max(0.0));
+        self
+    }
+
+    /// set the extra offset of this light's shadow at grazing angles in world units,
+    /// lower it if shadows detach from their casters.
+    ///
+    /// # Arguments
+    /// - `normal_bias` - the offset when the surface is parallel to the light
+    pub fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self {
+        self.normal_bias = Some(normal_bias.max(0.0));
+        self
+    }
+
+    /// go back to the engine default biases
+    pub fn reset_shadow_bias(&mut self) -> &mut Self {
+        self.shadow_bias = None;
+        self.normal_bias = None;
+        self
+    }
+
+    /// get the biases set on this light without the engine default
+    pub(crate) fn get_shadow_bias_override(&self) -> (Option<f32>, Option<f32>) {
+        (self.shadow_bias, self.normal_bias)
+    }
+
+    /// get the `(bias, normal_bias)` used for the shadow, falling back to the engine default
+    pub fn get_shadow_bias(&self) -> (f32, f32) {
+        let (bias, normal_bias) = shadow_map::default_shadow_bias();
+        (
+            self.shadow_bias.unwrap_or(bias),
+            self.normal_bias.unwrap_or(normal_bias),
+        )
+    }
+
+    /// tints the lit models by the shadow cascade they sample, to tune the shadow distance and resolution.
+    ///
+    /// the cascades are tinted red, green, yellow and magenta from the camera outwards with a checker of 16 by 16
+    /// shadow map texels, and the models beyond the shadow distance are tinted blue. bigger checker cells mean sharper
+    /// shadows. when several lights enable it the strongest of them is shown.
+    ///
+    /// # Arguments
+    /// - `enabled` - if the tint is drawn
+    pub fn set_debug_cascades(&mut self, enabled: bool) -> &mut Self {
+        self.debug_cascades = enabled;
+        self
+    }
+
+    /// if the lit models are tinted by their shadow cascade
+    pub fn is_debug_cascades(&self) -> bool {
+        self.debug_cascades
+    }
+
+    /// get the view distance where each shadow cascade ends, for showing in a debug panel. see
+    /// [`shadow_map::calculate_cascade_splits`].
+    pub fn get_cascade_splits(&self) -> Vec<f32> {
+        shadow_map::calculate_cascade_splits(
+            self.camera_near,
+            self.shadow_distance.min(self.camera_far),
+            CASCADE_COUNT,
+            self.cascade_lambda,
+        )
+    }
+
+    /// sets how the shadow distance is split into cascades. the cascades are fitted again to the last camera right
+    /// away and the shadow map is rendered with them from the next frame.
+    ///
+    /// # Arguments
+    /// - `lambda` - 1 for logarithmic splits (more detail near the camera), 0 for uniform splits
+    ///   ([`DEFAULT_CASCADE_LAMBDA`](shadow_map::DEFAULT_CASCADE_LAMBDA) is a good start)
+    pub fn set_cascade_lambda(&mut self, lambda: f32) -> &mut Self {
+        self.cascade_lambda = lambda.clamp(0.0, 1.0);
+        self.update_cascades();
+        self
+    }
+
+    /// get the blend between logarithmic and uniform cascade splits
+    pub fn get_cascade_lambda(&self) -> f32 {
+        self.cascade_lambda
+    }
+
+    /// get the direction the light is coming from
+    pub fn get_direction(&self) -> glm::Vec3 {
+        glm::quat_rotate_vec3(&self.transform.rotation, &glm::vec3(0.0, 0.0, 1.0))
+    }
+
+    /// get the resolution of the shadow map
+    pub fn get_shadow_resolution(&self) -> u32 {
+        self.shadow_map.width as u32
     }
 
     /// get the far plane of the shadow cast by the directional light
@@ -305,26 +675,12 @@ impl DirectionalLight {
         self.shadow_distance
     }
 
-    /// set the far plane of the shadow cast by the directional light
+    /// set the far plane of the shadow cast by the directional light, the cascades cover a box around the origin
+    /// until they are fitted to the camera again on the next frame
     pub fn set_far_plane(&mut self, distance: f32) {
         self.shadow_distance = distance;
-        self.shadow_projections = glm::ortho(
-            -self.shadow_distance / 2.0,
-            self.shadow_distance / 2.0,
-            -self.shadow_distance / 2.0,
-            self.shadow_distance / 2.0,
-            0.1,
-            self.shadow_distance,
-        );
-        let light_direction =
-            glm::quat_rotate_vec3(&self.transform.rotation, &glm::vec3(0.0, 0.0, 1.0));
-        let light_position = light_direction * (self.shadow_distance / 2.0); //self.shadow_distance;
-        let light_view = glm::look_at(
-            &light_position,
-            &glm::vec3(0.0, 0.0, 0.0),
-            &glm::vec3(0.0, 1.0, 0.0),
-        );
-        self.light_space_matrix = self.shadow_projections * light_view;
+        self.view_corners = None;
+        self.update_cascades();
     }
 
     /// define the ready callback that is called when ready
@@ -361,8 +717,16 @@ impl DirectionalLight {
 pub trait DirectLightBuilder {
     fn set_direction(&mut self, direction: glm::Vec3) -> &mut Self;
     fn set_intensity(&mut self, intensity: f32) -> &mut Self;
+    /// set the intensity in lux, see [`DirectionalLight::set_intensity_lux`]
+    fn set_intensity_lux(&mut self, lux: f32) -> &mut Self;
     fn set_color(&mut self, color: Color) -> &mut Self;
     fn set_far_plane(&mut self, far: f32) -> &mut Self;
+    /// set how the shadow edges are filtered, see [`DirectionalLight::set_shadow_quality`]
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self;
+    /// set the depth bias of the shadow, see [`DirectionalLight::set_shadow_bias`]
+    fn set_shadow_bias(&mut self, bias: f32) -> &mut Self;
+    /// set the normal bias of the shadow, see [`DirectionalLight::set_normal_bias`]
+    fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self;
 }
 
 impl DirectLightBuilder for NodeBuilder<DirectionalLight> {
@@ -378,10 +742,29 @@ impl DirectLightBuilder for NodeBuilder<DirectionalLight> {
         self.node.set_intensity(intensity);
         self
     }
+    fn set_intensity_lux(&mut self, lux: f32) -> &mut Self {
+        self.node.set_intensity_lux(lux);
+        self
+    }
     fn set_far_plane(&mut self, far: f32) -> &mut Self {
         self.node.set_far_plane(far);
         self
     }
+
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self {
+        self.node.set_shadow_quality(quality);
+        self
+    }
+
+    fn set_shadow_bias(&mut self, bias: f32) -> &mut Self {
+        self.node.set_shadow_bias(bias);
+        self
+    }
+
+    fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self {
+        self.node.set_normal_bias(normal_bias);
+        self
+    }
 }
 
 impl UseReadyCallback for NodeBuilder<DirectionalLight> {
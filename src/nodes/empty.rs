@@ -19,10 +19,10 @@ use crate::components::NodeTransform;
 use crate::context::node_manager::{Behavior, Node, NodeManager, Ready};
 use crate::context::GameContext;
 
-use crate::context::node_manager::{BehaviorCallback, ReadyCallback};
+use crate::context::node_manager::{BehaviorCallback, FixedBehavior, ReadyCallback};
 use std::sync::{Arc, Mutex};
 
-use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+use super::{NodeBuilder, UseBehaviorCallback, UseFixedBehaviorCallback, UseReadyCallback};
 
 /// Empty nodes are nodes with no special functionality.
 #[derive(Clone)]
@@ -36,6 +36,8 @@ pub struct Empty {
     pub ready_callback: ReadyCallback<Empty>,
     /// the behavior callback
     pub behavior_callback: BehaviorCallback<Empty, GameContext>,
+    /// the fixed behavior callback
+    pub fixed_behavior_callback: BehaviorCallback<Empty, GameContext>,
 }
 
 impl Ready for Empty {
@@ -61,6 +63,17 @@ impl Behavior for Empty {
     }
 }
 
+impl FixedBehavior for Empty {
+    fn fixed_behavior(&mut self, context: &mut GameContext) {
+        if let Some(callback) = self.fixed_behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.fixed_behavior_callback = Some(callback);
+        }
+    }
+}
+
 impl Node for Empty {
     fn get_transform(&mut self) -> &mut NodeTransform {
         &mut self.transform
@@ -70,6 +83,10 @@ impl Node for Empty {
         &mut self.children
     }
 
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
     fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
         Some(self)
     }
@@ -77,6 +94,10 @@ impl Node for Empty {
     fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
         Some(self)
     }
+
+    fn as_fixed_behavior(&mut self) -> Option<&mut (dyn FixedBehavior + 'static)> {
+        Some(self)
+    }
 }
 
 impl Default for Empty {
@@ -97,6 +118,7 @@ impl Empty {
 
             ready_callback: None,
             behavior_callback: None,
+            fixed_behavior_callback: None,
         }
     }
 
@@ -123,6 +145,18 @@ impl Empty {
         self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
         self
     }
+
+    /// define the fixed behavior callback for the node, called at the fixed timestep instead of every frame
+    ///
+    /// # Arguments
+    /// - `fixed_behavior_function` - The function to be called every fixed update.
+    pub fn define_fixed_behavior<F>(&mut self, fixed_behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.fixed_behavior_callback = Some(Arc::new(Mutex::new(fixed_behavior_function)));
+        self
+    }
 }
 
 impl UseReadyCallback for NodeBuilder<Empty> {
@@ -149,3 +183,14 @@ impl UseBehaviorCallback for NodeBuilder<Empty> {
     }
 }
 
+impl UseFixedBehaviorCallback for NodeBuilder<Empty> {
+    type Node = Empty;
+
+    fn with_fixed_behavior<F>(&mut self, fixed_behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Empty, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_fixed_behavior(fixed_behavior_function);
+        self
+    }
+}
@@ -4,22 +4,34 @@
 //! you can any node that implement the Node trait to the scene tree. even nodes that you create.
 
 // re-export nodes
-pub use camera::Camera3D;
+pub use audio_source::AudioSource;
+pub use camera::{Camera2D, Camera3D};
+pub use collider::{Collider, CollisionEvent};
 pub use container::Container;
 pub use directional_light::DirectionalLight;
 pub use empty::Empty;
-pub use model::Model;
+pub use model::{
+    BillboardMode, ImportOptions, Model, ModelError, ModelHandle, ModelSource, UpAxis, Vertex,
+};
 pub use point_light::PointLight;
-pub use ui::UI;
+pub use sprite::Sprite;
+pub use text::{Text, TextAnchor};
+pub use time_of_day::TimeOfDay;
+pub use ui::{UILayer, UI};
 
 pub use node_builder::NodeBuilder;
 
+pub mod audio_source;
 pub mod camera;
+pub mod collider;
 pub mod directional_light;
 pub mod empty;
 pub mod model;
 pub mod node_builder;
 pub mod point_light;
+pub mod sprite;
+pub mod text;
+pub mod time_of_day;
 pub mod ui;
 
 pub mod container;
@@ -43,3 +55,13 @@ pub trait UseBehaviorCallback {
     where
         F: 'static + FnMut(&mut Self::Node, &mut GameContext) + Send + Sync;
 }
+
+/// for nodes that use callbacks for their functions
+pub trait UseFixedBehaviorCallback {
+    /// the node passed to the callback
+    type Node;
+    /// define the callback called at the fixed timestep
+    fn with_fixed_behavior<F>(&mut self, fixed_behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self::Node, &mut GameContext) + Send + Sync;
+}
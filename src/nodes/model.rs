@@ -16,48 +16,49 @@
 //!
 //! // or load a model
 //!
-//! //engine.context.nodes.add("model", Model::new_gltf("res/models/model.gltf"));
+//! //engine.context.nodes.add("model", Model::new_gltf("res/models/model.gltf")?);
 //!
 //! //engine.begin();
 //! ```
 
 use gl::GetActiveSubroutineName;
 use glm::{Mat4, Vec3};
+use gltf::animation::util::ReadOutputs;
 use gltf::Document;
 use nalgebra_glm as glm;
+use std::cell::RefCell;
 use std::fs::read;
-use std::io::Write;
 use std::{collections::HashMap, path::Path, rc::Rc};
 
-use colored::*;
+use std::sync::{Arc, Mutex};
 
-use std::thread;
-use std::time::Duration;
-
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
-};
-
-use crate::context::node_manager::{BehaviorCallback, ReadyCallback};
+use crate::context::node_manager::{BehaviorCallback, FixedBehavior, ReadyCallback};
 
+use crate::context::asset_loader::{AssetError, AssetFuture};
 use crate::context::GameContext;
 
-use crate::renderer::texture::TextureType;
+use crate::renderer::draw_queue::DrawQueue;
+use crate::renderer::texture::{FilterMode, TextureConfig, TextureKey, TextureType, WrapMode};
 use crate::renderer::{shader::Shader, texture::Texture};
 
 use crate::components::NodeTransform;
 
+use crate::components::mesh::{calculate_tangents, flat_shade};
 use crate::components::{
+    animation::{
+        Animation, AnimationChannel, Animator, ChannelValues, Interpolation, SkeletonNode, Skin,
+        MAX_JOINTS,
+    },
     mesh::{AlphaMode, MaterialProperties},
-    Mesh,
+    obj, shapes, Aabb, Mesh,
 };
 
 use super::camera::Camera3D;
-use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+use super::{NodeBuilder, UseBehaviorCallback, UseFixedBehaviorCallback, UseReadyCallback};
 use crate::context::node_manager::{Behavior, Drawable, Node, NodeManager, Ready};
 
 /// Primitive shapes that can be loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Primitive {
     /// Cube primitive
     Cube,
@@ -69,12 +70,103 @@ pub enum Primitive {
     Pyramid,
     /// Cylinder primitive
     Cylinder,
-    /// Torus primitive
+    /// Torus primitive, 2.5 wide with a 0.5 thick tube (see [`Model::new_torus`] for other proportions)
     Torus,
     /// Cone primitive
     Cone,
     /// Teapot primitive
     Teapot,
+    /// Capsule primitive, 1 wide and 2 tall (see [`Model::new_capsule`] for other proportions)
+    Capsule,
+}
+
+/// The up axis of an imported file.
+///
+/// the engine is right-handed with +Y up and +Z forward, the same as glTF. files exported with Z up
+/// (common in CAD tools and some Blender exports) are rotated into the engine convention on import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum UpAxis {
+    /// +Y up (glTF default, no conversion)
+    #[default]
+    Y,
+    /// +Z up with -Y forward, rotated -90 degrees around X on import
+    Z,
+}
+
+impl UpAxis {
+    /// get the rotation that converts from this axis convention into the engine convention
+    pub fn to_engine_rotation(&self) -> glm::Quat {
+        match self {
+            UpAxis::Y => glm::Quat::identity(),
+            UpAxis::Z => {
+                glm::quat_angle_axis(-std::f32::consts::FRAC_PI_2, &glm::vec3(1.0, 0.0, 0.0))
+            }
+        }
+    }
+}
+
+/// options used when importing a model from a file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// the up axis of the file, see [`UpAxis`]
+    pub up_axis: UpAxis,
+    /// how the textures of the file are filtered and wrapped, see [`TextureConfig`]
+    pub texture: TextureConfig,
+}
+
+/// how a model turns to face the active camera when it is drawn, see [`Model::set_billboard_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BillboardMode {
+    /// the model keeps its own rotation
+    #[default]
+    Disabled,
+    /// +Z of the model points at the camera, for sprites, health bars and light flares
+    Full,
+    /// the model only turns around the world Y axis to face the camera so it stays upright, for trees and
+    /// characters drawn as quads
+    YAxis,
+}
+
+impl BillboardMode {
+    /// replaces the rotation of a world transform so it faces the camera, the position and scale are kept
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the model
+    /// - `camera_position` - the world position of the camera
+    ///
+    /// # Returns
+    /// the transform to draw the model with
+    pub fn apply(&self, world_transform: NodeTransform, camera_position: &Vec3) -> NodeTransform {
+        let up = glm::vec3(0.0, 1.0, 0.0);
+        let mut transform = world_transform;
+        match self {
+            BillboardMode::Disabled => {}
+            BillboardMode::Full => {
+                transform.look_at(*camera_position, up);
+            }
+            BillboardMode::YAxis => {
+                let target = glm::vec3(camera_position.x, transform.position.y, camera_position.z);
+                transform.look_at(target, up);
+            }
+        }
+        transform
+    }
+}
+
+/// where a model was loaded from so it can be loaded again, for example by [scene files](crate::context::scene_file)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelSource {
+    /// one of the built in shapes
+    Primitive(Primitive),
+    /// a gltf, glb or obj file
+    File {
+        /// the path the file was loaded from
+        path: String,
+        /// the up axis the file was imported with
+        #[serde(default)]
+        up_axis: UpAxis,
+    },
 }
 
 /// Vertex of a mesh
@@ -89,6 +181,43 @@ pub struct Vertex {
     pub color: glm::Vec4,
     /// texture uv of the vertex
     pub tex_uv: glm::Vec2,
+    /// direction of increasing u along the surface in xyz, used to orient the normal map (zero if the mesh has no normal
+    /// map). w is the handedness, -1 when the bitangent points the other way for mirrored uvs
+    pub tangent: glm::Vec4,
+}
+
+impl Vertex {
+    /// creates a white vertex, the tangent is filled in by [`Mesh::from_raw`] or
+    /// [`calculate_tangents`]
+    ///
+    /// # Arguments
+    /// - `position` - position of the vertex
+    /// - `normal` - normal of the vertex
+    /// - `tex_uv` - texture uv of the vertex
+    pub fn new(position: glm::Vec3, normal: glm::Vec3, tex_uv: glm::Vec2) -> Vertex {
+        Vertex {
+            position,
+            normal,
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+            tex_uv,
+            tangent: glm::Vec4::zeros(),
+        }
+    }
+
+    /// sets the color of the vertex which is multiplied with the material color
+    pub fn with_color(mut self, color: glm::Vec4) -> Vertex {
+        self.color = color;
+        self
+    }
+}
+
+/// the vertex data read from a gltf primitive
+struct PrimitiveData {
+    vertices: Vec<Vertex>,
+    /// empty when the vertices are drawn in order
+    indices: Vec<u32>,
+    /// the joints and weights of every vertex, when the primitive has them
+    skin: Option<(Vec<[u16; 4]>, Vec<[f32; 4]>)>,
 }
 
 /// Mesh node that holds the mesh data
@@ -100,6 +229,132 @@ pub struct MeshNode {
     pub transform: NodeTransform,
     /// mesh primitives of the node
     mesh_primitives: Vec<Mesh>,
+    /// the skin that moves the meshes, skinned nodes ignore their own transform
+    skin: Option<usize>,
+}
+
+impl MeshNode {
+    /// the transform the meshes of the node are drawn with
+    fn world_transform(&self, parent_transform: NodeTransform) -> NodeTransform {
+        if self.skin.is_some() {
+            // the joints already place the vertices relative to the model
+            parent_transform
+        } else {
+            parent_transform.compose(&self.transform)
+        }
+    }
+}
+
+/// Errors returned when loading a model file.
+#[derive(Debug)]
+pub enum ModelError {
+    /// the file or a buffer it references could not be read.
+    Io(std::io::Error),
+    /// the file is not a valid model, with the reason from the parser.
+    Parse(String),
+    /// the extension of the file is not a model format, see [`Model::load`].
+    UnsupportedFormat(String),
+    /// the file was parsed in the background but never uploaded to the gpu, see [`Model::load_async`].
+    Upload(AssetError),
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::Io(error) => write!(f, "failed to read model: {}", error),
+            ModelError::Parse(reason) => write!(f, "failed to parse model: {}", reason),
+            ModelError::UnsupportedFormat(file) => write!(f, "unsupported model format: {}", file),
+            ModelError::Upload(error) => write!(f, "failed to upload model: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelError::Io(error) => Some(error),
+            ModelError::Upload(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<AssetError> for ModelError {
+    fn from(error: AssetError) -> Self {
+        ModelError::Upload(error)
+    }
+}
+
+impl From<gltf::Error> for ModelError {
+    fn from(error: gltf::Error) -> Self {
+        match error {
+            gltf::Error::Io(error) => ModelError::Io(error),
+            error => ModelError::Parse(error.to_string()),
+        }
+    }
+}
+
+/// a gltf file loading in the background, returned by [`Model::new_gltf_async`]
+///
+/// poll [`is_ready`](ModelHandle::is_ready) and [`try_take`](ModelHandle::try_take) yourself, or turn the handle into a
+/// placeholder model with [`with_placeholder`](ModelHandle::with_placeholder) that swaps in the loaded meshes on its own.
+pub struct ModelHandle {
+    path: String,
+    future: AssetFuture<Model, ModelError>,
+}
+
+impl ModelHandle {
+    /// get the path of the file that is loading
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// check if the file is parsed and uploaded to the gpu
+    pub fn is_ready(&self) -> bool {
+        self.future.is_ready()
+    }
+
+    /// takes the loaded model out of the handle without waiting for it
+    ///
+    /// # Returns
+    /// the model or the [`ModelError`], `None` while it is still loading (or when it was already taken)
+    pub fn try_take(&mut self) -> Option<Result<Model, ModelError>> {
+        self.future.try_take()
+    }
+
+    /// turns the handle into a model node that draws the placeholder until the file is loaded
+    ///
+    /// the meshes, skin and animations of the file replace the ones of the placeholder in the first behavior after the
+    /// upload. the transform, children, callbacks, tint and shadow and lighting settings of the placeholder are kept. if
+    /// the file fails to load the error is logged and the placeholder stays.
+    ///
+    /// # Arguments
+    /// - `placeholder` - the model drawn while loading, for example [`Model::new_primitive`]
+    ///
+    /// # Returns
+    /// the placeholder model
+    pub fn with_placeholder(self, mut placeholder: Model) -> Model {
+        placeholder.source = Some(ModelSource::File {
+            path: self.path.clone(),
+            up_axis: UpAxis::Y,
+        });
+        placeholder.pending = Some(Rc::new(RefCell::new(PendingLoad::Loading(self))));
+        placeholder
+    }
+
+    /// turns the handle into a model node that draws nothing until the file is loaded, see
+    /// [`with_placeholder`](ModelHandle::with_placeholder)
+    pub fn into_model(self) -> Model {
+        self.with_placeholder(Model::empty())
+    }
+}
+
+/// the load shared by a placeholder model and its clones
+enum PendingLoad {
+    Loading(ModelHandle),
+    /// kept so every clone of the placeholder can copy the meshes
+    Loaded(Box<Model>),
+    Failed,
 }
 
 /// Model node that holds the mesh nodes from a file or primitive shapes
@@ -115,10 +370,28 @@ pub struct Model {
     cast_shadows: bool,
 
     has_lighting: bool,
+    /// color multiplied into the final color of every mesh (None draws the model unchanged)
+    tint: Option<glm::Vec4>,
+    /// plays the animations of skinned models (None for models without skins)
+    animator: Option<Animator>,
+    /// what the model was loaded from (None for models built in code or simplified)
+    source: Option<ModelSource>,
+    /// how the model turns to face the camera
+    billboard_mode: BillboardMode,
+    /// the layer the model is drawn on in the main pass, see [`Model::set_render_layer`]
+    render_layer: i32,
+    /// if [`bake_static`](Model::bake_static) leaves the meshes alone
+    dynamic: bool,
+    /// if the meshes were merged by [`bake_static`](Model::bake_static)
+    baked: bool,
+    /// the file loading in the background, the meshes are swapped in once it is uploaded (None when nothing is loading)
+    pending: Option<Rc<RefCell<PendingLoad>>>,
     /// callback to be called when the model is ready
     ready_callback: ReadyCallback<Model>,
     /// callback to be called when the model is behaving
     behavior_callback: BehaviorCallback<Model, GameContext>,
+    /// the fixed behavior callback
+    fixed_behavior_callback: BehaviorCallback<Model, GameContext>,
 }
 
 impl Node for Model {
@@ -130,6 +403,10 @@ impl Node for Model {
         &mut self.children
     }
 
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
     fn as_ready(&mut self) -> Option<&mut (dyn Ready)> {
         Some(self)
     }
@@ -137,6 +414,10 @@ impl Node for Model {
     fn as_behavior(&mut self) -> Option<&mut (dyn Behavior)> {
         Some(self)
     }
+
+    fn as_fixed_behavior(&mut self) -> Option<&mut dyn FixedBehavior> {
+        Some(self)
+    }
 }
 
 impl Ready for Model {
@@ -152,6 +433,9 @@ impl Ready for Model {
 
 impl Behavior for Model {
     fn behavior(&mut self, context: &mut GameContext) {
+        // the engine runs the uploads before the behaviors so a finished load shows up this frame
+        self.poll_pending();
+
         // take callback out of self so we can use self later
         if let Some(callback) = self.behavior_callback.take() {
             let mut guard = callback.lock().unwrap();
@@ -159,6 +443,21 @@ impl Behavior for Model {
             drop(guard); // delete stupid fucking guard because its stupid and dumb
             self.behavior_callback = Some(callback);
         }
+
+        if let Some(animator) = &mut self.animator {
+            animator.update(context.frame.time_delta.as_secs_f32());
+        }
+    }
+}
+
+impl FixedBehavior for Model {
+    fn fixed_behavior(&mut self, context: &mut GameContext) {
+        if let Some(callback) = self.fixed_behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.fixed_behavior_callback = Some(callback);
+        }
     }
 }
 
@@ -169,52 +468,12 @@ impl Drawable for Model {
         camera: (&Camera3D, NodeTransform),
         parent_transform: NodeTransform,
     ) {
-        shader.bind();
-        shader.set_uniform("u_LightingEnabled", self.has_lighting);
-
-        //draw order
-        // 1. opaque meshes
-        // 2. transparent meshes sorted by distance from camera
         let camera_position = camera.0.get_position(camera.1);
 
-        let mut opaque_meshes: Vec<(&mut Mesh, NodeTransform)> = Vec::new();
-        let mut transparent_meshes: Vec<(&mut Mesh, NodeTransform)> = Vec::new();
-
-        for node in &mut self.nodes {
-            let world_relative = node.transform + parent_transform;
-            for mesh in &mut node.mesh_primitives {
-                match mesh.material_properties.alpha_mode {
-                    AlphaMode::Opaque => {
-                        opaque_meshes.push((mesh, world_relative));
-                    }
-                    AlphaMode::Blend | AlphaMode::Mask => {
-                        transparent_meshes.push((mesh, world_relative));
-                    }
-                }
-            }
-        }
-
-        // Draw all opaque meshes first
-        for (mesh, transform) in &mut opaque_meshes {
-            shader.bind();
-            // println!("{:?}", transform);
-            shader.set_uniform("u_Model", transform.matrix);
-
-            mesh.draw(shader, camera);
-        }
-
-        // Sort transparent meshes by distance (back-to-front)
-        transparent_meshes.sort_by(|a, b| {
-            let a_distance = glm::length(&(camera_position - a.1.get_position())) as i32;
-            let b_distance = glm::length(&(camera_position - b.1.get_position())) as i32;
-            b_distance.cmp(&a_distance)
-        });
-
-        // Draw transparent meshes in sorted order
-        for (mesh, transform) in &mut transparent_meshes {
-            shader.bind();
-            shader.set_uniform("u_Model", transform.matrix);
-            mesh.draw(shader, camera);
+        let mut queue = DrawQueue::new();
+        self.queue_meshes(parent_transform, &camera_position, &mut queue);
+        for queued in queue.drain() {
+            self.draw_mesh(shader, camera, &queued.item);
         }
     }
 
@@ -223,18 +482,113 @@ impl Drawable for Model {
             return;
         }
 
-        for node in &self.nodes {
+        for (node, transform) in self.mesh_transforms(parent_transform) {
             depth_shader.bind();
-            depth_shader.set_uniform("u_Model", (node.transform + parent_transform).matrix);
+            depth_shader.set_uniform("u_Model", transform.matrix);
+            Self::bind_skin(self.animator.as_ref(), depth_shader, node.skin);
 
             for mesh in &node.mesh_primitives {
                 mesh.draw_shadow(depth_shader);
             }
         }
+
+        Self::unbind_skin(self.animator.as_ref(), depth_shader);
+    }
+}
+
+/// a mesh of a model waiting in a [`DrawQueue`] of the main pass
+pub(crate) struct MeshDraw {
+    /// the model the mesh belongs to
+    model: *const Model,
+    /// the mesh to draw
+    mesh: *const Mesh,
+    /// the world transform of the mesh node
+    transform: NodeTransform,
+    /// the skin of the mesh node
+    skin: Option<usize>,
+}
+
+impl MeshDraw {
+    /// the model the mesh belongs to
+    ///
+    /// # Safety
+    /// the model must not have been moved or dropped since it was queued
+    pub(crate) unsafe fn model(&self) -> &Model {
+        &*self.model
     }
 }
 
 impl Model {
+    /// the lowest render layer drawn without depth test or depth writes, see [`Model::set_render_layer`]
+    pub const OVERLAY_LAYER: i32 = 100;
+
+    /// queues the meshes of the model so they can be ordered with the meshes of other models, see [`DrawQueue`].
+    ///
+    /// the queued meshes point into the model so the model can't be moved or changed until the queue is drained.
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the model (its own transform composed with its parents)
+    /// - `camera_position` - the world position of the camera to sort by
+    /// - `queue` - the queue to add the meshes to
+    pub(crate) fn queue_meshes(
+        &self,
+        world_transform: NodeTransform,
+        camera_position: &glm::Vec3,
+        queue: &mut DrawQueue<MeshDraw>,
+    ) {
+        for (node, transform) in self.mesh_transforms(world_transform) {
+            for mesh in &node.mesh_primitives {
+                let center = (transform.matrix
+                    * glm::vec4(mesh.center.x, mesh.center.y, mesh.center.z, 1.0))
+                .xyz();
+                queue.push(
+                    MeshDraw {
+                        model: self,
+                        mesh,
+                        transform,
+                        skin: node.skin,
+                    },
+                    self.render_layer,
+                    mesh.material_properties.alpha_mode == AlphaMode::Blend,
+                    glm::distance2(camera_position, &center),
+                );
+            }
+        }
+    }
+
+    /// get the mesh nodes with the transform their meshes are drawn with, shared by the main and the shadow passes so
+    /// both upload the same `u_Model`
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the model
+    fn mesh_transforms(
+        &self,
+        world_transform: NodeTransform,
+    ) -> impl Iterator<Item = (&MeshNode, NodeTransform)> + '_ {
+        self.nodes
+            .iter()
+            .map(move |node| (node, node.world_transform(world_transform)))
+    }
+
+    /// draws a mesh queued by [`queue_meshes`](Model::queue_meshes) of this model
+    pub(crate) fn draw_mesh(
+        &self,
+        shader: &mut Shader,
+        camera: (&Camera3D, NodeTransform),
+        draw: &MeshDraw,
+    ) {
+        shader.bind();
+        shader.set_uniform("u_LightingEnabled", self.has_lighting);
+        shader.set_uniform("u_tint", self.tint.unwrap_or(glm::vec4(1.0, 1.0, 1.0, 1.0)));
+        shader.set_uniform("u_Model", draw.transform.matrix);
+        Self::bind_skin(self.animator.as_ref(), shader, draw.skin);
+
+        // SAFETY: the mesh belongs to this model which is not changed while its meshes are queued
+        unsafe { (*draw.mesh).draw(shader, camera) };
+
+        Self::unbind_skin(self.animator.as_ref(), shader);
+    }
+
     /// load a primitive shape model the shapes are self explanatory
     ///
     /// # Arguments
@@ -243,7 +597,7 @@ impl Model {
     /// # Returns
     /// the model node with the primitive shape loaded
     pub fn new_primitive(primitive: Primitive) -> Model {
-        match primitive {
+        let mut model = match primitive {
             Primitive::Cube => {
                 self::Model::from_slice(include_bytes!("../../res/primitives/cube.glb"))
             }
@@ -256,9 +610,7 @@ impl Model {
             Primitive::Pyramid => {
                 self::Model::from_slice(include_bytes!("../../res/primitives/pyramid.glb"))
             }
-            Primitive::Torus => {
-                self::Model::from_slice(include_bytes!("../../res/primitives/torus.glb"))
-            }
+            Primitive::Torus => self::Model::from_shape("torus", shapes::torus(1.0, 0.25, 48, 12)),
             Primitive::Cylinder => {
                 self::Model::from_slice(include_bytes!("../../res/primitives/cylinder.glb"))
             }
@@ -268,244 +620,1478 @@ impl Model {
             Primitive::Teapot => {
                 self::Model::from_slice(include_bytes!("../../res/primitives/teapot.glb"))
             }
-        }
+            Primitive::Capsule => {
+                self::Model::from_shape("capsule", shapes::capsule(0.5, 2.0, 32, 8))
+            }
+        };
+        model.source = Some(ModelSource::Primitive(primitive));
+        model
     }
 
-    /// load a model from a gltf file
+    /// generate a torus lying flat around the Y axis, see [`shapes::torus`]
     ///
     /// # Arguments
-    /// * `file` - the path to the gltf file
+    /// - `major_radius` - the distance from the center to the middle of the tube
+    /// - `minor_radius` - the radius of the tube
+    /// - `major_segments` - the segments around the ring
+    /// - `minor_segments` - the segments around the tube
     ///
     /// # Returns
-    /// the model node with the model loaded
-    ///
-    /// # Panics
-    /// if the file does not exist or is not a valid gltf file
-    pub fn new_gltf(file: &str) -> Model {
-        let model_loaded = Arc::new(AtomicBool::new(false));
-        let model_loaded_clone = model_loaded.clone();
-        let loading_thread = thread::spawn(move || {
-            let animation = ["\\", "|", "/", "-"];
-            let mut i = 0;
-            while !model_loaded_clone.load(Ordering::SeqCst) {
-                print!("{}", format!("\rloading model: {}", animation[i]).cyan()); // Overwrite the previous line
-                std::io::stdout().flush().unwrap();
-                i = (i + 1) % 4;
-
-                thread::sleep(Duration::from_millis(50));
-            }
-
-            // clear the loading animation
-            print!("\r                                \r");
-            std::io::stdout().flush().unwrap();
-        });
-
-        let gltf = gltf::import(Path::new(file)).expect("failed to open GLTF file");
-
-        //end thread here
-        model_loaded.store(true, Ordering::SeqCst);
-        loading_thread.join().unwrap();
-
-        Self::build_model(gltf)
+    /// the model node with the torus
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let ring = Model::new_torus(1.0, 0.25, 48, 16);
+    /// ```
+    pub fn new_torus(
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: u32,
+        minor_segments: u32,
+    ) -> Model {
+        Self::from_shape(
+            "torus",
+            shapes::torus(major_radius, minor_radius, major_segments, minor_segments),
+        )
     }
 
-    fn from_slice(data: &[u8]) -> Model {
-        let gltf = gltf::import_slice(data).expect("failed to open GLTF file");
-
-        Self::build_model(gltf)
+    /// generate a capsule standing along the Y axis, see [`shapes::capsule`]
+    ///
+    /// # Arguments
+    /// - `radius` - the radius of the cylinder and the half spheres
+    /// - `height` - the total height including the half spheres
+    /// - `segments` - the segments around the Y axis
+    /// - `rings` - the rings of each half sphere
+    ///
+    /// # Returns
+    /// the model node with the capsule
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // a placeholder for a player collider
+    /// let player = Model::new_capsule(0.4, 1.8, 32, 8);
+    /// ```
+    pub fn new_capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Model {
+        Self::from_shape("capsule", shapes::capsule(radius, height, segments, rings))
     }
 
-    fn build_model(gltf: (Document, Vec<gltf::buffer::Data>, Vec<gltf::image::Data>)) -> Model {
-        let (doc, buffers, images) = gltf;
-        let mut nodes: Vec<MeshNode> = Vec::new();
-
-        let mut texture_cache: HashMap<usize, Rc<Texture>> = HashMap::new(); // Cache with key as image index and value as a smart pointer to the texture
-
-        for node in doc.nodes() {
-            let (translation, rotation, scale) = node.transform().decomposed();
-            let translation: Vec3 = glm::make_vec3(&translation);
-            let rotation = glm::make_quat(&rotation);
-            let scale: Vec3 = glm::make_vec3(&scale);
-
-            if let Some(mesh) = node.mesh() {
-                let mut primitive_meshes: Vec<Mesh> = Vec::new();
-
-                for primitive in mesh.primitives() {
-                    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
-
-                    // Get vertex data from reader
-                    let positions: Vec<[f32; 3]> = reader.read_positions().unwrap().collect();
-                    let normals: Vec<[f32; 3]> = reader.read_normals().unwrap().collect();
-                    let tex_coords: Vec<[f32; 2]> =
-                        reader.read_tex_coords(0).unwrap().into_f32().collect();
-
-                    let color = if let Some(colors) = reader.read_colors(0) {
-                        let colors: Vec<[f32; 4]> = colors.into_rgba_f32().collect();
-                        glm::make_vec4(&colors[0])
-                    } else {
-                        glm::vec4(1.0, 1.0, 1.0, 1.0)
-                    };
-
-                    let indices = if let Some(indices) = reader.read_indices() {
-                        indices.into_u32().collect::<Vec<u32>>()
-                    } else {
-                        Vec::new()
-                    };
-
-                    // Construct vertices from the extracted data
-                    let vertices: Vec<Vertex> = positions
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, pos)| Vertex {
-                            position: glm::make_vec3(&pos),
-                            normal: glm::make_vec3(&normals[i]),
-                            tex_uv: glm::make_vec2(&tex_coords[i]),
-                            color,
-                        })
-                        .collect();
-
-                    // Load textures
-                    let mut textures: Vec<Rc<Texture>> = Vec::new();
-
-                    // Load diffuse texture
-                    if let Some(material) = primitive
-                        .material()
-                        .pbr_metallic_roughness()
-                        .base_color_texture()
-                    {
-                        let image_index = material.texture().source().index();
-                        let shared_texture = texture_cache
-                            .entry(image_index)
-                            .or_insert_with(|| {
-                                let image = &images[image_index];
-                                let format = match image.format {
-                                    gltf::image::Format::R8G8B8A8 => gl::RGBA,
-                                    gltf::image::Format::R8G8B8 => gl::RGB,
-                                    gltf::image::Format::R8 => gl::RED,
-                                    _ => panic!("unsupported image format not rgba, rgb, or r"),
-                                };
-                                Rc::new(Texture::load_from_gltf(
-                                    &image.pixels,
-                                    image.width,
-                                    image.height,
-                                    TextureType::Diffuse,
-                                    format,
-                                ))
-                            })
-                            .clone();
-
-                        textures.push(shared_texture);
-                    }
-
-                    // Load specular texture
-                    if let Some(material) = primitive
-                        .material()
-                        .pbr_metallic_roughness()
-                        .metallic_roughness_texture()
-                    {
-                        let image_index = material.texture().source().index();
-                        let shared_texture = texture_cache
-                            .entry(image_index)
-                            .or_insert_with(|| {
-                                let image = &images[image_index];
-                                let format = match image.format {
-                                    gltf::image::Format::R8G8B8A8 => gl::RGBA,
-                                    gltf::image::Format::R8G8B8 => gl::RGB,
-                                    _ => gl::RGB,
-                                };
-                                Rc::new(Texture::load_from_gltf(
-                                    &image.pixels,
-                                    image.width,
-                                    image.height,
-                                    TextureType::Specular,
-                                    format,
-                                ))
-                            })
-                            .clone();
-
-                        textures.push(shared_texture);
-                    }
-
-                    // Create the mesh
-                    let mesh = Mesh::new(
-                        vertices,
-                        indices,
-                        textures,
-                        MaterialProperties {
-                            base_color_factor: glm::make_vec4(
-                                &primitive
-                                    .material()
-                                    .pbr_metallic_roughness()
-                                    .base_color_factor(),
-                            ),
-                            metallic_factor: primitive
-                                .material()
-                                .pbr_metallic_roughness()
-                                .metallic_factor(),
-                            roughness_factor: primitive
-                                .material()
-                                .pbr_metallic_roughness()
-                                .roughness_factor(),
-                            double_sided: primitive.material().double_sided(),
-                            alpha_mode: match primitive.material().alpha_mode() {
-                                gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
-                                gltf::material::AlphaMode::Mask => AlphaMode::Mask,
-                                gltf::material::AlphaMode::Blend => AlphaMode::Blend,
-                            },
-                            alpha_cutoff: primitive.material().alpha_cutoff().unwrap_or(0.5),
-                        },
-                    );
-                    primitive_meshes.push(mesh);
-                }
-
-                let node = MeshNode {
-                    _name: node.name().unwrap_or_default().to_string(),
-                    transform: NodeTransform::new(translation, rotation, scale),
-                    mesh_primitives: primitive_meshes,
-                };
-                nodes.push(node);
-            }
-        }
+    /// builds a model with a single white mesh from generated vertices
+    fn from_shape(name: &str, (vertices, indices): (Vec<Vertex>, Vec<u32>)) -> Model {
+        let material = MaterialProperties::new(
+            glm::vec4(1.0, 1.0, 1.0, 1.0),
+            0.0,
+            0.5,
+            false,
+            AlphaMode::Opaque,
+            0.5,
+        );
+        let mut model = Self::from_mesh(Mesh::from_raw(vertices, indices, material));
+        model.nodes[0]._name = name.to_string();
+        model
+    }
 
+    /// create a model from a mesh built in code, for example terrain or voxel chunks
+    ///
+    /// # Arguments
+    /// - `mesh` - the mesh of the model
+    ///
+    /// # Returns
+    /// the model node with the mesh
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let vertices = vec![
+    ///     Vertex::new(glm::vec3(0.0, 0.0, 0.0), glm::vec3(0.0, 0.0, 1.0), glm::vec2(0.0, 1.0)),
+    ///     Vertex::new(glm::vec3(1.0, 0.0, 0.0), glm::vec3(0.0, 0.0, 1.0), glm::vec2(1.0, 1.0)),
+    ///     Vertex::new(glm::vec3(0.0, 1.0, 0.0), glm::vec3(0.0, 0.0, 1.0), glm::vec2(0.0, 0.0)),
+    /// ];
+    /// let triangle = Model::from_mesh(Mesh::from_raw(vertices, vec![0, 1, 2], MaterialProperties::default()));
+    /// engine.context.nodes.add("triangle", triangle);
+    /// ```
+    pub fn from_mesh(mesh: Mesh) -> Model {
         Model {
-            nodes,
+            nodes: vec![MeshNode {
+                _name: "mesh".to_string(),
+                transform: NodeTransform::default(),
+                skin: None,
+                mesh_primitives: vec![mesh],
+            }],
             cast_shadows: true,
             has_lighting: true,
+            tint: None,
+            billboard_mode: BillboardMode::Disabled,
+            render_layer: 0,
+            animator: None,
+            source: None,
             transform: NodeTransform::default(),
             children: NodeManager::new(),
             ready_callback: None,
             behavior_callback: None,
+            fixed_behavior_callback: None,
+            dynamic: false,
+            baked: false,
+            pending: None,
         }
     }
 
-    pub fn casts_shadows(&mut self, cast_shadow: bool) -> &mut Self {
-        self.cast_shadows = cast_shadow;
-        self
-    }
-
-    pub fn has_lighting(&mut self, lighting: bool) -> &mut Self {
-        self.has_lighting = lighting;
-        self
-    }
-
-    pub fn set_material(&mut self, material: MaterialProperties) -> &mut Self {
-        for node in &mut self.nodes {
-            for mesh in &mut node.mesh_primitives {
-                mesh.set_material(material.clone());
-            }
-        }
-        self
-    }
-
-    /// define a callback to be called when the model is ready
+    /// replaces the vertices and indices of a mesh and uploads them to its existing gpu buffers.
+    ///
+    /// the tangents are recalculated like [`Mesh::from_raw`]. create the mesh with
+    /// [`Mesh::new_with_usage`] and [`BufferUsage::Dynamic`](crate::renderer::buffers::BufferUsage) if it changes
+    /// every frame.
     ///
     /// # Arguments
-    /// - `ready_function` - the function to be called when the model is ready
+    /// - `index` - the index of the mesh in the order of [`meshes`](Model::meshes), 0 for models made with [`from_mesh`](Model::from_mesh)
+    /// - `vertices` - the new vertices
+    /// - `indices` - the new triangle indices, empty draws the vertices in order
     ///
     /// # Returns
-    /// Self
-    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
-    where
+    /// `false` if the model has no mesh at the index
+    pub fn update_mesh(
+        &mut self,
+        index: usize,
+        mut vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+    ) -> bool {
+        let Some(mesh) = self.meshes_mut().nth(index) else {
+            return false;
+        };
+        calculate_tangents(&mut vertices, &indices);
+        mesh.update_indices(&indices);
+        mesh.update_vertices(&vertices);
+        true
+    }
+
+    /// load a model from a gltf file
+    ///
+    /// # Arguments
+    /// * `file` - the path to the gltf file
+    ///
+    /// # Returns
+    /// the model node with the model loaded, or the [`ModelError`] if the file can't be read or is not a valid gltf
+    /// file
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// match Model::new_gltf("res/models/ship.glb") {
+    ///     Ok(ship) => engine.context.nodes.add("ship", ship),
+    ///     Err(error) => show_error_screen(&error.to_string()),
+    /// }
+    /// ```
+    pub fn new_gltf(file: &str) -> Result<Model, ModelError> {
+        Self::new_gltf_with_options(file, ImportOptions::default())
+    }
+
+    /// load a model from a gltf file and convert it into the engine coordinate system
+    ///
+    /// # Arguments
+    /// * `file` - the path to the gltf file
+    /// * `options` - how to convert the file, see [`ImportOptions`]
+    ///
+    /// # Returns
+    /// the model node with the model loaded or the [`ModelError`], see [`new_gltf`](Model::new_gltf)
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // a model exported with Z up no longer needs a manual -90 degree rotation
+    /// let model = Model::new_gltf_with_options(
+    ///     "res/models/model.glb",
+    ///     ImportOptions { up_axis: UpAxis::Z, ..Default::default() },
+    /// )?;
+    /// ```
+    pub fn new_gltf_with_options(file: &str, options: ImportOptions) -> Result<Model, ModelError> {
+        log::debug!("loading model {}", file);
+        let gltf = gltf::import(Path::new(file))?;
+
+        let mut model = Self::build_model(gltf, options, Some(file))?;
+        model.source = Some(ModelSource::File {
+            path: file.to_string(),
+            up_axis: options.up_axis,
+        });
+        Ok(model)
+    }
+
+    /// load a model from a gltf file without blocking the main thread
+    ///
+    /// the file is read and parsed on a background thread, the meshes and textures are then uploaded to the gpu on the main thread
+    /// when the engine updates its [`AssetLoader`](crate::context::asset_loader::AssetLoader). this means the future only resolves
+    /// while the render loop is running (or when you call `context.assets.update()` yourself on the main thread).
+    ///
+    /// # Arguments
+    /// - `file` - the path to the gltf file
+    ///
+    /// # Returns
+    /// a future that resolves to the loaded model or the [`ModelError`], it has to be awaited on the main thread (see
+    /// [`AssetFuture`])
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let model = Model::load_async("res/models/model.gltf").await?;
+    /// ```
+    pub fn load_async(file: &str) -> AssetFuture<Model, ModelError> {
+        let path = file.to_string();
+        let source_path = path.clone();
+        AssetFuture::spawn(
+            move || gltf::import(Path::new(&path)),
+            move |gltf| {
+                let mut model =
+                    Self::build_model(gltf?, ImportOptions::default(), Some(&source_path))?;
+                model.source = Some(ModelSource::File {
+                    path: source_path,
+                    up_axis: UpAxis::Y,
+                });
+                Ok(model)
+            },
+        )
+    }
+
+    /// load a model from a gltf file on a background thread, the main thread keeps running while it loads
+    ///
+    /// works like [`load_async`](Model::load_async) but returns a handle that can be checked every frame instead of awaited.
+    /// the upload runs when the engine updates its [`AssetLoader`](crate::context::asset_loader::AssetLoader).
+    ///
+    /// # Arguments
+    /// - `file` - the path to the gltf file
+    ///
+    /// # Returns
+    /// the handle of the loading model
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // a cube stands in for the ship until the file is loaded
+    /// let ship = Model::new_gltf_async("res/models/ship.glb")
+    ///     .with_placeholder(Model::new_primitive(Primitive::Cube));
+    /// engine.context.nodes.add("ship", ship);
+    /// ```
+    pub fn new_gltf_async(file: &str) -> ModelHandle {
+        ModelHandle {
+            path: file.to_string(),
+            future: Self::load_async(file),
+        }
+    }
+
+    /// a model without meshes
+    fn empty() -> Model {
+        Model {
+            nodes: Vec::new(),
+            cast_shadows: true,
+            has_lighting: true,
+            tint: None,
+            billboard_mode: BillboardMode::Disabled,
+            render_layer: 0,
+            animator: None,
+            source: None,
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            fixed_behavior_callback: None,
+            dynamic: false,
+            baked: false,
+            pending: None,
+        }
+    }
+
+    /// swaps in the meshes of the file loading in the background once it is uploaded
+    fn poll_pending(&mut self) {
+        let Some(pending) = self.pending.clone() else {
+            return;
+        };
+
+        let mut state = pending.borrow_mut();
+        if let PendingLoad::Loading(handle) = &mut *state {
+            match handle.try_take() {
+                Some(Ok(model)) => *state = PendingLoad::Loaded(Box::new(model)),
+                Some(Err(err)) => {
+                    log::error!("failed to load model {}: {}", handle.path, err);
+                    *state = PendingLoad::Failed;
+                }
+                None => return,
+            }
+        }
+
+        if let PendingLoad::Loaded(model) = &*state {
+            self.nodes = model.nodes.clone();
+            self.animator = model.animator.clone();
+            self.baked = false;
+        }
+        self.pending = None;
+    }
+
+    /// load a model from a wavefront obj file and the mtl material libraries it references
+    ///
+    /// every object (`o`) becomes a mesh node and every material used by an object becomes a mesh. the diffuse (`map_Kd`),
+    /// specular (`map_Ks`) and normal (`norm` or `map_Bump`) textures are loaded relative to the mtl file.
+    ///
+    /// # Arguments
+    /// * `file` - the path to the obj file
+    ///
+    /// # Returns
+    /// the model node with the model loaded, or the [`ModelError`] if the file can't be read or is not a valid obj file
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let model = Model::new_obj("res/models/crate.obj")?;
+    /// ```
+    pub fn new_obj(file: &str) -> Result<Model, ModelError> {
+        let source = std::fs::read_to_string(file).map_err(ModelError::Io)?;
+        let data = obj::parse_obj(&source).map_err(|error| ModelError::Parse(error.to_string()))?;
+
+        let directory = Path::new(file).parent().unwrap_or(Path::new(""));
+
+        // materials by name with the textures already loaded
+        let mut materials: HashMap<String, (MaterialProperties, Vec<Rc<Texture>>)> = HashMap::new();
+
+        for library in &data.material_libraries {
+            let library_path = directory.join(library);
+            let Ok(library_source) = std::fs::read_to_string(&library_path) else {
+                log::warn!(
+                    "failed to load material library: {}",
+                    library_path.display()
+                );
+                continue;
+            };
+            let library_directory = library_path.parent().unwrap_or(Path::new(""));
+
+            for material in obj::parse_mtl(&library_source) {
+                let mut textures = Vec::new();
+                for (path, tex_type) in [
+                    (&material.diffuse_texture, TextureType::Diffuse),
+                    (&material.specular_texture, TextureType::Specular),
+                    (&material.normal_texture, TextureType::Normal),
+                ] {
+                    let Some(path) = path else {
+                        continue;
+                    };
+                    let path = library_directory.join(path).to_string_lossy().to_string();
+                    match Texture::load_from_file(&path, tex_type) {
+                        Some(texture) => textures.push(texture),
+                        None => log::warn!("failed to load texture: {}", path),
+                    }
+                }
+
+                // the texture replaces the diffuse color like a gltf base color texture with a white factor
+                let color = if material.diffuse_texture.is_some() {
+                    glm::vec3(1.0, 1.0, 1.0)
+                } else {
+                    material.diffuse
+                };
+                let mut properties = MaterialProperties::new(
+                    glm::vec4(color.x, color.y, color.z, material.opacity),
+                    0.0,
+                    // approximate the roughness from the phong exponent
+                    (2.0 / (material.shininess + 2.0)).sqrt(),
+                    false,
+                    if material.opacity < 1.0 {
+                        AlphaMode::Blend
+                    } else {
+                        AlphaMode::Opaque
+                    },
+                    0.5,
+                );
+                properties.set_emissive_factor(material.emissive);
+
+                materials.insert(material.name, (properties, textures));
+            }
+        }
+
+        let nodes = data
+            .objects
+            .into_iter()
+            .map(|object| MeshNode {
+                _name: object.name,
+                transform: NodeTransform::default(),
+                skin: None,
+                mesh_primitives: object
+                    .meshes
+                    .into_iter()
+                    .map(|mut mesh| {
+                        let (properties, textures) = mesh
+                            .material
+                            .and_then(|name| materials.get(&name).cloned())
+                            .unwrap_or_default();
+                        calculate_tangents(&mut mesh.vertices, &mesh.indices);
+                        Mesh::new(mesh.vertices, mesh.indices, textures, properties)
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Model {
+            nodes,
+            cast_shadows: true,
+            has_lighting: true,
+            tint: None,
+            billboard_mode: BillboardMode::Disabled,
+            render_layer: 0,
+            animator: None,
+            source: Some(ModelSource::File {
+                path: file.to_string(),
+                up_axis: UpAxis::Y,
+            }),
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            fixed_behavior_callback: None,
+            dynamic: false,
+            baked: false,
+            pending: None,
+        })
+    }
+
+    /// load a model from a file, picking the loader from the extension (`gltf`, `glb` or `obj`)
+    ///
+    /// # Arguments
+    /// * `file` - the path to the model file
+    ///
+    /// # Returns
+    /// the model node with the model loaded, or the [`ModelError`] if the extension is not supported or the file can't be
+    /// loaded
+    pub fn load(file: &str) -> Result<Model, ModelError> {
+        let extension = Path::new(file)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("gltf") | Some("glb") => Self::new_gltf(file),
+            Some("obj") => Self::new_obj(file),
+            _ => Err(ModelError::UnsupportedFormat(file.to_string())),
+        }
+    }
+
+    /// loads the primitives embedded in the engine, they are known to be valid
+    fn from_slice(data: &[u8]) -> Model {
+        let gltf = gltf::import_slice(data).expect("failed to open GLTF file");
+
+        Self::build_model(gltf, ImportOptions::default(), None).expect("failed to load GLTF file")
+    }
+
+    /// get the texture of an image of a gltf file for a texture type, the image is uploaded the first time the model
+    /// uses it with the type
+    ///
+    /// # Arguments
+    /// - `cache` - the textures the model already uploaded, by image and type
+    /// - `images` - the images of the file
+    /// - `image_index` - the image to use
+    /// - `tex_type` - how the meshes sample it
+    /// - `path` - the path of the file, see [`upload_image`](Model::upload_image)
+    /// - `config` - how the texture is sampled
+    ///
+    /// # Returns
+    /// the texture, 16 bit and float images are reduced to 8 bits with a warning
+    fn load_texture(
+        cache: &mut HashMap<(usize, TextureType), Rc<Texture>>,
+        images: &[gltf::image::Data],
+        image_index: usize,
+        tex_type: TextureType,
+        path: Option<&str>,
+        config: TextureConfig,
+    ) -> Rc<Texture> {
+        if let Some(texture) = cache.get(&(image_index, tex_type.clone())) {
+            return texture.clone();
+        }
+
+        // the ORM layout packs the occlusion in the red channel of the metallic roughness image, sample the image that
+        // was already uploaded for it
+        let packed = cache.get(&(image_index, TextureType::Specular));
+        let texture = match packed {
+            Some(packed) if tex_type == TextureType::Occlusion => {
+                Rc::new(packed.shared_as(TextureType::Occlusion))
+            }
+            _ => {
+                let converted;
+                let mut image = &images[image_index];
+                if let Some(reduced) = to_8_bit(image) {
+                    log::warn!(
+                        "image {} of {} is {:?}, it is reduced to 8 bits per channel",
+                        image_index,
+                        path.unwrap_or("a model"),
+                        image.format
+                    );
+                    converted = reduced;
+                    image = &converted;
+                }
+
+                let format = match image.format {
+                    gltf::image::Format::R8 => gl::RED,
+                    gltf::image::Format::R8G8 => gl::RG,
+                    gltf::image::Format::R8G8B8A8 => gl::RGBA,
+                    _ => gl::RGB,
+                };
+                Self::upload_image(path, image_index, image, tex_type.clone(), format, config)
+            }
+        };
+
+        cache.insert((image_index, tex_type), texture.clone());
+        texture
+    }
+
+    /// uploads an image of a gltf file, shared with the other loads of the file when the path is known
+    fn upload_image(
+        path: Option<&str>,
+        image_index: usize,
+        image: &gltf::image::Data,
+        tex_type: TextureType,
+        format: u32,
+        config: TextureConfig,
+    ) -> Rc<Texture> {
+        let upload = || {
+            Texture::load_from_gltf_with_config(
+                &image.pixels,
+                image.width,
+                image.height,
+                tex_type.clone(),
+                format,
+                config,
+            )
+        };
+
+        match path {
+            Some(path) => {
+                let key = TextureKey {
+                    path: path.to_string(),
+                    image: Some(image_index),
+                    tex_type: tex_type.clone(),
+                };
+                Texture::shared(key, config, upload)
+            }
+            None => Rc::new(upload()),
+        }
+    }
+
+    /// reads the vertices and indices of a gltf primitive. primitives without normals are flat shaded and the ones
+    /// without uvs get zero uvs
+    ///
+    /// # Arguments
+    /// - `primitive` - the primitive to read
+    /// - `buffers` - the buffers of the file
+    /// - `path` - where the primitive is in the file, for the error
+    ///
+    /// # Returns
+    /// the vertex data or an error when the primitive has no positions
+    fn read_primitive(
+        primitive: &gltf::Primitive,
+        buffers: &[gltf::buffer::Data],
+        path: gltf::json::Path,
+    ) -> Result<PrimitiveData, gltf::Error> {
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+        // Get vertex data from reader
+        let positions: Vec<[f32; 3]> = match reader.read_positions() {
+            Some(positions) => positions.collect(),
+            None => {
+                return Err(gltf::Error::Validation(vec![(
+                    path.field("attributes").key("POSITION"),
+                    gltf::json::validation::Error::Missing,
+                )]))
+            }
+        };
+        let normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(|normals| normals.collect());
+        let tex_coords: Vec<[f32; 2]> = reader
+            .read_tex_coords(0)
+            .map_or_else(Vec::new, |tex_coords| tex_coords.into_f32().collect());
+
+        // every vertex has its own color, white when the primitive has none
+        let colors: Vec<[f32; 4]> = match reader.read_colors(0) {
+            Some(colors) => colors.into_rgba_f32().collect(),
+            None => Vec::new(),
+        };
+
+        let indices = if let Some(indices) = reader.read_indices() {
+            indices.into_u32().collect::<Vec<u32>>()
+        } else {
+            Vec::new()
+        };
+
+        // Construct vertices from the extracted data
+        let mut vertices: Vec<Vertex> = positions
+            .into_iter()
+            .enumerate()
+            .map(|(i, pos)| Vertex {
+                position: glm::make_vec3(&pos),
+                normal: normals
+                    .as_ref()
+                    .and_then(|normals| normals.get(i))
+                    .map_or(glm::Vec3::zeros(), |normal| glm::make_vec3(normal)),
+                tex_uv: tex_coords
+                    .get(i)
+                    .map_or(glm::vec2(0.0, 0.0), |uv| glm::make_vec2(uv)),
+                color: colors
+                    .get(i)
+                    .map_or(glm::vec4(1.0, 1.0, 1.0, 1.0), |color| glm::make_vec4(color)),
+                tangent: glm::Vec4::zeros(),
+            })
+            .collect();
+
+        // use the tangents of the file or compute them from the uvs
+        match reader.read_tangents() {
+            Some(tangents) => {
+                for (vertex, tangent) in vertices.iter_mut().zip(tangents) {
+                    vertex.tangent = glm::make_vec4(&tangent);
+                }
+            }
+            None => calculate_tangents(&mut vertices, &indices),
+        }
+
+        let mut skin = match (reader.read_joints(0), reader.read_weights(0)) {
+            (Some(joints), Some(weights)) => Some((
+                joints.into_u16().collect::<Vec<[u16; 4]>>(),
+                weights.into_f32().collect::<Vec<[f32; 4]>>(),
+            )),
+            _ => None,
+        };
+
+        if normals.is_none() {
+            // every triangle gets its own vertices so the indices aren't needed anymore
+            let (flat, sources) = flat_shade(&vertices, &indices);
+            skin = skin.map(|(joints, weights)| {
+                (
+                    sources
+                        .iter()
+                        .map(|&i| joints.get(i).copied().unwrap_or_default())
+                        .collect(),
+                    sources
+                        .iter()
+                        .map(|&i| weights.get(i).copied().unwrap_or_default())
+                        .collect(),
+                )
+            });
+            return Ok(PrimitiveData {
+                vertices: flat,
+                indices: Vec::new(),
+                skin,
+            });
+        }
+
+        Ok(PrimitiveData {
+            vertices,
+            indices,
+            skin,
+        })
+    }
+
+    /// builds a model from an imported gltf file
+    ///
+    /// # Arguments
+    /// - `gltf` - the document, buffers and images of the file
+    /// - `options` - how the file is converted
+    /// - `path` - the path of the file, its textures are shared with the other loads of the file. `None` only shares
+    ///   them within this model
+    ///
+    /// # Returns
+    /// the model or the error naming the primitive or image that could not be read
+    fn build_model(
+        gltf: (Document, Vec<gltf::buffer::Data>, Vec<gltf::image::Data>),
+        options: ImportOptions,
+        path: Option<&str>,
+    ) -> Result<Model, ModelError> {
+        let (doc, buffers, images) = gltf;
+        let axis_rotation = options.up_axis.to_engine_rotation();
+        let mut nodes: Vec<MeshNode> = Vec::new();
+
+        let mut texture_cache: HashMap<(usize, TextureType), Rc<Texture>> = HashMap::new(); // Cache with key as image index and texture type and value as a smart pointer to the texture
+
+        for node in doc.nodes() {
+            let (translation, rotation, scale) = node.transform().decomposed();
+            // convert the node into the engine convention (identity for Y up files)
+            let translation: Vec3 =
+                glm::quat_rotate_vec3(&axis_rotation, &glm::make_vec3(&translation));
+            let rotation = axis_rotation * glm::make_quat(&rotation);
+            let scale: Vec3 = glm::make_vec3(&scale);
+
+            if let Some(mesh) = node.mesh() {
+                let mut primitive_meshes: Vec<Mesh> = Vec::new();
+                // the shaders only have room for MAX_JOINTS joint matrices, bigger skins stay in the rest pose
+                let skin = node
+                    .skin()
+                    .filter(|skin| {
+                        let joints = skin.joints().len();
+                        if joints > MAX_JOINTS {
+                            log::warn!(
+                                "skin {} has {} joints, more than the {} supported, the mesh is not skinned",
+                                skin.index(),
+                                joints,
+                                MAX_JOINTS
+                            );
+                        }
+                        joints <= MAX_JOINTS
+                    })
+                    .map(|skin| skin.index());
+
+                for primitive in mesh.primitives() {
+                    let primitive_path = gltf::json::Path::new()
+                        .field("meshes")
+                        .index(mesh.index())
+                        .field("primitives")
+                        .index(primitive.index());
+                    let data = Self::read_primitive(&primitive, &buffers, primitive_path)?;
+
+                    // Load textures, the images are uploaded once per texture type and shared by the meshes
+                    let material = primitive.material();
+                    let pbr = material.pbr_metallic_roughness();
+                    let mut textures: Vec<Rc<Texture>> = Vec::new();
+                    for (texture, tex_type) in [
+                        (
+                            pbr.base_color_texture().map(|info| info.texture()),
+                            TextureType::Diffuse,
+                        ),
+                        (
+                            pbr.metallic_roughness_texture().map(|info| info.texture()),
+                            TextureType::Specular,
+                        ),
+                        (
+                            material.normal_texture().map(|info| info.texture()),
+                            TextureType::Normal,
+                        ),
+                        (
+                            material.occlusion_texture().map(|info| info.texture()),
+                            TextureType::Occlusion,
+                        ),
+                        (
+                            material.emissive_texture().map(|info| info.texture()),
+                            TextureType::Emissive,
+                        ),
+                    ] {
+                        let Some(texture) = texture else {
+                            continue;
+                        };
+                        textures.push(Self::load_texture(
+                            &mut texture_cache,
+                            &images,
+                            texture.source().index(),
+                            tex_type,
+                            path,
+                            options.texture,
+                        ));
+                    }
+
+                    // Create the mesh
+                    let mut mesh = Mesh::new(
+                        data.vertices,
+                        data.indices,
+                        textures,
+                        MaterialProperties {
+                            base_color_factor: glm::make_vec4(
+                                &primitive
+                                    .material()
+                                    .pbr_metallic_roughness()
+                                    .base_color_factor(),
+                            ),
+                            metallic_factor: primitive
+                                .material()
+                                .pbr_metallic_roughness()
+                                .metallic_factor(),
+                            roughness_factor: primitive
+                                .material()
+                                .pbr_metallic_roughness()
+                                .roughness_factor(),
+                            double_sided: primitive.material().double_sided(),
+                            alpha_mode: primitive.material().alpha_mode().into(),
+                            alpha_cutoff: primitive.material().alpha_cutoff().unwrap_or(0.5),
+                            // KHR_materials_emissive_strength scales the emissive factor (1.0 when absent)
+                            emissive_factor: glm::make_vec3(
+                                &primitive.material().emissive_factor(),
+                            ) * primitive
+                                .material()
+                                .emissive_strength()
+                                .unwrap_or(1.0),
+                            occlusion_strength: primitive
+                                .material()
+                                .occlusion_texture()
+                                .map_or(1.0, |occlusion| occlusion.strength()),
+                        },
+                    );
+                    if skin.is_some() {
+                        if let Some((joints, weights)) = &data.skin {
+                            mesh.set_skin(joints, weights);
+                        }
+                    }
+                    primitive_meshes.push(mesh);
+                }
+
+                let node = MeshNode {
+                    _name: node.name().unwrap_or_default().to_string(),
+                    transform: NodeTransform::new(translation, rotation, scale),
+                    mesh_primitives: primitive_meshes,
+                    skin,
+                };
+                nodes.push(node);
+            }
+        }
+
+        let animator = Self::load_animator(&doc, &buffers, axis_rotation);
+
+        Ok(Model {
+            nodes,
+            cast_shadows: true,
+            has_lighting: true,
+            tint: None,
+            billboard_mode: BillboardMode::Disabled,
+            render_layer: 0,
+            animator,
+            source: None,
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            fixed_behavior_callback: None,
+            dynamic: false,
+            baked: false,
+            pending: None,
+        })
+    }
+
+    /// reads the node hierarchy, skins and animations of a gltf file
+    ///
+    /// # Returns
+    /// the animator or `None` if the file has no skins
+    fn load_animator(
+        doc: &Document,
+        buffers: &[gltf::buffer::Data],
+        axis_rotation: glm::Quat,
+    ) -> Option<Animator> {
+        if doc.skins().len() == 0 {
+            return None;
+        }
+
+        let mut skeleton: Vec<SkeletonNode> = doc
+            .nodes()
+            .map(|node| {
+                let (translation, rotation, scale) = node.transform().decomposed();
+                SkeletonNode {
+                    parent: None,
+                    translation: glm::make_vec3(&translation),
+                    rotation: glm::make_quat(&rotation),
+                    scale: glm::make_vec3(&scale),
+                }
+            })
+            .collect();
+        for node in doc.nodes() {
+            for child in node.children() {
+                skeleton[child.index()].parent = Some(node.index());
+            }
+        }
+
+        let skins = doc
+            .skins()
+            .map(|skin| {
+                let reader = skin.reader(|buffer| Some(&buffers[buffer.index()]));
+                Skin {
+                    joints: skin.joints().map(|joint| joint.index()).collect(),
+                    inverse_bind_matrices: reader
+                        .read_inverse_bind_matrices()
+                        .map(|matrices| {
+                            matrices
+                                .map(|matrix| glm::make_mat4(matrix.as_flattened()))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let animations = doc
+            .animations()
+            .enumerate()
+            .map(|(index, animation)| {
+                let channels = animation
+                    .channels()
+                    .filter_map(|channel| {
+                        let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+                        let times: Vec<f32> = reader.read_inputs()?.collect();
+                        let values = match reader.read_outputs()? {
+                            ReadOutputs::Translations(values) => ChannelValues::Translations(
+                                values.map(|value| glm::make_vec3(&value)).collect(),
+                            ),
+                            ReadOutputs::Rotations(values) => ChannelValues::Rotations(
+                                values
+                                    .into_f32()
+                                    .map(|value| glm::make_quat(&value))
+                                    .collect(),
+                            ),
+                            ReadOutputs::Scales(values) => ChannelValues::Scales(
+                                values.map(|value| glm::make_vec3(&value)).collect(),
+                            ),
+                            // morph targets are not supported
+                            ReadOutputs::MorphTargetWeights(_) => return None,
+                        };
+                        Some(AnimationChannel {
+                            node: channel.target().node().index(),
+                            times,
+                            values,
+                            interpolation: match channel.sampler().interpolation() {
+                                gltf::animation::Interpolation::Step => Interpolation::Step,
+                                gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                                gltf::animation::Interpolation::CubicSpline => {
+                                    Interpolation::CubicSpline
+                                }
+                            },
+                        })
+                    })
+                    .collect();
+
+                let name = animation
+                    .name()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Animation{}", index));
+                Animation::new(&name, channels)
+            })
+            .collect();
+
+        Some(Animator::new(
+            skeleton,
+            glm::quat_to_mat4(&axis_rotation),
+            skins,
+            animations,
+        ))
+    }
+
+    /// generates a lower detail copy of the model by collapsing the edges that change the shape the least.
+    ///
+    /// uv seams and open borders are kept intact so the result can have more triangles than requested.
+    /// the copy shares textures with this model but has no children or callbacks.
+    ///
+    /// # Arguments
+    /// - `target_ratio` - the fraction of triangles to keep (0.0 - 1.0), 0.5 halves the triangle count
+    ///
+    /// # Returns
+    /// the lower detail model
+    pub fn generate_lod(&self, target_ratio: f32) -> Model {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| MeshNode {
+                _name: node._name.clone(),
+                transform: node.transform,
+                // the simplified vertices lose their joints so the lod uses the rest pose
+                skin: None,
+                mesh_primitives: node
+                    .mesh_primitives
+                    .iter()
+                    .map(|mesh| mesh.simplified(target_ratio))
+                    .collect(),
+            })
+            .collect();
+
+        Model {
+            nodes,
+            cast_shadows: self.cast_shadows,
+            has_lighting: self.has_lighting,
+            tint: self.tint,
+            billboard_mode: self.billboard_mode,
+            render_layer: self.render_layer,
+            animator: None,
+            source: None,
+            transform: self.transform,
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            fixed_behavior_callback: None,
+            dynamic: false,
+            baked: false,
+            pending: None,
+        }
+    }
+
+    /// merges the meshes that share a material and textures into one mesh each, so a scene made of many parts is
+    /// drawn with one draw call per material instead of one per part.
+    ///
+    /// the transforms of the parts are baked into the vertices, so the parts can't move on their own afterwards. the
+    /// model itself still moves as a whole. models marked with [`mark_dynamic`](Model::mark_dynamic) and models with
+    /// skins or animations are left alone. the meshes are uploaded again, call this once after loading.
+    ///
+    /// # Returns
+    /// the number of draw calls saved
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut city = Model::new_gltf("res/scenes/japan/scene.gltf")?;
+    /// let saved = city.bake_static();
+    /// log::info!("baking saved {} draw calls", saved);
+    /// ```
+    pub fn bake_static(&mut self) -> usize {
+        if self.dynamic
+            || self.baked
+            || self.animator.is_some()
+            || self.nodes.iter().any(|node| node.skin.is_some())
+        {
+            return 0;
+        }
+
+        struct Group<'a> {
+            textures: &'a [Rc<Texture>],
+            material: &'a MaterialProperties,
+            vertices: Vec<Vertex>,
+            indices: Vec<u32>,
+        }
+
+        let mut groups: Vec<Group> = Vec::new();
+        let mut mesh_count = 0;
+        for node in &self.nodes {
+            for mesh in &node.mesh_primitives {
+                mesh_count += 1;
+                let textures = mesh.get_textures();
+                let position = groups.iter().position(|group| {
+                    group.material == &mesh.material_properties
+                        && group.textures.len() == textures.len()
+                        && group
+                            .textures
+                            .iter()
+                            .zip(textures)
+                            .all(|(a, b)| Rc::ptr_eq(a, b))
+                });
+                let group = match position {
+                    Some(index) => &mut groups[index],
+                    None => {
+                        groups.push(Group {
+                            textures,
+                            material: &mesh.material_properties,
+                            vertices: Vec::new(),
+                            indices: Vec::new(),
+                        });
+                        groups.last_mut().unwrap()
+                    }
+                };
+                append_transformed(
+                    &mut group.vertices,
+                    &mut group.indices,
+                    mesh.get_vertices(),
+                    &mesh.indices,
+                    &node.transform.matrix,
+                );
+            }
+        }
+
+        let meshes: Vec<Mesh> = groups
+            .into_iter()
+            .map(|group| {
+                Mesh::new(
+                    group.vertices,
+                    group.indices,
+                    group.textures.to_vec(),
+                    group.material.clone(),
+                )
+            })
+            .collect();
+        let saved = mesh_count - meshes.len();
+
+        self.nodes = vec![MeshNode {
+            _name: "baked".to_string(),
+            transform: NodeTransform::default(),
+            skin: None,
+            mesh_primitives: meshes,
+        }];
+        self.baked = true;
+        saved
+    }
+
+    /// keeps [`bake_static`](Model::bake_static) and [`NodeManager::bake_static`] from merging the meshes of this
+    /// model, for models whose parts are moved in code
+    pub fn mark_dynamic(&mut self) -> &mut Self {
+        self.dynamic = true;
+        self
+    }
+
+    /// check if the model was marked with [`mark_dynamic`](Model::mark_dynamic)
+    pub fn is_dynamic(&self) -> bool {
+        self.dynamic
+    }
+
+    /// check if the meshes were merged by [`bake_static`](Model::bake_static)
+    pub fn is_baked(&self) -> bool {
+        self.baked
+    }
+
+    /// uploads the joint matrices of a skin, does nothing for models without an animator so shaders without skinning
+    /// uniforms keep working for static models
+    fn bind_skin(animator: Option<&Animator>, shader: &mut Shader, skin: Option<usize>) {
+        let Some(animator) = animator else {
+            return;
+        };
+        let matrices = skin
+            .map(|skin| animator.joint_matrices(skin))
+            .unwrap_or_default();
+        shader.set_uniform("u_skinned", !matrices.is_empty());
+        if !matrices.is_empty() {
+            shader.set_uniform("u_jointMatrices", matrices.as_slice());
+        }
+    }
+
+    /// turns skinning off again after drawing a model with an animator
+    fn unbind_skin(animator: Option<&Animator>, shader: &mut Shader) {
+        if animator.is_some() {
+            shader.set_uniform("u_skinned", false);
+        }
+    }
+
+    /// plays an animation of a skinned model from the beginning. the animation advances every frame with the frame time.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the animation in the file (unnamed animations are called `Animation0`, `Animation1`, ...)
+    ///
+    /// # Returns
+    /// false if the model has no skin or no animation with the name
+    pub fn play_animation(&mut self, name: &str) -> bool {
+        self.animator
+            .as_mut()
+            .is_some_and(|animator| animator.play(name))
+    }
+
+    /// pauses the current animation, keeping the current pose
+    pub fn pause_animation(&mut self) -> &mut Self {
+        if let Some(animator) = &mut self.animator {
+            animator.pause();
+        }
+        self
+    }
+
+    /// resumes the current animation after [`Model::pause_animation`]
+    pub fn resume_animation(&mut self) -> &mut Self {
+        if let Some(animator) = &mut self.animator {
+            animator.resume();
+        }
+        self
+    }
+
+    /// stops the current animation and goes back to the rest pose
+    pub fn stop_animation(&mut self) -> &mut Self {
+        if let Some(animator) = &mut self.animator {
+            animator.stop();
+        }
+        self
+    }
+
+    /// set the playback speed of the animations
+    ///
+    /// # Arguments
+    /// - `speed` - 1.0 is normal speed, negative values play backwards
+    pub fn set_animation_speed(&mut self, speed: f32) -> &mut Self {
+        if let Some(animator) = &mut self.animator {
+            animator.set_speed(speed);
+        }
+        self
+    }
+
+    /// set if the animations start over when they end (true by default)
+    ///
+    /// # Arguments
+    /// - `looping` - false pauses the animation on its last frame
+    pub fn set_animation_looping(&mut self, looping: bool) -> &mut Self {
+        if let Some(animator) = &mut self.animator {
+            animator.set_looping(looping);
+        }
+        self
+    }
+
+    /// get the animator of a skinned model, for example to list the animation names
+    ///
+    /// # Returns
+    /// the animator or `None` if the model has no skin
+    pub fn get_animator(&self) -> Option<&Animator> {
+        self.animator.as_ref()
+    }
+
+    pub fn casts_shadows(&mut self, cast_shadow: bool) -> &mut Self {
+        self.cast_shadows = cast_shadow;
+        self
+    }
+
+    pub fn has_lighting(&mut self, lighting: bool) -> &mut Self {
+        self.has_lighting = lighting;
+        self
+    }
+
+    /// get the bounding box of the model relative to the model node (the transform of the model is not applied)
+    ///
+    /// # Returns
+    /// the box around every mesh, empty if the model has no meshes
+    pub fn get_local_aabb(&self) -> Aabb {
+        self.get_world_aabb(&NodeTransform::default())
+    }
+
+    /// get the bounding box of the model in world space.
+    ///
+    /// the eight corners of every mesh box are transformed and a new box is fitted around them, so rotated models stay
+    /// correct. skinned meshes are bounded in their current pose. this is the box used for frustum culling.
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the model (its own transform composed with its parents)
+    ///
+    /// # Returns
+    /// the box around every mesh, empty if the model has no meshes
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let bounds = model.get_world_aabb(&model.transform);
+    /// // frame the model with the camera
+    /// camera.set_position(bounds.center() - glm::vec3(0.0, 0.0, glm::length(&bounds.size())));
+    /// ```
+    pub fn get_world_aabb(&self, world_transform: &NodeTransform) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for node in &self.nodes {
+            let matrix = node.world_transform(*world_transform).matrix;
+            let joint_matrices = self.node_joint_matrices(node);
+            for mesh in &node.mesh_primitives {
+                let mesh_bounds = match &joint_matrices {
+                    Some(joint_matrices) => mesh.get_posed_bounds(joint_matrices),
+                    None => mesh.get_bounds(),
+                };
+                bounds = bounds.merge(&mesh_bounds.transformed(&matrix));
+            }
+        }
+        bounds
+    }
+
+    /// iterate over all the meshes of the model, for example to update the vertices of a procedural mesh
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// for mesh in model.meshes_mut() {
+    ///     let mut vertices = mesh.get_vertices().to_vec();
+    ///     vertices.iter_mut().for_each(|v| v.position.y = (v.position.x + time).sin());
+    ///     mesh.update_vertices(&vertices);
+    /// }
+    /// ```
+    pub fn meshes_mut(&mut self) -> impl Iterator<Item = &mut Mesh> {
+        self.nodes
+            .iter_mut()
+            .flat_map(|node| node.mesh_primitives.iter_mut())
+    }
+
+    /// intersects a ray with the triangles of the model
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the model (its own transform composed with its parents)
+    /// - `origin` - the start of the ray in world space
+    /// - `direction` - the normalized direction of the ray in world space
+    ///
+    /// # Returns
+    /// the distance to the closest hit or `None` if the ray misses
+    pub fn raycast(
+        &self,
+        world_transform: &NodeTransform,
+        origin: &glm::Vec3,
+        direction: &glm::Vec3,
+    ) -> Option<f32> {
+        self.get_world_aabb(world_transform)
+            .intersect_ray(origin, direction)?;
+
+        let mut closest: Option<f32> = None;
+        for node in &self.nodes {
+            let inverse = glm::inverse(&node.world_transform(*world_transform).matrix);
+            // the direction is not normalized again so the distances stay in world units
+            let local_origin = (inverse * glm::vec4(origin.x, origin.y, origin.z, 1.0)).xyz();
+            let local_direction =
+                (inverse * glm::vec4(direction.x, direction.y, direction.z, 0.0)).xyz();
+
+            let joint_matrices = self.node_joint_matrices(node);
+            for mesh in &node.mesh_primitives {
+                let hit = match &joint_matrices {
+                    Some(joint_matrices) => {
+                        mesh.raycast_posed(&local_origin, &local_direction, joint_matrices)
+                    }
+                    None => mesh.raycast(&local_origin, &local_direction),
+                };
+                if let Some(hit) = hit {
+                    closest = Some(closest.map_or(hit, |closest| closest.min(hit)));
+                }
+            }
+        }
+        closest
+    }
+
+    /// get the joint matrices of the current pose for a skinned node, `None` if the node is not moved by joints
+    fn node_joint_matrices(&self, node: &MeshNode) -> Option<Vec<Mat4>> {
+        let animator = self.animator.as_ref()?;
+        Some(animator.joint_matrices(node.skin?))
+    }
+
+    /// replaces the material of a mesh, the new values are used from the next draw.
+    ///
+    /// the mesh moves between the opaque and the sorted transparent meshes when the alpha mode changes and
+    /// `double_sided` only changes the culling of this mesh.
+    ///
+    /// # Arguments
+    /// - `mesh_index` - the index of the mesh in the order of [`meshes`](Model::meshes)
+    /// - `material` - the new material
+    ///
+    /// # Returns
+    /// `false` if the model has no mesh at the index
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // fade the model out
+    /// let mut material = MaterialProperties::default();
+    /// material
+    ///     .set_base_color_factor(glm::vec4(1.0, 1.0, 1.0, alpha))
+    ///     .set_alpha_mode(AlphaMode::Blend);
+    /// model.set_mesh_material(0, material);
+    /// ```
+    pub fn set_mesh_material(&mut self, mesh_index: usize, material: MaterialProperties) -> bool {
+        match self.material_mut(mesh_index) {
+            Some(current) => {
+                *current = material;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// get the material of a mesh to change it in place, see [`set_mesh_material`](Model::set_mesh_material).
+    ///
+    /// # Arguments
+    /// - `mesh_index` - the index of the mesh in the order of [`meshes`](Model::meshes)
+    ///
+    /// # Returns
+    /// the material or `None` if the model has no mesh at the index
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // highlight the hovered mesh
+    /// if let Some(material) = model.material_mut(0) {
+    ///     material.set_emissive_factor(glm::vec3(0.2, 0.2, 0.0));
+    /// }
+    /// ```
+    pub fn material_mut(&mut self, mesh_index: usize) -> Option<&mut MaterialProperties> {
+        self.meshes_mut()
+            .nth(mesh_index)
+            .map(|mesh| &mut mesh.material_properties)
+    }
+
+    /// changes how the textures of every mesh are wrapped and filtered, use
+    /// [`Mesh::set_texture_sampling`] on one of the [`meshes`](Model::meshes) to change a single mesh.
+    ///
+    /// # Arguments
+    /// - `wrap` - how the textures are sampled outside of the 0 to 1 uv range
+    /// - `filter` - how the textures are filtered
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // a ground plane with the texture repeated 50 times
+    /// ground.set_texture_sampling(WrapMode::Repeat, FilterMode::Linear);
+    ///
+    /// // a pixel art level
+    /// level.set_texture_sampling(WrapMode::ClampToEdge, FilterMode::Nearest);
+    /// ```
+    pub fn set_texture_sampling(&self, wrap: WrapMode, filter: FilterMode) {
+        for mesh in self.meshes() {
+            mesh.set_texture_sampling(wrap, filter);
+        }
+    }
+
+    /// iterate over all the meshes of the model
+    pub fn meshes(&self) -> impl Iterator<Item = &Mesh> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.mesh_primitives.iter())
+    }
+
+    /// multiplies the final color of the whole model, useful for damage flashes or team colors.
+    ///
+    /// the tint is per model and does not change the materials. alpha is multiplied too.
+    ///
+    /// # Arguments
+    /// - `tint` - the color to multiply with or `None` to remove the tint
+    pub fn set_tint(&mut self, tint: Option<glm::Vec4>) -> &mut Self {
+        self.tint = tint;
+        self
+    }
+
+    /// get the tint of the model
+    pub fn get_tint(&self) -> Option<glm::Vec4> {
+        self.tint
+    }
+
+    /// makes the model turn to face the active camera when it is drawn, its children turn with it.
+    ///
+    /// the rotation of the node is replaced while drawing, the transform itself is not changed. the shadow passes
+    /// draw the model with its own rotation.
+    ///
+    /// # Arguments
+    /// - `mode` - how the model faces the camera, [`BillboardMode::Disabled`] by default
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut flare = Model::new_primitive(Primitive::Plane);
+    /// flare.set_billboard_mode(BillboardMode::Full);
+    /// ```
+    pub fn set_billboard_mode(&mut self, mode: BillboardMode) -> &mut Self {
+        self.billboard_mode = mode;
+        self
+    }
+
+    /// get how the model faces the camera
+    pub fn get_billboard_mode(&self) -> BillboardMode {
+        self.billboard_mode
+    }
+
+    /// set the layer the model is drawn on in the main pass
+    ///
+    /// the layers are drawn from the lowest up, inside a layer the opaque meshes come first and the blended meshes
+    /// after them. the skybox is drawn after the opaque meshes of layer 0, so lower layers suit backdrops and higher
+    /// layers effects that should blend over everything. models on [`Model::OVERLAY_LAYER`] or above ignore the
+    /// depth buffer and are drawn on top of the scene, like gizmos.
+    ///
+    /// # Arguments
+    /// - `layer` - the layer, 0 by default
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut gizmo = Model::new_primitive(Primitive::Cone);
+    /// gizmo.set_render_layer(Model::OVERLAY_LAYER);
+    /// ```
+    pub fn set_render_layer(&mut self, layer: i32) -> &mut Self {
+        self.render_layer = layer;
+        self
+    }
+
+    /// get the layer the model is drawn on
+    pub fn get_render_layer(&self) -> i32 {
+        self.render_layer
+    }
+
+    /// get what the model was loaded from, `None` for models built in code or simplified with [`Model::generate_lod`]
+    pub fn get_source(&self) -> Option<&ModelSource> {
+        self.source.as_ref()
+    }
+
+    /// check if the model is a placeholder waiting for its file, see [`Model::new_gltf_async`]
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// check if the model casts shadows
+    pub fn get_casts_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+
+    /// check if the model is lit by the scene lights
+    pub fn get_has_lighting(&self) -> bool {
+        self.has_lighting
+    }
+
+    /// replaces the material of every mesh of the model, use [`set_mesh_material`](Model::set_mesh_material) for
+    /// a single mesh
+    pub fn set_material(&mut self, material: MaterialProperties) -> &mut Self {
+        for node in &mut self.nodes {
+            for mesh in &mut node.mesh_primitives {
+                mesh.set_material(material.clone());
+            }
+        }
+        self
+    }
+
+    /// define a callback to be called when the model is ready
+    ///
+    /// # Arguments
+    /// - `ready_function` - the function to be called when the model is ready
+    ///
+    /// # Returns
+    /// Self
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
         F: 'static + FnMut(&mut Self) + Send + Sync,
     {
         self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
@@ -526,12 +2112,30 @@ impl Model {
         self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
         self
     }
+
+    /// define the fixed behavior callback for the node, called at the fixed timestep instead of every frame
+    ///
+    /// # Arguments
+    /// - `fixed_behavior_function` - The function to be called every fixed update.
+    pub fn define_fixed_behavior<F>(&mut self, fixed_behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.fixed_behavior_callback = Some(Arc::new(Mutex::new(fixed_behavior_function)));
+        self
+    }
 }
 
 pub trait ModelBuilder {
     fn cast_shadows(&mut self, value: bool) -> &mut Self;
     fn has_lighting(&mut self, value: bool) -> &mut Self;
     fn set_material(&mut self, material: MaterialProperties) -> &mut Self;
+    /// multiply the color of the whole model, see [`Model::set_tint`]
+    fn set_tint(&mut self, tint: Option<glm::Vec4>) -> &mut Self;
+    /// turn the model to face the camera, see [`Model::set_billboard_mode`]
+    fn set_billboard_mode(&mut self, mode: BillboardMode) -> &mut Self;
+    /// set the layer the model is drawn on, see [`Model::set_render_layer`]
+    fn set_render_layer(&mut self, layer: i32) -> &mut Self;
 }
 
 impl ModelBuilder for NodeBuilder<Model> {
@@ -547,6 +2151,18 @@ impl ModelBuilder for NodeBuilder<Model> {
         self.node.set_material(material);
         self
     }
+    fn set_tint(&mut self, tint: Option<glm::Vec4>) -> &mut Self {
+        self.node.set_tint(tint);
+        self
+    }
+    fn set_billboard_mode(&mut self, mode: BillboardMode) -> &mut Self {
+        self.node.set_billboard_mode(mode);
+        self
+    }
+    fn set_render_layer(&mut self, layer: i32) -> &mut Self {
+        self.node.set_render_layer(layer);
+        self
+    }
 }
 
 impl UseReadyCallback for NodeBuilder<Model> {
@@ -572,3 +2188,345 @@ impl UseBehaviorCallback for NodeBuilder<Model> {
         self
     }
 }
+
+impl UseFixedBehaviorCallback for NodeBuilder<Model> {
+    type Node = Model;
+
+    fn with_fixed_behavior<F>(&mut self, fixed_behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Model, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_fixed_behavior(fixed_behavior_function);
+        self
+    }
+}
+
+/// reduces a 16 bit or float gltf image to 8 bits per channel so it can be uploaded like the other images
+///
+/// # Arguments
+/// - `image` - the decoded image
+///
+/// # Returns
+/// the image with the same channels in 8 bits, or `None` if it already has 8 bits per channel
+fn to_8_bit(image: &gltf::image::Data) -> Option<gltf::image::Data> {
+    use gltf::image::Format;
+
+    let (format, pixels) = match image.format {
+        Format::R8 | Format::R8G8 | Format::R8G8B8 | Format::R8G8B8A8 => return None,
+        Format::R16 | Format::R16G16 | Format::R16G16B16 | Format::R16G16B16A16 => {
+            let format = match image.format {
+                Format::R16 => Format::R8,
+                Format::R16G16 => Format::R8G8,
+                Format::R16G16B16 => Format::R8G8B8,
+                _ => Format::R8G8B8A8,
+            };
+            // the high byte of a channel is its 8 bit value
+            let pixels = image
+                .pixels
+                .chunks_exact(2)
+                .map(|channel| (u16::from_ne_bytes([channel[0], channel[1]]) >> 8) as u8)
+                .collect();
+            (format, pixels)
+        }
+        Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => {
+            let format = match image.format {
+                Format::R32G32B32FLOAT => Format::R8G8B8,
+                _ => Format::R8G8B8A8,
+            };
+            // values above 1 can't be kept without a float texture so they are clamped
+            let pixels = image
+                .pixels
+                .chunks_exact(4)
+                .map(|channel| {
+                    let value =
+                        f32::from_ne_bytes([channel[0], channel[1], channel[2], channel[3]]);
+                    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+                })
+                .collect();
+            (format, pixels)
+        }
+    };
+
+    Some(gltf::image::Data {
+        format,
+        width: image.width,
+        height: image.height,
+        pixels,
+    })
+}
+
+/// appends the vertices and triangles of a mesh moved by a matrix, used to merge meshes
+///
+/// # Arguments
+/// - `vertices` - the merged vertices
+/// - `indices` - the merged indices
+/// - `mesh_vertices` - the vertices of the mesh
+/// - `mesh_indices` - the indices of the mesh, empty draws the vertices in order
+/// - `matrix` - moves the mesh into the space of the merged mesh
+fn append_transformed(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    mesh_vertices: &[Vertex],
+    mesh_indices: &[u32],
+    matrix: &Mat4,
+) {
+    let offset = vertices.len() as u32;
+    let linear = glm::mat4_to_mat3(matrix);
+    let normal_matrix = glm::transpose(&glm::inverse(&linear));
+    // a mirroring matrix flips the bitangent
+    let handedness = linear.determinant().signum();
+
+    vertices.extend(mesh_vertices.iter().map(|vertex| {
+        let mut vertex = vertex.clone();
+        vertex.position = (matrix * vertex.position.push(1.0)).xyz();
+        vertex.normal = (normal_matrix * vertex.normal).normalize();
+        if vertex.tangent != glm::Vec4::zeros() {
+            let tangent = (linear * vertex.tangent.xyz()).normalize();
+            vertex.tangent = tangent.push(vertex.tangent.w * handedness);
+        }
+        vertex
+    }));
+
+    let triangles: Vec<u32> = if mesh_indices.is_empty() {
+        (0..mesh_vertices.len() as u32).collect()
+    } else {
+        mesh_indices.to_vec()
+    };
+    // a mirroring matrix turns the triangles around, swap two corners to keep them facing out
+    let mirrored = glm::determinant(&linear) < 0.0;
+    for triangle in triangles.chunks_exact(3) {
+        let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+        let corners = if mirrored { [a, c, b] } else { [a, b, c] };
+        indices.extend(corners.iter().map(|index| index + offset));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_8_bit() {
+        let image = |format, pixels: Vec<u8>| gltf::image::Data {
+            format,
+            width: 1,
+            height: 1,
+            pixels,
+        };
+
+        assert!(to_8_bit(&image(gltf::image::Format::R8G8B8A8, vec![1, 2, 3, 4])).is_none());
+
+        let pixels = [0u16, 0x8000, 0xffff]
+            .iter()
+            .flat_map(|channel| channel.to_ne_bytes())
+            .collect();
+        let reduced = to_8_bit(&image(gltf::image::Format::R16G16B16, pixels)).unwrap();
+        assert_eq!(reduced.format, gltf::image::Format::R8G8B8);
+        assert_eq!(reduced.pixels, vec![0, 128, 255]);
+
+        let pixels = [-1.0f32, 0.5, 2.0, 1.0]
+            .iter()
+            .flat_map(|channel| channel.to_ne_bytes())
+            .collect();
+        let reduced = to_8_bit(&image(gltf::image::Format::R32G32B32A32FLOAT, pixels)).unwrap();
+        assert_eq!(reduced.format, gltf::image::Format::R8G8B8A8);
+        assert_eq!(reduced.pixels, vec![0, 128, 255, 255]);
+        assert_eq!((reduced.width, reduced.height), (1, 1));
+    }
+
+    #[test]
+    fn test_mesh_transforms_match_the_chain() {
+        let mesh_node = |transform, skin| MeshNode {
+            _name: String::new(),
+            transform,
+            mesh_primitives: Vec::new(),
+            skin,
+        };
+        let parent = NodeTransform::new(
+            glm::vec3(1.0, 0.0, 0.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(90.0)).x, &glm::vec3(0.0, 1.0, 0.0)),
+            glm::vec3(2.0, 2.0, 2.0),
+        );
+        let child = NodeTransform::new(
+            glm::vec3(0.0, 1.0, 0.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(45.0)).x, &glm::vec3(1.0, 0.0, 0.0)),
+            glm::vec3(3.0, 3.0, 3.0),
+        );
+        let mesh = NodeTransform::new(
+            glm::vec3(0.0, 0.0, 1.0),
+            glm::quat_angle_axis(glm::radians(&glm::vec1(30.0)).x, &glm::vec3(0.0, 0.0, 1.0)),
+            glm::vec3(0.5, 0.5, 2.0),
+        );
+
+        let mut model = Model::empty();
+        model.nodes.push(mesh_node(mesh, None));
+        model.nodes.push(mesh_node(mesh, Some(0)));
+
+        // the tree traversal composes the scene nodes, the model adds its mesh nodes on top
+        let world = parent.compose(&child);
+        let transforms: Vec<NodeTransform> = model
+            .mesh_transforms(world)
+            .map(|(_, transform)| transform)
+            .collect();
+
+        let expected = parent.matrix * child.matrix * mesh.matrix;
+        for (a, b) in transforms[0].matrix.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+        // skinned nodes are placed by their joints and only get the model's world transform
+        assert_eq!(transforms[1].matrix, world.matrix);
+    }
+
+    #[test]
+    fn test_billboard_mode() {
+        let world = NodeTransform::new(
+            glm::vec3(0.0, 0.0, 0.0),
+            glm::quat_angle_axis(1.0, &glm::vec3(1.0, 0.0, 0.0)),
+            glm::vec3(2.0, 2.0, 2.0),
+        );
+        let camera = glm::vec3(3.0, 4.0, 0.0);
+
+        assert_eq!(BillboardMode::Disabled.apply(world, &camera), world);
+
+        let full = BillboardMode::Full.apply(world, &camera);
+        assert!(glm::distance(&full.get_forward_vector(), &glm::vec3(0.6, 0.8, 0.0)) < 1e-5);
+        assert_eq!(full.scale, world.scale);
+
+        // the y axis mode stays upright
+        let upright = BillboardMode::YAxis.apply(world, &camera);
+        assert!(glm::distance(&upright.get_forward_vector(), &glm::vec3(1.0, 0.0, 0.0)) < 1e-5);
+        assert!(glm::distance(&upright.get_up_vector(), &glm::vec3(0.0, 1.0, 0.0)) < 1e-5);
+    }
+
+    #[test]
+    fn test_append_transformed() {
+        let triangle = [
+            Vertex::new(
+                glm::vec3(0.0, 0.0, 0.0),
+                glm::vec3(0.0, 0.0, 1.0),
+                glm::vec2(0.0, 0.0),
+            ),
+            Vertex::new(
+                glm::vec3(1.0, 0.0, 0.0),
+                glm::vec3(0.0, 0.0, 1.0),
+                glm::vec2(1.0, 0.0),
+            ),
+            Vertex::new(
+                glm::vec3(0.0, 1.0, 0.0),
+                glm::vec3(0.0, 0.0, 1.0),
+                glm::vec2(0.0, 1.0),
+            ),
+        ];
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        let moved = glm::translation(&glm::vec3(5.0, 0.0, 0.0));
+        append_transformed(&mut vertices, &mut indices, &triangle, &[], &moved);
+        // mirrored on x, the winding is swapped to keep the triangle facing out
+        let mirrored = glm::scaling(&glm::vec3(-1.0, 1.0, 1.0));
+        append_transformed(
+            &mut vertices,
+            &mut indices,
+            &triangle,
+            &[0, 1, 2],
+            &mirrored,
+        );
+
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(indices, vec![0, 1, 2, 3, 5, 4]);
+        assert_eq!(vertices[1].position, glm::vec3(6.0, 0.0, 0.0));
+        assert_eq!(vertices[4].position, glm::vec3(-1.0, 0.0, 0.0));
+        assert_eq!(vertices[4].normal, glm::vec3(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_read_vertex_colors() {
+        // a triangle with a red, a green and a blue corner
+        let source = r#"{
+            "asset": { "version": "2.0" },
+            "buffers": [{
+                "byteLength": 144,
+                "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AACAPwAAAAAAAAAAAACAPwAAAAAAAIA/AAAAAAAAgD8AAAAAAAAAAAAAgD8AAIA/"
+            }],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+                { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+                { "buffer": 0, "byteOffset": 72, "byteLength": 24 },
+                { "buffer": 0, "byteOffset": 96, "byteLength": 48 }
+            ],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                  "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+                { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+                { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2" },
+                { "bufferView": 3, "componentType": 5126, "count": 3, "type": "VEC4" }
+            ],
+            "meshes": [{
+                "primitives": [{
+                    "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2, "COLOR_0": 3 }
+                }]
+            }]
+        }"#;
+        let (doc, buffers, _) = gltf::import_slice(source.as_bytes()).unwrap();
+        let primitive = doc.meshes().next().unwrap().primitives().next().unwrap();
+
+        let data = Model::read_primitive(&primitive, &buffers, gltf::json::Path::new()).unwrap();
+        let vertices = data.vertices;
+        assert!(data.indices.is_empty());
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(vertices[0].color, glm::vec4(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(vertices[1].color, glm::vec4(0.0, 1.0, 0.0, 1.0));
+        assert_ne!(vertices[0].color, vertices[2].color);
+    }
+
+    #[test]
+    fn test_read_primitive_without_normals() {
+        // two triangles sharing an edge, with positions and indices only
+        let source = r#"{
+            "asset": { "version": "2.0" },
+            "buffers": [{
+                "byteLength": 60,
+                "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAABAAIAAAADAAEA"
+            }],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": 48 },
+                { "buffer": 0, "byteOffset": 48, "byteLength": 12 }
+            ],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3",
+                  "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 1.0] },
+                { "bufferView": 1, "componentType": 5123, "count": 6, "type": "SCALAR" }
+            ],
+            "meshes": [{
+                "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }]
+            }]
+        }"#;
+        let (doc, buffers, _) = gltf::import_slice(source.as_bytes()).unwrap();
+        let primitive = doc.meshes().next().unwrap().primitives().next().unwrap();
+
+        // the shared vertices are split so each triangle gets the normal of its face
+        let data = Model::read_primitive(&primitive, &buffers, gltf::json::Path::new()).unwrap();
+        assert!(data.indices.is_empty());
+        assert_eq!(data.vertices.len(), 6);
+        assert_eq!(data.vertices[0].normal, glm::vec3(0.0, 0.0, 1.0));
+        assert_eq!(data.vertices[3].normal, glm::vec3(0.0, 1.0, 0.0));
+        assert_eq!(data.vertices[4].position, glm::vec3(0.0, 0.0, 1.0));
+        assert_eq!(data.vertices[5].tex_uv, glm::vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert!(matches!(
+            Model::load("res/models/model.fbx"),
+            Err(ModelError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            Model::new_gltf("res/models/missing.glb"),
+            Err(ModelError::Io(_))
+        ));
+        assert!(matches!(
+            Model::new_obj("res/models/missing.obj"),
+            Err(ModelError::Io(_))
+        ));
+    }
+}
@@ -41,8 +41,13 @@ impl<T: Node + Clone> NodeBuilder<T> {
     pub fn directional_light(
         shadow_distance: f32,
         shadow_resolution: u32,
+        cascade_lambda: f32,
     ) -> NodeBuilder<DirectionalLight> {
-        NodeBuilder::new(DirectionalLight::new(shadow_distance, shadow_resolution))
+        NodeBuilder::new(DirectionalLight::new(
+            shadow_distance,
+            shadow_resolution,
+            cascade_lambda,
+        ))
     }
 
     pub fn empty() -> NodeBuilder<Empty> {
@@ -53,8 +58,15 @@ impl<T: Node + Clone> NodeBuilder<T> {
         NodeBuilder::new(Model::new_primitive(primitive))
     }
 
+    /// creates a model node from a gltf file, see [`Model::new_gltf`] to handle the error yourself
+    ///
+    /// # Panics
+    /// if the file can't be loaded
     pub fn model_gltf(file_path: &str) -> NodeBuilder<Model> {
-        NodeBuilder::new(Model::new_gltf(file_path))
+        NodeBuilder::new(
+            Model::new_gltf(file_path)
+                .unwrap_or_else(|error| panic!("failed to load {}: {}", file_path, error)),
+        )
     }
 
     pub fn point_light(
@@ -65,6 +77,11 @@ impl<T: Node + Clone> NodeBuilder<T> {
         NodeBuilder::new(PointLight::new(near_plane, far_plane, shadow_resolution))
     }
 
+    /// creates a time of day node starting at the given hour
+    pub fn time_of_day(time: f32) -> NodeBuilder<TimeOfDay> {
+        NodeBuilder::new(TimeOfDay::new(time))
+    }
+
     pub fn ui(window: &mut glfw::PWindow) -> NodeBuilder<UI> {
         NodeBuilder::new(UI::init(window))
     }
@@ -1,9 +1,11 @@
 use crate::components::NodeTransform;
 use crate::context::node_manager::{Behavior, Drawable, Node, NodeManager, Ready};
 use crate::context::GameContext;
-use crate::nodes::Model;
+use crate::nodes::{directional_light, Model};
 use crate::renderer::depth_cube_map::DepthCubeMap;
 use crate::renderer::shader::Shader;
+use crate::renderer::shadow_map::{self, ShadowQuality};
+use crate::utils::light_units;
 
 use std::sync::{Arc, Mutex};
 
@@ -14,6 +16,181 @@ use nalgebra_glm::{self as glm, Mat4, Vec4};
 
 use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
 
+/// the max number of shadow samples, the shaders get it as `MAX_POINT_SHADOW_SAMPLES`
+pub const MAX_SHADOW_SAMPLES: u32 = 64;
+
+/// the most point lights that light a frame, the shaders get it as `MAX_POINT_LIGHTS`.
+///
+/// when the scene has more enabled lights the strongest ones (by intensity) are used and the rest are skipped with a
+/// warning. raising it needs the shader changes listed in [`shader_defines`](crate::renderer::shader::shader_defines).
+pub const MAX_POINT_LIGHTS: usize = 8;
+
+/// the texture unit of the shadow map of the first point light, light `i` uses `POINT_SHADOW_UNIT + i`. the units
+/// below are left to the textures of the meshes, which bind one unit per texture type, and the directional lights.
+const POINT_SHADOW_UNIT: u32 =
+    directional_light::DIRECT_SHADOW_UNIT + directional_light::MAX_DIRECT_LIGHTS as u32;
+
+// OpenGL only guarantees 16 texture units to a fragment shader
+const _: () = assert!(
+    POINT_SHADOW_UNIT as usize + MAX_POINT_LIGHTS <= 16,
+    "the shadow maps of the lights need more than 16 texture units"
+);
+
+/// an owned copy of a point light for the shadow pass.
+///
+/// the engine copies the lights out of the scene tree before the pass, so drawing the tree into the shadow map never
+/// aliases a light inside the tree. the shadow map is shared with the light it was copied from.
+#[derive(Clone)]
+pub(crate) struct PointLightPass {
+    world_position: glm::Vec3,
+    shadow_transformations: [Mat4; 6],
+    shadow_map: DepthCubeMap,
+    far_plane: f32,
+    color: Vec4,
+    intensity: f32,
+    shadow_samples: u32,
+    shadow_quality: ShadowQuality,
+    constant: f32,
+    linear: f32,
+    quadratic: f32,
+    range: f32,
+}
+
+impl PointLightPass {
+    /// renders the models of the tree into the shadow map of the light
+    ///
+    /// # Arguments
+    /// - `root_nodes` - the top level nodes of the scene tree
+    pub(crate) fn render<'a>(
+        &mut self,
+        root_nodes: impl IntoIterator<Item = &'a mut Box<dyn Node>>,
+    ) {
+        let depth_shader = self.shadow_map.prepare_shadow_map();
+        depth_shader.bind();
+        depth_shader.set_uniform("shadowMatrices", self.shadow_transformations.as_slice());
+        depth_shader.set_uniform("lightPos", self.world_position);
+        depth_shader.set_uniform("farPlane", self.far_plane);
+
+        for node in root_nodes {
+            draw_node_shadow(depth_shader, node, NodeTransform::default());
+        }
+
+        self.shadow_map.finish_shadow_map();
+    }
+
+    /// get the intensity the lights are ranked by when there are more than [`MAX_POINT_LIGHTS`]
+    pub(crate) fn get_intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// get the world position the shadow map is rendered from
+    pub(crate) fn get_position(&self) -> glm::Vec3 {
+        self.world_position
+    }
+
+    /// binds the light and its shadow map to a slot of the `u_pointLights` array of a shader
+    ///
+    /// # Arguments
+    /// - `shader` - the shader to bind to
+    /// - `index` - the slot, below [`MAX_POINT_LIGHTS`]
+    pub(crate) fn bind_uniforms(&mut self, shader: &mut Shader, index: usize) {
+        let field = |name: &str| format!("u_pointLights[{}].{}", index, name);
+
+        shader.bind();
+        shader.set_uniform(&field("position"), self.world_position);
+        shader.set_uniform(&field("farPlane"), self.far_plane);
+        shader.set_uniform(&field("color"), self.color);
+        shader.set_uniform(&field("intensity"), self.intensity);
+        shader.set_uniform(&field("shadowSamples"), self.shadow_samples as i32);
+        shader.set_uniform(&field("shadowQuality"), self.shadow_quality.mode());
+        shader.set_uniform(
+            &field("shadowGrid"),
+            self.shadow_quality.grid_width() as i32,
+        );
+        shader.set_uniform(&field("constant"), self.constant);
+        shader.set_uniform(&field("linear"), self.linear);
+        shader.set_uniform(&field("quadratic"), self.quadratic);
+        shader.set_uniform(&field("range"), self.range);
+
+        self.shadow_map.bind_shadow_map(
+            shader,
+            &format!("u_pointShadowMaps[{}]", index),
+            POINT_SHADOW_UNIT + index as u32,
+        );
+    }
+}
+
+/// binds the lights to the `u_pointLights` array of a shader and sets how many are used
+///
+/// every shadow map sampler gets its own unit even when its slot is empty, so no cube sampler shares a unit with the
+/// 2D textures of the meshes.
+///
+/// # Arguments
+/// - `shader` - the shader to bind to
+/// - `passes` - the lights, at most [`MAX_POINT_LIGHTS`]
+pub(crate) fn bind_point_lights(shader: &mut Shader, passes: &mut [PointLightPass]) {
+    shader.bind();
+    shader.set_uniform(
+        "u_pointLightCount",
+        passes.len().min(MAX_POINT_LIGHTS) as i32,
+    );
+    for index in passes.len()..MAX_POINT_LIGHTS {
+        shader.set_uniform(
+            &format!("u_pointShadowMaps[{}]", index),
+            (POINT_SHADOW_UNIT + index as u32) as i32,
+        );
+    }
+
+    for (index, pass) in passes.iter_mut().take(MAX_POINT_LIGHTS).enumerate() {
+        pass.bind_uniforms(shader, index);
+    }
+}
+
+/// sorts the items from the strongest to the weakest and drops the ones past the limit
+///
+/// # Arguments
+/// - `items` - the items to limit
+/// - `max` - the number of items to keep
+/// - `intensity` - how strong an item is
+///
+/// # Returns
+/// the number of items that were dropped
+pub(crate) fn keep_strongest<T>(
+    items: &mut Vec<T>,
+    max: usize,
+    intensity: impl Fn(&T) -> f32,
+) -> usize {
+    if items.len() <= max {
+        return 0;
+    }
+
+    // the sort is stable so lights of the same intensity keep the tree order
+    items.sort_by(|a, b| intensity(b).total_cmp(&intensity(a)));
+    let dropped = items.len() - max;
+    items.truncate(max);
+    dropped
+}
+
+/// draws the models of a node and its children into a shadow map
+fn draw_node_shadow(
+    shader: &mut Shader,
+    node: &mut Box<dyn Node>,
+    parent_transform: NodeTransform,
+) {
+    if !node.is_enabled() {
+        return;
+    }
+
+    let world_transform = parent_transform.compose(node.get_transform());
+    if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
+        model.draw_shadow(shader, world_transform);
+    }
+
+    for child in node.get_children() {
+        draw_node_shadow(shader, child.1, world_transform);
+    }
+}
+
 #[derive(Clone)]
 pub struct PointLight {
     transform: NodeTransform,
@@ -24,7 +201,8 @@ pub struct PointLight {
     pub ready_callback: ReadyCallback<PointLight>,
     /// the behavior callback
     pub behavior_callback: BehaviorCallback<PointLight, GameContext>,
-    strength: f32,
+    /// the shader intensity, see [`light_units`](crate::utils::light_units)
+    intensity: f32,
 
     color: Vec4,
 
@@ -35,6 +213,22 @@ pub struct PointLight {
     far_plane: f32,
 
     near_plane: f32,
+
+    shadow_samples: u32,
+
+    shadow_quality: ShadowQuality,
+
+    /// the shadow biases, `None` uses the engine default
+    shadow_bias: Option<f32>,
+    normal_bias: Option<f32>,
+
+    /// the falloff terms, the light is divided by `constant + linear * d + quadratic * d^2`
+    constant: f32,
+    linear: f32,
+    quadratic: f32,
+
+    /// the distance where the light fades out completely, 0 for no limit
+    range: f32,
 }
 
 impl Ready for PointLight {
@@ -78,6 +272,10 @@ impl Node for PointLight {
         &mut self.children
     }
 
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
     fn as_ready(&mut self) -> Option<&mut (dyn Ready)> {
         Some(self)
     }
@@ -89,6 +287,25 @@ impl Node for PointLight {
 
 impl PointLight {
     pub fn new(near_plane: f32, far_plane: f32, shadow_resolution: u32) -> PointLight {
+        let shader = Shader::from_slice(
+            include_str!("../../res/shaders/cubeDepthShader/cubeDepthShader.vert"),
+            include_str!("../../res/shaders/cubeDepthShader/cubeDepthShader.frag"),
+            Some(include_str!(
+                "../../res/shaders/cubeDepthShader/cubeDepthShader.geom"
+            )),
+        );
+        // the shadow pass sets the matrices of the depth shader every time it renders
+        let shadow_map = DepthCubeMap::gen_map(shadow_resolution, shadow_resolution, shader);
+
+        Self::with_shadow_map(near_plane, far_plane, shadow_map)
+    }
+
+    /// builds the light around a shadow map, [`PointLight::new`] generates the map
+    pub(crate) fn with_shadow_map(
+        near_plane: f32,
+        far_plane: f32,
+        shadow_map: DepthCubeMap,
+    ) -> PointLight {
         let transform = NodeTransform::default();
 
         let shadow_proj =
@@ -132,24 +349,10 @@ impl PointLight {
                 ),
         ];
 
-        let mut shader = Shader::from_slice(
-            include_str!("../../res/shaders/cubeDepthShader/cubeDepthShader.vert"),
-            include_str!("../../res/shaders/cubeDepthShader/cubeDepthShader.frag"),
-            Some(include_str!(
-                "../../res/shaders/cubeDepthShader/cubeDepthShader.geom"
-            )),
-        );
-        shader.bind();
-        for i in 0..6 {
-            shader.set_uniform(&format!("shadowMatrices[{}]", i), shadow_transformations[i]);
-        }
-
-        let shadow_map = DepthCubeMap::gen_map(shadow_resolution, shadow_resolution, shader);
-
         let world_position = transform.get_position().clone();
 
         PointLight {
-            strength: 1.0,
+            intensity: 1.0,
             shadow_map,
             shadow_transformations: shadow_transformations,
             near_plane,
@@ -160,16 +363,23 @@ impl PointLight {
             ready_callback: None,
             behavior_callback: None,
             color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            shadow_samples: 16,
+            shadow_quality: ShadowQuality::PcfPoisson,
+            shadow_bias: None,
+            normal_bias: None,
+            constant: 1.0,
+            linear: 0.02,
+            quadratic: 0.1,
+            range: 0.0,
         }
     }
 
+    /// binds the light as the only point light of a shader
+    ///
+    /// # Arguments
+    /// - `shader` - the shader to bind to
     pub fn bind_uniforms(&mut self, shader: &mut Shader) {
-        shader.bind();
-        shader.set_uniform("lightPos", self.world_position);
-        shader.set_uniform("farPlane", self.far_plane);
-        shader.set_uniform("lightColor", self.color);
-
-        self.shadow_map.bind_shadow_map(shader, "shadowCubeMap", 2);
+        bind_point_lights(shader, &mut [self.to_pass()]);
     }
 
     pub fn render_shadow_map(
@@ -177,49 +387,37 @@ impl PointLight {
         root_nodes: Vec<&mut Box<dyn Node>>,
         world_transform: NodeTransform,
     ) {
-        let camera_transform = world_transform;
-
-        //println!("{:?}", camera_transform);
-
-        if camera_transform.position != self.world_position {
-            //println!("{:?}", camera_transform);
-            self.update_shadow_transformations(camera_transform);
-            self.world_position = camera_transform.position.clone();
-        }
-
-        let depth_shader = self.shadow_map.prepare_shadow_map();
-        depth_shader.bind();
-        // for i in 0..6 {
-        //     depth_shader.set_uniform(
-        //         &format!("shadowMatrices[{}]", i),
-        //         self.shadow_transformations[i],
-        //     );
-        // }
-        depth_shader.set_uniform("shadowMatrices", self.shadow_transformations.as_slice());
-        depth_shader.set_uniform("lightPos", self.world_position);
-        depth_shader.set_uniform("farPlane", self.far_plane);
-
-        for node in root_nodes {
-            Self::draw_node_shadow(depth_shader, node, NodeTransform::default());
-        }
-
-        self.shadow_map.finish_shadow_map();
-
-        //self.last_position = camera_transform.get_position().clone();
+        self.shadow_pass(world_transform).render(root_nodes);
     }
 
-    fn draw_node_shadow(
-        shader: &mut Shader,
-        node: &mut Box<dyn Node>,
-        parent_transform: NodeTransform,
-    ) {
-        let world_transfrom = parent_transform + *node.get_transform();
-        if let Some(model) = node.as_any_mut().downcast_mut::<Model>() {
-            model.draw_shadow(shader, world_transfrom);
+    /// moves the shadow projections to the world position of the light and copies what the shadow pass needs, the
+    /// pass can then draw the scene tree without borrowing the light
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the light
+    pub(crate) fn shadow_pass(&mut self, world_transform: NodeTransform) -> PointLightPass {
+        if world_transform.position != self.world_position {
+            self.update_shadow_transformations(world_transform);
+            self.world_position = world_transform.position;
         }
+        self.to_pass()
+    }
 
-        for child in node.get_children() {
-            Self::draw_node_shadow(shader, child.1, world_transfrom);
+    /// copies the light for a shadow pass, the shadow map is shared with the light
+    fn to_pass(&self) -> PointLightPass {
+        PointLightPass {
+            world_position: self.world_position,
+            shadow_transformations: self.shadow_transformations,
+            shadow_map: self.shadow_map.clone(),
+            far_plane: self.far_plane,
+            color: self.color,
+            intensity: self.intensity,
+            shadow_samples: self.shadow_samples,
+            shadow_quality: self.shadow_quality,
+            constant: self.constant,
+            linear: self.linear,
+            quadratic: self.quadratic,
+            range: self.range,
         }
     }
 
@@ -279,6 +477,175 @@ impl PointLight {
         self
     }
 
+    /// get the color of the light
+    pub fn get_color(&self) -> Vec4 {
+        self.color
+    }
+
+    /// get the near and far plane of the shadow
+    pub fn get_shadow_planes(&self) -> (f32, f32) {
+        (self.near_plane, self.far_plane)
+    }
+
+    /// get the resolution of every face of the shadow cube map
+    pub fn get_shadow_resolution(&self) -> u32 {
+        self.shadow_map.get_size().0
+    }
+
+    /// set the intensity of the light as a plain multiplier (1.0 by default).
+    ///
+    /// # Arguments
+    /// - `intensity` - the shader intensity
+    pub fn set_intensity(&mut self, intensity: f32) -> &mut Self {
+        self.intensity = intensity.max(0.0);
+        self
+    }
+
+    /// set the intensity of the light from the total light it emits.
+    ///
+    /// 800 lumens (a 60W bulb) is an intensity of 1.0, see [`light_units`](crate::utils::light_units) for the conversion.
+    ///
+    /// # Arguments
+    /// - `lumens` - the luminous flux of the light
+    pub fn set_intensity_lumens(&mut self, lumens: f32) -> &mut Self {
+        self.set_intensity(light_units::lumens_to_intensity(lumens))
+    }
+
+    /// set the intensity of the light from its luminous intensity in candela.
+    ///
+    /// # Arguments
+    /// - `candela` - the luminous intensity of the light
+    pub fn set_intensity_candela(&mut self, candela: f32) -> &mut Self {
+        self.set_intensity(light_units::candela_to_intensity(candela))
+    }
+
+    /// get the shader intensity of the light
+    pub fn get_intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// set how the shadow edges are filtered (default [`ShadowQuality::PcfPoisson`]).
+    ///
+    /// # Arguments
+    /// - `quality` - the filtering of the shadow map
+    pub fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self {
+        self.shadow_quality = quality;
+        self
+    }
+
+    /// get how the shadow edges are filtered
+    pub fn get_shadow_quality(&self) -> ShadowQuality {
+        self.shadow_quality
+    }
+
+    /// set the smallest depth offset of this light's shadow in world units, raise it to remove shadow acne.
+    ///
+    /// lights that don't set it use the engine default, see [`Engine::set_shadow_bias`](crate::Engine::set_shadow_bias).
+    ///
+    /// # Arguments
+    /// - `bias` - the depth offset
+    pub fn set_shadow_bias(&mut self, bias: f32) -> &mut Self {
+        self.shadow_bias = Some(bias.max(0.0));
+        self
+    }
+
+    /// set the extra offset of this light's shadow at grazing angles in world units,
+    /// lower it if shadows detach from their casters.
+    ///
+    /// # Arguments
+    /// - `normal_bias` - the offset when the surface is parallel to the light
+    pub fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self {
+        self.normal_bias = Some(normal_bias.max(0.0));
+        self
+    }
+
+    /// go back to the engine default biases
+    pub fn reset_shadow_bias(&mut self) -> &mut Self {
+        self.shadow_bias = None;
+        self.normal_bias = None;
+        self
+    }
+
+    /// get the biases set on this light without the engine default
+    pub(crate) fn get_shadow_bias_override(&self) -> (Option<f32>, Option<f32>) {
+        (self.shadow_bias, self.normal_bias)
+    }
+
+    /// get the `(bias, normal_bias)` used for the shadow, falling back to the engine default
+    pub fn get_shadow_bias(&self) -> (f32, f32) {
+        let (bias, normal_bias) = shadow_map::default_shadow_bias();
+        (
+            self.shadow_bias.unwrap_or(bias),
+            self.normal_bias.unwrap_or(normal_bias),
+        )
+    }
+
+    /// set the number of samples used to soften the shadow edges with [`ShadowQuality::PcfPoisson`].
+    ///
+    /// more samples give smoother shadows at the cost of performance. the pattern is rotated per pixel
+    /// so low sample counts show noise instead of banding.
+    ///
+    /// # Arguments
+    /// - `samples` - the number of samples per pixel, clamped between 1 and 64 (default 16).
+    pub fn set_shadow_samples(&mut self, samples: u32) -> &mut Self {
+        self.shadow_samples = samples.clamp(1, MAX_SHADOW_SAMPLES);
+        self
+    }
+
+    /// get the number of samples used to soften the shadow edges.
+    pub fn get_shadow_samples(&self) -> u32 {
+        self.shadow_samples
+    }
+
+    /// set how the light falls off with distance. the intensity is divided by
+    /// `constant + linear * distance + quadratic * distance^2` (1.0, 0.02, 0.1 by default).
+    ///
+    /// # Arguments
+    /// - `constant` - the constant term, keeps the light from blowing up close to the source
+    /// - `linear` - the linear term
+    /// - `quadratic` - the quadratic term, physically lights only have this one
+    pub fn set_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32) -> &mut Self {
+        self.constant = constant.max(0.0);
+        self.linear = linear.max(0.0);
+        self.quadratic = quadratic.max(0.0);
+        self
+    }
+
+    /// get the `(constant, linear, quadratic)` falloff terms
+    pub fn get_attenuation(&self) -> (f32, f32, f32) {
+        (self.constant, self.linear, self.quadratic)
+    }
+
+    /// set the distance where the light fades out completely. fragments past it skip the light and its shadow.
+    ///
+    /// this does not change the falloff, use [`PointLight::set_range_falloff`] to derive the falloff from the range.
+    ///
+    /// # Arguments
+    /// - `range` - the range in world units, 0 for no limit (default)
+    pub fn set_range(&mut self, range: f32) -> &mut Self {
+        self.range = range.max(0.0);
+        self
+    }
+
+    /// get the range of the light, 0 means no limit
+    pub fn get_range(&self) -> f32 {
+        self.range
+    }
+
+    /// set the range and pick linear and quadratic terms that fit it, so the light is mostly
+    /// gone by the time it reaches the range.
+    ///
+    /// # Arguments
+    /// - `range` - the range in world units, 0 removes the limit and keeps the falloff
+    pub fn set_range_falloff(&mut self, range: f32) -> &mut Self {
+        self.set_range(range);
+        if self.range > 0.0 {
+            let (linear, quadratic) = falloff_for_range(self.range);
+            self.set_attenuation(1.0, linear, quadratic);
+        }
+        self
+    }
+
     /// define the ready callback of the directional light
     ///
     /// # Arguments
@@ -304,8 +671,29 @@ impl PointLight {
     }
 }
 
+/// linear and quadratic terms that bring the light down to about 1% at the range
+fn falloff_for_range(range: f32) -> (f32, f32) {
+    (4.5 / range, 75.0 / (range * range))
+}
+
 pub trait PointLightBuilder {
     fn set_color(&mut self, color: Vec4) -> &mut Self;
+    /// set the number of samples used to soften the shadow edges, see [`PointLight::set_shadow_samples`]
+    fn set_shadow_samples(&mut self, samples: u32) -> &mut Self;
+    /// set how the shadow edges are filtered, see [`PointLight::set_shadow_quality`]
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self;
+    /// set the depth bias of the shadow, see [`PointLight::set_shadow_bias`]
+    fn set_shadow_bias(&mut self, bias: f32) -> &mut Self;
+    /// set the normal bias of the shadow, see [`PointLight::set_normal_bias`]
+    fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self;
+    /// set the intensity as a plain multiplier, see [`PointLight::set_intensity`]
+    fn set_intensity(&mut self, intensity: f32) -> &mut Self;
+    /// set the intensity in lumens, see [`PointLight::set_intensity_lumens`]
+    fn set_intensity_lumens(&mut self, lumens: f32) -> &mut Self;
+    /// set the falloff terms, see [`PointLight::set_attenuation`]
+    fn set_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32) -> &mut Self;
+    /// set the range and derive the falloff from it, see [`PointLight::set_range_falloff`]
+    fn with_range(&mut self, range: f32) -> &mut Self;
 }
 
 impl PointLightBuilder for NodeBuilder<PointLight> {
@@ -313,6 +701,46 @@ impl PointLightBuilder for NodeBuilder<PointLight> {
         self.node.set_color(color);
         self
     }
+
+    fn set_shadow_samples(&mut self, samples: u32) -> &mut Self {
+        self.node.set_shadow_samples(samples);
+        self
+    }
+
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) -> &mut Self {
+        self.node.set_shadow_quality(quality);
+        self
+    }
+
+    fn set_shadow_bias(&mut self, bias: f32) -> &mut Self {
+        self.node.set_shadow_bias(bias);
+        self
+    }
+
+    fn set_normal_bias(&mut self, normal_bias: f32) -> &mut Self {
+        self.node.set_normal_bias(normal_bias);
+        self
+    }
+
+    fn set_intensity(&mut self, intensity: f32) -> &mut Self {
+        self.node.set_intensity(intensity);
+        self
+    }
+
+    fn set_intensity_lumens(&mut self, lumens: f32) -> &mut Self {
+        self.node.set_intensity_lumens(lumens);
+        self
+    }
+
+    fn set_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32) -> &mut Self {
+        self.node.set_attenuation(constant, linear, quadratic);
+        self
+    }
+
+    fn with_range(&mut self, range: f32) -> &mut Self {
+        self.node.set_range_falloff(range);
+        self
+    }
 }
 
 impl UseReadyCallback for NodeBuilder<PointLight> {
@@ -338,3 +766,28 @@ impl UseBehaviorCallback for NodeBuilder<PointLight> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_falloff_is_dim_at_range() {
+        for range in [1.0, 10.0, 100.0] {
+            let (linear, quadratic) = falloff_for_range(range);
+            let remaining = 1.0 / (1.0 + linear * range + quadratic * range * range);
+            assert!(remaining < 0.02 && remaining > 0.005);
+        }
+    }
+
+    #[test]
+    fn test_keep_strongest() {
+        let mut lights = vec![1.0, 5.0, 2.0, 5.0, 0.5];
+        assert_eq!(keep_strongest(&mut lights, 3, |light| *light), 2);
+        assert_eq!(lights, vec![5.0, 5.0, 2.0]);
+
+        let mut lights = vec![1.0, 3.0];
+        assert_eq!(keep_strongest(&mut lights, 3, |light| *light), 0);
+        assert_eq!(lights, vec![1.0, 3.0]);
+    }
+}
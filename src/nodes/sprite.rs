@@ -0,0 +1,304 @@
+//! Sprite is a node that draws a flat textured quad through the 2D camera of the game context.
+//!
+//! sprites are drawn after the 3D scene without depth testing, so they sit on top of the models like a HUD. the
+//! position, size and rotation are in the units of the [`Camera2D`](crate::nodes::camera::Camera2D), with y up. a
+//! sprite under another node also moves with the transform of its parents.
+//!
+//! sprites with a lower layer are drawn first and covered by the sprites with a higher layer. the order of sprites on
+//! the same layer is not fixed, give overlapping sprites different layers. nothing is drawn while the context has no
+//! 2D camera.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::nodes::camera::Camera2D;
+//! use quaturn::nodes::Sprite;
+//! use quaturn::utils::color::Color;
+//!
+//! // one unit is one pixel of an 800x600 window
+//! engine.context.set_camera_2d(Camera2D::new(0.0, 0.0, 600.0, 800.0));
+//!
+//! let mut heart = Sprite::load("res/textures/heart.png").unwrap();
+//! heart.set_position(glm::vec2(-360.0, 260.0)).set_layer(1);
+//! engine.context.nodes.add("heart", heart);
+//!
+//! let mut panel = Sprite::new(glm::vec2(200.0, 60.0));
+//! panel
+//!     .set_position(glm::vec2(-300.0, 260.0))
+//!     .set_tint(Color::from_normalized(0.0, 0.0, 0.0, 0.5));
+//! engine.context.nodes.add("panel", panel);
+//! ```
+
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm as glm;
+
+use crate::components::NodeTransform;
+use crate::context::node_manager::{
+    Behavior, BehaviorCallback, Node, NodeManager, Ready, ReadyCallback,
+};
+use crate::context::GameContext;
+use crate::renderer::texture::{Texture, TextureType};
+use crate::utils::color::Color;
+
+use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+
+/// a textured quad drawn by the 2D camera, see the [module docs](self).
+#[derive(Clone)]
+pub struct Sprite {
+    transform: NodeTransform,
+    children: NodeManager,
+
+    /// the ready callback
+    pub ready_callback: ReadyCallback<Sprite>,
+    /// the behavior callback
+    pub behavior_callback: BehaviorCallback<Sprite, GameContext>,
+
+    /// the image of the quad, `None` fills the quad with the tint
+    texture: Option<Rc<Texture>>,
+    /// the center of the quad
+    position: glm::Vec2,
+    size: glm::Vec2,
+    /// the counter clockwise rotation in degrees
+    rotation: f32,
+    layer: i32,
+    tint: Color,
+}
+
+impl Ready for Sprite {
+    fn ready(&mut self) {
+        if let Some(callback) = self.ready_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self);
+            drop(guard);
+            self.ready_callback = Some(callback)
+        }
+    }
+}
+
+impl Behavior for Sprite {
+    fn behavior(&mut self, context: &mut GameContext) {
+        // take callback out of self so we can use self later
+        if let Some(callback) = self.behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.behavior_callback = Some(callback);
+        }
+    }
+}
+
+impl Node for Sprite {
+    fn get_transform(&mut self) -> &mut NodeTransform {
+        &mut self.transform
+    }
+
+    fn get_children(&mut self) -> &mut NodeManager {
+        &mut self.children
+    }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
+    fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
+        Some(self)
+    }
+
+    fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
+        Some(self)
+    }
+}
+
+impl Sprite {
+    /// creates a white quad without a texture at the origin
+    ///
+    /// # Arguments
+    /// - `size` - the width and height of the quad
+    ///
+    /// # Returns
+    /// the new sprite
+    pub fn new(size: glm::Vec2) -> Sprite {
+        Sprite {
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            texture: None,
+            position: glm::Vec2::zeros(),
+            size,
+            rotation: 0.0,
+            layer: 0,
+            tint: Color::from_normalized(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+
+    /// creates a sprite showing a texture, one pixel of the texture is one unit of the 2D camera
+    ///
+    /// # Arguments
+    /// - `texture` - the image of the quad
+    ///
+    /// # Returns
+    /// the new sprite
+    pub fn from_texture(texture: Rc<Texture>) -> Sprite {
+        let size = glm::vec2(texture.get_width() as f32, texture.get_height() as f32);
+        Sprite {
+            texture: Some(texture),
+            ..Self::new(size)
+        }
+    }
+
+    /// loads an image file into a sprite, the texture is shared with the other loads of the same file
+    ///
+    /// # Arguments
+    /// - `path` - the path of the image
+    ///
+    /// # Returns
+    /// the new sprite or `None` if the image could not be loaded
+    pub fn load(path: &str) -> Option<Sprite> {
+        Texture::load_from_file(path, TextureType::Diffuse).map(Self::from_texture)
+    }
+
+    /// get the texture of the quad
+    pub fn get_texture(&self) -> Option<&Rc<Texture>> {
+        self.texture.as_ref()
+    }
+
+    /// sets the texture of the quad, the size is kept
+    ///
+    /// # Arguments
+    /// - `texture` - the image of the quad, `None` fills the quad with the tint
+    pub fn set_texture(&mut self, texture: Option<Rc<Texture>>) -> &mut Self {
+        self.texture = texture;
+        self
+    }
+
+    /// get the center of the quad
+    pub fn get_position(&self) -> glm::Vec2 {
+        self.position
+    }
+
+    /// sets the center of the quad
+    ///
+    /// # Arguments
+    /// - `position` - the center relative to the parent
+    pub fn set_position(&mut self, position: glm::Vec2) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    /// get the width and height of the quad
+    pub fn get_size(&self) -> glm::Vec2 {
+        self.size
+    }
+
+    /// sets the width and height of the quad, negative values mirror the texture
+    ///
+    /// # Arguments
+    /// - `size` - the new size
+    pub fn set_size(&mut self, size: glm::Vec2) -> &mut Self {
+        self.size = size;
+        self
+    }
+
+    /// get the counter clockwise rotation in degrees
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// sets the rotation around the center of the quad
+    ///
+    /// # Arguments
+    /// - `degrees` - the counter clockwise rotation
+    pub fn set_rotation(&mut self, degrees: f32) -> &mut Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// get the draw order of the sprite
+    pub fn get_layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// sets the draw order, sprites on higher layers cover the sprites on lower layers
+    ///
+    /// # Arguments
+    /// - `layer` - the layer, 0 by default
+    pub fn set_layer(&mut self, layer: i32) -> &mut Self {
+        self.layer = layer;
+        self
+    }
+
+    /// get the color the texture is multiplied with
+    pub fn get_tint(&self) -> Color {
+        self.tint
+    }
+
+    /// sets the color the texture is multiplied with, the alpha fades the sprite
+    ///
+    /// # Arguments
+    /// - `tint` - the color, white shows the texture as it is
+    pub fn set_tint(&mut self, tint: Color) -> &mut Self {
+        self.tint = tint;
+        self
+    }
+
+    /// get the matrix that maps the -0.5 to 0.5 unit quad onto the sprite
+    ///
+    /// # Arguments
+    /// - `world_transform` - the world transform of the sprite (its own transform composed with its parents)
+    pub fn get_quad_matrix(&self, world_transform: &NodeTransform) -> glm::Mat4 {
+        let local = glm::translation(&glm::vec3(self.position.x, self.position.y, 0.0))
+            * glm::rotation(self.rotation.to_radians(), &glm::Vec3::z())
+            * glm::scaling(&glm::vec3(self.size.x, self.size.y, 1.0));
+        world_transform.matrix * local
+    }
+
+    /// define the ready callback for the node
+    ///
+    /// # Arguments
+    /// - `ready_function` - The function to be called when the node is ready.
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self) + Sync + Send,
+    {
+        self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
+        self
+    }
+
+    /// define the behavior callback for the node
+    ///
+    /// # Arguments
+    /// - `behavior_function` - The function to be called every frame.
+    pub fn define_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
+        self
+    }
+}
+
+impl UseReadyCallback for NodeBuilder<Sprite> {
+    type Node = Sprite;
+
+    fn with_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Sprite) + Send + Sync,
+    {
+        self.node.define_ready(ready_function);
+        self
+    }
+}
+
+impl UseBehaviorCallback for NodeBuilder<Sprite> {
+    type Node = Sprite;
+
+    fn with_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Sprite, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_behavior(behavior_function);
+        self
+    }
+}
@@ -0,0 +1,429 @@
+//! Text is a node that draws a string through the 2D camera of the game context, on top of the 3D scene.
+//!
+//! the text is rasterized from a [`Font`] into a texture atlas and drawn as one quad per letter together with the
+//! [`Sprite`](crate::nodes::Sprite) nodes, so the layer orders texts and sprites. the font size is the height of a
+//! line in the units of the [`Camera2D`](crate::nodes::Camera2D), with one unit per pixel the text is drawn without
+//! scaling.
+//!
+//! the anchor is the point of the text placed at its position. in screen space the position is also measured from
+//! the same point of the camera view, so a text anchored at the top left stays in the top left corner when the
+//! camera moves or zooms, use it for HUDs. in world space the text moves with the transform of its parents.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::nodes::{Text, TextAnchor};
+//! use quaturn::utils::color::Color;
+//!
+//! engine.context.set_camera_2d(Camera2D::new(0.0, 0.0, 600.0, 800.0));
+//!
+//! let mut score = Text::new("Score: 0");
+//! score
+//!     .set_font_size(32.0)
+//!     .set_color(Color::from_hex(0xffd700))
+//!     .set_anchor(TextAnchor::TopLeft)
+//!     .set_screen_space(true)
+//!     .set_position(glm::vec2(16.0, -16.0));
+//! score.define_behavior(|text, context| {
+//!     text.set_text(&format!("Score: {}", context.frame.frame_count()));
+//! });
+//! engine.context.nodes.add("score", score);
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm as glm;
+
+use crate::components::NodeTransform;
+use crate::context::node_manager::{
+    Behavior, BehaviorCallback, Node, NodeManager, Ready, ReadyCallback,
+};
+use crate::context::GameContext;
+use crate::renderer::font::Font;
+use crate::renderer::sprite_batch::SpriteBatch;
+use crate::utils::color::Color;
+
+use super::camera::Camera2D;
+use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+
+/// the point of a text placed at its position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAnchor {
+    /// the top left corner
+    #[default]
+    TopLeft,
+    /// the middle of the top edge
+    Top,
+    /// the top right corner
+    TopRight,
+    /// the middle of the left edge
+    Left,
+    /// the center
+    Center,
+    /// the middle of the right edge
+    Right,
+    /// the bottom left corner
+    BottomLeft,
+    /// the middle of the bottom edge
+    Bottom,
+    /// the bottom right corner
+    BottomRight,
+}
+
+impl TextAnchor {
+    /// get the anchor point in fractions of the size, 0 to 1 from the left and from the top
+    pub fn fraction(&self) -> glm::Vec2 {
+        let (x, y) = match self {
+            TextAnchor::TopLeft => (0.0, 0.0),
+            TextAnchor::Top => (0.5, 0.0),
+            TextAnchor::TopRight => (1.0, 0.0),
+            TextAnchor::Left => (0.0, 0.5),
+            TextAnchor::Center => (0.5, 0.5),
+            TextAnchor::Right => (1.0, 0.5),
+            TextAnchor::BottomLeft => (0.0, 1.0),
+            TextAnchor::Bottom => (0.5, 1.0),
+            TextAnchor::BottomRight => (1.0, 1.0),
+        };
+        glm::vec2(x, y)
+    }
+}
+
+/// a string drawn by the 2D camera, see the [module docs](self).
+#[derive(Clone)]
+pub struct Text {
+    transform: NodeTransform,
+    children: NodeManager,
+
+    /// the ready callback
+    pub ready_callback: ReadyCallback<Text>,
+    /// the behavior callback
+    pub behavior_callback: BehaviorCallback<Text, GameContext>,
+
+    text: String,
+    font: Font,
+    font_size: f32,
+    color: Color,
+    /// where the anchor of the text is placed
+    position: glm::Vec2,
+    anchor: TextAnchor,
+    /// if the position is measured from the camera view instead of the world
+    screen_space: bool,
+    layer: i32,
+}
+
+impl Ready for Text {
+    fn ready(&mut self) {
+        if let Some(callback) = self.ready_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self);
+            drop(guard);
+            self.ready_callback = Some(callback)
+        }
+    }
+}
+
+impl Behavior for Text {
+    fn behavior(&mut self, context: &mut GameContext) {
+        // take callback out of self so we can use self later
+        if let Some(callback) = self.behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context);
+            drop(guard);
+            self.behavior_callback = Some(callback);
+        }
+    }
+}
+
+impl Node for Text {
+    fn get_transform(&mut self) -> &mut NodeTransform {
+        &mut self.transform
+    }
+
+    fn get_children(&mut self) -> &mut NodeManager {
+        &mut self.children
+    }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
+    fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
+        Some(self)
+    }
+
+    fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
+        Some(self)
+    }
+}
+
+impl Text {
+    /// creates a white 24 unit high text in the default font, anchored at its top left in world space
+    ///
+    /// # Arguments
+    /// - `text` - the string to draw, `\n` starts a new line
+    ///
+    /// # Returns
+    /// the new text
+    pub fn new(text: &str) -> Text {
+        Text {
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+            ready_callback: None,
+            behavior_callback: None,
+            text: text.to_string(),
+            font: Font::default_font(),
+            font_size: 24.0,
+            color: Color::from_normalized(1.0, 1.0, 1.0, 1.0),
+            position: glm::Vec2::zeros(),
+            anchor: TextAnchor::default(),
+            screen_space: false,
+            layer: 0,
+        }
+    }
+
+    /// get the string that is drawn
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// sets the string that is drawn
+    ///
+    /// # Arguments
+    /// - `text` - the new string, `\n` starts a new line
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+        if self.text != text {
+            self.text = text.to_string();
+        }
+        self
+    }
+
+    /// get the font of the text
+    pub fn get_font(&self) -> &Font {
+        &self.font
+    }
+
+    /// sets the font of the text
+    ///
+    /// # Arguments
+    /// - `font` - the font, see [`Font::load`]
+    pub fn set_font(&mut self, font: Font) -> &mut Self {
+        self.font = font;
+        self
+    }
+
+    /// get the height of a line
+    pub fn get_font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// sets the height of a line in the units of the 2D camera
+    ///
+    /// # Arguments
+    /// - `size` - the new size
+    pub fn set_font_size(&mut self, size: f32) -> &mut Self {
+        self.font_size = size.max(0.0);
+        self
+    }
+
+    /// get the color of the text
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    /// sets the color of the text, the alpha fades it
+    ///
+    /// # Arguments
+    /// - `color` - the new color
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    /// get the position of the anchor
+    pub fn get_position(&self) -> glm::Vec2 {
+        self.position
+    }
+
+    /// sets where the anchor of the text is placed
+    ///
+    /// # Arguments
+    /// - `position` - relative to the parent, or to the anchor point of the camera view in screen space
+    pub fn set_position(&mut self, position: glm::Vec2) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    /// get the point of the text placed at its position
+    pub fn get_anchor(&self) -> TextAnchor {
+        self.anchor
+    }
+
+    /// sets the point of the text placed at its position, this also aligns the lines
+    ///
+    /// # Arguments
+    /// - `anchor` - the point of the text
+    pub fn set_anchor(&mut self, anchor: TextAnchor) -> &mut Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// if the position is measured from the camera view
+    pub fn is_screen_space(&self) -> bool {
+        self.screen_space
+    }
+
+    /// sets if the position is measured from the anchor point of the camera view instead of the world. screen space
+    /// texts ignore the transforms of their parents.
+    ///
+    /// # Arguments
+    /// - `screen_space` - if the text is pinned to the view
+    pub fn set_screen_space(&mut self, screen_space: bool) -> &mut Self {
+        self.screen_space = screen_space;
+        self
+    }
+
+    /// get the draw order of the text
+    pub fn get_layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// sets the draw order, shared with the sprites
+    ///
+    /// # Arguments
+    /// - `layer` - the layer, 0 by default
+    pub fn set_layer(&mut self, layer: i32) -> &mut Self {
+        self.layer = layer;
+        self
+    }
+
+    /// get the width and height of the text in the units of the 2D camera
+    pub fn get_size(&self) -> glm::Vec2 {
+        self.font.measure(&self.text, self.font_size)
+    }
+
+    /// queues the letters of the text into the sprite batch
+    ///
+    /// # Arguments
+    /// - `batch` - the sprite batch of the frame
+    /// - `world_transform` - the world transform of the text (its own transform composed with its parents)
+    /// - `camera` - the 2D camera the text is drawn through
+    pub(crate) fn queue(
+        &self,
+        batch: &mut SpriteBatch,
+        world_transform: &NodeTransform,
+        camera: &Camera2D,
+    ) {
+        if self.text.is_empty() || self.font_size <= 0.0 {
+            return;
+        }
+
+        let pixel_size = Font::pixel_size(self.font_size);
+        let atlas = self.font.atlas(pixel_size);
+        let layout = atlas.layout(&self.text);
+        if layout.quads.is_empty() {
+            return;
+        }
+
+        let scale = self.font_size / pixel_size as f32;
+        let fraction = self.anchor.fraction();
+        let anchor = layout
+            .size
+            .component_mul(&glm::vec2(-fraction.x, fraction.y))
+            * scale;
+        let origin = if self.screen_space {
+            let point = camera.view_point(fraction) + self.position;
+            glm::translation(&glm::vec3(point.x, point.y, 0.0))
+        } else {
+            world_transform.matrix
+                * glm::translation(&glm::vec3(self.position.x, self.position.y, 0.0))
+        };
+
+        let texture = atlas.texture();
+        for quad in &layout.quads {
+            let center = anchor + (quad.min + quad.max) * (scale / 2.0);
+            let size = (quad.max - quad.min) * scale;
+            let matrix = origin
+                * glm::translation(&glm::vec3(center.x, center.y, 0.0))
+                * glm::scaling(&glm::vec3(size.x, size.y, 1.0));
+            batch.push_quad(
+                self.layer,
+                Some(texture.clone()),
+                matrix,
+                self.color,
+                quad.uv,
+            );
+        }
+    }
+
+    /// define the ready callback for the node
+    ///
+    /// # Arguments
+    /// - `ready_function` - The function to be called when the node is ready.
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self) + Sync + Send,
+    {
+        self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
+        self
+    }
+
+    /// define the behavior callback for the node
+    ///
+    /// # Arguments
+    /// - `behavior_function` - The function to be called every frame.
+    pub fn define_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
+        self
+    }
+}
+
+impl UseReadyCallback for NodeBuilder<Text> {
+    type Node = Text;
+
+    fn with_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Text) + Send + Sync,
+    {
+        self.node.define_ready(ready_function);
+        self
+    }
+}
+
+impl UseBehaviorCallback for NodeBuilder<Text> {
+    type Node = Text;
+
+    fn with_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Text, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_behavior(behavior_function);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_anchor() {
+        let mut camera = Camera2D::new(10.0, 0.0, 600.0, 800.0);
+        assert_eq!(
+            camera.view_point(TextAnchor::TopLeft.fraction()),
+            glm::vec2(-390.0, 300.0)
+        );
+        assert_eq!(
+            camera.view_point(TextAnchor::Center.fraction()),
+            glm::vec2(10.0, 0.0)
+        );
+
+        // zooming out shows more of the world so the corners move away
+        camera.zoom_camera(1.0);
+        assert_eq!(
+            camera.view_point(TextAnchor::BottomRight.fraction()),
+            glm::vec2(810.0, -600.0)
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! TimeOfDay drives a day-night cycle. it moves the sun across the sky and changes its color and intensity with the time of day.
+//!
+//! ## Usage
+//! add directional lights as children of the time of day node. every frame the time is advanced and the direction, color, and
+//! intensity of all the child directional lights is updated to match the sun. the ambient tint of the sky lights the scene
+//! as the [hemisphere ambient](crate::context::GameContext::set_hemisphere_ambient) unless it is turned off with
+//! [`TimeOfDay::set_drives_ambient`].
+//!
+//! the sun rises in the east (+x) at 6:00, is highest at 12:00, and sets in the west (-x) at 18:00.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::Engine;
+//! use quaturn::nodes::{DirectionalLight, TimeOfDay};
+//!
+//! let mut engine = Engine::init("Example", 800, 600);
+//!
+//! let mut time_of_day = TimeOfDay::new(8.0);
+//! time_of_day.set_speed(0.5); // half an hour per second
+//! time_of_day.children.add("sun", DirectionalLight::new(100.0, 2048, 0.7));
+//!
+//! engine.context.nodes.add("time_of_day", time_of_day);
+//! ```
+
+use crate::components::NodeTransform;
+use crate::context::node_manager::{Behavior, Node, NodeManager, Ready};
+use crate::context::GameContext;
+use crate::nodes::DirectionalLight;
+use crate::utils::color::Color;
+
+use crate::context::node_manager::{BehaviorCallback, ReadyCallback};
+use nalgebra_glm as glm;
+use std::sync::{Arc, Mutex};
+
+use super::{NodeBuilder, UseBehaviorCallback, UseReadyCallback};
+
+/// color temperature of the sun at the horizon
+const HORIZON_KELVIN: f32 = 2000.0;
+/// color temperature of the sun at its highest point
+const ZENITH_KELVIN: f32 = 6500.0;
+/// how much of the sky ambient the ground bounces back up
+const GROUND_BOUNCE: f32 = 0.35;
+
+/// TimeOfDay moves the sun and updates the child directional lights to match the time of day.
+#[derive(Clone)]
+pub struct TimeOfDay {
+    /// The transform of the node.
+    pub transform: NodeTransform,
+    /// The children of the node. all directional lights in here are driven by the time of day.
+    pub children: NodeManager,
+
+    /// the time of day in hours (0.0 - 24.0)
+    time: f32,
+    /// how many in game hours pass every second
+    speed: f32,
+    /// if the time is paused
+    paused: bool,
+    /// how far the sun path is tilted towards +z (avoids the sun being straight overhead)
+    tilt: f32,
+
+    /// the color of the sky light at day
+    day_ambient: glm::Vec3,
+    /// the color of the sky light at night
+    night_ambient: glm::Vec3,
+    /// if the ambient color is set as the hemisphere ambient every frame
+    drives_ambient: bool,
+
+    /// the ready callback
+    pub ready_callback: ReadyCallback<TimeOfDay>,
+    /// the behavior callback
+    pub behavior_callback: BehaviorCallback<TimeOfDay, GameContext>,
+}
+
+impl Ready for TimeOfDay {
+    fn ready(&mut self) {
+        self.update_lights();
+
+        if let Some(callback) = self.ready_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self);
+            drop(guard);
+            self.ready_callback = Some(callback)
+        }
+    }
+}
+
+impl Behavior for TimeOfDay {
+    fn behavior(&mut self, context: &mut GameContext) {
+        if !self.paused {
+            self.advance(context.frame.time_delta.as_secs_f32());
+        }
+
+        // take callback out of self so we can use self later
+        if let Some(callback) = self.behavior_callback.take() {
+            let mut guard = callback.lock().unwrap();
+            guard(self, context); //"call back"
+            drop(guard);
+            self.behavior_callback = Some(callback);
+        }
+
+        self.update_lights();
+
+        if self.drives_ambient {
+            let (sky, ground) = self.get_hemisphere_colors();
+            context.set_hemisphere_ambient(sky, ground);
+        }
+    }
+}
+
+impl Node for TimeOfDay {
+    fn get_transform(&mut self) -> &mut NodeTransform {
+        &mut self.transform
+    }
+
+    fn get_children(&mut self) -> &mut NodeManager {
+        &mut self.children
+    }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
+
+    fn as_ready(&mut self) -> Option<&mut (dyn Ready + 'static)> {
+        Some(self)
+    }
+
+    fn as_behavior(&mut self) -> Option<&mut (dyn Behavior + 'static)> {
+        Some(self)
+    }
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self::new(12.0)
+    }
+}
+
+impl TimeOfDay {
+    /// creates a new time of day node
+    ///
+    /// # Arguments
+    /// - `time` - the starting time of day in hours (0.0 - 24.0)
+    ///
+    /// # Returns
+    /// The new time of day node.
+    pub fn new(time: f32) -> Self {
+        TimeOfDay {
+            transform: NodeTransform::default(),
+            children: NodeManager::new(),
+
+            time: time.rem_euclid(24.0),
+            speed: 1.0 / 60.0, // 1 in game hour per minute
+            paused: false,
+            tilt: 0.3,
+
+            day_ambient: glm::vec3(0.55, 0.65, 0.8),
+            night_ambient: glm::vec3(0.03, 0.04, 0.1),
+            drives_ambient: true,
+
+            ready_callback: None,
+            behavior_callback: None,
+        }
+    }
+
+    /// moves the time forward
+    ///
+    /// # Arguments
+    /// - `seconds` - the real time that passed in seconds
+    pub fn advance(&mut self, seconds: f32) {
+        self.time = (self.time + self.speed * seconds).rem_euclid(24.0);
+    }
+
+    /// get the time of day in hours (0.0 - 24.0)
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// set the time of day in hours. values outside 0.0 - 24.0 wrap around
+    pub fn set_time(&mut self, time: f32) -> &mut Self {
+        self.time = time.rem_euclid(24.0);
+        self
+    }
+
+    /// get how many in game hours pass every second
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// set how many in game hours pass every second
+    ///
+    /// # Arguments
+    /// - `speed` - hours per second. negative values run the time backwards
+    pub fn set_speed(&mut self, speed: f32) -> &mut Self {
+        self.speed = speed;
+        self
+    }
+
+    /// check if the time is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// pause or resume the time. the lights are still updated when paused so [`set_time`](TimeOfDay::set_time) still works
+    pub fn set_paused(&mut self, paused: bool) -> &mut Self {
+        self.paused = paused;
+        self
+    }
+
+    /// set the ambient colors of the sky at day and at night. the ambient color blends between them as the sun rises and sets
+    pub fn set_ambient_colors(&mut self, day: Color, night: Color) -> &mut Self {
+        self.day_ambient = glm::vec3(day.r, day.g, day.b);
+        self.night_ambient = glm::vec3(night.r, night.g, night.b);
+        self
+    }
+
+    /// the direction pointing towards the sun (the direction the light is coming from)
+    pub fn get_sun_direction(&self) -> glm::Vec3 {
+        // 6:00 is sunrise in the east and 18:00 is sunset in the west
+        let angle = (self.time - 6.0) / 12.0 * glm::pi::<f32>();
+        glm::normalize(&glm::vec3(angle.cos(), angle.sin(), self.tilt))
+    }
+
+    /// how much the sun is lighting the scene from 0.0 (night) to 1.0 (day), fades around the horizon
+    pub fn get_daylight(&self) -> f32 {
+        let elevation = self.get_sun_direction().y;
+        glm::smoothstep(-0.1, 0.1, elevation)
+    }
+
+    /// the color of the sun based on its color temperature, warm at the horizon and white at noon
+    pub fn get_sun_color(&self) -> Color {
+        let elevation = self.get_sun_direction().y.max(0.0);
+        Color::from_kelvin(HORIZON_KELVIN + (ZENITH_KELVIN - HORIZON_KELVIN) * elevation)
+    }
+
+    /// the ambient tint of the sky for the current time
+    pub fn get_ambient_color(&self) -> Color {
+        let ambient = glm::lerp(&self.night_ambient, &self.day_ambient, self.get_daylight());
+        Color::from_normalized(ambient.x, ambient.y, ambient.z, 1.0)
+    }
+
+    /// the sky and ground colors of the hemisphere ambient for the current time, the ground bounces part of the sky
+    pub fn get_hemisphere_colors(&self) -> (Color, Color) {
+        let sky = self.get_ambient_color();
+        let ground = Color::from_normalized(
+            sky.r * GROUND_BOUNCE,
+            sky.g * GROUND_BOUNCE,
+            sky.b * GROUND_BOUNCE,
+            1.0,
+        );
+        (sky, ground)
+    }
+
+    /// check if the ambient color lights the scene, see [`set_drives_ambient`](TimeOfDay::set_drives_ambient)
+    pub fn get_drives_ambient(&self) -> bool {
+        self.drives_ambient
+    }
+
+    /// set if the behavior sets the [hemisphere ambient](GameContext::set_hemisphere_ambient) to the ambient color of
+    /// the time every frame. turn it off to light the scene with your own ambient.
+    ///
+    /// # Arguments
+    /// - `drives_ambient` - if the ambient is set every frame, true by default
+    pub fn set_drives_ambient(&mut self, drives_ambient: bool) -> &mut Self {
+        self.drives_ambient = drives_ambient;
+        self
+    }
+
+    /// updates the direction, color, and intensity of all the child directional lights
+    pub fn update_lights(&mut self) {
+        let direction = self.get_sun_direction();
+        let color = self.get_sun_color();
+        let intensity = self.get_daylight();
+
+        for light in self.children.get_iter::<DirectionalLight>() {
+            light
+                .set_direction(direction)
+                .set_color(color)
+                .set_intensity(intensity);
+        }
+    }
+
+    /// define the ready callback for the node
+    ///
+    /// # Arguments
+    /// - `ready_function` - The function to be called when the node is ready.
+    pub fn define_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self) + Sync + Send,
+    {
+        self.ready_callback = Some(Arc::new(Mutex::new(ready_function)));
+        self
+    }
+
+    /// define the behavior callback for the node
+    ///
+    /// # Arguments
+    /// - `behavior_function` - The function to be called every frame before the lights are updated.
+    pub fn define_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut Self, &mut GameContext) + Sync + Send,
+    {
+        self.behavior_callback = Some(Arc::new(Mutex::new(behavior_function)));
+        self
+    }
+}
+
+/// builder functions for the time of day node
+pub trait TimeOfDayBuilder {
+    /// set how many in game hours pass every second
+    fn set_speed(&mut self, speed: f32) -> &mut Self;
+    /// pause or resume the time
+    fn set_paused(&mut self, paused: bool) -> &mut Self;
+    /// set the ambient colors of the sky at day and at night
+    fn set_ambient_colors(&mut self, day: Color, night: Color) -> &mut Self;
+    /// set if the ambient color lights the scene
+    fn set_drives_ambient(&mut self, drives_ambient: bool) -> &mut Self;
+}
+
+impl TimeOfDayBuilder for NodeBuilder<TimeOfDay> {
+    fn set_speed(&mut self, speed: f32) -> &mut Self {
+        self.node.set_speed(speed);
+        self
+    }
+
+    fn set_paused(&mut self, paused: bool) -> &mut Self {
+        self.node.set_paused(paused);
+        self
+    }
+
+    fn set_ambient_colors(&mut self, day: Color, night: Color) -> &mut Self {
+        self.node.set_ambient_colors(day, night);
+        self
+    }
+
+    fn set_drives_ambient(&mut self, drives_ambient: bool) -> &mut Self {
+        self.node.set_drives_ambient(drives_ambient);
+        self
+    }
+}
+
+impl UseReadyCallback for NodeBuilder<TimeOfDay> {
+    type Node = TimeOfDay;
+
+    fn with_ready<F>(&mut self, ready_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut TimeOfDay) + Send + Sync,
+    {
+        self.node.define_ready(ready_function);
+        self
+    }
+}
+
+impl UseBehaviorCallback for NodeBuilder<TimeOfDay> {
+    type Node = TimeOfDay;
+
+    fn with_behavior<F>(&mut self, behavior_function: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut TimeOfDay, &mut GameContext) + Send + Sync,
+    {
+        self.node.define_behavior(behavior_function);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_path() {
+        let mut time_of_day = TimeOfDay::new(12.0);
+        assert!(time_of_day.get_sun_direction().y > 0.9);
+        assert_eq!(time_of_day.get_daylight(), 1.0);
+
+        time_of_day.set_time(6.0);
+        assert!(time_of_day.get_sun_direction().x > 0.9);
+
+        time_of_day.set_time(0.0);
+        assert!(time_of_day.get_sun_direction().y < -0.9);
+        assert_eq!(time_of_day.get_daylight(), 0.0);
+    }
+
+    #[test]
+    fn test_hemisphere_follows_daylight() {
+        let mut time_of_day = TimeOfDay::new(12.0);
+        let (day_sky, day_ground) = time_of_day.get_hemisphere_colors();
+        assert_eq!(day_sky, time_of_day.get_ambient_color());
+        assert!(day_ground.b < day_sky.b);
+
+        time_of_day.set_time(0.0);
+        let (night_sky, _) = time_of_day.get_hemisphere_colors();
+        assert!(night_sky.b < day_sky.b);
+    }
+
+    #[test]
+    fn test_advance_wraps() {
+        let mut time_of_day = TimeOfDay::new(23.0);
+        time_of_day.set_speed(1.0);
+        time_of_day.advance(2.0);
+        assert!((time_of_day.get_time() - 1.0).abs() < 1e-5);
+    }
+}
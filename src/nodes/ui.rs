@@ -57,8 +57,30 @@ pub struct UI {
     native_pixels_per_point: f32,
 
     ui_window: Option<Arc<Mutex<dyn FnMut(&egui::Context, &mut GameContext)>>>,
+
+    /// if the gamepad can move the focus between widgets
+    gamepad_navigation: bool,
+    /// the direction the left stick is held in and when it last moved the focus
+    stick_navigation: Option<(egui::Key, f64)>,
+    /// where the ui is drawn relative to the 3D scene
+    layer: UILayer,
+}
+
+/// where a UI is drawn relative to the 3D scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UILayer {
+    /// drawn after the scene is cleared and before the models so the 3D scene covers it
+    Background,
+    /// drawn on top of the scene after post processing (default)
+    #[default]
+    Overlay,
 }
 
+/// how far the stick has to be pushed to move the focus
+const STICK_THRESHOLD: f32 = 0.5;
+/// how often the focus moves while the stick is held in seconds
+const STICK_REPEAT: f64 = 0.25;
+
 impl Node for UI {
     fn get_transform(&mut self) -> &mut NodeTransform {
         &mut self.transform
@@ -67,6 +89,10 @@ impl Node for UI {
     fn get_children(&mut self) -> &mut NodeManager {
         &mut self.children
     }
+
+    fn get_children_ref(&self) -> &NodeManager {
+        &self.children
+    }
 }
 
 impl UI {
@@ -105,23 +131,160 @@ impl UI {
             native_pixels_per_point,
 
             ui_window: None,
+
+            gamepad_navigation: false,
+            stick_navigation: None,
+            layer: UILayer::default(),
+        }
+    }
+
+    /// lets the gamepad navigate the ui without a mouse.
+    ///
+    /// - d-pad / left stick: move the focus between widgets
+    /// - bumpers: move the focus to the next / previous widget
+    /// - A (bottom face button): activate the focused widget
+    /// - B (right face button): close popups and drop the focus
+    ///
+    /// # Arguments
+    /// - `enabled` - if the gamepad should navigate the ui (off by default)
+    pub fn set_gamepad_navigation(&mut self, enabled: bool) -> &mut UI {
+        self.gamepad_navigation = enabled;
+        self
+    }
+
+    /// check if the gamepad can navigate the ui
+    pub fn gamepad_navigation(&self) -> bool {
+        self.gamepad_navigation
+    }
+
+    /// set where the ui is drawn relative to the 3D scene.
+    ///
+    /// background uis are drawn behind the models (and go through post processing), overlays are drawn on top.
+    ///
+    /// # Arguments
+    /// - `layer` - the layer to draw the ui on ([`UILayer::Overlay`] by default)
+    pub fn set_layer(&mut self, layer: UILayer) -> &mut UI {
+        self.layer = layer;
+        self
+    }
+
+    /// get the layer the ui is drawn on
+    pub fn get_layer(&self) -> UILayer {
+        self.layer
+    }
+
+    /// translates the gamepad input into the key presses egui uses for focus navigation
+    fn gamepad_events(&mut self, context: &GameContext, time: f64) -> Vec<egui::Event> {
+        use glfw::{GamepadAxis, GamepadButton};
+
+        let input = &context.input;
+        let mut keys = Vec::new();
+
+        for button in &input.gamepad_button_just_pressed {
+            let key = match button {
+                GamepadButton::ButtonDpadUp => (egui::Key::ArrowUp, false),
+                GamepadButton::ButtonDpadDown => (egui::Key::ArrowDown, false),
+                GamepadButton::ButtonDpadLeft => (egui::Key::ArrowLeft, false),
+                GamepadButton::ButtonDpadRight => (egui::Key::ArrowRight, false),
+                GamepadButton::ButtonRightBumper => (egui::Key::Tab, false),
+                GamepadButton::ButtonLeftBumper => (egui::Key::Tab, true),
+                GamepadButton::ButtonA => (egui::Key::Enter, false),
+                GamepadButton::ButtonB => (egui::Key::Escape, false),
+                _ => continue,
+            };
+            keys.push(key);
         }
+
+        // the stick moves the focus once when pushed and then repeats while held
+        let x = input.gamepad_axis(GamepadAxis::AxisLeftX);
+        let y = input.gamepad_axis(GamepadAxis::AxisLeftY);
+        let direction = if x.abs().max(y.abs()) < STICK_THRESHOLD {
+            None
+        } else if x.abs() > y.abs() {
+            Some(if x > 0.0 {
+                egui::Key::ArrowRight
+            } else {
+                egui::Key::ArrowLeft
+            })
+        } else {
+            // stick y is positive when pushed down
+            Some(if y > 0.0 {
+                egui::Key::ArrowDown
+            } else {
+                egui::Key::ArrowUp
+            })
+        };
+
+        self.stick_navigation = match (direction, self.stick_navigation) {
+            (Some(key), Some((held, last))) if key == held => {
+                if time - last >= STICK_REPEAT {
+                    keys.push((key, false));
+                    Some((key, time))
+                } else {
+                    Some((held, last))
+                }
+            }
+            (Some(key), _) => {
+                keys.push((key, false));
+                Some((key, time))
+            }
+            (None, _) => None,
+        };
+
+        keys.into_iter()
+            .flat_map(|(key, shift)| {
+                let modifiers = egui::Modifiers {
+                    shift,
+                    ..Default::default()
+                };
+                [true, false].map(|pressed| egui::Event::Key {
+                    key,
+                    physical_key: None,
+                    pressed,
+                    repeat: false,
+                    modifiers,
+                })
+            })
+            .collect()
     }
 
     pub fn update(&mut self, context: &mut GameContext) {
+        let time = context.frame.start_time.elapsed().as_secs_f64();
+        let gamepad_events = if self.gamepad_navigation {
+            self.gamepad_events(context, time)
+        } else {
+            Vec::new()
+        };
+
         // Lock the input to handle events
         if let Ok(mut input) = self.input.lock() {
             for (_, event) in context.input.events.iter() {
+                // the painter clips and projects with its own copy of the window size
+                if let glfw::WindowEvent::FramebufferSize(width, height) = event {
+                    if let Ok(mut painter) = self.painter.lock() {
+                        painter.set_size(*width as u32, *height as u32);
+                    }
+                }
                 // Clone the event because we need to use it multiple times
                 egui_backend::handle_event(event.clone(), &mut *input);
             }
 
             // Update time and prepare the frame
-            input.input.time = Some(context.frame.start_time.elapsed().as_secs_f64());
+            input.input.time = Some(time);
+            input.input.events.extend(gamepad_events);
+
             self.ctx.begin_frame(input.input.take());
             input.pixels_per_point = self.native_pixels_per_point;
         } else {
-            eprintln!("Failed to lock input for update");
+            log::error!("failed to lock input for update");
+        }
+
+        // stop the game from also handling input meant for the ui (click-through)
+        if self.ctx.is_pointer_over_area() || self.ctx.wants_pointer_input() {
+            context.input.consume_mouse();
+        }
+        if self.ctx.wants_keyboard_input() {
+            context.input.consume_keyboard();
         }
     }
 
@@ -159,7 +322,7 @@ impl UI {
                 egui_backend::copy_to_clipboard(&mut *input, platform_output.copied_text);
             }
         } else {
-            eprintln!("Failed to lock input for clipboard copy");
+            log::error!("failed to lock input for clipboard copy");
         }
 
         // Tessellate the shapes for rendering
@@ -169,7 +332,7 @@ impl UI {
         if let Ok(mut painter) = self.painter.lock() {
             painter.paint_and_update_textures(1.0, &clipped_shapes, &textures_delta);
         } else {
-            eprintln!("Failed to lock painter for rendering");
+            log::error!("failed to lock painter for rendering");
         }
 
         Renderer::ui_mode(false);
@@ -180,6 +343,10 @@ pub trait UIBuilder {
     fn ui_component<F>(&mut self, ui_window: F) -> &mut Self
     where
         F: FnMut(&egui::Context, &mut GameContext) + 'static;
+    /// lets the gamepad navigate the ui, see [`UI::set_gamepad_navigation`]
+    fn gamepad_navigation(&mut self, enabled: bool) -> &mut Self;
+    /// set where the ui is drawn relative to the 3D scene, see [`UI::set_layer`]
+    fn layer(&mut self, layer: UILayer) -> &mut Self;
 }
 
 impl UIBuilder for NodeBuilder<UI> {
@@ -190,4 +357,14 @@ impl UIBuilder for NodeBuilder<UI> {
         self.node.define_ui(ui_window);
         self
     }
+
+    fn gamepad_navigation(&mut self, enabled: bool) -> &mut Self {
+        self.node.set_gamepad_navigation(enabled);
+        self
+    }
+
+    fn layer(&mut self, layer: UILayer) -> &mut Self {
+        self.node.set_layer(layer);
+        self
+    }
 }
@@ -3,16 +3,37 @@
 /// The FrameBuffer struct is used to create and manage frame buffers in the OpenGL pipeline
 pub struct FrameBuffer {
     fbo: gl::types::GLuint,
+    /// the color texture (0 when the frame buffer is multisampled)
     texture: gl::types::GLuint,
+    /// the multisampled color renderbuffer (0 when the color is stored in a texture)
+    color_rbo: gl::types::GLuint,
+    /// the depth renderbuffer (0 when the depth is stored in a texture)
     rbo: gl::types::GLuint,
+    /// the depth texture (0 when the depth is stored in a renderbuffer)
+    depth_texture: gl::types::GLuint,
+    width: i32,
+    height: i32,
 }
 
 impl Drop for FrameBuffer {
     fn drop(&mut self) {
+        if !crate::renderer::gl_object::is_context_alive() {
+            return;
+        }
         unsafe {
             gl::DeleteFramebuffers(1, &self.fbo);
-            gl::DeleteTextures(1, &self.texture);
-            gl::DeleteRenderbuffers(1, &self.rbo);
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+            if self.color_rbo != 0 {
+                gl::DeleteRenderbuffers(1, &self.color_rbo);
+            }
+            if self.rbo != 0 {
+                gl::DeleteRenderbuffers(1, &self.rbo);
+            }
+            if self.depth_texture != 0 {
+                gl::DeleteTextures(1, &self.depth_texture);
+            }
         }
     }
 }
@@ -27,31 +48,121 @@ impl FrameBuffer {
     /// # Returns
     /// The frame buffer
     pub fn new(width: i32, height: i32) -> Self {
+        Self::create(width, height, false)
+    }
+
+    /// Creates a new frame buffer that stores its depth in a texture so it can be sampled later
+    ///
+    /// # Arguments
+    /// - `width` - the width of the frame buffer
+    /// - `height` - the height of the frame buffer
+    ///
+    /// # Returns
+    /// The frame buffer
+    pub fn new_with_depth_texture(width: i32, height: i32) -> Self {
+        Self::create(width, height, true)
+    }
+
+    /// Creates a new multisampled frame buffer. it can't be sampled, resolve it into a normal frame buffer with
+    /// [`FrameBuffer::resolve_into`] first.
+    ///
+    /// # Arguments
+    /// - `width` - the width of the frame buffer
+    /// - `height` - the height of the frame buffer
+    /// - `samples` - the samples per pixel, must not be more than `GL_MAX_SAMPLES`
+    ///
+    /// # Returns
+    /// The frame buffer
+    pub fn new_multisampled(width: i32, height: i32, samples: u32) -> Self {
+        let mut fbo: gl::types::GLuint = 0;
+        let mut color_rbo: gl::types::GLuint = 0;
+        let mut rbo: gl::types::GLuint = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+            //floating point color so hdr colors survive the resolve
+            gl::GenRenderbuffers(1, &mut color_rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, color_rbo);
+            gl::RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples as i32,
+                gl::RGBA16F,
+                width,
+                height,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                color_rbo,
+            );
+
+            gl::GenRenderbuffers(1, &mut rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
+            gl::RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples as i32,
+                gl::DEPTH24_STENCIL8,
+                width,
+                height,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                rbo,
+            );
+
+            //check framebuffer
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                panic!("Multisampled framebuffer is not complete!");
+            }
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        FrameBuffer {
+            fbo,
+            texture: 0,
+            color_rbo,
+            rbo,
+            depth_texture: 0,
+            width,
+            height,
+        }
+    }
+
+    fn create(width: i32, height: i32, sample_depth: bool) -> Self {
         let mut fbo: gl::types::GLuint = 0;
         let mut texture: gl::types::GLuint = 0;
         let mut rbo: gl::types::GLuint = 0;
+        let mut depth_texture: gl::types::GLuint = 0;
 
         unsafe {
             //generate frame buffer
             gl::GenFramebuffers(1, &mut fbo);
             gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
 
-            //generate texture for color attachment
+            //generate texture for color attachment (floating point so hdr colors are kept between passes)
             gl::GenTextures(1, &mut texture);
             gl::BindTexture(gl::TEXTURE_2D, texture);
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                gl::RGB as i32,
+                gl::RGBA16F as i32,
                 width,
                 height,
                 0,
-                gl::RGB,
-                gl::UNSIGNED_BYTE,
+                gl::RGBA,
+                gl::FLOAT,
                 std::ptr::null(),
             );
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
             gl::FramebufferTexture2D(
                 gl::FRAMEBUFFER,
                 gl::COLOR_ATTACHMENT0,
@@ -60,16 +171,44 @@ impl FrameBuffer {
                 0,
             );
 
-            //create renderbuffer object for depth and stencil attachment
-            gl::GenRenderbuffers(1, &mut rbo);
-            gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
-            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
-            gl::FramebufferRenderbuffer(
-                gl::FRAMEBUFFER,
-                gl::DEPTH_STENCIL_ATTACHMENT,
-                gl::RENDERBUFFER,
-                rbo,
-            );
+            if sample_depth {
+                //create a depth texture so the depth can be read by later passes
+                gl::GenTextures(1, &mut depth_texture);
+                gl::BindTexture(gl::TEXTURE_2D, depth_texture);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    gl::DEPTH24_STENCIL8 as i32,
+                    width,
+                    height,
+                    0,
+                    gl::DEPTH_STENCIL,
+                    gl::UNSIGNED_INT_24_8,
+                    std::ptr::null(),
+                );
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    gl::DEPTH_STENCIL_ATTACHMENT,
+                    gl::TEXTURE_2D,
+                    depth_texture,
+                    0,
+                );
+            } else {
+                //create renderbuffer object for depth and stencil attachment
+                gl::GenRenderbuffers(1, &mut rbo);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
+                gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
+                gl::FramebufferRenderbuffer(
+                    gl::FRAMEBUFFER,
+                    gl::DEPTH_STENCIL_ATTACHMENT,
+                    gl::RENDERBUFFER,
+                    rbo,
+                );
+            }
 
             //check framebuffer
             if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
@@ -79,6 +218,69 @@ impl FrameBuffer {
             gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
 
-        FrameBuffer { fbo, texture, rbo }
+        FrameBuffer {
+            fbo,
+            texture,
+            color_rbo: 0,
+            rbo,
+            depth_texture,
+            width,
+            height,
+        }
+    }
+
+    /// binds the frame buffer so the next draws render into it and sets the viewport to its size
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Viewport(0, 0, self.width, self.height);
+        }
+    }
+
+    /// binds the default frame buffer (the window)
+    pub fn unbind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// copies the color, depth and stencil into another frame buffer of the same size, averaging the samples when this
+    /// frame buffer is multisampled. leaves the default frame buffer bound.
+    ///
+    /// # Arguments
+    /// - `target` - the frame buffer to copy into
+    pub fn resolve_into(&self, target: &FrameBuffer) {
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.fbo);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, target.fbo);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                self.width,
+                self.height,
+                0,
+                0,
+                target.width,
+                target.height,
+                gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT,
+                gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// get the color texture of the frame buffer (0 when it is multisampled)
+    pub fn get_texture(&self) -> gl::types::GLuint {
+        self.texture
+    }
+
+    /// get the depth texture of the frame buffer if it was created with [`FrameBuffer::new_with_depth_texture`]
+    pub fn get_depth_texture(&self) -> Option<gl::types::GLuint> {
+        (self.depth_texture != 0).then_some(self.depth_texture)
+    }
+
+    /// get the size of the frame buffer (width, height)
+    pub fn get_size(&self) -> (i32, i32) {
+        (self.width, self.height)
     }
 }
@@ -1,10 +1,16 @@
 //! the index buffer is used to store the indices of the vertices
 
+use super::BufferUsage;
+use crate::renderer::gl_object::{GlKind, GlObject};
+
 /// stores the index buffer
 #[derive(Clone, Debug)]
 pub struct IndexBuffer {
-    id: u32,
+    id: GlObject,
     count: i32,
+    /// the size of the allocation in bytes
+    capacity: isize,
+    usage: BufferUsage,
 }
 
 impl IndexBuffer {
@@ -13,6 +19,15 @@ impl IndexBuffer {
     /// # Arguments
     /// - `data` - the data to store in the index buffer
     pub fn new(data: &[u32]) -> IndexBuffer {
+        Self::new_with_usage(data, BufferUsage::Static)
+    }
+
+    /// Creates a new index buffer with a usage hint
+    ///
+    /// # Arguments
+    /// - `data` - the data to store in the index buffer
+    /// - `usage` - how often the data will be changed
+    pub fn new_with_usage(data: &[u32], usage: BufferUsage) -> IndexBuffer {
         unsafe {
             let mut id = 0;
             gl::GenBuffers(1, &mut id);
@@ -21,19 +36,32 @@ impl IndexBuffer {
                 gl::ELEMENT_ARRAY_BUFFER,
                 std::mem::size_of_val(data) as isize,
                 data.as_ptr() as *const std::ffi::c_void,
-                gl::STATIC_DRAW,
+                usage.to_gl(),
             );
             IndexBuffer {
-                id,
+                id: GlObject::new(id, GlKind::Buffer),
                 count: data.len() as i32,
+                capacity: std::mem::size_of_val(data) as isize,
+                usage,
             }
         }
     }
 
+    /// replaces the indices. binds the buffer to the currently bound vertex array, so bind the vertex array that
+    /// uses this buffer first.
+    ///
+    /// # Arguments
+    /// - `data` - the new indices
+    pub fn set_data(&mut self, data: &[u32]) {
+        self.bind();
+        self.capacity = super::upload(gl::ELEMENT_ARRAY_BUFFER, data, self.capacity, self.usage);
+        self.count = data.len() as i32;
+    }
+
     /// Binds the index buffer
     pub fn bind(&self) {
         unsafe {
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.id);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.id.id());
         }
     }
 
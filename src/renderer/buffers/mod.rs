@@ -2,6 +2,53 @@
 
 pub mod frame_buffer;
 pub mod index_buffer;
+pub mod uniform_buffer;
 pub mod vertex_array;
 pub mod vertex_buffer;
 pub mod vertex_buffer_layout;
+
+/// hints how often the data of a buffer changes so the driver can place it in the right memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferUsage {
+    /// uploaded once and drawn many times (default)
+    #[default]
+    Static,
+    /// changed occasionally and drawn many times
+    Dynamic,
+    /// changed every frame
+    Stream,
+}
+
+impl BufferUsage {
+    /// get the gl enum of the usage
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            BufferUsage::Static => gl::STATIC_DRAW,
+            BufferUsage::Dynamic => gl::DYNAMIC_DRAW,
+            BufferUsage::Stream => gl::STREAM_DRAW,
+        }
+    }
+}
+
+/// uploads data to the buffer bound to `target`. the data is written in place when it fits the current allocation,
+/// otherwise (or when streaming) the buffer is reallocated (orphaned) so the driver does not wait on the previous draw.
+///
+/// # Returns
+/// the size of the buffer allocation in bytes
+fn upload<T>(target: gl::types::GLenum, data: &[T], capacity: isize, usage: BufferUsage) -> isize {
+    let size = std::mem::size_of_val(data) as isize;
+    unsafe {
+        if usage == BufferUsage::Stream || size > capacity {
+            gl::BufferData(
+                target,
+                size,
+                data.as_ptr() as *const std::ffi::c_void,
+                usage.to_gl(),
+            );
+            size
+        } else {
+            gl::BufferSubData(target, 0, size, data.as_ptr() as *const std::ffi::c_void);
+            capacity
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! the uniform buffer stores a block of uniforms that many shaders can read, so shared data
+//! (like the camera matrices) is uploaded once instead of with a `set_uniform` call per shader.
+//!
+//! ## Usage
+//! the struct has to match the `std140` layout of the block in the shader: vec3s take the space of a vec4
+//! and every member is aligned to its size (vec4 and mat4 columns to 16 bytes).
+//!
+//! ## Example
+//! ```rust,ignore
+//! // glsl: layout(std140) uniform Wind { vec4 u_direction; float u_strength; };
+//! #[repr(C)]
+//! #[derive(Clone, Copy)]
+//! struct Wind {
+//!     direction: [f32; 4],
+//!     strength: f32,
+//!     _padding: [f32; 3],
+//! }
+//! unsafe impl quaturn::bytemuck::Zeroable for Wind {}
+//! unsafe impl quaturn::bytemuck::Pod for Wind {}
+//!
+//! let mut wind = UniformBuffer::new(&Wind { direction: [1.0, 0.0, 0.0, 0.0], strength: 2.0, _padding: [0.0; 3] });
+//! wind.bind(1);
+//! shader.bind_uniform_block("Wind", 1);
+//! ```
+
+use bytemuck::Pod;
+use std::marker::PhantomData;
+
+use super::BufferUsage;
+use crate::renderer::gl_object::{GlKind, GlObject};
+
+/// stores a block of uniforms on the gpu
+#[derive(Clone, Debug)]
+pub struct UniformBuffer<T: Pod> {
+    id: GlObject,
+    /// the size of the allocation in bytes
+    capacity: isize,
+    usage: BufferUsage,
+    _data: PhantomData<T>,
+}
+
+impl<T: Pod> UniformBuffer<T> {
+    /// creates a new uniform buffer that is expected to change every now and then
+    ///
+    /// # Arguments
+    /// - `data` - the initial value of the block
+    pub fn new(data: &T) -> UniformBuffer<T> {
+        Self::new_with_usage(data, BufferUsage::Dynamic)
+    }
+
+    /// creates a new uniform buffer with a usage hint
+    ///
+    /// # Arguments
+    /// - `data` - the initial value of the block
+    /// - `usage` - how often the data will be changed
+    pub fn new_with_usage(data: &T, usage: BufferUsage) -> UniformBuffer<T> {
+        let bytes = bytemuck::bytes_of(data);
+        unsafe {
+            let mut id = 0;
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::UNIFORM_BUFFER, id);
+            gl::BufferData(
+                gl::UNIFORM_BUFFER,
+                bytes.len() as isize,
+                bytes.as_ptr() as *const std::ffi::c_void,
+                usage.to_gl(),
+            );
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+            UniformBuffer {
+                id: GlObject::new(id, GlKind::Buffer),
+                capacity: bytes.len() as isize,
+                usage,
+                _data: PhantomData,
+            }
+        }
+    }
+
+    /// replaces the value of the block
+    ///
+    /// # Arguments
+    /// - `data` - the new value of the block
+    pub fn set_data(&mut self, data: &T) {
+        unsafe { gl::BindBuffer(gl::UNIFORM_BUFFER, self.id.id()) };
+        self.capacity = super::upload(
+            gl::UNIFORM_BUFFER,
+            bytemuck::bytes_of(data),
+            self.capacity,
+            self.usage,
+        );
+        unsafe { gl::BindBuffer(gl::UNIFORM_BUFFER, 0) };
+    }
+
+    /// binds the buffer to a binding point, shaders read it from blocks bound to the same point
+    /// with [`Shader::bind_uniform_block`](crate::renderer::shader::Shader::bind_uniform_block)
+    ///
+    /// # Arguments
+    /// - `slot` - the binding point
+    pub fn bind(&self, slot: u32) {
+        unsafe {
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, slot, self.id.id());
+        }
+    }
+
+    /// get the opengl id of the buffer
+    pub fn get_id(&self) -> u32 {
+        self.id.id()
+    }
+}
@@ -4,11 +4,12 @@
 
 use super::vertex_buffer::VertexBuffer;
 use super::vertex_buffer_layout::{VertexBufferElement, VertexBufferLayout};
+use crate::renderer::gl_object::{GlKind, GlObject};
 
 /// stores the vertex array
 #[derive(Clone, Debug)]
 pub struct VertexArray {
-    id: u32,
+    id: GlObject,
 }
 
 impl Default for VertexArray {
@@ -23,7 +24,9 @@ impl VertexArray {
         unsafe {
             let mut id = 0;
             gl::GenVertexArrays(1, &mut id);
-            VertexArray { id }
+            VertexArray {
+                id: GlObject::new(id, GlKind::VertexArray),
+            }
         }
     }
 
@@ -33,11 +36,27 @@ impl VertexArray {
     /// - `buffer` - the buffer to add
     /// - `layout` - the layout of the buffer
     pub fn add_buffer(&self, buffer: &VertexBuffer, layout: &VertexBufferLayout) {
+        self.add_buffer_at(buffer, layout, 0);
+    }
+
+    /// Adds a buffer to the vertex array starting at an attribute location, used to add a second buffer after the first
+    ///
+    /// # Arguments
+    /// - `buffer` - the buffer to add
+    /// - `layout` - the layout of the buffer
+    /// - `first_location` - the attribute location of the first element of the layout
+    pub fn add_buffer_at(
+        &self,
+        buffer: &VertexBuffer,
+        layout: &VertexBufferLayout,
+        first_location: u32,
+    ) {
         buffer.bind();
         self.bind();
 
         let mut offset = 0;
         for (i, element) in layout.elements.iter().enumerate() {
+            let i = first_location as usize + i;
             unsafe {
                 gl::EnableVertexAttribArray(i as u32);
                 gl::VertexAttribPointer(
@@ -61,7 +80,7 @@ impl VertexArray {
     /// Binds the vertex array
     pub fn bind(&self) {
         unsafe {
-            gl::BindVertexArray(self.id);
+            gl::BindVertexArray(self.id.id());
         }
     }
     /// Unbinds the vertex array
@@ -2,9 +2,16 @@
 
 extern crate nalgebra_glm as glm;
 
+use super::BufferUsage;
+use crate::renderer::gl_object::{GlKind, GlObject};
+
 /// stores the vertex buffer
+#[derive(Clone, Debug)]
 pub struct VertexBuffer {
-    id: u32,
+    id: GlObject,
+    /// the size of the allocation in bytes
+    capacity: isize,
+    usage: BufferUsage,
 }
 
 impl VertexBuffer {
@@ -13,6 +20,15 @@ impl VertexBuffer {
     /// # Arguments
     /// - `data` - the data to store in the vertex buffer
     pub fn new<T>(data: &[T]) -> VertexBuffer {
+        Self::new_with_usage(data, BufferUsage::Static)
+    }
+
+    /// creates a new vertex buffer with a usage hint
+    ///
+    /// # Arguments
+    /// - `data` - the data to store in the vertex buffer
+    /// - `usage` - how often the data will be changed
+    pub fn new_with_usage<T>(data: &[T], usage: BufferUsage) -> VertexBuffer {
         unsafe {
             let mut id = 0;
             gl::GenBuffers(1, &mut id);
@@ -21,16 +37,34 @@ impl VertexBuffer {
                 gl::ARRAY_BUFFER,
                 std::mem::size_of_val(data) as isize,
                 data.as_ptr() as *const std::ffi::c_void,
-                gl::STATIC_DRAW,
+                usage.to_gl(),
             );
-            VertexBuffer { id }
+            VertexBuffer {
+                id: GlObject::new(id, GlKind::Buffer),
+                capacity: std::mem::size_of_val(data) as isize,
+                usage,
+            }
         }
     }
 
+    /// replaces the data of the vertex buffer. the buffer grows when the new data is larger
+    ///
+    /// # Arguments
+    /// - `data` - the new data to store in the vertex buffer
+    pub fn set_data<T>(&mut self, data: &[T]) {
+        self.bind();
+        self.capacity = super::upload(gl::ARRAY_BUFFER, data, self.capacity, self.usage);
+    }
+
+    /// get the usage hint of the buffer
+    pub fn get_usage(&self) -> BufferUsage {
+        self.usage
+    }
+
     /// binds the vertex buffer
     pub fn bind(&self) {
         unsafe {
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.id);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.id.id());
         }
     }
 
@@ -0,0 +1,302 @@
+//! immediate mode debug drawing for lines and gizmos.
+//!
+//! lines are collected during the frame and drawn on top of the scene after the models, then cleared. the draws use the
+//! current transform of a matrix stack (like the old OpenGL `glPushMatrix` / `glPopMatrix`) so a hierarchy of gizmos can be
+//! drawn without multiplying the matrices by hand. the stack is reset every frame.
+//!
+//! ## Usage
+//! the debug drawer lives in the game context as `context.debug`.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::nodes::Empty;
+//! use quaturn::utils::color::Color;
+//!
+//! .define_behavior(|_node: &mut Empty, context: &mut GameContext| {
+//!     let debug = &mut context.debug;
+//!
+//!     debug.push_transform(glm::translation(&glm::vec3(0.0, 1.0, 0.0)));
+//!     debug.draw_axes(1.0);
+//!
+//!     // relative to the previous transform
+//!     debug.push_transform(glm::rotation(45f32.to_radians(), &glm::Vec3::y()));
+//!     debug.draw_wire_box(glm::vec3(-0.5, -0.5, -0.5), glm::vec3(0.5, 0.5, 0.5), Color::from_normalized(1.0, 1.0, 0.0, 1.0));
+//!     debug.pop_transform();
+//!
+//!     debug.pop_transform();
+//! })
+//! ```
+
+use nalgebra_glm as glm;
+
+use crate::components::Aabb;
+use crate::renderer::buffers::vertex_array::VertexArray;
+use crate::renderer::buffers::vertex_buffer::VertexBuffer;
+use crate::renderer::buffers::vertex_buffer_layout::VertexBufferLayout;
+use crate::renderer::buffers::BufferUsage;
+use crate::renderer::shader::Shader;
+use crate::renderer::{Renderer, Topology};
+use crate::utils::color::Color;
+
+/// a single vertex of a debug line
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct DebugVertex {
+    position: glm::Vec3,
+    color: glm::Vec4,
+}
+
+/// the gpu side of the debug drawer, created the first time something is drawn
+struct DebugBuffers {
+    vertex_array: VertexArray,
+    vertex_buffer: VertexBuffer,
+    shader: Shader,
+}
+
+/// collects debug lines during the frame and draws them after the scene
+pub struct DebugDraw {
+    vertices: Vec<DebugVertex>,
+    /// the matrix stack, the first entry is the identity and is never popped
+    stack: Vec<glm::Mat4>,
+    buffers: Option<DebugBuffers>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugDraw {
+    /// creates an empty debug drawer
+    pub fn new() -> DebugDraw {
+        DebugDraw {
+            vertices: Vec::new(),
+            stack: vec![glm::Mat4::identity()],
+            buffers: None,
+        }
+    }
+
+    /// pushes a transform relative to the current transform. every draw until the matching [`DebugDraw::pop_transform`]
+    /// uses the accumulated transform.
+    ///
+    /// # Arguments
+    /// - `transform` - the transform to apply on top of the current one
+    pub fn push_transform(&mut self, transform: glm::Mat4) {
+        let current = self.current_transform();
+        self.stack.push(current * transform);
+    }
+
+    /// pops the last pushed transform. popping more than was pushed does nothing.
+    pub fn pop_transform(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    /// get the current accumulated transform
+    pub fn current_transform(&self) -> glm::Mat4 {
+        *self.stack.last().unwrap_or(&glm::Mat4::identity())
+    }
+
+    /// draws a line using the current transform
+    ///
+    /// # Arguments
+    /// - `start` - the start of the line
+    /// - `end` - the end of the line
+    /// - `color` - the color of the line
+    pub fn draw_line(&mut self, start: glm::Vec3, end: glm::Vec3, color: Color) {
+        let transform = self.current_transform();
+        let color: glm::Vec4 = color.into();
+        for point in [start, end] {
+            let position = transform * glm::vec4(point.x, point.y, point.z, 1.0);
+            self.vertices.push(DebugVertex {
+                position: position.xyz(),
+                color,
+            });
+        }
+    }
+
+    /// draws the x (red), y (green) and z (blue) axes using the current transform
+    ///
+    /// # Arguments
+    /// - `size` - the length of the axes
+    pub fn draw_axes(&mut self, size: f32) {
+        let origin = glm::Vec3::zeros();
+        self.draw_line(
+            origin,
+            glm::vec3(size, 0.0, 0.0),
+            Color::from_normalized(1.0, 0.0, 0.0, 1.0),
+        );
+        self.draw_line(
+            origin,
+            glm::vec3(0.0, size, 0.0),
+            Color::from_normalized(0.0, 1.0, 0.0, 1.0),
+        );
+        self.draw_line(
+            origin,
+            glm::vec3(0.0, 0.0, size),
+            Color::from_normalized(0.0, 0.0, 1.0, 1.0),
+        );
+    }
+
+    /// draws the edges of a box using the current transform
+    ///
+    /// # Arguments
+    /// - `min` - the minimum corner of the box
+    /// - `max` - the maximum corner of the box
+    /// - `color` - the color of the edges
+    pub fn draw_wire_box(&mut self, min: glm::Vec3, max: glm::Vec3, color: Color) {
+        let corner = |i: usize| {
+            glm::vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+
+        // every pair of corners that differ in exactly one axis is an edge
+        for a in 0..8 {
+            for axis in [1, 2, 4] {
+                if a & axis == 0 {
+                    self.draw_line(corner(a), corner(a | axis), color);
+                }
+            }
+        }
+    }
+
+    /// draws the edges of a bounding box using the current transform. empty boxes are skipped.
+    ///
+    /// # Arguments
+    /// - `aabb` - the bounding box, e.g. from [`Mesh::get_bounds`](crate::components::Mesh::get_bounds)
+    /// - `color` - the color of the edges
+    pub fn draw_aabb(&mut self, aabb: &Aabb, color: Color) {
+        if !aabb.is_empty() {
+            self.draw_wire_box(aabb.min, aabb.max, color);
+        }
+    }
+
+    /// draws the edges of the volume a camera sees using the current transform
+    ///
+    /// # Arguments
+    /// - `view_projection` - the view projection matrix of the camera
+    /// - `color` - the color of the edges
+    pub fn draw_frustum(&mut self, view_projection: &glm::Mat4, color: Color) {
+        let inverse = glm::inverse(view_projection);
+        let corner = |i: usize| {
+            let ndc = glm::vec4(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+                1.0,
+            );
+            let world = inverse * ndc;
+            world.xyz() / world.w
+        };
+
+        for a in 0..8 {
+            for axis in [1, 2, 4] {
+                if a & axis == 0 {
+                    self.draw_line(corner(a), corner(a | axis), color);
+                }
+            }
+        }
+    }
+
+    /// get the number of lines queued this frame
+    pub fn line_count(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// draws all the queued lines, then clears them and resets the transform stack
+    ///
+    /// # Arguments
+    /// - `view_projection` - the view projection matrix of the camera
+    pub fn render(&mut self, view_projection: glm::Mat4) {
+        self.draw(view_projection);
+        self.clear();
+    }
+
+    /// draws all the queued lines and keeps them, so they can be drawn from several cameras
+    pub(crate) fn draw(&mut self, view_projection: glm::Mat4) {
+        if !self.vertices.is_empty() {
+            let buffers = self.buffers.get_or_insert_with(|| {
+                let vertex_array = VertexArray::new();
+                let vertex_buffer =
+                    VertexBuffer::new_with_usage::<DebugVertex>(&[], BufferUsage::Stream);
+                let mut layout = VertexBufferLayout::new();
+                layout.push::<f32>(3);
+                layout.push::<f32>(4);
+                vertex_array.add_buffer(&vertex_buffer, &layout);
+
+                DebugBuffers {
+                    vertex_array,
+                    vertex_buffer,
+                    shader: Shader::from_slice(
+                        include_str!("../../res/shaders/debug/debug.vert"),
+                        include_str!("../../res/shaders/debug/debug.frag"),
+                        None,
+                    ),
+                }
+            });
+
+            buffers.vertex_buffer.set_data(&self.vertices);
+            buffers.shader.bind();
+            buffers.shader.set_uniform("u_VP", view_projection);
+            buffers.vertex_array.bind();
+            Renderer::draw_arrays(Topology::Lines, 0, self.vertices.len() as i32);
+            buffers.vertex_array.unbind();
+        }
+    }
+
+    /// drops all the queued lines and resets the transform stack without drawing
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.stack.truncate(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_stack_accumulates() {
+        let mut debug = DebugDraw::new();
+        let white = Color::from_normalized(1.0, 1.0, 1.0, 1.0);
+
+        debug.push_transform(glm::translation(&glm::vec3(1.0, 0.0, 0.0)));
+        debug.push_transform(glm::translation(&glm::vec3(0.0, 2.0, 0.0)));
+        debug.draw_line(glm::Vec3::zeros(), glm::vec3(0.0, 0.0, 1.0), white);
+        debug.pop_transform();
+        debug.draw_line(glm::Vec3::zeros(), glm::vec3(0.0, 0.0, 1.0), white);
+
+        assert_eq!(debug.vertices[0].position, glm::vec3(1.0, 2.0, 0.0));
+        assert_eq!(debug.vertices[1].position, glm::vec3(1.0, 2.0, 1.0));
+        assert_eq!(debug.vertices[2].position, glm::vec3(1.0, 0.0, 0.0));
+        assert_eq!(debug.line_count(), 2);
+    }
+
+    #[test]
+    fn test_draw_frustum_corners() {
+        let mut debug = DebugDraw::new();
+        let white = Color::from_normalized(1.0, 1.0, 1.0, 1.0);
+
+        debug.draw_aabb(&Aabb::empty(), white);
+        assert_eq!(debug.line_count(), 0);
+
+        // the identity projection sees the -1..1 cube
+        debug.draw_frustum(&glm::Mat4::identity(), white);
+        assert_eq!(debug.line_count(), 12);
+        assert_eq!(debug.vertices[0].position, glm::vec3(-1.0, -1.0, -1.0));
+        assert_eq!(debug.vertices[1].position, glm::vec3(1.0, -1.0, -1.0));
+    }
+
+    #[test]
+    fn test_pop_never_removes_base() {
+        let mut debug = DebugDraw::new();
+        debug.pop_transform();
+        debug.pop_transform();
+        assert_eq!(debug.current_transform(), glm::Mat4::identity());
+    }
+}
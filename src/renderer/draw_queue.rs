@@ -0,0 +1,133 @@
+//! orders the meshes of the main pass across every model in the scene.
+//!
+//! the meshes are drawn layer by layer from the lowest [render layer](crate::nodes::Model::set_render_layer) up.
+//! inside a layer, opaque meshes (including alpha masked ones since they write depth) are drawn front to back so the
+//! depth test can skip hidden fragments early. blended meshes are drawn after the opaque meshes of their layer from
+//! back to front so every transparent surface blends over what is behind it. the distances are squared distances to
+//! the camera.
+
+use std::cmp::Ordering;
+
+/// an item of the queue with what it is ordered by
+pub(crate) struct Queued<T> {
+    /// the item to draw
+    pub(crate) item: T,
+    /// the render layer of the item
+    pub(crate) layer: i32,
+    /// if the item is drawn with blending
+    pub(crate) blended: bool,
+    distance2: f32,
+}
+
+impl<T> Queued<T> {
+    /// check if the item is drawn after the skybox, the skybox comes after the opaque meshes of layer 0 so every
+    /// lower layer and the opaque meshes of layer 0 are behind it where they overlap
+    pub(crate) fn after_sky(&self) -> bool {
+        self.layer > 0 || (self.layer == 0 && self.blended)
+    }
+
+    /// the draw order of two items, see the [module docs](self)
+    fn order(&self, other: &Self) -> Ordering {
+        self.layer
+            .cmp(&other.layer)
+            .then(self.blended.cmp(&other.blended))
+            .then_with(|| {
+                if self.blended {
+                    other.distance2.total_cmp(&self.distance2)
+                } else {
+                    self.distance2.total_cmp(&other.distance2)
+                }
+            })
+    }
+}
+
+/// the items of one pass with their layer, their blending and their distance to the camera.
+pub(crate) struct DrawQueue<T> {
+    items: Vec<Queued<T>>,
+}
+
+impl<T> DrawQueue<T> {
+    /// creates an empty queue
+    pub(crate) fn new() -> Self {
+        DrawQueue { items: Vec::new() }
+    }
+
+    /// adds an item to the queue
+    ///
+    /// # Arguments
+    /// - `item` - the item to draw
+    /// - `layer` - the render layer of the item, lower layers are drawn first
+    /// - `blended` - if the item is drawn with blending (`AlphaMode::Blend`)
+    /// - `distance2` - the squared distance from the camera to the item
+    pub(crate) fn push(&mut self, item: T, layer: i32, blended: bool, distance2: f32) {
+        self.items.push(Queued {
+            item,
+            layer,
+            blended,
+            distance2,
+        });
+    }
+
+    /// takes the items out of the queue in draw order, layer by layer with the opaque items front to back then the
+    /// blended items back to front. items that tie keep the order they were pushed in
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = Queued<T>> + '_ {
+        self.items.sort_by(Queued::order);
+        self.items.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm as glm;
+
+    #[test]
+    fn test_draw_order() {
+        // two overlapping transparent quads in front of the camera with a wall behind them and a crate in front
+        let camera = glm::vec3(0.0, 0.0, 5.0);
+        let scene = [
+            ("near quad", glm::vec3(0.2, 0.0, 1.0), true),
+            ("wall", glm::vec3(0.0, 0.0, -4.0), false),
+            ("far quad", glm::vec3(0.0, 0.0, 0.0), true),
+            ("crate", glm::vec3(0.0, -1.0, 3.0), false),
+        ];
+
+        let mut queue = DrawQueue::new();
+        for (name, position, blended) in scene {
+            queue.push(name, 0, blended, glm::distance2(&camera, &position));
+        }
+
+        let order: Vec<_> = queue.drain().map(|queued| queued.item).collect();
+        assert_eq!(order, ["crate", "wall", "far quad", "near quad"]);
+        // the queue is empty and can be reused the next frame
+        assert_eq!(queue.drain().count(), 0);
+    }
+
+    #[test]
+    fn test_layer_order() {
+        let mut queue = DrawQueue::new();
+        queue.push("gizmo", 100, false, 1.0);
+        queue.push("glass", 0, true, 4.0);
+        queue.push("backdrop", -1, false, 50.0);
+        queue.push("smoke", -1, true, 2.0);
+        queue.push("wall", 0, false, 9.0);
+        queue.push("crate", 0, false, 9.0);
+
+        let order: Vec<_> = queue
+            .drain()
+            .map(|queued| (queued.item, queued.after_sky()))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("backdrop", false),
+                ("smoke", false),
+                // the tie keeps the push order
+                ("wall", false),
+                ("crate", false),
+                ("glass", true),
+                ("gizmo", true),
+            ]
+        );
+    }
+}
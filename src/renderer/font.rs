@@ -0,0 +1,380 @@
+//! TrueType and OpenType fonts rasterized into texture atlases for the [`Text`](crate::nodes::Text) node.
+//!
+//! a font is rasterized once per pixel size into a single texture holding the printable ASCII and Latin-1
+//! characters, other characters are drawn as `?`. the atlases are kept by the font and shared by every text using it,
+//! so cloning a font is cheap.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::renderer::font::Font;
+//!
+//! let font = Font::load("res/fonts/Roboto-Regular.ttf").unwrap();
+//! let size = font.measure("Score: 100", 32.0);
+//! ```
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+
+use ab_glyph::{Font as _, FontArc, GlyphId, PxScale, ScaleFont};
+use egui_gl_glfw::egui;
+use nalgebra_glm as glm;
+
+use super::texture::{FilterMode, Texture, TextureConfig, TextureType, WrapMode};
+
+/// the largest pixel size a font is rasterized at, bigger texts scale the atlas up
+const MAX_PIXEL_SIZE: u32 = 256;
+/// the width of the atlas textures, the height grows with the glyphs
+const ATLAS_WIDTH: usize = 512;
+/// the empty pixels around every glyph so filtering doesn't pick up the neighbours
+const PADDING: usize = 1;
+
+/// the reason a font could not be loaded
+#[derive(Debug)]
+pub enum FontError {
+    /// the file could not be read
+    Io(std::io::Error),
+    /// the data is not a TrueType or OpenType font
+    InvalidFont,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "failed to read font file: {}", error),
+            FontError::InvalidFont => write!(f, "the data is not a TrueType or OpenType font"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+impl From<ab_glyph::InvalidFont> for FontError {
+    fn from(_: ab_glyph::InvalidFont) -> Self {
+        FontError::InvalidFont
+    }
+}
+
+thread_local! {
+    /// the font used by texts without one, loaded the first time it is needed
+    static DEFAULT_FONT: OnceCell<Font> = const { OnceCell::new() };
+}
+
+/// a font and the atlases rasterized from it, see the [module docs](self).
+#[derive(Clone)]
+pub struct Font {
+    font: FontArc,
+    /// the atlases by pixel size
+    atlases: Rc<RefCell<HashMap<u32, Rc<GlyphAtlas>>>>,
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("glyphs", &self.font.glyph_count())
+            .finish()
+    }
+}
+
+impl Font {
+    /// loads a font file
+    ///
+    /// # Arguments
+    /// - `path` - the path of the TTF or OTF file
+    ///
+    /// # Returns
+    /// the font or the reason it could not be loaded
+    pub fn load(path: impl AsRef<Path>) -> Result<Font, FontError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// creates a font from the bytes of a font file, for example one embedded with `include_bytes!`
+    ///
+    /// # Arguments
+    /// - `data` - the contents of the TTF or OTF file
+    ///
+    /// # Returns
+    /// the font or the reason it could not be parsed
+    pub fn from_bytes(data: Vec<u8>) -> Result<Font, FontError> {
+        Ok(Font {
+            font: FontArc::try_from_vec(data)?,
+            atlases: Rc::new(RefCell::new(HashMap::new())),
+        })
+    }
+
+    /// get the font texts use when they are not given one, the proportional font of egui
+    pub fn default_font() -> Font {
+        DEFAULT_FONT.with(|font| {
+            font.get_or_init(|| {
+                let mut definitions = egui::FontDefinitions::default();
+                let data = definitions
+                    .font_data
+                    .remove("Ubuntu-Light")
+                    .expect("egui ships the Ubuntu-Light font");
+                Self::from_bytes(data.font.into_owned()).expect("the egui font is valid")
+            })
+            .clone()
+        })
+    }
+
+    /// get the width and height of a text, the height covers every line from the top of the highest letters to the
+    /// bottom of the lowest
+    ///
+    /// # Arguments
+    /// - `text` - the text, `\n` starts a new line
+    /// - `size` - the height of a line in the units the text is drawn in
+    pub fn measure(&self, text: &str, size: f32) -> glm::Vec2 {
+        let pixel_size = Self::pixel_size(size);
+        let layout = self.atlas(pixel_size).layout(text);
+        layout.size * (size / pixel_size as f32)
+    }
+
+    /// get the pixel size a text of a size is rasterized at
+    pub(crate) fn pixel_size(size: f32) -> u32 {
+        (size.round() as u32).clamp(1, MAX_PIXEL_SIZE)
+    }
+
+    /// get the atlas of a pixel size, rasterizing it the first time
+    pub(crate) fn atlas(&self, pixel_size: u32) -> Rc<GlyphAtlas> {
+        self.atlases
+            .borrow_mut()
+            .entry(pixel_size)
+            .or_insert_with(|| Rc::new(GlyphAtlas::new(self.font.clone(), pixel_size)))
+            .clone()
+    }
+}
+
+/// where a character is in the atlas and how it is placed on the line
+#[derive(Debug, Clone, Copy)]
+struct GlyphInfo {
+    id: GlyphId,
+    advance: f32,
+    /// the bottom left and top right of the glyph relative to the pen on the baseline with y up, `None` for
+    /// characters without pixels like the space
+    bounds: Option<(glm::Vec2, glm::Vec2)>,
+    /// the uv of the bottom left and the top right of the glyph
+    uv: (glm::Vec2, glm::Vec2),
+}
+
+/// a glyph placed by [`GlyphAtlas::layout`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GlyphQuad {
+    /// the bottom left corner, relative to the top left of the text with y up
+    pub(crate) min: glm::Vec2,
+    /// the top right corner
+    pub(crate) max: glm::Vec2,
+    /// the uv of the bottom left and the top right corner
+    pub(crate) uv: (glm::Vec2, glm::Vec2),
+}
+
+/// the glyphs of a text and the size of the block they cover
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextLayout {
+    pub(crate) quads: Vec<GlyphQuad>,
+    pub(crate) size: glm::Vec2,
+}
+
+/// the characters of a font rasterized at one pixel size
+pub(crate) struct GlyphAtlas {
+    font: FontArc,
+    scale: PxScale,
+    glyphs: HashMap<char, GlyphInfo>,
+    width: usize,
+    height: usize,
+    /// the coverage of every pixel, the first row is the bottom of the texture
+    pixels: Vec<u8>,
+    /// the texture, uploaded the first time the atlas is drawn
+    texture: OnceCell<Rc<Texture>>,
+}
+
+impl GlyphAtlas {
+    /// rasterizes the printable ASCII and Latin-1 characters
+    fn new(font: FontArc, pixel_size: u32) -> GlyphAtlas {
+        let scale = PxScale::from(pixel_size as f32);
+        let scaled = font.as_scaled(scale);
+
+        // packs the glyphs in rows from the top left, the rows are as high as their highest glyph
+        let mut glyphs = HashMap::new();
+        let mut outlines = Vec::new();
+        let (mut x, mut y, mut row_height) = (PADDING, PADDING, 0);
+        for c in (' '..='~').chain('\u{a0}'..='\u{ff}') {
+            let id = font.glyph_id(c);
+            let mut info = GlyphInfo {
+                id,
+                advance: scaled.h_advance(id),
+                bounds: None,
+                uv: (glm::Vec2::zeros(), glm::Vec2::zeros()),
+            };
+
+            if let Some(outline) = font.outline_glyph(id.with_scale(scale)) {
+                let bounds = outline.px_bounds();
+                let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+                if x + width + PADDING > ATLAS_WIDTH {
+                    x = PADDING;
+                    y += row_height + PADDING;
+                    row_height = 0;
+                }
+                info.bounds = Some((
+                    glm::vec2(bounds.min.x, -bounds.max.y),
+                    glm::vec2(bounds.max.x, -bounds.min.y),
+                ));
+                outlines.push((c, outline, x, y));
+                x += width + PADDING;
+                row_height = row_height.max(height);
+            }
+            glyphs.insert(c, info);
+        }
+        let height = (y + row_height + PADDING).next_power_of_two();
+
+        let mut pixels = vec![0; ATLAS_WIDTH * height];
+        for (c, outline, x, y) in outlines {
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                let index = (y + gy as usize) * ATLAS_WIDTH + x + gx as usize;
+                pixels[index] = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+            });
+
+            // the rows are stored top down so the top of the glyph has the lower v
+            let texel = glm::vec2(1.0 / ATLAS_WIDTH as f32, 1.0 / height as f32);
+            let top_left = glm::vec2(x as f32, y as f32).component_mul(&texel);
+            let size = glm::vec2(bounds.width(), bounds.height()).component_mul(&texel);
+            if let Some(info) = glyphs.get_mut(&c) {
+                info.uv = (
+                    glm::vec2(top_left.x, top_left.y + size.y),
+                    glm::vec2(top_left.x + size.x, top_left.y),
+                );
+            }
+        }
+
+        GlyphAtlas {
+            font,
+            scale,
+            glyphs,
+            width: ATLAS_WIDTH,
+            height,
+            pixels,
+            texture: OnceCell::new(),
+        }
+    }
+
+    /// get the glyph of a character or of `?` if the atlas doesn't have it
+    fn glyph(&self, c: char) -> Option<&GlyphInfo> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// places the glyphs of a text in pixels, the first line starts at the top left with y up
+    ///
+    /// # Arguments
+    /// - `text` - the text, `\n` starts a new line
+    pub(crate) fn layout(&self, text: &str) -> TextLayout {
+        let scaled = self.font.as_scaled(self.scale);
+        let line_height = scaled.height() + scaled.line_gap();
+
+        let mut quads = Vec::new();
+        let mut width: f32 = 0.0;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            let baseline = -scaled.ascent() - line_height * lines as f32;
+            let mut pen = 0.0;
+            let mut previous: Option<GlyphId> = None;
+            for c in line.chars() {
+                let Some(glyph) = self.glyph(c) else {
+                    continue;
+                };
+                if let Some(previous) = previous {
+                    pen += scaled.kern(previous, glyph.id);
+                }
+                if let Some((min, max)) = glyph.bounds {
+                    let origin = glm::vec2(pen, baseline);
+                    quads.push(GlyphQuad {
+                        min: origin + min,
+                        max: origin + max,
+                        uv: glyph.uv,
+                    });
+                }
+                pen += glyph.advance;
+                previous = Some(glyph.id);
+            }
+            width = width.max(pen);
+            lines += 1;
+        }
+
+        TextLayout {
+            quads,
+            size: glm::vec2(width, scaled.height() + line_height * (lines - 1) as f32),
+        }
+    }
+
+    /// get the texture of the atlas, uploading it the first time
+    pub(crate) fn texture(&self) -> Rc<Texture> {
+        self.texture
+            .get_or_init(|| {
+                // white with the coverage as alpha, so the tint of the text is its color
+                let pixels: Vec<u8> = self
+                    .pixels
+                    .iter()
+                    .flat_map(|&coverage| [255, coverage])
+                    .collect();
+                let config = TextureConfig {
+                    mipmaps: false,
+                    anisotropy: 1.0,
+                    wrap: WrapMode::ClampToEdge,
+                    filter: FilterMode::Linear,
+                };
+                Rc::new(Texture::load_from_gltf_with_config(
+                    &pixels,
+                    self.width as u32,
+                    self.height as u32,
+                    TextureType::Diffuse,
+                    gl::RG,
+                    config,
+                ))
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let font = Font::default_font();
+        assert!(matches!(
+            Font::from_bytes(vec![0; 16]),
+            Err(FontError::InvalidFont)
+        ));
+
+        let atlas = font.atlas(32);
+        assert!(atlas.pixels.iter().any(|&coverage| coverage > 0));
+
+        // spaces move the pen without a quad
+        let layout = atlas.layout("A A");
+        assert_eq!(layout.quads.len(), 2);
+        assert!(layout.quads[1].min.x > layout.quads[0].max.x);
+        assert!(layout.quads.iter().all(|quad| quad.max.y <= 0.0));
+
+        // a second line moves down and makes the block higher
+        let two_lines = atlas.layout("A\nA");
+        assert!(two_lines.quads[1].max.y < two_lines.quads[0].min.y);
+        assert!(two_lines.size.y > layout.size.y);
+        assert!(two_lines.size.x < layout.size.x);
+
+        // unknown characters are drawn as a question mark
+        assert_eq!(atlas.layout("\u{4e2d}").quads, atlas.layout("?").quads);
+
+        // measuring scales with the size
+        let small = font.measure("Hello", 16.0);
+        let large = font.measure("Hello", 32.0);
+        assert!((large.x / small.x - 2.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,101 @@
+//! OpenGL object names that are deleted when their last owner is dropped.
+//!
+//! the renderer types ([`Shader`](super::shader::Shader), [`Texture`](super::texture::Texture), the buffers, ...) can
+//! be cloned, and the clones draw with the same gpu object. the name is kept in a [`GlObject`] that is shared by all the
+//! clones and deletes the object once the last one is dropped, so removing a node or reloading a level frees its gpu
+//! memory.
+//!
+//! deleting needs the context that created the object. the context is marked alive on the thread that loaded it and
+//! marked gone when the [`GameContext`](crate::context::GameContext) is dropped, objects dropped after that (or on
+//! another thread) are left to the driver which frees them with the context.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+thread_local! {
+    /// if the OpenGL context of this thread can still delete objects
+    static CONTEXT_ALIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// marks the OpenGL context of the current thread as usable or gone
+///
+/// # Arguments
+/// - `alive` - true once the functions are loaded, false before the context is destroyed
+pub(crate) fn set_context_alive(alive: bool) {
+    CONTEXT_ALIVE.with(|cell| cell.set(alive));
+}
+
+/// check if objects can be deleted on the current thread
+pub fn is_context_alive() -> bool {
+    CONTEXT_ALIVE.with(|cell| cell.get())
+}
+
+/// the kind of an OpenGL object, each kind is deleted with its own call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GlKind {
+    Buffer,
+    VertexArray,
+    Texture,
+    Program,
+    Framebuffer,
+}
+
+/// the name of an object, deleted on drop
+#[derive(Debug)]
+struct GlName {
+    id: u32,
+    kind: GlKind,
+}
+
+impl Drop for GlName {
+    fn drop(&mut self) {
+        if self.id == 0 || !is_context_alive() {
+            return;
+        }
+
+        unsafe {
+            match self.kind {
+                GlKind::Buffer => gl::DeleteBuffers(1, &self.id),
+                GlKind::VertexArray => gl::DeleteVertexArrays(1, &self.id),
+                GlKind::Texture => gl::DeleteTextures(1, &self.id),
+                GlKind::Program => gl::DeleteProgram(self.id),
+                GlKind::Framebuffer => gl::DeleteFramebuffers(1, &self.id),
+            }
+        }
+    }
+}
+
+/// an OpenGL object shared by the clones of its owner, see the [module docs](self)
+#[derive(Clone, Debug)]
+pub(crate) struct GlObject(Rc<GlName>);
+
+impl GlObject {
+    /// takes ownership of an object name
+    ///
+    /// # Arguments
+    /// - `id` - the name returned by `gl::Gen*` or `gl::Create*`, 0 is never deleted
+    /// - `kind` - what the name belongs to
+    pub(crate) fn new(id: u32, kind: GlKind) -> GlObject {
+        GlObject(Rc::new(GlName { id, kind }))
+    }
+
+    /// get the OpenGL name of the object
+    pub(crate) fn id(&self) -> u32 {
+        self.0.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_without_context() {
+        // no context was loaded on the test thread so dropping must not call into OpenGL
+        assert!(!is_context_alive());
+        let object = GlObject::new(3, GlKind::Buffer);
+        let clone = object.clone();
+        drop(object);
+        assert_eq!(clone.id(), 3);
+    }
+}
@@ -5,12 +5,21 @@ use egui_gl_glfw as egui_backend;
 
 use crate::components::mesh::AlphaMode;
 use crate::components::mesh::Mesh;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 pub mod buffers;
+pub mod debug_draw;
 pub mod depth_cube_map;
+pub(crate) mod draw_queue;
+pub mod font;
+pub mod gl_object;
+pub mod post_process;
 pub mod shader;
 pub mod shadow_map;
+pub mod skybox;
+pub mod sprite_batch;
 pub mod texture;
+pub mod viewport;
 
 use colored::*;
 
@@ -69,16 +78,106 @@ pub extern "system" fn debug_message_callback(
         );
     }
 
-    // println!(
-    //     "\n{}\nSource: {} Type: {} ID: {} Severity: {}\n",
-    //     message.yellow(),
-    //     source_str.yellow(),
-    //     _type.to_string().yellow(),
-    //     id.to_string().yellow(),
-    //     severity_str.yellow(),
-    // );
+    log::debug!(
+        "{} (source: {}, type: {}, id: {}, severity: {})",
+        message,
+        source_str,
+        _type,
+        id,
+        severity_str
+    );
 }
 
+/// the primitive type used to assemble vertices when drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// every vertex is a point
+    Points,
+    /// every 2 vertices are a line
+    Lines,
+    /// every vertex connects to the previous one
+    LineStrip,
+    /// a line strip that connects the last vertex back to the first
+    LineLoop,
+    /// every 3 vertices are a triangle
+    Triangles,
+    /// every vertex makes a triangle with the previous 2
+    TriangleStrip,
+    /// every vertex makes a triangle with the previous one and the first one
+    TriangleFan,
+}
+
+impl Topology {
+    /// get the opengl primitive mode of the topology
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            Topology::Points => gl::POINTS,
+            Topology::Lines => gl::LINES,
+            Topology::LineStrip => gl::LINE_STRIP,
+            Topology::LineLoop => gl::LINE_LOOP,
+            Topology::Triangles => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan => gl::TRIANGLE_FAN,
+        }
+    }
+}
+
+/// how the faces of meshes are rasterized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PolygonMode {
+    /// faces are filled (the default)
+    #[default]
+    Fill,
+    /// only the edges of the faces are drawn (wireframe)
+    Line,
+    /// only the vertices of the faces are drawn
+    Point,
+}
+
+impl PolygonMode {
+    /// get the opengl polygon mode
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            PolygonMode::Fill => gl::FILL,
+            PolygonMode::Line => gl::LINE,
+            PolygonMode::Point => gl::POINT,
+        }
+    }
+}
+
+/// which faces of meshes are skipped, see [`Renderer::set_cull_mode`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CullMode {
+    /// faces turned away from the camera are skipped (the default)
+    #[default]
+    Back,
+    /// faces turned towards the camera are skipped
+    Front,
+    /// every face is drawn
+    Disabled,
+}
+
+impl CullMode {
+    fn to_bits(self) -> u8 {
+        match self {
+            CullMode::Back => 0,
+            CullMode::Front => 1,
+            CullMode::Disabled => 2,
+        }
+    }
+
+    fn from_bits(bits: u8) -> CullMode {
+        match bits {
+            1 => CullMode::Front,
+            2 => CullMode::Disabled,
+            _ => CullMode::Back,
+        }
+    }
+}
+
+/// the cull mode set with [`Renderer::set_cull_mode`], stored as [`CullMode::to_bits`]
+static CULL_MODE: AtomicU8 = AtomicU8::new(0);
+
 /// Renderer struct contains a bunch of static methods to initialize and render the scene
 pub struct Renderer {}
 
@@ -94,10 +193,11 @@ impl Renderer {
 
             gl::Enable(gl::MULTISAMPLE);
 
-            gl::Enable(gl::CULL_FACE);
-            gl::CullFace(gl::BACK);
             gl::FrontFace(gl::CCW);
+        }
+        Self::restore_cull_mode();
 
+        unsafe {
             //enable on draw call
             //gl::Enable(gl::BLEND);
 
@@ -105,12 +205,32 @@ impl Renderer {
         }
     }
 
+    /// get the most anti-aliasing samples the gpu supports (`GL_MAX_SAMPLES`)
+    pub fn max_samples() -> u32 {
+        let mut samples = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut samples);
+        }
+        samples.max(0) as u32
+    }
+
+    /// get the anti-aliasing samples of the window, 0 when it is not multisampled
+    pub fn window_samples() -> u32 {
+        let mut samples = 0;
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::GetIntegerv(gl::SAMPLES, &mut samples);
+        }
+        samples.max(0) as u32
+    }
+
     /// add the context to the window
     ///
     /// # Arguments
     /// - `window` - the window to add the context to
     pub fn context(window: &mut glfw::Window) {
         gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+        gl_object::set_context_alive(true);
     }
 
     /// clear the screen
@@ -120,6 +240,14 @@ impl Renderer {
         }
     }
 
+    /// clear only the depth buffer so the next draws are not hidden by the earlier ones, limited to the scissor
+    /// rect when it is enabled
+    pub fn clear_depth() {
+        unsafe {
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+        }
+    }
+
     /// set the clear color
     ///
     /// # Arguments
@@ -141,8 +269,81 @@ impl Renderer {
         }
     }
 
+    /// draw into a rectangle of the window, the clears are limited to it too
+    ///
+    /// # Arguments
+    /// - `rect` - the x, y (from the bottom), width and height in pixels or `None` to draw to the whole window
+    /// - `width` - the width of the window, used when `rect` is `None`
+    /// - `height` - the height of the window, used when `rect` is `None`
+    pub fn viewport_rect(rect: Option<[i32; 4]>, width: i32, height: i32) {
+        unsafe {
+            match rect {
+                Some([x, y, rect_width, rect_height]) => {
+                    gl::Viewport(x, y, rect_width, rect_height);
+                    gl::Scissor(x, y, rect_width, rect_height);
+                    gl::Enable(gl::SCISSOR_TEST);
+                }
+                None => {
+                    gl::Disable(gl::SCISSOR_TEST);
+                    gl::Viewport(0, 0, width, height);
+                }
+            }
+        }
+    }
+
+    /// set how the faces of the next draws are rasterized. applies to front and back faces.
+    ///
+    /// # Arguments
+    /// - `mode` - fill, wireframe or points
+    pub fn set_polygon_mode(mode: PolygonMode) {
+        unsafe {
+            gl::PolygonMode(gl::FRONT_AND_BACK, mode.to_gl());
+        }
+    }
+
+    /// set which faces are skipped for the rest of the frames. meshes with a double sided material are always drawn
+    /// from both sides, and the passes that change the culling (shadows, sprites, ui) restore this mode after.
+    ///
+    /// # Arguments
+    /// - `mode` - the faces to skip
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // look at the inside of the models
+    /// Renderer::set_cull_mode(CullMode::Front);
+    /// ```
+    pub fn set_cull_mode(mode: CullMode) {
+        CULL_MODE.store(mode.to_bits(), Ordering::Relaxed);
+        Self::restore_cull_mode();
+    }
+
+    /// get which faces are skipped
+    pub fn get_cull_mode() -> CullMode {
+        CullMode::from_bits(CULL_MODE.load(Ordering::Relaxed))
+    }
+
+    /// applies the mode set with [`Renderer::set_cull_mode`] again after a pass changed the culling
+    pub(crate) fn restore_cull_mode() {
+        unsafe {
+            match Self::get_cull_mode() {
+                CullMode::Back => {
+                    gl::Enable(gl::CULL_FACE);
+                    gl::CullFace(gl::BACK);
+                }
+                CullMode::Front => {
+                    gl::Enable(gl::CULL_FACE);
+                    gl::CullFace(gl::FRONT);
+                }
+                CullMode::Disabled => gl::Disable(gl::CULL_FACE),
+            }
+        }
+    }
+
     /// draw a mesh
     ///
+    /// double sided meshes are drawn without culling, the culling set with [`Renderer::set_cull_mode`] is restored
+    /// after.
+    ///
     /// # Arguments
     /// - `mesh` - the mesh to draw
     pub fn draw(mesh: &Mesh) {
@@ -168,19 +369,10 @@ impl Renderer {
             },
         }
 
-        unsafe {
-            gl::DrawElements(
-                gl::TRIANGLES,
-                mesh.indices.len() as i32,
-                gl::UNSIGNED_INT,
-                std::ptr::null(),
-            );
-        }
+        Self::submit(mesh, Topology::Triangles);
 
         if mesh.material_properties.double_sided {
-            unsafe {
-                gl::Enable(gl::CULL_FACE);
-            }
+            Self::restore_cull_mode();
         }
 
         // Reset the blending and depth mask
@@ -192,6 +384,118 @@ impl Renderer {
         }
     }
 
+    /// draw a mesh into a shadow depth map
+    ///
+    /// double sided meshes are drawn without culling so both faces cast shadows. single sided meshes cull their
+    /// front faces like the rest of the depth pass which keeps shadow acne off lit surfaces.
+    ///
+    /// # Arguments
+    /// - `mesh` - the mesh to draw
+    pub fn draw_shadow(mesh: &Mesh) {
+        unsafe {
+            if mesh.material_properties.double_sided {
+                gl::Disable(gl::CULL_FACE);
+            } else {
+                gl::Enable(gl::CULL_FACE);
+                gl::CullFace(gl::FRONT);
+            }
+
+            Self::submit(mesh, Topology::Triangles);
+
+            // restore the depth pass culling state
+            gl::Enable(gl::CULL_FACE);
+            gl::CullFace(gl::FRONT);
+        }
+    }
+
+    /// draw a mesh with the given topology instead of triangles. useful for lines and points (debug drawing, grids, ...)
+    ///
+    /// the mesh buffers are bound by this function. the shader and its uniforms must be bound before calling this.
+    /// meshes without indices are drawn in vertex order.
+    ///
+    /// # Arguments
+    /// - `mesh` - the mesh to draw
+    /// - `topology` - how the vertices (or indices) are assembled into primitives
+    pub fn draw_with_topology(mesh: &Mesh, topology: Topology) {
+        mesh.bind();
+        Self::submit(mesh, topology);
+    }
+
+    /// draw vertices from the currently bound vertex array without an index buffer
+    ///
+    /// # Arguments
+    /// - `topology` - how the vertices are assembled into primitives
+    /// - `first` - the first vertex to draw
+    /// - `count` - the number of vertices to draw
+    pub fn draw_arrays(topology: Topology, first: i32, count: i32) {
+        unsafe {
+            gl::DrawArrays(topology.to_gl(), first, count);
+        }
+    }
+
+    /// issue the draw call for the bound mesh buffers, using the indices if the mesh has any
+    fn submit(mesh: &Mesh, topology: Topology) {
+        if mesh.indices.is_empty() {
+            Self::draw_arrays(topology, 0, mesh.get_vertex_count() as i32);
+        } else {
+            unsafe {
+                gl::DrawElements(
+                    topology.to_gl(),
+                    mesh.indices.len() as i32,
+                    gl::UNSIGNED_INT,
+                    std::ptr::null(),
+                );
+            }
+        }
+    }
+
+    /// read the current contents of the window into an image.
+    ///
+    /// the back buffer of the default framebuffer is read so call this after the frame is drawn and before the buffers
+    /// are swapped. the window's multisampled buffer is resolved by the driver when it is read so the image is anti-aliased
+    /// like the frame on screen. the size of the image is the size of the current viewport.
+    ///
+    /// the alpha of the back buffer is whatever blending left there, so the image is made opaque like the window.
+    ///
+    /// # Returns
+    /// the frame as an opaque rgba image with the first row at the top
+    pub fn capture_frame() -> image::RgbaImage {
+        let mut viewport = [0; 4];
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        }
+        let width = viewport[2].max(0) as u32;
+        let height = viewport[3].max(0) as u32;
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::ReadBuffer(gl::BACK);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                viewport[0],
+                viewport[1],
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut std::ffi::c_void,
+            );
+        }
+
+        // opengl returns the bottom row first
+        let row = (width * 4) as usize;
+        let mut flipped = Vec::with_capacity(pixels.len());
+        for line in pixels.chunks_exact(row.max(1)).rev() {
+            flipped.extend_from_slice(line);
+        }
+        for pixel in flipped.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+
+        image::RgbaImage::from_raw(width, height, flipped).expect("frame buffer size mismatch")
+    }
+
     /// set the renderer to ui mode to render the ui
     pub fn ui_mode(enabled: bool) {
         if enabled {
@@ -200,8 +504,8 @@ impl Renderer {
                 gl::Disable(gl::DEPTH_TEST);
             }
         } else {
+            Self::restore_cull_mode();
             unsafe {
-                gl::Enable(gl::CULL_FACE);
                 gl::Enable(gl::DEPTH_TEST);
             }
         }
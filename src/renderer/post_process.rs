@@ -0,0 +1,588 @@
+//! post processing runs full screen effects over the rendered scene before it is shown.
+//!
+//! ## How it works
+//! when at least one effect is added the scene is rendered into an offscreen hdr frame buffer (RGBA16F) instead of the
+//! window. every effect implements [`PostProcess`], it gets the texture of the previous effect (or the scene for the first
+//! effect) and returns the texture holding its result. the output of the last effect is copied to the window. the UI is
+//! drawn after post processing so it is not tonemapped or blurred.
+//!
+//! ## Built in effects
+//! - [`Tonemap`] maps the hdr colors into the range of the window
+//! - [`Bloom`] makes bright parts of the scene glow
+//! - [`Gamma`] converts linear colors to the gamma space of the monitor
+//!
+//! bloom works on hdr colors so it goes before tonemapping, and gamma goes last.
+//!
+//! ## Shader passes
+//! a fragment shader can be added as an effect with [`PostProcessChain::add`] (`engine.add_post_process`). every shader pass
+//! gets these uniforms before its callback runs:
+//! - `u_screenTexture` (sampler2D) - the color of the previous effect (or the scene for the first effect)
+//! - `u_depthTexture` (sampler2D) - the depth of the scene
+//! - `u_texelSize` (vec2) - the size of one pixel in texture coordinates
+//!
+//! the vertex shader outputs `v_TexCoord`. use [`post_process_shader`] to pair it with your fragment shader.
+//!
+//! ## Scene depth
+//! the depth of the main pass can be kept in a texture without adding any effects with
+//! [`PostProcessChain::set_capture_depth`] (`engine.set_depth_texture(true)`). the scene is then rendered offscreen and
+//! copied to the window, and the depth texture is available through `context.depth_texture()` once the main pass is drawn.
+//!
+//! ## Anti-aliasing
+//! when the window is multisampled the scene is rendered into a multisampled frame buffer with the same sample count, which
+//! is resolved into the scene texture before the effects run, so the scene keeps its anti-aliasing.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::renderer::post_process::{post_process_shader, Bloom, Gamma, Tonemap};
+//!
+//! engine.add_post_effect(Box::new(Bloom::new(1.0, 0.6)));
+//! engine.add_post_effect(Box::new(Tonemap::aces(1.0)));
+//! engine.add_post_effect(Box::new(Gamma::new(2.2)));
+//!
+//! let vignette = post_process_shader(include_str!("vignette.frag"));
+//! engine.add_post_process(vignette, |shader, context| {
+//!     shader.set_uniform("u_time", context.frame.start_time.elapsed().as_secs_f32());
+//! });
+//! ```
+
+use crate::context::GameContext;
+use crate::renderer::buffers::frame_buffer::FrameBuffer;
+use crate::renderer::buffers::vertex_array::VertexArray;
+use crate::renderer::shader::Shader;
+use crate::renderer::{Renderer, Topology};
+
+use nalgebra_glm as glm;
+
+/// the callback that sets the custom uniforms of a pass every frame
+pub type PostProcessCallback = Box<dyn FnMut(&mut Shader, &GameContext)>;
+
+/// creates a post process shader from a fragment shader using the built in full screen vertex shader.
+///
+/// # Arguments
+/// - `fragment_source` - the glsl source of the fragment shader
+///
+/// # Returns
+/// the compiled shader
+pub fn post_process_shader(fragment_source: &str) -> Shader {
+    Shader::from_slice(
+        include_str!("../../res/shaders/postProcess/postProcess.vert"),
+        fragment_source,
+        None,
+    )
+}
+
+/// what an effect gets to know about the frame besides its input texture
+pub struct PostProcessFrame<'a> {
+    /// the game context
+    pub context: &'a GameContext,
+    /// the depth texture of the scene
+    pub depth: gl::types::GLuint,
+    /// the width of the scene in pixels
+    pub width: i32,
+    /// the height of the scene in pixels
+    pub height: i32,
+}
+
+impl PostProcessFrame<'_> {
+    /// get the size of one pixel of the scene in texture coordinates
+    pub fn texel_size(&self) -> glm::Vec2 {
+        glm::vec2(1.0 / self.width as f32, 1.0 / self.height as f32)
+    }
+}
+
+/// A full screen effect in the post process chain.
+///
+/// effects are ran in the order they are added. the full screen vertex array is bound while effects run so
+/// [`draw_fullscreen`] can be used to draw into the effect's own frame buffers.
+pub trait PostProcess {
+    /// runs the effect.
+    ///
+    /// # Arguments
+    /// - `input` - the color texture of the previous effect, must not be written to
+    /// - `frame` - the size and depth of the scene and the game context
+    ///
+    /// # Returns
+    /// the texture holding the result, usually the color texture of a frame buffer owned by the effect
+    fn apply(&mut self, input: gl::types::GLuint, frame: &PostProcessFrame) -> gl::types::GLuint;
+}
+
+/// (re)creates a frame buffer when it does not match the given size and returns it.
+///
+/// # Arguments
+/// - `target` - the frame buffer kept by the effect
+/// - `width` - the width the frame buffer should have
+/// - `height` - the height the frame buffer should have
+pub fn fit_target(target: &mut Option<FrameBuffer>, width: i32, height: i32) -> &FrameBuffer {
+    if target.as_ref().map(|fb| fb.get_size()) != Some((width, height)) {
+        *target = Some(FrameBuffer::new(width, height));
+    }
+    target.as_ref().unwrap()
+}
+
+/// binds the shader and its built in inputs and draws a full screen triangle into the bound frame buffer.
+///
+/// sets `u_screenTexture`, `u_depthTexture` and `u_texelSize` (from the scene size) before `uniforms` is called.
+///
+/// # Arguments
+/// - `shader` - the shader to draw with
+/// - `input` - the texture bound to `u_screenTexture`
+/// - `frame` - the frame of the effect
+/// - `uniforms` - sets any other uniforms of the shader
+pub fn draw_fullscreen<F>(
+    shader: &mut Shader,
+    input: gl::types::GLuint,
+    frame: &PostProcessFrame,
+    uniforms: F,
+) where
+    F: FnOnce(&mut Shader),
+{
+    bind_inputs(shader, input, frame.depth, frame.texel_size());
+    uniforms(shader);
+    Renderer::draw_arrays(Topology::Triangles, 0, 3);
+}
+
+/// a fragment shader ran as an effect
+struct ShaderPass {
+    shader: Shader,
+    callback: PostProcessCallback,
+    target: Option<FrameBuffer>,
+}
+
+impl PostProcess for ShaderPass {
+    fn apply(&mut self, input: gl::types::GLuint, frame: &PostProcessFrame) -> gl::types::GLuint {
+        let target = fit_target(&mut self.target, frame.width, frame.height);
+        target.bind();
+
+        let callback = &mut self.callback;
+        draw_fullscreen(&mut self.shader, input, frame, |shader| {
+            callback(shader, frame.context)
+        });
+
+        target.get_texture()
+    }
+}
+
+/// the curve used by [`Tonemap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TonemapOperator {
+    /// simple and soft, never fully reaches white
+    Reinhard,
+    /// filmic curve with more contrast and saturated highlights
+    Aces,
+}
+
+/// Maps hdr colors into the 0-1 range of the window.
+pub struct Tonemap {
+    /// the curve used to compress the colors
+    pub operator: TonemapOperator,
+    /// the scene color is multiplied by this before the curve (1.0 by default)
+    pub exposure: f32,
+    shader: Option<Shader>,
+    target: Option<FrameBuffer>,
+}
+
+impl Tonemap {
+    /// creates a tonemap effect
+    ///
+    /// # Arguments
+    /// - `operator` - the curve used to compress the colors
+    /// - `exposure` - the scene color is multiplied by this before the curve
+    pub fn new(operator: TonemapOperator, exposure: f32) -> Tonemap {
+        Tonemap {
+            operator,
+            exposure,
+            shader: None,
+            target: None,
+        }
+    }
+
+    /// creates a reinhard tonemap effect
+    pub fn reinhard(exposure: f32) -> Tonemap {
+        Tonemap::new(TonemapOperator::Reinhard, exposure)
+    }
+
+    /// creates an aces tonemap effect
+    pub fn aces(exposure: f32) -> Tonemap {
+        Tonemap::new(TonemapOperator::Aces, exposure)
+    }
+}
+
+impl Default for Tonemap {
+    fn default() -> Self {
+        Tonemap::aces(1.0)
+    }
+}
+
+impl PostProcess for Tonemap {
+    fn apply(&mut self, input: gl::types::GLuint, frame: &PostProcessFrame) -> gl::types::GLuint {
+        let target = fit_target(&mut self.target, frame.width, frame.height);
+        target.bind();
+
+        let shader = self.shader.get_or_insert_with(|| {
+            post_process_shader(include_str!("../../res/shaders/postProcess/tonemap.frag"))
+        });
+        let (operator, exposure) = (self.operator, self.exposure);
+        draw_fullscreen(shader, input, frame, |shader| {
+            shader.set_uniform("u_exposure", exposure);
+            shader.set_uniform("u_operator", (operator == TonemapOperator::Aces) as i32);
+        });
+
+        target.get_texture()
+    }
+}
+
+/// Converts linear colors to the gamma space of the monitor.
+pub struct Gamma {
+    /// the gamma of the monitor (2.2 by default)
+    pub gamma: f32,
+    shader: Option<Shader>,
+    target: Option<FrameBuffer>,
+}
+
+impl Gamma {
+    /// creates a gamma correction effect
+    ///
+    /// # Arguments
+    /// - `gamma` - the gamma of the monitor, usually 2.2
+    pub fn new(gamma: f32) -> Gamma {
+        Gamma {
+            gamma,
+            shader: None,
+            target: None,
+        }
+    }
+}
+
+impl Default for Gamma {
+    fn default() -> Self {
+        Gamma::new(2.2)
+    }
+}
+
+impl PostProcess for Gamma {
+    fn apply(&mut self, input: gl::types::GLuint, frame: &PostProcessFrame) -> gl::types::GLuint {
+        let target = fit_target(&mut self.target, frame.width, frame.height);
+        target.bind();
+
+        let shader = self.shader.get_or_insert_with(|| {
+            post_process_shader(include_str!("../../res/shaders/postProcess/gamma.frag"))
+        });
+        let gamma = self.gamma.max(0.0001);
+        draw_fullscreen(shader, input, frame, |shader| {
+            shader.set_uniform("u_gamma", gamma);
+        });
+
+        target.get_texture()
+    }
+}
+
+/// Makes the bright parts of the scene glow.
+///
+/// the colors above the threshold are blurred at half resolution and added back on top of the scene.
+pub struct Bloom {
+    /// how bright a color has to be to glow, in hdr units (1.0 by default)
+    pub threshold: f32,
+    /// how strong the glow is added to the scene (0.6 by default)
+    pub intensity: f32,
+    /// how many times the highlights are blurred, more is wider and slower (4 by default)
+    pub blur_passes: u32,
+    bright_shader: Option<Shader>,
+    blur_shader: Option<Shader>,
+    composite_shader: Option<Shader>,
+    /// the half resolution frame buffers the highlights are blurred between
+    blur_targets: [Option<FrameBuffer>; 2],
+    target: Option<FrameBuffer>,
+}
+
+impl Bloom {
+    /// creates a bloom effect
+    ///
+    /// # Arguments
+    /// - `threshold` - how bright a color has to be to glow
+    /// - `intensity` - how strong the glow is added to the scene
+    pub fn new(threshold: f32, intensity: f32) -> Bloom {
+        Bloom {
+            threshold,
+            intensity,
+            blur_passes: 4,
+            bright_shader: None,
+            blur_shader: None,
+            composite_shader: None,
+            blur_targets: [None, None],
+            target: None,
+        }
+    }
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom::new(1.0, 0.6)
+    }
+}
+
+impl PostProcess for Bloom {
+    fn apply(&mut self, input: gl::types::GLuint, frame: &PostProcessFrame) -> gl::types::GLuint {
+        let half_width = (frame.width / 2).max(1);
+        let half_height = (frame.height / 2).max(1);
+        let half_texel = glm::vec2(1.0 / half_width as f32, 1.0 / half_height as f32);
+
+        let [first, second] = &mut self.blur_targets;
+        let first = fit_target(first, half_width, half_height);
+        let second = fit_target(second, half_width, half_height);
+
+        // keep the highlights
+        first.bind();
+        let bright = self.bright_shader.get_or_insert_with(|| {
+            post_process_shader(include_str!(
+                "../../res/shaders/postProcess/bloomBright.frag"
+            ))
+        });
+        let threshold = self.threshold;
+        draw_fullscreen(bright, input, frame, |shader| {
+            shader.set_uniform("u_threshold", threshold);
+        });
+
+        // blur them horizontally then vertically
+        let blur = self.blur_shader.get_or_insert_with(|| {
+            post_process_shader(include_str!("../../res/shaders/postProcess/blur.frag"))
+        });
+        for _ in 0..self.blur_passes {
+            second.bind();
+            draw_fullscreen(blur, first.get_texture(), frame, |shader| {
+                shader.set_uniform("u_texelSize", half_texel);
+                shader.set_uniform("u_direction", glm::vec2(1.0, 0.0));
+            });
+            first.bind();
+            draw_fullscreen(blur, second.get_texture(), frame, |shader| {
+                shader.set_uniform("u_texelSize", half_texel);
+                shader.set_uniform("u_direction", glm::vec2(0.0, 1.0));
+            });
+        }
+        let bloom = first.get_texture();
+
+        // add them back on top of the scene
+        let target = fit_target(&mut self.target, frame.width, frame.height);
+        target.bind();
+        let composite = self.composite_shader.get_or_insert_with(|| {
+            post_process_shader(include_str!(
+                "../../res/shaders/postProcess/bloomComposite.frag"
+            ))
+        });
+        let intensity = self.intensity;
+        draw_fullscreen(composite, input, frame, |shader| {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE2);
+                gl::BindTexture(gl::TEXTURE_2D, bloom);
+            }
+            shader.set_uniform("u_bloomTexture", 2);
+            shader.set_uniform("u_intensity", intensity);
+        });
+
+        target.get_texture()
+    }
+}
+
+/// The chain of post process effects ran after the scene is rendered
+#[derive(Default)]
+pub struct PostProcessChain {
+    effects: Vec<Box<dyn PostProcess>>,
+    /// the frame buffer the scene is resolved into and the effects read from
+    scene: Option<FrameBuffer>,
+    /// the frame buffer the scene is rendered into when anti-aliasing is on
+    multisampled: Option<FrameBuffer>,
+    /// the anti-aliasing samples of the scene, 0 or 1 renders straight into `scene`
+    samples: u32,
+    /// empty vertex array to draw the full screen triangle with
+    quad: Option<VertexArray>,
+    /// render offscreen even without effects so the scene depth can be sampled
+    capture_depth: bool,
+    /// copies the output of the last effect to the window
+    passthrough: Option<Shader>,
+}
+
+impl PostProcessChain {
+    /// creates an empty post process chain
+    pub fn new() -> PostProcessChain {
+        PostProcessChain::default()
+    }
+
+    /// appends a shader pass to the end of the chain
+    ///
+    /// # Arguments
+    /// - `shader` - the shader of the pass, see [`post_process_shader`]
+    /// - `callback` - sets the custom uniforms of the pass every frame
+    pub fn add<F>(&mut self, shader: Shader, callback: F)
+    where
+        F: FnMut(&mut Shader, &GameContext) + 'static,
+    {
+        self.add_effect(Box::new(ShaderPass {
+            shader,
+            callback: Box::new(callback),
+            target: None,
+        }));
+    }
+
+    /// appends an effect to the end of the chain
+    ///
+    /// # Arguments
+    /// - `effect` - the effect, see [`Tonemap`], [`Bloom`] and [`Gamma`]
+    pub fn add_effect(&mut self, effect: Box<dyn PostProcess>) {
+        self.effects.push(effect);
+    }
+
+    /// check if there are no effects
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// get the number of effects
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// render the scene into a depth texture that can be sampled after the main pass, even when there are no effects.
+    ///
+    /// # Arguments
+    /// - `capture` - if the scene depth should be kept in a texture
+    pub fn set_capture_depth(&mut self, capture: bool) {
+        self.capture_depth = capture;
+    }
+
+    /// set the anti-aliasing samples of the offscreen scene, usually the samples of the window.
+    ///
+    /// # Arguments
+    /// - `samples` - the samples per pixel, 0 disables anti-aliasing
+    pub fn set_samples(&mut self, samples: u32) {
+        self.samples = samples.min(Renderer::max_samples());
+        self.multisampled = None;
+    }
+
+    /// get the anti-aliasing samples of the offscreen scene
+    pub fn get_samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// check if the scene is rendered offscreen (there are effects or the depth is captured)
+    pub fn is_active(&self) -> bool {
+        !self.effects.is_empty() || self.capture_depth
+    }
+
+    /// get the depth texture of the last rendered scene
+    ///
+    /// # Returns
+    /// the texture or `None` if the scene is not rendered offscreen
+    pub fn depth_texture(&self) -> Option<gl::types::GLuint> {
+        if !self.is_active() {
+            return None;
+        }
+        self.scene
+            .as_ref()
+            .and_then(|scene| scene.get_depth_texture())
+    }
+
+    /// redirects rendering into the offscreen scene frame buffer. does nothing when the chain is not active.
+    ///
+    /// # Arguments
+    /// - `width` - the width of the window frame buffer
+    /// - `height` - the height of the window frame buffer
+    pub fn begin(&mut self, width: i32, height: i32) {
+        if !self.is_active() || width <= 0 || height <= 0 {
+            return;
+        }
+
+        // (re)create the frame buffers when the window size changes
+        if self.scene.as_ref().map(|fb| fb.get_size()) != Some((width, height)) {
+            self.scene = Some(FrameBuffer::new_with_depth_texture(width, height));
+            self.multisampled = None;
+        }
+        if self.samples > 1 && self.multisampled.is_none() {
+            self.multisampled = Some(FrameBuffer::new_multisampled(width, height, self.samples));
+        }
+
+        if let Some(target) = self.multisampled.as_ref().or(self.scene.as_ref()) {
+            target.bind();
+            Renderer::clear();
+        }
+    }
+
+    /// binds the offscreen scene frame buffer again without clearing it, for passes that render elsewhere in between
+    pub(crate) fn rebind(&self) {
+        if !self.is_active() {
+            return;
+        }
+        if let Some(target) = self.multisampled.as_ref().or(self.scene.as_ref()) {
+            target.bind();
+        }
+    }
+
+    /// runs all the effects and draws the result to the window. does nothing when the chain is not active.
+    ///
+    /// # Arguments
+    /// - `context` - the game context given to the effects
+    pub fn end(&mut self, context: &GameContext) {
+        if !self.is_active() {
+            return;
+        }
+        let Some(scene) = &self.scene else {
+            return;
+        };
+        if let Some(multisampled) = &self.multisampled {
+            multisampled.resolve_into(scene);
+        }
+
+        let (width, height) = scene.get_size();
+        let frame = PostProcessFrame {
+            context,
+            depth: scene.get_depth_texture().unwrap_or(0),
+            width,
+            height,
+        };
+        let quad = self.quad.get_or_insert_with(VertexArray::new);
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::BLEND);
+        }
+        quad.bind();
+
+        let mut input = scene.get_texture();
+        for effect in self.effects.iter_mut() {
+            input = effect.apply(input, &frame);
+        }
+
+        // copy the result to the window
+        let shader = self.passthrough.get_or_insert_with(|| {
+            post_process_shader(include_str!(
+                "../../res/shaders/postProcess/postProcess.frag"
+            ))
+        });
+        scene.unbind();
+        Renderer::viewport(width, height);
+        draw_fullscreen(shader, input, &frame, |_| {});
+
+        quad.unbind();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+    }
+}
+
+/// binds the shader and the built in inputs of a pass
+fn bind_inputs(
+    shader: &mut Shader,
+    color: gl::types::GLuint,
+    depth: gl::types::GLuint,
+    texel_size: glm::Vec2,
+) {
+    shader.bind();
+    unsafe {
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, color);
+        gl::ActiveTexture(gl::TEXTURE1);
+        gl::BindTexture(gl::TEXTURE_2D, depth);
+    }
+    shader.set_uniform("u_screenTexture", 0);
+    shader.set_uniform("u_depthTexture", 1);
+    shader.set_uniform("u_texelSize", texel_size);
+}
@@ -1,6 +1,11 @@
 //! the shader module contains the Shader struct, which is used to compile and manage shaders in the OpenGL pipeline
-use colored::*;
+use crate::components::animation;
+use crate::nodes::{directional_light, point_light};
+use crate::renderer::gl_object::{GlKind, GlObject};
 use nalgebra_glm as glm; // Importing the nalgebra_glm crate for mathematical operations
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub enum Uniforms {
     ModelMatrix,
@@ -32,11 +37,186 @@ impl Uniforms {
     }
 }
 
+/// a stage of a shader program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    /// the vertex shader
+    Vertex,
+    /// the fragment shader
+    Fragment,
+    /// the geometry shader
+    Geometry,
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+            ShaderStage::Geometry => write!(f, "geometry"),
+        }
+    }
+}
+
+/// Errors returned when creating a shader.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShaderError {
+    /// a source file could not be read.
+    Read {
+        /// the stage the file is for.
+        stage: ShaderStage,
+        /// the path of the file.
+        path: String,
+        /// the reason the file could not be read.
+        message: String,
+    },
+    /// a stage failed to compile.
+    Compile {
+        /// the stage that failed.
+        stage: ShaderStage,
+        /// the info log of the compiler.
+        log: String,
+    },
+    /// the stages compiled but could not be linked into a program.
+    Link {
+        /// the info log of the linker.
+        log: String,
+    },
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderError::Read {
+                stage,
+                path,
+                message,
+            } => write!(
+                f,
+                "failed to read {} shader \"{}\": {}",
+                stage, path, message
+            ),
+            ShaderError::Compile { stage, log } => {
+                write!(f, "failed to compile {} shader:\n{}", stage, log)
+            }
+            ShaderError::Link { log } => write!(f, "failed to link shader:\n{}", log),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// reads the source of a stage
+fn read_source(stage: ShaderStage, path: &Path) -> Result<String, ShaderError> {
+    std::fs::read_to_string(path).map_err(|error| ShaderError::Read {
+        stage,
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })
+}
+
+/// get the limits the engine shares with the shaders as `#define`s.
+///
+/// the lines are inserted after the `#version` line of every stage that is compiled, so custom shaders can size
+/// their arrays with them too:
+/// - `MAX_POINT_LIGHTS` - [`MAX_POINT_LIGHTS`](crate::nodes::point_light::MAX_POINT_LIGHTS)
+/// - `MAX_POINT_SHADOW_SAMPLES` - [`MAX_SHADOW_SAMPLES`](crate::nodes::point_light::MAX_SHADOW_SAMPLES)
+/// - `MAX_DIRECT_LIGHTS` - [`MAX_DIRECT_LIGHTS`](crate::nodes::directional_light::MAX_DIRECT_LIGHTS)
+/// - `MAX_JOINTS` - [`MAX_JOINTS`](crate::components::animation::MAX_JOINTS)
+/// - `SHADOW_CASCADES` - [`CASCADE_COUNT`](crate::nodes::directional_light::CASCADE_COUNT)
+/// - `SHADOW_CASCADE_GRID` - [`CASCADE_GRID`](crate::nodes::directional_light::CASCADE_GRID)
+///
+/// sampler arrays can only be indexed with constants, so `default.frag` unrolls the shadow map lookups of the lights
+/// into a `switch` with one `case` per light in `pointShadowDepth` and `directShadowDepth`. raising
+/// `MAX_POINT_LIGHTS` or `MAX_DIRECT_LIGHTS` past the cases there needs more of them, the `#error` below each
+/// function stops the compile until then. the texture units of the shadow maps are checked against the 16 every
+/// driver has when the engine is compiled.
+pub fn shader_defines() -> String {
+    format!(
+        "#define MAX_POINT_LIGHTS {}\n#define MAX_POINT_SHADOW_SAMPLES {}\n#define MAX_DIRECT_LIGHTS {}\n\
+         #define MAX_JOINTS {}\n#define SHADOW_CASCADES {}\n#define SHADOW_CASCADE_GRID {}\n",
+        point_light::MAX_POINT_LIGHTS,
+        point_light::MAX_SHADOW_SAMPLES,
+        directional_light::MAX_DIRECT_LIGHTS,
+        animation::MAX_JOINTS,
+        directional_light::CASCADE_COUNT,
+        directional_light::CASCADE_GRID
+    )
+}
+
+/// inserts the [`shader_defines`] after the `#version` line of a source, or at the start when there is none. a
+/// `#line` directive keeps the line numbers of compile errors pointing at the file.
+fn insert_defines(source: &str) -> String {
+    let version_end = source
+        .lines()
+        .position(|line| line.trim_start().starts_with("#version"))
+        .map_or(0, |line| line + 1);
+
+    let mut lines = source.lines();
+    let mut result = String::with_capacity(source.len() + 128);
+    for line in lines.by_ref().take(version_end) {
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.push_str(&shader_defines());
+    // glsl 330 numbers the line after `#line n` as n + 1
+    result.push_str(&format!("#line {}\n", version_end));
+    for line in lines {
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
 /// The Shader struct is used to compile and manage shaders in the OpenGL pipeline
 #[derive(Clone, Debug)]
 pub struct Shader {
-    m_renderer_id: u32,
+    m_renderer_id: GlObject,
     m_uniform_location_cache: std::collections::HashMap<String, i32>,
+    m_uniform_block_cache: std::collections::HashMap<String, u32>,
+    /// the binding points requested with [`Shader::bind_uniform_block`], applied again after a reload
+    m_uniform_block_bindings: std::collections::HashMap<String, u32>,
+    /// the files the shader was read from (None for shaders made from source code)
+    sources: Option<ShaderSources>,
+    /// the newest modification time of the sources when watched, see [`Shader::watch`]
+    watched: Option<SystemTime>,
+}
+
+/// the source files of a shader
+#[derive(Clone, Debug)]
+struct ShaderSources {
+    vertex: PathBuf,
+    fragment: PathBuf,
+    geometry: Option<PathBuf>,
+}
+
+impl ShaderSources {
+    /// the newest modification time of the files, None if one can't be read
+    fn modified(&self) -> Option<SystemTime> {
+        let mut newest = SystemTime::UNIX_EPOCH;
+        for path in [
+            Some(&self.vertex),
+            Some(&self.fragment),
+            self.geometry.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            newest = newest.max(std::fs::metadata(path).and_then(|m| m.modified()).ok()?);
+        }
+        Some(newest)
+    }
+
+    /// reads the sources and compiles them into a program
+    fn compile(&self) -> Result<u32, ShaderError> {
+        let vertex = read_source(ShaderStage::Vertex, &self.vertex)?;
+        let fragment = read_source(ShaderStage::Fragment, &self.fragment)?;
+        let geometry = match &self.geometry {
+            Some(path) => Some(read_source(ShaderStage::Geometry, path)?),
+            None => None,
+        };
+        Shader::create_shader(&vertex, &fragment, geometry.as_deref())
+    }
 }
 
 impl Default for Shader {
@@ -50,35 +230,51 @@ impl Default for Shader {
 }
 
 impl Shader {
-    /// Creates a new shader object, optionally with a geometry shader
+    /// Creates a new shader object from files, optionally with a geometry shader
     ///
     /// # Arguments
     /// - `vertex_path` - The path to the vertex shader file
     /// - `fragment_path` - The path to the fragment shader file
     /// - `geometry_path` - The path to the geometry shader file (optional)
-    pub fn new(vertex_path: &str, fragment_path: &str, geometry_path: Option<&str>) -> Shader {
-        //println!("Compiling shader {:?}... ", vertex_path);
-        let vertex_shader =
-            std::fs::read_to_string(vertex_path).expect("Failed to read vertex shader file");
-        //println!("Compiling shader {:?}... ", fragment_path);
-        let fragment_shader =
-            std::fs::read_to_string(fragment_path).expect("Failed to read fragment shader file");
-
-        let geometry_shader = if let Some(path) = geometry_path {
-            //println!("Compiling shader {:?}... ", path);
-            Some(std::fs::read_to_string(path).expect("Failed to read geometry shader file"))
-        } else {
-            None
+    ///
+    /// # Returns
+    /// the shader or the [`ShaderError`] with the stage and log of the failure
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// match Shader::new("res/shaders/water.vert", "res/shaders/water.frag", None) {
+    ///     Ok(shader) => engine.context.nodes.add_shader("water", shader),
+    ///     Err(error) => eprintln!("{}", error),
+    /// }
+    /// ```
+    pub fn new(
+        vertex_path: &str,
+        fragment_path: &str,
+        geometry_path: Option<&str>,
+    ) -> Result<Shader, ShaderError> {
+        let sources = ShaderSources {
+            vertex: vertex_path.into(),
+            fragment: fragment_path.into(),
+            geometry: geometry_path.map(PathBuf::from),
         };
 
-        Shader {
-            m_renderer_id: Self::create_shader(
-                &vertex_shader,
-                &fragment_shader,
-                geometry_shader.as_deref(),
-            ),
+        Ok(Shader {
+            m_renderer_id: GlObject::new(sources.compile()?, GlKind::Program),
             m_uniform_location_cache: std::collections::HashMap::new(),
-        }
+            m_uniform_block_cache: std::collections::HashMap::new(),
+            m_uniform_block_bindings: std::collections::HashMap::new(),
+            sources: Some(sources),
+            watched: None,
+        })
+    }
+
+    /// Creates a new shader object from files like [`Shader::new`] but panics if it fails.
+    ///
+    /// # Panics
+    /// if a file can't be read or the shader doesn't compile, with the error in the message
+    pub fn load(vertex_path: &str, fragment_path: &str, geometry_path: Option<&str>) -> Shader {
+        Self::new(vertex_path, fragment_path, geometry_path)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     /// Creates a new shader object from literal shader code, optionally with a geometry shader
@@ -87,10 +283,149 @@ impl Shader {
     /// - `vertex` - The source code for the vertex shader
     /// - `fragment` - The source code for the fragment shader
     /// - `geometry` - The source code for the geometry shader (optional)
+    ///
+    /// # Returns
+    /// the shader or the [`ShaderError`] with the stage and log of the failure
+    pub fn try_from_slice(
+        vertex: &str,
+        fragment: &str,
+        geometry: Option<&str>,
+    ) -> Result<Shader, ShaderError> {
+        Ok(Shader {
+            m_renderer_id: GlObject::new(
+                Self::create_shader(vertex, fragment, geometry)?,
+                GlKind::Program,
+            ),
+            m_uniform_location_cache: std::collections::HashMap::new(),
+            m_uniform_block_cache: std::collections::HashMap::new(),
+            m_uniform_block_bindings: std::collections::HashMap::new(),
+            sources: None,
+            watched: None,
+        })
+    }
+
+    /// Creates a new shader object from literal shader code like [`Shader::try_from_slice`] but panics if it fails.
+    ///
+    /// # Arguments
+    /// - `vertex` - The source code for the vertex shader
+    /// - `fragment` - The source code for the fragment shader
+    /// - `geometry` - The source code for the geometry shader (optional)
+    ///
+    /// # Panics
+    /// if the shader doesn't compile, with the error in the message
     pub fn from_slice(vertex: &str, fragment: &str, geometry: Option<&str>) -> Shader {
+        Self::try_from_slice(vertex, fragment, geometry).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// a shader without a program, so the tests can build the types that own one without an OpenGL context
+    #[cfg(test)]
+    pub(crate) fn unloaded() -> Shader {
         Shader {
-            m_renderer_id: Self::create_shader(vertex, fragment, geometry),
+            m_renderer_id: GlObject::new(0, GlKind::Program),
             m_uniform_location_cache: std::collections::HashMap::new(),
+            m_uniform_block_cache: std::collections::HashMap::new(),
+            m_uniform_block_bindings: std::collections::HashMap::new(),
+            sources: None,
+            watched: None,
+        }
+    }
+
+    /// watch the source files of the shader so [`reload_if_changed`](Shader::reload_if_changed) can recompile it.
+    ///
+    /// only works for shaders made with [`Shader::new`], use [`watch_files`](Shader::watch_files) for the others.
+    /// the engine reloads the watched shaders in the scene every frame when
+    /// [`set_shader_hot_reload`](crate::Engine::set_shader_hot_reload) is enabled.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut shader = Shader::load("res/shaders/water.vert", "res/shaders/water.frag", None);
+    /// shader.watch();
+    /// engine.context.nodes.add_shader("water", shader);
+    /// engine.set_shader_hot_reload(true);
+    /// ```
+    pub fn watch(&mut self) -> &mut Self {
+        match &self.sources {
+            Some(sources) => self.watched = sources.modified().or(Some(SystemTime::UNIX_EPOCH)),
+            None => log::warn!("shader was not loaded from files, use watch_files to watch it"),
+        }
+        self
+    }
+
+    /// watch files as the source of the shader, for shaders made from source code (like the default shader).
+    ///
+    /// the files are compiled the first time [`reload_if_changed`](Shader::reload_if_changed) is called.
+    ///
+    /// # Arguments
+    /// - `vertex_path` - The path to the vertex shader file
+    /// - `fragment_path` - The path to the fragment shader file
+    /// - `geometry_path` - The path to the geometry shader file (optional)
+    pub fn watch_files(
+        &mut self,
+        vertex_path: &str,
+        fragment_path: &str,
+        geometry_path: Option<&str>,
+    ) -> &mut Self {
+        self.sources = Some(ShaderSources {
+            vertex: vertex_path.into(),
+            fragment: fragment_path.into(),
+            geometry: geometry_path.map(PathBuf::from),
+        });
+        self.watched = Some(SystemTime::UNIX_EPOCH);
+        self
+    }
+
+    /// stop watching the source files
+    pub fn unwatch(&mut self) -> &mut Self {
+        self.watched = None;
+        self
+    }
+
+    /// check if the source files are watched
+    pub fn is_watched(&self) -> bool {
+        self.watched.is_some()
+    }
+
+    /// recompiles the shader if one of its watched files changed since the last check.
+    ///
+    /// if the new source fails to compile the error is printed and the old program is kept so the scene keeps rendering.
+    ///
+    /// # Returns
+    /// true if the shader was recompiled
+    pub fn reload_if_changed(&mut self) -> bool {
+        let (Some(sources), Some(last_modified)) = (&self.sources, self.watched) else {
+            return false;
+        };
+        // the file can be missing for a moment while an editor saves it, try again next frame
+        let Some(modified) = sources.modified() else {
+            return false;
+        };
+        if modified <= last_modified {
+            return false;
+        }
+        self.watched = Some(modified);
+
+        match sources.compile() {
+            Ok(program) => {
+                // the old program is deleted once the clones of the shader let go of it
+                self.m_renderer_id = GlObject::new(program, GlKind::Program);
+                self.m_uniform_location_cache.clear();
+                self.m_uniform_block_cache.clear();
+                log::info!("reloaded shader {}", sources.fragment.display());
+                // the bindings are stored in the program so the new one starts without them
+                let bindings = std::mem::take(&mut self.m_uniform_block_bindings);
+                for (name, binding) in bindings {
+                    self.bind_uniform_block(&name, binding);
+                }
+                true
+            }
+            Err(error) => {
+                log::error!(
+                    "failed to reload shader {}, keeping the previous version:\n{}",
+                    sources.fragment.display(),
+                    error
+                );
+                false
+            }
         }
     }
 
@@ -100,43 +435,96 @@ impl Shader {
     /// - `vertex_shader` - The source code for the vertex shader
     /// - `fragment_shader` - The source code for the fragment shader
     /// - `geometry_shader` - The source code for the geometry shader (optional)
+    ///
+    /// # Returns
+    /// the linked program or the error with the compile or link log
     fn create_shader(
         vertex_shader: &str,
         fragment_shader: &str,
         geometry_shader: Option<&str>,
-    ) -> u32 {
-        let program = unsafe { gl::CreateProgram() };
-        let vs = Self::compile_shader(gl::VERTEX_SHADER, vertex_shader);
-        let fs = Self::compile_shader(gl::FRAGMENT_SHADER, fragment_shader);
+    ) -> Result<u32, ShaderError> {
+        let vs = Self::compile_shader(ShaderStage::Vertex, vertex_shader)?;
+        let fs = match Self::compile_shader(ShaderStage::Fragment, fragment_shader) {
+            Ok(fs) => fs,
+            Err(error) => {
+                unsafe { gl::DeleteShader(vs) };
+                return Err(error);
+            }
+        };
+        let gs = match geometry_shader
+            .map(|gs_src| Self::compile_shader(ShaderStage::Geometry, gs_src))
+        {
+            Some(Err(error)) => {
+                unsafe {
+                    gl::DeleteShader(vs);
+                    gl::DeleteShader(fs);
+                }
+                return Err(error);
+            }
+            Some(Ok(gs)) => Some(gs),
+            None => None,
+        };
 
         unsafe {
+            let program = gl::CreateProgram();
             gl::AttachShader(program, vs);
             gl::AttachShader(program, fs);
 
-            if let Some(gs_src) = geometry_shader {
-                let gs = Self::compile_shader(gl::GEOMETRY_SHADER, gs_src);
+            if let Some(gs) = gs {
                 gl::AttachShader(program, gs);
                 gl::DeleteShader(gs); // Clean up after attaching
             }
 
             gl::LinkProgram(program);
-            gl::ValidateProgram(program);
 
             gl::DeleteShader(vs);
             gl::DeleteShader(fs);
-        }
 
-        program
+            let mut result = gl::FALSE as i32;
+            gl::GetProgramiv(program, gl::LINK_STATUS, &mut result);
+            if result == gl::FALSE as i32 {
+                let mut length = 0;
+                gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut length);
+                let mut message = vec![0u8; length.max(1) as usize];
+                gl::GetProgramInfoLog(
+                    program,
+                    length,
+                    std::ptr::null_mut(),
+                    message.as_mut_ptr() as *mut i8,
+                );
+                gl::DeleteProgram(program);
+                return Err(ShaderError::Link {
+                    log: String::from_utf8_lossy(&message)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                });
+            }
+
+            gl::ValidateProgram(program);
+            Ok(program)
+        }
     }
 
     /// Compiles individual shader stages
     ///
     /// # Arguments
-    /// - `type_` - The type of shader to compile
+    /// - `stage` - The stage to compile
     /// - `source` - The source code for the shader
-    fn compile_shader(type_: u32, source: &str) -> u32 {
+    ///
+    /// # Returns
+    /// the compiled stage or the error with the compile log
+    fn compile_shader(stage: ShaderStage, source: &str) -> Result<u32, ShaderError> {
+        let type_ = match stage {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+        };
+        let c_str =
+            std::ffi::CString::new(insert_defines(source)).map_err(|_| ShaderError::Compile {
+                stage,
+                log: "the source contains a nul byte".to_string(),
+            })?;
         let id = unsafe { gl::CreateShader(type_) };
-        let c_str = std::ffi::CString::new(source).unwrap();
 
         unsafe {
             gl::ShaderSource(id, 1, &c_str.as_ptr(), std::ptr::null());
@@ -147,38 +535,30 @@ impl Shader {
             if result == gl::FALSE as i32 {
                 let mut length = 0;
                 gl::GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut length);
-                let mut message = vec![0; length as usize];
+                let mut message = vec![0u8; length.max(1) as usize];
                 gl::GetShaderInfoLog(
                     id,
                     length,
                     std::ptr::null_mut(),
                     message.as_mut_ptr() as *mut i8,
                 );
-                println!(
-                    "Failed to compile {:?} shader!",
-                    match type_ {
-                        gl::VERTEX_SHADER => "Vertex",
-                        gl::FRAGMENT_SHADER => "Fragment",
-                        gl::GEOMETRY_SHADER => "Geometry",
-                        _ => "Unknown",
-                    }
-                );
-                println!(
-                    "{}",
-                    std::str::from_utf8(&message).expect("Shader info log is not valid UTF-8")
-                );
                 gl::DeleteShader(id);
-                return 0;
+                return Err(ShaderError::Compile {
+                    stage,
+                    log: String::from_utf8_lossy(&message)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                });
             }
         }
 
-        id
+        Ok(id)
     }
 
     /// Binds the shader for use in the OpenGL pipeline
     pub fn bind(&self) {
         unsafe {
-            gl::UseProgram(self.m_renderer_id);
+            gl::UseProgram(self.m_renderer_id.id());
         }
     }
 
@@ -222,12 +602,9 @@ impl Shader {
         //get the location of the uniform if not in the cache
         let c_str = std::ffi::CString::new(name).unwrap();
         let location = unsafe {
-            let location = gl::GetUniformLocation(self.m_renderer_id, c_str.as_ptr());
+            let location = gl::GetUniformLocation(self.m_renderer_id.id(), c_str.as_ptr());
             if location == -1 {
-                println!(
-                    "{}",
-                    format!("Warning: uniform '{:?}' doesn't exist!", name).yellow()
-                );
+                log::warn!("uniform '{:?}' doesn't exist!", name);
             }
             location
         };
@@ -236,9 +613,58 @@ impl Shader {
             .insert(name.to_string(), location);
         location
     }
+
+    /// connects a uniform block of the shader to a binding point so it reads the
+    /// [`UniformBuffer`](super::buffers::uniform_buffer::UniformBuffer) bound to the same slot.
+    ///
+    /// # Arguments
+    /// - `name` - the name of the uniform block in the shader
+    /// - `binding` - the binding point (the slot the buffer is bound to)
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // layout(std140) uniform Camera { mat4 u_view; mat4 u_projection; };
+    /// shader.bind_uniform_block("Camera", 0);
+    /// camera_buffer.bind(0);
+    /// ```
+    pub fn bind_uniform_block(&mut self, name: &str, binding: u32) {
+        self.m_uniform_block_bindings
+            .insert(name.to_string(), binding);
+        if let Some(index) = self.get_uniform_block_index(name) {
+            unsafe { gl::UniformBlockBinding(self.m_renderer_id.id(), index, binding) };
+        }
+    }
+
+    /// Get the index of a uniform block in the shader
+    ///
+    /// the index is cached like the uniform locations
+    ///
+    /// # Arguments
+    /// - `name` - the name of the uniform block
+    ///
+    /// # Returns
+    /// the index of the block or None if the shader has no block with that name
+    pub fn get_uniform_block_index(&mut self, name: &str) -> Option<u32> {
+        if let Some(index) = self.m_uniform_block_cache.get(name) {
+            return (*index != gl::INVALID_INDEX).then_some(*index);
+        }
+
+        let c_str = std::ffi::CString::new(name).unwrap();
+        let index = unsafe { gl::GetUniformBlockIndex(self.m_renderer_id.id(), c_str.as_ptr()) };
+        if index == gl::INVALID_INDEX {
+            log::warn!("uniform block '{:?}' doesn't exist!", name);
+        }
+
+        self.m_uniform_block_cache.insert(name.to_string(), index);
+        (index != gl::INVALID_INDEX).then_some(index)
+    }
 }
 
 /// a type implementing this has a equivelent type in glsl
+///
+/// implemented for `i32`, `u32`, `f32`, `bool`, the glm vectors and matrices, and slices (`&[T]`, `&[T; N]`, `&Vec<T>`)
+/// of those which set a glsl array starting at the location. a new uniform type only needs an impl of this trait
+/// to work with [`Shader::set_uniform`].
 pub trait Uniform {
     /// calls the gl::unform for the equivelent glsl type
     fn set_uniform(&self, location: i32);
@@ -300,37 +726,75 @@ impl Uniform for bool {
     }
 }
 
-impl Uniform for &[f32] {
+impl Uniform for u32 {
     fn set_uniform(&self, location: i32) {
         unsafe {
-            gl::Uniform1fv(location, self.len() as i32, self.as_ptr());
+            gl::Uniform1ui(location, *self);
         }
     }
 }
 
-impl Uniform for &[i32] {
-    fn set_uniform(&self, location: i32) {
-        unsafe {
-            gl::Uniform1iv(location, self.len() as i32, self.as_ptr());
+/// implements [`Uniform`] for a slice that is uploaded to a glsl array with one `glUniform*v` call.
+/// `$components` is the number of scalars in one element, matrices pass `matrix` to upload them without transposing.
+macro_rules! impl_uniform_slice {
+    ($type:ty, $scalar:ty, $components:expr, $function:path) => {
+        impl_uniform_slice!(@impl $type, $scalar, $components, |location, count, ptr| $function(location, count, ptr));
+    };
+    (matrix $type:ty, $components:expr, $function:path) => {
+        impl_uniform_slice!(@impl $type, f32, $components, |location, count, ptr| $function(location, count, gl::FALSE, ptr));
+    };
+    (@impl $type:ty, $scalar:ty, $components:expr, |$location:ident, $count:ident, $ptr:ident| $upload:expr) => {
+        impl Uniform for &[$type] {
+            fn set_uniform(&self, location: i32) {
+                if self.is_empty() {
+                    log::warn!("tried to set array uniform to empty array!");
+                    return;
+                }
+                // glm types store their components contiguously so the slice is one flat array
+                debug_assert_eq!(
+                    std::mem::size_of::<$type>(),
+                    std::mem::size_of::<$scalar>() * $components
+                );
+                let ($location, $count, $ptr) =
+                    (location, self.len() as i32, self.as_ptr() as *const $scalar);
+                unsafe { $upload }
+            }
         }
+    };
+}
+
+impl_uniform_slice!(f32, f32, 1, gl::Uniform1fv);
+impl_uniform_slice!(i32, i32, 1, gl::Uniform1iv);
+impl_uniform_slice!(u32, u32, 1, gl::Uniform1uiv);
+impl_uniform_slice!(glm::Vec2, f32, 2, gl::Uniform2fv);
+impl_uniform_slice!(glm::Vec3, f32, 3, gl::Uniform3fv);
+impl_uniform_slice!(glm::Vec4, f32, 4, gl::Uniform4fv);
+impl_uniform_slice!(matrix glm::Mat3, 9, gl::UniformMatrix3fv);
+impl_uniform_slice!(matrix glm::Mat4, 16, gl::UniformMatrix4fv);
+
+impl Uniform for &[bool] {
+    fn set_uniform(&self, location: i32) {
+        // glsl bools are set as ints
+        let values: Vec<i32> = self.iter().map(|value| *value as i32).collect();
+        values.as_slice().set_uniform(location);
     }
 }
 
-impl Uniform for &[glm::Mat4] {
+impl<T, const N: usize> Uniform for &[T; N]
+where
+    for<'a> &'a [T]: Uniform,
+{
     fn set_uniform(&self, location: i32) {
-        unsafe {
-            if self.is_empty() {
-                eprintln!("Tried to set array uniform to empty array!");
-                return;
-            }
+        self.as_slice().set_uniform(location);
+    }
+}
 
-            gl::UniformMatrix4fv(
-                location,
-                self.len() as i32,
-                gl::FALSE,
-                (*self.get_unchecked(0)).as_ptr(),
-            );
-        }
+impl<T> Uniform for &Vec<T>
+where
+    for<'a> &'a [T]: Uniform,
+{
+    fn set_uniform(&self, location: i32) {
+        self.as_slice().set_uniform(location);
     }
 }
 
@@ -349,3 +813,28 @@ impl Uniform for glm::Mat2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_defines() {
+        let source = "#version 330 core\nuniform mat4 u_joints[MAX_JOINTS];\n";
+        let result = insert_defines(source);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], "#version 330 core");
+        assert_eq!(
+            lines[1],
+            format!("#define MAX_POINT_LIGHTS {}", point_light::MAX_POINT_LIGHTS)
+        );
+        let defines = shader_defines().lines().count();
+        assert_eq!(lines[defines + 1], "#line 1");
+        assert_eq!(lines[defines + 2], "uniform mat4 u_joints[MAX_JOINTS];");
+
+        // without a version the defines go first
+        let result = insert_defines("void main() {}");
+        assert!(result.starts_with(&shader_defines()));
+        assert!(result.ends_with("#line 0\nvoid main() {}\n"));
+    }
+}
@@ -1,13 +1,110 @@
 //! shadow maps store depth information from the light's perspective to render shadows at the draw stage
+use crate::renderer::gl_object::{GlKind, GlObject};
 use crate::renderer::shader::Shader;
+use crate::renderer::Renderer;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// the depth bias of lights that don't set their own, stored as the bits of an f32
+static DEFAULT_SHADOW_BIAS: AtomicU32 = AtomicU32::new(0.0005f32.to_bits());
+/// the normal bias of lights that don't set their own, stored as the bits of an f32
+static DEFAULT_NORMAL_BIAS: AtomicU32 = AtomicU32::new(0.5f32.to_bits());
+
+/// sets the shadow bias used by lights that leave theirs unset.
+///
+/// both values are in world units. the bias used for a pixel is `max(normal_bias * (1 - n.l), bias)`
+/// so surfaces at a grazing angle to the light get pushed further.
+///
+/// # Arguments
+/// - `bias` - the smallest depth offset (0.0005 by default), raise it to remove shadow acne
+/// - `normal_bias` - the extra offset at grazing angles (0.5 by default), lower it if shadows detach from their casters
+pub fn set_default_shadow_bias(bias: f32, normal_bias: f32) {
+    DEFAULT_SHADOW_BIAS.store(bias.max(0.0).to_bits(), Ordering::Relaxed);
+    DEFAULT_NORMAL_BIAS.store(normal_bias.max(0.0).to_bits(), Ordering::Relaxed);
+}
+
+/// gets the shadow bias used by lights that leave theirs unset
+///
+/// # Returns
+/// the `(bias, normal_bias)` in world units
+pub fn default_shadow_bias() -> (f32, f32) {
+    (
+        f32::from_bits(DEFAULT_SHADOW_BIAS.load(Ordering::Relaxed)),
+        f32::from_bits(DEFAULT_NORMAL_BIAS.load(Ordering::Relaxed)),
+    )
+}
+
+/// the blend between logarithmic and uniform cascade splits of lights that don't set their own
+pub const DEFAULT_CASCADE_LAMBDA: f32 = 0.7;
+
+/// splits the view distance into shadow cascades with the practical split scheme, a blend of logarithmic splits
+/// (more detail near the camera) and uniform splits (the same detail everywhere).
+///
+/// # Arguments
+/// - `near` - the near plane of the camera
+/// - `far` - the distance the shadows reach
+/// - `count` - the number of cascades
+/// - `lambda` - 1 for logarithmic splits, 0 for uniform splits. open worlds want higher values than tight indoor
+///   scenes
+///
+/// # Returns
+/// the view distance where each cascade ends, the last one is `far`
+pub fn calculate_cascade_splits(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    let near = near.max(0.0001);
+    let far = far.max(near);
+    let lambda = lambda.clamp(0.0, 1.0);
+    (1..=count)
+        .map(|i| {
+            let fraction = i as f32 / count as f32;
+            let log = near * (far / near).powf(fraction);
+            let uniform = near + (far - near) * fraction;
+            lambda * log + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// the largest grid width of [`ShadowQuality::Pcf`], 8x8 taps match the 64 sample limit of the shaders
+const MAX_PCF_WIDTH: u32 = 8;
+
+/// how the edges of a shadow are filtered when the shadow map is sampled (percentage closer filtering)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ShadowQuality {
+    /// a single depth comparison per pixel, sharp and aliased edges
+    Hard,
+    /// averages a grid of comparisons around the pixel
+    Pcf {
+        /// the width of the grid, clamped between 1 and 8
+        samples: u32,
+    },
+    /// averages comparisons spread over a disk that is rotated per pixel, trading banding for noise
+    PcfPoisson,
+}
+
+impl ShadowQuality {
+    /// the value of the quality uniform in the shaders
+    pub(crate) fn mode(&self) -> i32 {
+        match self {
+            ShadowQuality::Hard => 0,
+            ShadowQuality::Pcf { .. } => 1,
+            ShadowQuality::PcfPoisson => 2,
+        }
+    }
+
+    /// the grid width of [`ShadowQuality::Pcf`] after clamping, 1 for the other qualities
+    pub fn grid_width(&self) -> u32 {
+        match self {
+            ShadowQuality::Pcf { samples } => (*samples).clamp(1, MAX_PCF_WIDTH),
+            _ => 1,
+        }
+    }
+}
 
 /// The ShadowMap struct is used to create and manage shadow maps
 #[derive(Clone, Debug)]
 pub struct ShadowMap {
     /// The framebuffer object
-    pub framebuffer: u32,
+    framebuffer: GlObject,
     /// The shadow map texture
-    pub texture: u32,
+    texture: GlObject,
     /// The depth shader
     pub depth_shader: Shader,
     /// The width of the shadow map
@@ -89,18 +186,30 @@ impl ShadowMap {
         }
 
         ShadowMap {
-            framebuffer,
-            texture: shadow_map,
+            framebuffer: GlObject::new(framebuffer, GlKind::Framebuffer),
+            texture: GlObject::new(shadow_map, GlKind::Texture),
             depth_shader,
             width,
             height,
         }
     }
 
+    /// a shadow map without gpu objects, so the tests can build lights without an OpenGL context
+    #[cfg(test)]
+    pub(crate) fn unloaded(width: i32, height: i32) -> ShadowMap {
+        ShadowMap {
+            framebuffer: GlObject::new(0, GlKind::Framebuffer),
+            texture: GlObject::new(0, GlKind::Texture),
+            depth_shader: Shader::unloaded(),
+            width,
+            height,
+        }
+    }
+
     /// Binds the shadow map
     pub fn bind(&self) {
         unsafe {
-            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer.id());
         }
     }
     /// Unbinds the shadow map
@@ -114,7 +223,7 @@ impl ShadowMap {
     /// # Returns
     /// The shadow map texture
     pub fn get_texture(&self) -> u32 {
-        self.texture
+        self.texture.id()
     }
 
     /// Binds the shadow map to a shader
@@ -126,7 +235,7 @@ impl ShadowMap {
     pub fn bind_shadow_map(&self, shader: &mut Shader, uniform: &str, slot: u32) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0 + slot);
-            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture.id());
 
             shader.bind();
             shader.set_uniform(uniform, slot as i32);
@@ -157,9 +266,9 @@ impl ShadowMap {
         self.depth_shader.unbind();
 
         unsafe {
-            gl::CullFace(gl::BACK);
             gl::Disable(gl::BLEND);
         }
+        Renderer::restore_cull_mode();
 
         Self::unbind();
     }
@@ -185,9 +294,29 @@ impl ShadowMap {
         }
         render_function(&mut self.depth_shader);
         unsafe {
-            gl::CullFace(gl::BACK);
             gl::Disable(gl::BLEND);
         }
+        Renderer::restore_cull_mode();
         Self::unbind();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascade_splits() {
+        let uniform = calculate_cascade_splits(1.0, 100.0, 4, 0.0);
+        assert_eq!(uniform, [25.75, 50.5, 75.25, 100.0]);
+
+        let log = calculate_cascade_splits(1.0, 100.0, 2, 1.0);
+        assert!((log[0] - 10.0).abs() < 1e-4);
+        assert!((log[1] - 100.0).abs() < 1e-3);
+
+        // the blend keeps the first cascade between the two schemes
+        let blend = calculate_cascade_splits(1.0, 100.0, 2, DEFAULT_CASCADE_LAMBDA);
+        assert!(blend[0] > log[0] && blend[0] < 50.5);
+        assert_eq!(calculate_cascade_splits(0.1, 50.0, 1, 0.3), [50.0]);
+    }
+}
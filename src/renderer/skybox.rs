@@ -0,0 +1,386 @@
+//! a cubemap environment drawn behind the scene.
+//!
+//! the skybox is loaded from six face images or from one equirectangular (latitude / longitude) image like the `.hdr`
+//! panoramas of most hdri sites, which is converted to the six faces on load. it is drawn after the opaque meshes with
+//! only the rotation of the camera so it never moves with the player, and at the far plane so it only fills the pixels
+//! nothing else was drawn to.
+//!
+//! the cubemap is stored as floats so hdr images keep their bright values, [`Skybox::cubemap`] exposes it to sample
+//! reflections or image based lighting.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::renderer::skybox::Skybox;
+//!
+//! let skybox = Skybox::from_equirectangular("res/sky/meadow.hdr", 1024).unwrap();
+//! context.set_skybox(skybox);
+//! ```
+
+use std::f32::consts::{PI, TAU};
+use std::ffi::CString;
+
+use nalgebra_glm as glm;
+use stb_image::stb_image;
+
+use crate::renderer::buffers::vertex_array::VertexArray;
+use crate::renderer::buffers::vertex_buffer::VertexBuffer;
+use crate::renderer::buffers::vertex_buffer_layout::VertexBufferLayout;
+use crate::renderer::shader::Shader;
+use crate::renderer::{Renderer, Topology};
+
+/// the corners of the triangles of a unit cube, seen from inside
+#[rustfmt::skip]
+const CUBE_VERTICES: [f32; 108] = [
+    -1.0,  1.0, -1.0,  -1.0, -1.0, -1.0,   1.0, -1.0, -1.0,
+     1.0, -1.0, -1.0,   1.0,  1.0, -1.0,  -1.0,  1.0, -1.0,
+
+    -1.0, -1.0,  1.0,  -1.0, -1.0, -1.0,  -1.0,  1.0, -1.0,
+    -1.0,  1.0, -1.0,  -1.0,  1.0,  1.0,  -1.0, -1.0,  1.0,
+
+     1.0, -1.0, -1.0,   1.0, -1.0,  1.0,   1.0,  1.0,  1.0,
+     1.0,  1.0,  1.0,   1.0,  1.0, -1.0,   1.0, -1.0, -1.0,
+
+    -1.0, -1.0,  1.0,  -1.0,  1.0,  1.0,   1.0,  1.0,  1.0,
+     1.0,  1.0,  1.0,   1.0, -1.0,  1.0,  -1.0, -1.0,  1.0,
+
+    -1.0,  1.0, -1.0,   1.0,  1.0, -1.0,   1.0,  1.0,  1.0,
+     1.0,  1.0,  1.0,  -1.0,  1.0,  1.0,  -1.0,  1.0, -1.0,
+
+    -1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0, -1.0,
+     1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0,  1.0,
+];
+
+/// an image loaded as rgb floats
+struct Image {
+    width: usize,
+    height: usize,
+    /// `width * height * 3` floats, the rows from top to bottom
+    pixels: Vec<f32>,
+}
+
+/// a cubemap drawn behind everything else in the scene, see the [module docs](self).
+pub struct Skybox {
+    cubemap: u32,
+    vertex_array: VertexArray,
+    _vertex_buffer: VertexBuffer,
+    shader: Shader,
+    /// multiplies the color of the sky, used to tone down or brighten hdr skies
+    pub intensity: f32,
+}
+
+impl Skybox {
+    /// loads a skybox from six square images of the same size.
+    ///
+    /// # Arguments
+    /// - `faces` - the paths of the faces in the order +X (right), -X (left), +Y (top), -Y (bottom), +Z (front),
+    ///   -Z (back)
+    ///
+    /// # Returns
+    /// the skybox or the reason the faces could not be loaded
+    pub fn from_faces(faces: [&str; 6]) -> Result<Skybox, String> {
+        let mut images = Vec::with_capacity(6);
+        for path in faces {
+            let image = load_image(path)?;
+            if image.width != image.height {
+                return Err(format!("skybox face {} is not square", path));
+            }
+            if images
+                .first()
+                .is_some_and(|first: &Image| first.width != image.width)
+            {
+                return Err(format!(
+                    "skybox face {} has a different size than the other faces",
+                    path
+                ));
+            }
+            images.push(image);
+        }
+
+        let size = images[0].width;
+        let faces: Vec<Vec<f32>> = images.into_iter().map(|image| image.pixels).collect();
+        Ok(Self::from_pixels(size, &faces))
+    }
+
+    /// loads a skybox from an equirectangular image (usually a `.hdr` file twice as wide as it is high) and converts it
+    /// to a cubemap.
+    ///
+    /// # Arguments
+    /// - `path` - the path of the image
+    /// - `face_size` - the width and height of each face of the cubemap
+    ///
+    /// # Returns
+    /// the skybox or the reason the image could not be loaded
+    pub fn from_equirectangular(path: &str, face_size: u32) -> Result<Skybox, String> {
+        let image = load_image(path)?;
+        let size = face_size.max(1) as usize;
+        let faces: Vec<Vec<f32>> = (0..6)
+            .map(|face| equirectangular_face(&image, face, size))
+            .collect();
+        Ok(Self::from_pixels(size, &faces))
+    }
+
+    /// uploads the faces to a new cubemap and creates the cube to draw it with
+    fn from_pixels(size: usize, faces: &[Vec<f32>]) -> Skybox {
+        let mut cubemap = 0;
+        unsafe {
+            gl::GenTextures(1, &mut cubemap);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            for (i, pixels) in faces.iter().enumerate() {
+                gl::TexImage2D(
+                    gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
+                    0,
+                    gl::RGB16F as i32,
+                    size as i32,
+                    size as i32,
+                    0,
+                    gl::RGB,
+                    gl::FLOAT,
+                    pixels.as_ptr() as *const std::ffi::c_void,
+                );
+            }
+            gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+            gl::TexParameteri(
+                gl::TEXTURE_CUBE_MAP,
+                gl::TEXTURE_MIN_FILTER,
+                gl::LINEAR_MIPMAP_LINEAR as i32,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_CUBE_MAP,
+                gl::TEXTURE_MAG_FILTER,
+                gl::LINEAR as i32,
+            );
+            for wrap in [gl::TEXTURE_WRAP_S, gl::TEXTURE_WRAP_T, gl::TEXTURE_WRAP_R] {
+                gl::TexParameteri(gl::TEXTURE_CUBE_MAP, wrap, gl::CLAMP_TO_EDGE as i32);
+            }
+            // filter across the edges of the faces instead of showing seams
+            gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+
+        let vertex_array = VertexArray::new();
+        let vertex_buffer = VertexBuffer::new(&CUBE_VERTICES);
+        let mut layout = VertexBufferLayout::new();
+        layout.push::<f32>(3);
+        vertex_array.add_buffer(&vertex_buffer, &layout);
+
+        Skybox {
+            cubemap,
+            vertex_array,
+            _vertex_buffer: vertex_buffer,
+            shader: Shader::from_slice(
+                include_str!("../../res/shaders/skybox/skybox.vert"),
+                include_str!("../../res/shaders/skybox/skybox.frag"),
+                None,
+            ),
+            intensity: 1.0,
+        }
+    }
+
+    /// get the opengl id of the cubemap to sample the environment in other shaders
+    pub fn cubemap(&self) -> u32 {
+        self.cubemap
+    }
+
+    /// draws the skybox at the far plane, call this after the opaque geometry so only the empty pixels are filled.
+    ///
+    /// # Arguments
+    /// - `view` - the view matrix of the camera, the translation is ignored
+    /// - `projection` - the projection matrix of the camera
+    pub(crate) fn render(&mut self, view: &glm::Mat4, projection: &glm::Mat4) {
+        let rotation = glm::mat3_to_mat4(&glm::mat4_to_mat3(view));
+
+        self.shader.bind();
+        self.shader.set_uniform("u_VP", projection * rotation);
+        self.shader.set_uniform("u_intensity", self.intensity);
+        self.shader.set_uniform("u_skybox", 0);
+
+        // the depth state of the pass is put back after the draw
+        let mut depth_func = gl::LESS as i32;
+        let mut depth_mask = gl::TRUE;
+        unsafe {
+            gl::GetIntegerv(gl::DEPTH_FUNC, &mut depth_func);
+            gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.cubemap);
+            // the cube is at depth 1.0 which only passes where nothing was drawn
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+            gl::Disable(gl::CULL_FACE);
+        }
+
+        self.vertex_array.bind();
+        Renderer::draw_arrays(Topology::Triangles, 0, 36);
+        self.vertex_array.unbind();
+
+        unsafe {
+            gl::DepthMask(depth_mask);
+            gl::DepthFunc(depth_func as u32);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+        Renderer::restore_cull_mode();
+    }
+}
+
+impl Drop for Skybox {
+    fn drop(&mut self) {
+        if !crate::renderer::gl_object::is_context_alive() {
+            return;
+        }
+        unsafe {
+            gl::DeleteTextures(1, &self.cubemap);
+        }
+    }
+}
+
+/// loads an image as rgb floats with the first row at the top. `.hdr` images keep their values, other images are
+/// mapped to 0..1.
+fn load_image(path: &str) -> Result<Image, String> {
+    let c_path = CString::new(path).map_err(|_| format!("invalid path {}", path))?;
+    let (mut width, mut height, mut channels) = (0, 0, 0);
+
+    unsafe {
+        // cubemaps and panoramas are stored top to bottom unlike the other textures
+        stb_image::stbi_set_flip_vertically_on_load(0);
+        let pixels = if path.to_ascii_lowercase().ends_with(".hdr") {
+            let data =
+                stb_image::stbi_loadf(c_path.as_ptr(), &mut width, &mut height, &mut channels, 3);
+            (!data.is_null()).then(|| {
+                let pixels =
+                    std::slice::from_raw_parts(data, (width * height * 3) as usize).to_vec();
+                stb_image::stbi_image_free(data as *mut std::ffi::c_void);
+                pixels
+            })
+        } else {
+            let data =
+                stb_image::stbi_load(c_path.as_ptr(), &mut width, &mut height, &mut channels, 3);
+            (!data.is_null()).then(|| {
+                let pixels = std::slice::from_raw_parts(data, (width * height * 3) as usize)
+                    .iter()
+                    .map(|&value| value as f32 / 255.0)
+                    .collect();
+                stb_image::stbi_image_free(data as *mut std::ffi::c_void);
+                pixels
+            })
+        };
+        stb_image::stbi_set_flip_vertically_on_load(1);
+
+        pixels
+            .map(|pixels| Image {
+                width: width as usize,
+                height: height as usize,
+                pixels,
+            })
+            .ok_or_else(|| format!("failed to load skybox image {}", path))
+    }
+}
+
+/// the direction through a texel of a cubemap face, following the face orientation of opengl.
+///
+/// # Arguments
+/// - `face` - the face in the order +X, -X, +Y, -Y, +Z, -Z
+/// - `s` - the horizontal position on the face from -1 (left) to 1 (right)
+/// - `t` - the vertical position on the face from -1 (top row) to 1 (bottom row)
+fn face_direction(face: usize, s: f32, t: f32) -> glm::Vec3 {
+    let direction = match face {
+        0 => glm::vec3(1.0, -t, -s),
+        1 => glm::vec3(-1.0, -t, s),
+        2 => glm::vec3(s, 1.0, t),
+        3 => glm::vec3(s, -1.0, -t),
+        4 => glm::vec3(s, -t, 1.0),
+        _ => glm::vec3(-s, -t, -1.0),
+    };
+    glm::normalize(&direction)
+}
+
+/// the position in an equirectangular image looking in a direction, `u` goes around the horizon and `v` from the top
+/// (+Y) to the bottom (-Y), both from 0 to 1.
+fn direction_to_equirectangular(direction: &glm::Vec3) -> glm::Vec2 {
+    let u = 0.5 + direction.z.atan2(direction.x) / TAU;
+    let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+    glm::vec2(u, v)
+}
+
+/// samples the image with bilinear filtering, wrapping around horizontally
+fn sample_bilinear(image: &Image, uv: glm::Vec2) -> glm::Vec3 {
+    let x = uv.x * image.width as f32 - 0.5;
+    let y = (uv.y * image.height as f32 - 0.5).clamp(0.0, (image.height - 1) as f32);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let texel = |x: f32, y: f32| {
+        let x = (x as i64).rem_euclid(image.width as i64) as usize;
+        let y = (y as usize).min(image.height - 1);
+        let i = (y * image.width + x) * 3;
+        glm::vec3(image.pixels[i], image.pixels[i + 1], image.pixels[i + 2])
+    };
+
+    let top = glm::lerp(&texel(x0, y0), &texel(x0 + 1.0, y0), fx);
+    let bottom = glm::lerp(&texel(x0, y0 + 1.0), &texel(x0 + 1.0, y0 + 1.0), fx);
+    glm::lerp(&top, &bottom, fy)
+}
+
+/// renders one face of a cubemap from an equirectangular image
+fn equirectangular_face(image: &Image, face: usize, size: usize) -> Vec<f32> {
+    let mut pixels = Vec::with_capacity(size * size * 3);
+    for y in 0..size {
+        for x in 0..size {
+            let s = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+            let t = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+            let uv = direction_to_equirectangular(&face_direction(face, s, t));
+            pixels.extend_from_slice(sample_bilinear(image, uv).as_slice());
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_face_direction() {
+        let axes = [
+            glm::Vec3::x(),
+            -glm::Vec3::x(),
+            glm::Vec3::y(),
+            -glm::Vec3::y(),
+            glm::Vec3::z(),
+            -glm::Vec3::z(),
+        ];
+        for (face, axis) in axes.iter().enumerate() {
+            assert!(glm::distance(&face_direction(face, 0.0, 0.0), axis) < 1e-6);
+        }
+        // the top row of the side faces looks up
+        for face in [0, 1, 4, 5] {
+            assert!(face_direction(face, 0.0, -1.0).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_equirectangular_conversion() {
+        // a panorama with a bright sky in the top half and a dark ground in the bottom half
+        let (width, height) = (16, 8);
+        let pixels = (0..width * height)
+            .flat_map(|i| {
+                let value = if i / width < height / 2 { 1.0 } else { 0.0 };
+                [value; 3]
+            })
+            .collect();
+        let image = Image {
+            width,
+            height,
+            pixels,
+        };
+
+        let top = equirectangular_face(&image, 2, 4);
+        let bottom = equirectangular_face(&image, 3, 4);
+        assert_eq!(top.len(), 4 * 4 * 3);
+        assert!(top.iter().all(|&value| value == 1.0));
+        assert!(bottom.iter().all(|&value| value == 0.0));
+
+        // the side faces show the sky at the top and the ground at the bottom
+        let front = equirectangular_face(&image, 4, 4);
+        assert_eq!(front[0], 1.0);
+        assert_eq!(front[front.len() - 1], 0.0);
+    }
+}
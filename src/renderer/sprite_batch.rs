@@ -0,0 +1,285 @@
+//! draws the [`Sprite`] and [`Text`](crate::nodes::Text) nodes through the 2D camera with as few draw calls as possible.
+//!
+//! the sprites are queued every frame, sorted by their layer and turned into one vertex buffer. consecutive sprites
+//! with the same texture share a draw call, so keeping the sprites of one texture on the same layer keeps the batch
+//! count low.
+
+use std::ops::Range;
+use std::rc::Rc;
+
+use nalgebra_glm as glm;
+
+use crate::components::NodeTransform;
+use crate::nodes::Sprite;
+use crate::renderer::buffers::vertex_array::VertexArray;
+use crate::renderer::buffers::vertex_buffer::VertexBuffer;
+use crate::renderer::buffers::vertex_buffer_layout::VertexBufferLayout;
+use crate::renderer::buffers::BufferUsage;
+use crate::renderer::shader::Shader;
+use crate::renderer::texture::Texture;
+use crate::renderer::{Renderer, Topology};
+use crate::utils::color::Color;
+
+/// the corners of the unit quad and their uv, as two counter clockwise triangles
+const QUAD: [([f32; 2], [f32; 2]); 6] = [
+    ([-0.5, -0.5], [0.0, 0.0]),
+    ([0.5, -0.5], [1.0, 0.0]),
+    ([0.5, 0.5], [1.0, 1.0]),
+    ([-0.5, -0.5], [0.0, 0.0]),
+    ([0.5, 0.5], [1.0, 1.0]),
+    ([-0.5, 0.5], [0.0, 1.0]),
+];
+
+/// a single vertex of a sprite quad
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct SpriteVertex {
+    position: glm::Vec2,
+    uv: glm::Vec2,
+    color: glm::Vec4,
+}
+
+/// a sprite waiting to be drawn
+struct QueuedSprite {
+    layer: i32,
+    texture: Option<Rc<Texture>>,
+    matrix: glm::Mat4,
+    tint: glm::Vec4,
+    /// the uv of the bottom left and the top right corner
+    uv: (glm::Vec2, glm::Vec2),
+}
+
+/// vertices drawn with one texture
+struct Batch {
+    texture: Option<Rc<Texture>>,
+    vertices: Range<usize>,
+}
+
+/// the gpu side of the sprite batch, created the first time a sprite is drawn
+struct SpriteBuffers {
+    vertex_array: VertexArray,
+    vertex_buffer: VertexBuffer,
+    shader: Shader,
+}
+
+/// collects the sprites during the frame and draws them in batches
+pub struct SpriteBatch {
+    sprites: Vec<QueuedSprite>,
+    vertices: Vec<SpriteVertex>,
+    buffers: Option<SpriteBuffers>,
+    /// the draw calls of the last render
+    draw_calls: usize,
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpriteBatch {
+    /// creates an empty sprite batch
+    pub fn new() -> SpriteBatch {
+        SpriteBatch {
+            sprites: Vec::new(),
+            vertices: Vec::new(),
+            buffers: None,
+            draw_calls: 0,
+        }
+    }
+
+    /// queues a sprite for the next render
+    ///
+    /// # Arguments
+    /// - `sprite` - the sprite to draw
+    /// - `world_transform` - the world transform of the sprite (its own transform composed with its parents)
+    pub fn push(&mut self, sprite: &Sprite, world_transform: &NodeTransform) {
+        self.push_quad(
+            sprite.get_layer(),
+            sprite.get_texture().cloned(),
+            sprite.get_quad_matrix(world_transform),
+            sprite.get_tint(),
+            (glm::vec2(0.0, 0.0), glm::vec2(1.0, 1.0)),
+        );
+    }
+
+    /// queues a quad showing a part of a texture, used for the glyphs of texts
+    ///
+    /// # Arguments
+    /// - `layer` - the draw order of the quad
+    /// - `texture` - the texture, `None` fills the quad with the tint
+    /// - `matrix` - maps the -0.5 to 0.5 unit quad onto the quad
+    /// - `tint` - the color the texture is multiplied with
+    /// - `uv` - the uv of the bottom left and the top right corner
+    pub(crate) fn push_quad(
+        &mut self,
+        layer: i32,
+        texture: Option<Rc<Texture>>,
+        matrix: glm::Mat4,
+        tint: Color,
+        uv: (glm::Vec2, glm::Vec2),
+    ) {
+        self.sprites.push(QueuedSprite {
+            layer,
+            texture,
+            matrix,
+            tint: tint.into(),
+            uv,
+        });
+    }
+
+    /// get the number of sprites queued this frame
+    pub fn sprite_count(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// get the number of draw calls the last render needed
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls
+    }
+
+    /// sorts the queued sprites by layer and writes their vertices, the queue is emptied
+    ///
+    /// # Returns
+    /// the ranges of the vertices that share a texture, in draw order
+    fn build(&mut self) -> Vec<Batch> {
+        // the sort is stable so sprites on one layer keep the order they were queued in
+        self.sprites.sort_by_key(|sprite| sprite.layer);
+        self.vertices.clear();
+
+        let mut batches: Vec<Batch> = Vec::new();
+        for sprite in self.sprites.drain(..) {
+            let start = self.vertices.len();
+            let (uv_min, uv_max) = sprite.uv;
+            for ([x, y], [u, v]) in QUAD {
+                let position = sprite.matrix * glm::vec4(x, y, 0.0, 1.0);
+                self.vertices.push(SpriteVertex {
+                    position: position.xy(),
+                    uv: uv_min + (uv_max - uv_min).component_mul(&glm::vec2(u, v)),
+                    color: sprite.tint,
+                });
+            }
+
+            let same_texture =
+                batches
+                    .last()
+                    .is_some_and(|batch| match (&batch.texture, &sprite.texture) {
+                        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                        (None, None) => true,
+                        _ => false,
+                    });
+            match batches.last_mut() {
+                Some(batch) if same_texture => batch.vertices.end = self.vertices.len(),
+                _ => batches.push(Batch {
+                    texture: sprite.texture,
+                    vertices: start..self.vertices.len(),
+                }),
+            }
+        }
+        batches
+    }
+
+    /// draws all the queued sprites on top of the current frame, then clears the queue
+    ///
+    /// # Arguments
+    /// - `view_projection` - the view projection matrix of the 2D camera
+    pub fn render(&mut self, view_projection: glm::Mat4) {
+        let batches = self.build();
+        self.draw_calls = batches.len();
+        if batches.is_empty() {
+            return;
+        }
+
+        let buffers = self.buffers.get_or_insert_with(|| {
+            let vertex_array = VertexArray::new();
+            let vertex_buffer =
+                VertexBuffer::new_with_usage::<SpriteVertex>(&[], BufferUsage::Stream);
+            let mut layout = VertexBufferLayout::new();
+            layout.push::<f32>(2);
+            layout.push::<f32>(2);
+            layout.push::<f32>(4);
+            vertex_array.add_buffer(&vertex_buffer, &layout);
+
+            SpriteBuffers {
+                vertex_array,
+                vertex_buffer,
+                shader: Shader::from_slice(
+                    include_str!("../../res/shaders/sprite/sprite.vert"),
+                    include_str!("../../res/shaders/sprite/sprite.frag"),
+                    None,
+                ),
+            }
+        });
+
+        // sprites are flat and drawn in order, mirrored sprites turn their back to the camera
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::CULL_FACE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        }
+
+        buffers.vertex_buffer.set_data(&self.vertices);
+        buffers.shader.bind();
+        buffers.shader.set_uniform("u_VP", view_projection);
+        buffers.shader.set_uniform("u_texture", 0);
+        buffers.vertex_array.bind();
+        for batch in &batches {
+            buffers
+                .shader
+                .set_uniform("u_hasTexture", batch.texture.is_some());
+            if let Some(texture) = &batch.texture {
+                texture.bind(0);
+            }
+            Renderer::draw_arrays(
+                Topology::Triangles,
+                batch.vertices.start as i32,
+                batch.vertices.len() as i32,
+            );
+        }
+        buffers.vertex_array.unbind();
+
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+        Renderer::restore_cull_mode();
+    }
+
+    /// drops all the queued sprites without drawing
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sorts_layers() {
+        let mut batch = SpriteBatch::new();
+        let mut front = Sprite::new(glm::vec2(2.0, 2.0));
+        front
+            .set_layer(1)
+            .set_tint(Color::from_normalized(1.0, 0.0, 0.0, 1.0));
+        let mut back = Sprite::new(glm::vec2(4.0, 2.0));
+        back.set_position(glm::vec2(10.0, 0.0));
+
+        batch.push(&front, &NodeTransform::default());
+        batch.push(&back, &NodeTransform::default());
+        assert_eq!(batch.sprite_count(), 2);
+
+        // both sprites are untextured so they share a single draw call
+        let batches = batch.build();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].vertices, 0..12);
+        assert_eq!(batch.sprite_count(), 0);
+
+        // the lower layer comes first even though it was queued last
+        assert_eq!(batch.vertices[0].position, glm::vec2(8.0, -1.0));
+        assert_eq!(batch.vertices[2].position, glm::vec2(12.0, 1.0));
+        assert_eq!(batch.vertices[6].position, glm::vec2(-1.0, -1.0));
+        assert_eq!(batch.vertices[6].color, glm::vec4(1.0, 0.0, 0.0, 1.0));
+    }
+}
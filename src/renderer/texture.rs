@@ -1,8 +1,13 @@
 //! This module contains the texture struct and its implementation
 
 use stb_image::stb_image;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::CString;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU32, Ordering};
 
+use super::gl_object::{GlKind, GlObject};
 use super::shader::Shader;
 
 /// Type of texture that this texture represents
@@ -16,12 +21,18 @@ use super::shader::Shader;
 /// let texture_type = TextureType::Specular;
 /// assert_eq!(texture_type.get_uniform_name(), "u_specularMap");
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TextureType {
     /// (**u_albdedoMap**) Diffuse texture (representing the color of the material)
     Diffuse,
     /// (**u_specularMap**) Specular texture (representing the shininess of the material)
     Specular,
+    /// (**u_normalMap**) Normal texture (tangent space normals that add detail to the surface)
+    Normal,
+    /// (**u_emissiveMap**) Emissive texture (the light the surface gives off, multiplied by the emissive factor)
+    Emissive,
+    /// (**u_occlusionMap**) Ambient occlusion texture (how much ambient light reaches the surface, in the red channel)
+    Occlusion,
 }
 
 impl TextureType {
@@ -30,14 +41,154 @@ impl TextureType {
         match self {
             TextureType::Diffuse => "u_albedoMap".to_string(),
             TextureType::Specular => "u_specularMap".to_string(),
+            TextureType::Normal => "u_normalMap".to_string(),
+            TextureType::Emissive => "u_emissiveMap".to_string(),
+            TextureType::Occlusion => "u_occlusionMap".to_string(),
         }
     }
 }
 
+/// `GL_TEXTURE_MAX_ANISOTROPY`, core in OpenGL 4.6 and the same value as the EXT_texture_filter_anisotropic enum
+const TEXTURE_MAX_ANISOTROPY: gl::types::GLenum = 0x84FE;
+/// `GL_MAX_TEXTURE_MAX_ANISOTROPY`, the highest anisotropy the driver supports
+const MAX_TEXTURE_MAX_ANISOTROPY: gl::types::GLenum = 0x84FF;
+
+/// how a texture is sampled outside of the 0 to 1 uv range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// the texture tiles
+    #[default]
+    Repeat,
+    /// the texture tiles and every other tile is mirrored
+    MirroredRepeat,
+    /// the edge pixels are stretched
+    ClampToEdge,
+}
+
+impl WrapMode {
+    /// get the opengl enum of the wrap mode
+    fn to_gl(self) -> i32 {
+        (match self {
+            WrapMode::Repeat => gl::REPEAT,
+            WrapMode::MirroredRepeat => gl::MIRRORED_REPEAT,
+            WrapMode::ClampToEdge => gl::CLAMP_TO_EDGE,
+        }) as i32
+    }
+}
+
+/// how a texture is filtered when it is drawn larger or smaller than its size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// blends the nearest pixels (and mipmaps) for smooth textures
+    #[default]
+    Linear,
+    /// takes the nearest pixel without mipmaps so pixel art stays crisp
+    Nearest,
+}
+
+/// how a texture is filtered and wrapped, given when the texture is loaded
+///
+/// # Example
+/// ```rust,ignore
+/// // a tiling floor seen at grazing angles
+/// let config = TextureConfig { anisotropy: 16.0, ..Default::default() };
+/// let floor = Texture::from_file_with_config("res/textures/floor.png", TextureType::Diffuse, config);
+///
+/// let sprite = Texture::from_file_with_config("res/sprites/hero.png", TextureType::Diffuse, TextureConfig::pixel_art());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureConfig {
+    /// generate mipmaps and blend between them (trilinear filtering) so distant textures don't shimmer
+    pub mipmaps: bool,
+    /// the anisotropic filtering level, 1 turns it off. clamped to [`Texture::max_anisotropy`] and ignored when the
+    /// driver doesn't support it
+    pub anisotropy: f32,
+    /// how the texture is sampled outside of the 0 to 1 uv range
+    pub wrap: WrapMode,
+    /// how the texture is filtered, [`FilterMode::Nearest`] skips the mipmaps and anisotropic filtering
+    pub filter: FilterMode,
+}
+
+impl TextureConfig {
+    /// nearest filtering without mipmaps for pixel art, the uvs still repeat
+    pub fn pixel_art() -> Self {
+        TextureConfig {
+            mipmaps: false,
+            anisotropy: 1.0,
+            wrap: WrapMode::Repeat,
+            filter: FilterMode::Nearest,
+        }
+    }
+
+    /// if the texture gets mipmaps, they are only used with linear filtering
+    fn uses_mipmaps(&self) -> bool {
+        self.mipmaps && self.filter == FilterMode::Linear
+    }
+}
+
+impl Default for TextureConfig {
+    /// mipmaps with 4x anisotropic filtering and repeating uvs
+    fn default() -> Self {
+        TextureConfig {
+            mipmaps: true,
+            anisotropy: 4.0,
+            wrap: WrapMode::Repeat,
+            filter: FilterMode::Linear,
+        }
+    }
+}
+
+/// the lod bias given to new textures, stored as the bits of an f32 (0 = 0.0)
+static DEFAULT_LOD_BIAS: AtomicU32 = AtomicU32::new(0);
+
+/// identifies a texture shared between loads, see [`Texture::cache_stats`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextureKey {
+    /// the image file, or the model file the image is embedded in
+    pub path: String,
+    /// the index of the image in the model file, `None` for image files
+    pub image: Option<usize>,
+    /// the type of texture
+    pub tex_type: TextureType,
+}
+
+/// the numbers of the shared texture cache, see [`Texture::cache_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextureCacheStats {
+    /// the shared textures that are still in use
+    pub textures: usize,
+    /// the estimated gpu memory of those textures in bytes
+    pub bytes: usize,
+    /// the loads that reused a texture
+    pub hits: usize,
+    /// the loads that had to upload a texture
+    pub misses: usize,
+}
+
+/// a shared texture and the config it was made with
+struct CacheEntry {
+    config: TextureConfig,
+    texture: Weak<Texture>,
+}
+
+/// the textures shared between loads. the cache only holds weak references so a texture is deleted as soon as the
+/// last model or sprite using it is dropped.
+#[derive(Default)]
+struct TextureCache {
+    entries: HashMap<TextureKey, CacheEntry>,
+    hits: usize,
+    misses: usize,
+}
+
+thread_local! {
+    /// the shared textures by path, image and type, opengl only runs on the main thread
+    static TEXTURE_CACHE: RefCell<TextureCache> = RefCell::new(TextureCache::default());
+}
+
 /// The Texture struct is used to create and manage textures
 #[derive(Clone, Debug)]
 pub struct Texture {
-    id: u32,
+    id: GlObject,
     /// type of texture
     pub tex_type: TextureType,
     _file_path: String,
@@ -46,6 +197,9 @@ pub struct Texture {
     height: i32,
     _format: u32,
     _bpp: i32,
+    /// if the texture has mipmaps, the lod bias only affects mipmapped textures
+    mipmapped: bool,
+    lod_bias: f32,
 }
 
 impl Texture {
@@ -95,18 +249,6 @@ impl Texture {
             //gl::ActiveTexture(gl::TEXTURE0 + slot);
             gl::BindTexture(gl::TEXTURE_2D, id);
 
-            // configure the way the image is resized in opengl
-            gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_MIN_FILTER,
-                gl::NEAREST_MIPMAP_LINEAR as i32,
-            );
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-
-            // set image to repeat
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-
             // let format = if bpp == 4 { gl::RGBA } else { gl::RGB };
 
             gl::TexImage2D(
@@ -121,19 +263,20 @@ impl Texture {
                 _local_buffer as *const std::ffi::c_void,
             );
 
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            // configure the way the image is resized in opengl
+            Self::configure(&TextureConfig::default());
 
             //gl::BindTexture(gl::TEXTURE_2D, 0);
 
             if !_local_buffer.is_null() {
                 stb_image::stbi_image_free(_local_buffer as *mut std::ffi::c_void);
             } else {
-                println!("Failed to load texture: {}", path);
+                log::warn!("failed to load texture: {}", path);
             }
         }
 
         Texture {
-            id,
+            id: GlObject::new(id, GlKind::Texture),
             tex_type,
             _file_path: path.to_string(),
             _local_buffer,
@@ -141,10 +284,12 @@ impl Texture {
             height,
             _format: format,
             _bpp: 0,
+            mipmapped: true,
+            lod_bias: Self::default_lod_bias(),
         }
     }
 
-    /// Creates a new texture from a gltf file
+    /// Creates a new texture from a gltf file with the default [`TextureConfig`]
     ///
     /// # Arguments
     /// - `pixel` - the pixel data
@@ -162,12 +307,42 @@ impl Texture {
         tex_type: TextureType,
         format: u32,
     ) -> Texture {
-        let mut bpp = 0;
-        if format == gl::RGB {
-            bpp = 3;
-        } else if format == gl::RGBA {
-            bpp = 4;
-        }
+        Self::load_from_gltf_with_config(
+            pixel,
+            width,
+            height,
+            tex_type,
+            format,
+            TextureConfig::default(),
+        )
+    }
+
+    /// Creates a new texture from pixel data with the given filtering and wrapping
+    ///
+    /// # Arguments
+    /// - `pixel` - the pixel data
+    /// - `width` - the width of the texture
+    /// - `height` - the height of the texture
+    /// - `tex_type` - the type of texture
+    /// - `format` - the format of the texture
+    /// - `config` - how the texture is filtered and wrapped
+    ///
+    /// # Returns
+    /// the texture
+    pub fn load_from_gltf_with_config(
+        pixel: &[u8],
+        width: u32,
+        height: u32,
+        tex_type: TextureType,
+        format: u32,
+        config: TextureConfig,
+    ) -> Texture {
+        let (bpp, internal_format) = match format {
+            gl::RED => (1, gl::R8),
+            gl::RG => (2, gl::RG8),
+            gl::RGBA => (4, gl::RGBA8),
+            _ => (3, gl::RGB8),
+        };
 
         unsafe {
             let mut id: u32 = 0;
@@ -175,27 +350,12 @@ impl Texture {
             //bind the texture
             gl::BindTexture(gl::TEXTURE_2D, id);
 
-            gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_MIN_FILTER,
-                gl::NEAREST_MIPMAP_LINEAR as i32,
-            );
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-
-            // set image to repeat
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-
-            let internal_format = if bpp == 4 {
-                gl::RGBA8 as i32
-            } else {
-                gl::RGB8 as i32
-            };
-
+            // rows of 1 to 3 channel images are not padded to 4 bytes
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                internal_format,
+                internal_format as i32,
                 width as i32,
                 height as i32,
                 0,
@@ -203,15 +363,26 @@ impl Texture {
                 gl::UNSIGNED_BYTE,
                 pixel.as_ptr() as *const std::ffi::c_void,
             );
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
 
-            //generate mipmaps
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            // grayscale images are sampled as gray instead of red, with the second channel as alpha
+            let swizzle = match format {
+                gl::RED => Some([gl::RED, gl::RED, gl::RED, gl::ONE]),
+                gl::RG => Some([gl::RED, gl::RED, gl::RED, gl::GREEN]),
+                _ => None,
+            };
+            if let Some(swizzle) = swizzle {
+                let swizzle = swizzle.map(|channel| channel as i32);
+                gl::TexParameteriv(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_RGBA, swizzle.as_ptr());
+            }
+
+            Self::configure(&config);
 
             //unbind the texture
             gl::BindTexture(gl::TEXTURE_2D, 0);
 
             Texture {
-                id,
+                id: GlObject::new(id, GlKind::Texture),
                 tex_type,
                 _file_path: String::new(),
                 _local_buffer: std::ptr::null_mut(),
@@ -219,10 +390,324 @@ impl Texture {
                 height: height as i32,
                 _format: format,
                 _bpp: bpp,
+                mipmapped: config.uses_mipmaps(),
+                lod_bias: Self::default_lod_bias(),
+            }
+        }
+    }
+
+    /// Loads a texture from an image file (png, jpg, tga, bmp, ...) with the default [`TextureConfig`] and shares it
+    /// with every other load of the same file and type.
+    ///
+    /// the first load decodes and uploads the image, later loads return the same texture without touching the file.
+    /// the cache only holds the texture while something uses it, see [`Texture::cache_stats`].
+    ///
+    /// # Arguments
+    /// - `path` - the path to the image
+    /// - `tex_type` - the type of texture
+    ///
+    /// # Returns
+    /// the shared texture or `None` if the image could not be loaded
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let albedo = Texture::load_from_file("res/textures/tiles.png", TextureType::Diffuse).unwrap();
+    /// let (vertices, indices) = shapes::torus(1.0, 0.25, 32, 16);
+    /// let mesh = Mesh::new(vertices, indices, vec![albedo], MaterialProperties::default());
+    /// let model = Model::from_mesh(mesh);
+    /// ```
+    pub fn load_from_file(path: &str, tex_type: TextureType) -> Option<Rc<Texture>> {
+        let key = TextureKey {
+            path: path.to_string(),
+            image: None,
+            tex_type: tex_type.clone(),
+        };
+        Self::try_shared(key, TextureConfig::default(), || {
+            Self::from_file(path, tex_type)
+        })
+    }
+
+    /// gets a shared texture or creates it, the texture is reused while any clone of the returned `Rc` is alive
+    ///
+    /// # Arguments
+    /// - `key` - what the texture was loaded from
+    /// - `config` - the config of the texture, a cached texture with another config is not reused
+    /// - `create` - makes the texture when it is not cached
+    ///
+    /// # Returns
+    /// the shared texture
+    pub(crate) fn shared<F>(key: TextureKey, config: TextureConfig, create: F) -> Rc<Texture>
+    where
+        F: FnOnce() -> Texture,
+    {
+        Self::try_shared(key, config, || Some(create())).expect("the texture was just created")
+    }
+
+    /// like [`Texture::shared`] with a `create` that can fail, nothing is cached when it returns `None`
+    fn try_shared<F>(key: TextureKey, config: TextureConfig, create: F) -> Option<Rc<Texture>>
+    where
+        F: FnOnce() -> Option<Texture>,
+    {
+        let cached = TEXTURE_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let texture = cache
+                .entries
+                .get(&key)
+                .filter(|entry| entry.config == config)
+                .and_then(|entry| entry.texture.upgrade());
+            if texture.is_some() {
+                cache.hits += 1;
+            }
+            texture
+        });
+        if cached.is_some() {
+            return cached;
+        }
+
+        // created outside of the borrow so `create` can load other shared textures
+        let texture = Rc::new(create()?);
+        TEXTURE_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.misses += 1;
+            cache
+                .entries
+                .retain(|_, entry| entry.texture.strong_count() > 0);
+            cache.entries.insert(
+                key,
+                CacheEntry {
+                    config,
+                    texture: Rc::downgrade(&texture),
+                },
+            );
+        });
+        Some(texture)
+    }
+
+    /// Forgets every shared texture so the next load reads the file again. textures still in use are kept alive by
+    /// their users and deleted when they drop them.
+    pub fn clear_file_cache() {
+        TEXTURE_CACHE.with(|cache| cache.borrow_mut().entries.clear());
+    }
+
+    /// Gets the numbers of the cache shared by [`Texture::load_from_file`] and the model loaders, useful to see how much
+    /// gpu memory the textures take
+    ///
+    /// # Returns
+    /// the live textures, their estimated size and the hits and misses since the start
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let stats = Texture::cache_stats();
+    /// log::info!("{} textures, {} MB", stats.textures, stats.bytes / (1024 * 1024));
+    /// ```
+    pub fn cache_stats() -> TextureCacheStats {
+        TEXTURE_CACHE.with(|cache| {
+            let cache = cache.borrow();
+            let mut stats = TextureCacheStats {
+                hits: cache.hits,
+                misses: cache.misses,
+                ..Default::default()
+            };
+            for texture in cache
+                .entries
+                .values()
+                .filter_map(|entry| entry.texture.upgrade())
+            {
+                stats.textures += 1;
+                stats.bytes += texture.memory_size();
             }
+            stats
+        })
+    }
+
+    /// estimates the gpu memory of the texture from its size and channels, a third more with mipmaps
+    pub fn memory_size(&self) -> usize {
+        let channels = match self._format {
+            gl::RED => 1,
+            gl::RG => 2,
+            gl::RGB => 3,
+            _ => 4,
+        };
+        let size = self.width.max(0) as usize * self.height.max(0) as usize * channels;
+        if self.mipmapped {
+            size * 4 / 3
+        } else {
+            size
         }
     }
 
+    /// get a texture that samples the same gpu image as another type, for files that pack several maps into the
+    /// channels of one image (like the occlusion, roughness and metallic of the ORM layout)
+    ///
+    /// # Arguments
+    /// - `tex_type` - the type of the new texture
+    ///
+    /// # Returns
+    /// a texture sharing the image, it is only deleted once both are dropped
+    pub fn shared_as(&self, tex_type: TextureType) -> Texture {
+        Texture {
+            tex_type,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new texture from an image file with the default [`TextureConfig`]
+    ///
+    /// # Arguments
+    /// - `path` - the path to the image
+    /// - `tex_type` - the type of texture
+    ///
+    /// # Returns
+    /// the texture or `None` if the image could not be loaded
+    pub fn from_file(path: &str, tex_type: TextureType) -> Option<Texture> {
+        Self::from_file_with_config(path, tex_type, TextureConfig::default())
+    }
+
+    /// Creates a new texture from an image file, the texture keeps the channels of the image (gray, gray and alpha,
+    /// rgb or rgba)
+    ///
+    /// # Arguments
+    /// - `path` - the path to the image
+    /// - `tex_type` - the type of texture
+    /// - `config` - how the texture is filtered and wrapped
+    ///
+    /// # Returns
+    /// the texture or `None` if the image could not be loaded
+    pub fn from_file_with_config(
+        path: &str,
+        tex_type: TextureType,
+        config: TextureConfig,
+    ) -> Option<Texture> {
+        let c_path = CString::new(path).ok()?;
+        let mut width = 0;
+        let mut height = 0;
+        let mut channels = 0;
+
+        unsafe {
+            stb_image::stbi_set_flip_vertically_on_load(1);
+            let pixels =
+                stb_image::stbi_load(c_path.as_ptr(), &mut width, &mut height, &mut channels, 0);
+            if pixels.is_null() {
+                return None;
+            }
+
+            let format = match channels {
+                1 => gl::RED,
+                2 => gl::RG,
+                3 => gl::RGB,
+                _ => gl::RGBA,
+            };
+            let data = std::slice::from_raw_parts(pixels, (width * height * channels) as usize);
+            let mut texture = Self::load_from_gltf_with_config(
+                data,
+                width as u32,
+                height as u32,
+                tex_type,
+                format,
+                config,
+            );
+            texture._file_path = path.to_string();
+
+            stb_image::stbi_image_free(pixels as *mut std::ffi::c_void);
+            Some(texture)
+        }
+    }
+
+    /// sets the filtering and wrapping of the bound texture after its pixels were uploaded
+    ///
+    /// # Safety
+    /// a texture has to be bound to `GL_TEXTURE_2D`
+    unsafe fn configure(config: &TextureConfig) {
+        let mipmaps = config.uses_mipmaps();
+        if mipmaps {
+            gl::GenerateMipmap(gl::TEXTURE_2D);
+            gl::TexParameterf(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_LOD_BIAS,
+                Self::default_lod_bias(),
+            );
+        }
+        Self::set_filter_parameters(config.filter, mipmaps);
+        Self::set_wrap_parameters(config.wrap);
+
+        let max_anisotropy = Self::max_anisotropy();
+        if max_anisotropy > 1.0 && config.filter == FilterMode::Linear {
+            gl::TexParameterf(
+                gl::TEXTURE_2D,
+                TEXTURE_MAX_ANISOTROPY,
+                config.anisotropy.clamp(1.0, max_anisotropy),
+            );
+        }
+    }
+
+    /// sets the min and mag filter of the bound texture
+    ///
+    /// # Safety
+    /// a texture has to be bound to `GL_TEXTURE_2D`
+    unsafe fn set_filter_parameters(filter: FilterMode, mipmapped: bool) {
+        let (min, mag) = match filter {
+            FilterMode::Linear if mipmapped => (gl::LINEAR_MIPMAP_LINEAR, gl::LINEAR),
+            FilterMode::Linear => (gl::LINEAR, gl::LINEAR),
+            FilterMode::Nearest => (gl::NEAREST, gl::NEAREST),
+        };
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mag as i32);
+    }
+
+    /// sets the wrapping of the bound texture
+    ///
+    /// # Safety
+    /// a texture has to be bound to `GL_TEXTURE_2D`
+    unsafe fn set_wrap_parameters(wrap: WrapMode) {
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap.to_gl());
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap.to_gl());
+    }
+
+    /// changes how the texture is sampled outside of the 0 to 1 uv range
+    ///
+    /// textures loaded with [`Texture::load_from_file`] or from the same model file are shared, this changes the
+    /// texture everywhere it is used.
+    ///
+    /// # Arguments
+    /// - `wrap` - the new wrap mode
+    pub fn set_wrap(&self, wrap: WrapMode) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id.id());
+            Self::set_wrap_parameters(wrap);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    /// changes how the texture is filtered. [`FilterMode::Nearest`] ignores the mipmaps, switching back to
+    /// [`FilterMode::Linear`] uses them again if the texture was loaded with mipmaps.
+    ///
+    /// textures loaded with [`Texture::load_from_file`] or from the same model file are shared, this changes the
+    /// texture everywhere it is used.
+    ///
+    /// # Arguments
+    /// - `filter` - the new filter mode
+    pub fn set_filter(&self, filter: FilterMode) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id.id());
+            Self::set_filter_parameters(filter, self.mipmapped);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    /// Gets the highest anisotropic filtering level the driver supports
+    ///
+    /// # Returns
+    /// the maximum anisotropy or 1.0 if anisotropic filtering is not supported
+    pub fn max_anisotropy() -> f32 {
+        let mut max = 0.0;
+        unsafe {
+            gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max);
+            // drivers without the extension report an invalid enum, don't leave it for the next error check
+            gl::GetError();
+        }
+        max.max(1.0)
+    }
+
     /// Binds the texture to a texture unit in the shader
     ///
     /// # Arguments
@@ -241,7 +726,7 @@ impl Texture {
     pub fn bind(&self, unit: u32) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0 + unit);
-            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::BindTexture(gl::TEXTURE_2D, self.id.id());
         }
     }
 
@@ -267,4 +752,115 @@ impl Texture {
     pub fn get_height(&self) -> i32 {
         self.height
     }
+
+    /// sets the mipmap lod bias of the texture. positive values pick smaller mips (softer), negative values pick larger
+    /// mips (sharper). does nothing if the texture has no mipmaps.
+    ///
+    /// # Arguments
+    /// - `bias` - the bias added to the mip level
+    pub fn set_lod_bias(&mut self, bias: f32) {
+        if !self.mipmapped {
+            return;
+        }
+
+        self.lod_bias = bias;
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id.id());
+            gl::TexParameterf(gl::TEXTURE_2D, gl::TEXTURE_LOD_BIAS, bias);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    /// Gets the mipmap lod bias of the texture
+    ///
+    /// # Returns
+    /// the lod bias of the texture
+    pub fn get_lod_bias(&self) -> f32 {
+        self.lod_bias
+    }
+
+    /// sets the lod bias given to textures created after this call. existing textures keep their bias.
+    ///
+    /// # Arguments
+    /// - `bias` - the default bias added to the mip level
+    pub fn set_default_lod_bias(bias: f32) {
+        DEFAULT_LOD_BIAS.store(bias.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Gets the lod bias given to new textures
+    ///
+    /// # Returns
+    /// the default lod bias
+    pub fn default_lod_bias() -> f32 {
+        f32::from_bits(DEFAULT_LOD_BIAS.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a texture without a gpu object, the cache only looks at its size
+    fn texture(width: i32, height: i32) -> Texture {
+        Texture {
+            id: GlObject::new(0, GlKind::Texture),
+            tex_type: TextureType::Diffuse,
+            _file_path: String::new(),
+            _local_buffer: std::ptr::null_mut(),
+            width,
+            height,
+            _format: gl::RGBA,
+            _bpp: 4,
+            mipmapped: false,
+            lod_bias: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_shared_cache() {
+        let key = TextureKey {
+            path: "model.glb".to_string(),
+            image: Some(0),
+            tex_type: TextureType::Diffuse,
+        };
+        let config = TextureConfig::default();
+
+        let first = Texture::shared(key.clone(), config, || texture(4, 4));
+        let second = Texture::shared(key.clone(), config, || {
+            unreachable!("the texture is cached")
+        });
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(
+            Texture::cache_stats(),
+            TextureCacheStats {
+                textures: 1,
+                bytes: 64,
+                hits: 1,
+                misses: 1,
+            }
+        );
+
+        // another config gets its own texture
+        let pixel_art = Texture::shared(key.clone(), TextureConfig::pixel_art(), || texture(4, 4));
+        assert!(!Rc::ptr_eq(&first, &pixel_art));
+
+        // the cache doesn't keep the textures alive
+        drop((first, second, pixel_art));
+        assert_eq!(Texture::cache_stats().textures, 0);
+        let reloaded = Texture::shared(key, config, || texture(2, 2));
+        assert_eq!(reloaded.get_width(), 2);
+    }
+
+    #[test]
+    fn test_shared_as() {
+        // no context on the test thread, so the name is never deleted
+        let packed = Texture {
+            id: GlObject::new(7, GlKind::Texture),
+            ..texture(4, 4)
+        };
+        let occlusion = packed.shared_as(TextureType::Occlusion);
+        assert_eq!(occlusion.tex_type, TextureType::Occlusion);
+        assert_eq!(packed.tex_type, TextureType::Diffuse);
+        assert_eq!(occlusion.id.id(), 7);
+    }
 }
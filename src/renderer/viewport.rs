@@ -0,0 +1,171 @@
+//! viewports draw the scene from several cameras into parts of the window, for split screen and picture in picture.
+//!
+//! a viewport is a camera path and a rectangle in fractions of the window so it follows the window size. while there
+//! are viewports they replace the full screen pass of the active camera, each camera gets the aspect ratio of its
+//! rectangle and only the depth of the rectangle is cleared before it is drawn, so the color of the background UI (or of
+//! an earlier viewport) shows where the scene draws nothing. the overlay UI is drawn once over the whole window.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use quaturn::renderer::viewport::ViewportRect;
+//!
+//! // two players side by side
+//! engine.add_viewport("player_1/camera", ViewportRect::left_half());
+//! engine.add_viewport("player_2/camera", ViewportRect::right_half());
+//!
+//! // a minimap in the top right corner drawn over the main view
+//! engine.add_viewport("main_camera", ViewportRect::full());
+//! engine.add_viewport("minimap_camera", ViewportRect::new(0.75, 0.0, 0.25, 0.25));
+//! ```
+
+/// a rectangle of the window in fractions of its size, (0, 0) is the top left corner and (1, 1) the bottom right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportRect {
+    /// the left edge from 0 to 1
+    pub x: f32,
+    /// the top edge from 0 to 1
+    pub y: f32,
+    /// the width from 0 to 1
+    pub width: f32,
+    /// the height from 0 to 1
+    pub height: f32,
+}
+
+impl ViewportRect {
+    /// creates a rectangle in fractions of the window size
+    ///
+    /// # Arguments
+    /// - `x` - the left edge, 0 is the left of the window
+    /// - `y` - the top edge, 0 is the top of the window
+    /// - `width` - the width, 1 is the width of the window
+    /// - `height` - the height, 1 is the height of the window
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> ViewportRect {
+        ViewportRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// the whole window
+    pub fn full() -> ViewportRect {
+        Self::new(0.0, 0.0, 1.0, 1.0)
+    }
+
+    /// the left half of the window
+    pub fn left_half() -> ViewportRect {
+        Self::new(0.0, 0.0, 0.5, 1.0)
+    }
+
+    /// the right half of the window
+    pub fn right_half() -> ViewportRect {
+        Self::new(0.5, 0.0, 0.5, 1.0)
+    }
+
+    /// the top half of the window
+    pub fn top_half() -> ViewportRect {
+        Self::new(0.0, 0.0, 1.0, 0.5)
+    }
+
+    /// the bottom half of the window
+    pub fn bottom_half() -> ViewportRect {
+        Self::new(0.0, 0.5, 1.0, 0.5)
+    }
+
+    /// converts the rectangle to pixels in opengl window coordinates, where y starts at the bottom. the edges are
+    /// rounded so rectangles that touch share the edge without a gap.
+    ///
+    /// # Arguments
+    /// - `window_width` - the width of the framebuffer in pixels
+    /// - `window_height` - the height of the framebuffer in pixels
+    ///
+    /// # Returns
+    /// the x, y, width and height in pixels, clamped to the window
+    pub fn to_pixels(&self, window_width: i32, window_height: i32) -> [i32; 4] {
+        let edge =
+            |fraction: f32, size: i32| (fraction.clamp(0.0, 1.0) * size as f32).round() as i32;
+        let left = edge(self.x, window_width);
+        let right = edge(self.x + self.width, window_width);
+        let top = edge(self.y, window_height);
+        let bottom = edge(self.y + self.height, window_height);
+        [
+            left,
+            window_height - bottom,
+            (right - left).max(0),
+            (bottom - top).max(0),
+        ]
+    }
+}
+
+/// a camera drawn into a part of the window, see the [module docs](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    camera_path: String,
+    rect: ViewportRect,
+}
+
+impl Viewport {
+    /// creates a viewport of the camera at a path
+    ///
+    /// # Arguments
+    /// - `camera_path` - the path of the camera like `"player_1/camera"`
+    /// - `rect` - the part of the window it is drawn to
+    pub fn new(camera_path: &str, rect: ViewportRect) -> Viewport {
+        Viewport {
+            camera_path: camera_path.to_string(),
+            rect,
+        }
+    }
+
+    /// get the path of the camera
+    pub fn camera_path(&self) -> &str {
+        &self.camera_path
+    }
+
+    /// get the part of the window the camera is drawn to
+    pub fn rect(&self) -> ViewportRect {
+        self.rect
+    }
+
+    /// sets the part of the window the camera is drawn to
+    ///
+    /// # Arguments
+    /// - `rect` - the rectangle in fractions of the window
+    pub fn set_rect(&mut self, rect: ViewportRect) -> &mut Self {
+        self.rect = rect;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pixels() {
+        assert_eq!(ViewportRect::full().to_pixels(800, 600), [0, 0, 800, 600]);
+
+        // the halves share the middle column without a gap
+        let left = ViewportRect::left_half().to_pixels(801, 600);
+        let right = ViewportRect::right_half().to_pixels(801, 600);
+        assert_eq!(left[0] + left[2], right[0]);
+        assert_eq!(right[0] + right[2], 801);
+
+        // y is flipped to start at the bottom
+        assert_eq!(
+            ViewportRect::top_half().to_pixels(800, 600),
+            [0, 300, 800, 300]
+        );
+        assert_eq!(
+            ViewportRect::new(0.75, 0.0, 0.25, 0.25).to_pixels(800, 600),
+            [600, 450, 200, 150]
+        );
+
+        // rectangles outside the window are clamped
+        assert_eq!(
+            ViewportRect::new(0.9, 0.0, 0.5, 1.0).to_pixels(100, 100),
+            [90, 0, 10, 100]
+        );
+    }
+}
@@ -1,6 +1,30 @@
 use nalgebra_glm as glm;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone)]
+/// the reason a hex color string could not be parsed, see [`Color::from_hex_str`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// the string does not have 6 or 8 digits after the optional `#`
+    InvalidLength(usize),
+    /// the string contains a character that is not a hex digit
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::InvalidLength(length) => {
+                write!(f, "expected 6 or 8 hex digits but got {}", length)
+            }
+            ColorParseError::InvalidDigit(digit) => write!(f, "'{}' is not a hex digit", digit),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -57,6 +81,166 @@ impl Color {
     }
 }
 
+impl Color {
+    /// parses a color written as `"#RRGGBB"` or `"#RRGGBBAA"` like in css, the `#` is optional
+    ///
+    /// # Arguments
+    /// - `hex` - the hex string, upper or lower case
+    ///
+    /// # Returns
+    /// the color or the reason the string is not a valid hex color
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let orange = Color::from_hex_str("#FF8040")?;
+    /// let glass = Color::from_hex_str("88CCFF80")?;
+    /// ```
+    pub fn from_hex_str(hex: &str) -> Result<Color, ColorParseError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if let Some(digit) = digits.chars().find(|digit| !digit.is_ascii_hexdigit()) {
+            return Err(ColorParseError::InvalidDigit(digit));
+        }
+
+        let value = u32::from_str_radix(digits, 16);
+        match (digits.len(), value) {
+            (6, Ok(value)) => Ok(Color::from_hex(value)),
+            // from_hex reads values that fit in 24 bits as rgb so the channels are split here
+            (8, Ok(value)) => {
+                let [r, g, b, a] = value.to_be_bytes();
+                Ok(Color::from_8bit_rgba(r, g, b, a))
+            }
+            (length, _) => Err(ColorParseError::InvalidLength(length)),
+        }
+    }
+
+    /// creates a color from hue, saturation and value with alpha 1.0
+    ///
+    /// # Arguments
+    /// - `h` - the hue in degrees, 0 is red, 120 green and 240 blue. wraps around outside 0 to 360
+    /// - `s` - the saturation from 0.0 (gray) to 1.0 (full color)
+    /// - `v` - the value from 0.0 (black) to 1.0 (full brightness)
+    ///
+    /// # Returns
+    /// the normalized color
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let s = s.clamp(0.0, 1.0);
+        let v = v.clamp(0.0, 1.0);
+
+        let chroma = v * s;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = v - chroma;
+        Color::from_normalized(r + m, g + m, b + m, 1.0)
+    }
+
+    /// get the hue in degrees (0 to 360), saturation and value of the color, the inverse of
+    /// [`from_hsv`](Color::from_hsv). the alpha is ignored.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let h = if chroma == 0.0 {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / chroma + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / chroma + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { chroma / max };
+
+        (h, s, max)
+    }
+
+    /// get the red, green and blue channels from 0 to 255, the inverse of [`from_8bit_rgb`](Color::from_8bit_rgb).
+    /// channels outside 0.0 to 1.0 are clamped.
+    pub fn to_8bit_rgb(&self) -> (u8, u8, u8) {
+        let (r, g, b, _) = self.to_8bit_rgba();
+        (r, g, b)
+    }
+
+    /// get the channels from 0 to 255, the inverse of [`from_8bit_rgba`](Color::from_8bit_rgba). channels outside
+    /// 0.0 to 1.0 are clamped.
+    pub fn to_8bit_rgba(&self) -> (u8, u8, u8, u8) {
+        let to_8bit = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        (
+            to_8bit(self.r),
+            to_8bit(self.g),
+            to_8bit(self.b),
+            to_8bit(self.a),
+        )
+    }
+
+    /// interpolates linearly between this color and another one, alpha included
+    ///
+    /// # Arguments
+    /// - `other` - the color at `t = 1.0`
+    /// - `t` - the interpolation factor, 0.0 gives this color and 1.0 gives `other`
+    ///
+    /// # Returns
+    /// the interpolated color
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color::from_normalized(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    /// approximates the color of a black body light source at the given temperature.
+    ///
+    /// candle light is around 1900K, sunrise 2000-3000K, noon daylight 5500-6500K and overcast sky 7000K+.
+    ///
+    /// # Arguments
+    /// - `kelvin` - the color temperature in kelvin, clamped between 1000 and 40000
+    ///
+    /// # Returns
+    /// the normalized color with alpha 1.0
+    pub fn from_kelvin(kelvin: f32) -> Color {
+        // curve fit of the black body spectrum by Tanner Helland
+        let temp = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if temp <= 66.0 {
+            255.0
+        } else {
+            329.69873 * (temp - 60.0).powf(-0.13320476)
+        };
+
+        let g = if temp <= 66.0 {
+            99.4708 * temp.ln() - 161.11957
+        } else {
+            288.12216 * (temp - 60.0).powf(-0.07551485)
+        };
+
+        let b = if temp >= 66.0 {
+            255.0
+        } else if temp <= 19.0 {
+            0.0
+        } else {
+            138.51773 * (temp - 10.0).ln() - 305.0448
+        };
+
+        Color::from_normalized(
+            r.clamp(0.0, 255.0) / 255.0,
+            g.clamp(0.0, 255.0) / 255.0,
+            b.clamp(0.0, 255.0) / 255.0,
+            1.0,
+        )
+    }
+}
+
 impl From<Color> for glm::Vec4 {
     fn from(color: Color) -> Self {
         glm::vec4(color.r, color.g, color.b, color.a)
@@ -74,6 +258,15 @@ impl From<glm::Vec4> for Color {
     }
 }
 
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    /// parses a hex color like `"#FF8040"`, see [`Color::from_hex_str`]
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Color::from_hex_str(hex)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,6 +314,70 @@ mod tests {
         assert_eq!(color.a, 1.0); // Default alpha is 255 (1.0 normalized)
     }
 
+    #[test]
+    fn test_from_hex_str() {
+        let color = Color::from_hex_str("#FF8040").unwrap();
+        assert_eq!(color, Color::from_hex(0xFF8040));
+        assert_eq!(color.to_8bit_rgb(), (255, 128, 64));
+
+        // alpha is read even when the red channel is 0
+        let color: Color = "0080ffa0".parse().unwrap();
+        assert_eq!(color.to_8bit_rgba(), (0, 128, 255, 160));
+
+        assert_eq!(
+            Color::from_hex_str("#FF80"),
+            Err(ColorParseError::InvalidLength(4))
+        );
+        assert_eq!(
+            Color::from_hex_str("#FF80G0"),
+            Err(ColorParseError::InvalidDigit('G'))
+        );
+        assert_eq!(
+            Color::from_hex_str("#+F8040"),
+            Err(ColorParseError::InvalidDigit('+'))
+        );
+    }
+
+    #[test]
+    fn test_hsv() {
+        let red = Color::from_hsv(0.0, 1.0, 1.0);
+        assert_eq!(red.to_8bit_rgb(), (255, 0, 0));
+        assert_eq!(Color::from_hsv(120.0, 1.0, 1.0).to_8bit_rgb(), (0, 255, 0));
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 0.5).to_8bit_rgb(), (0, 0, 128));
+        assert_eq!(
+            Color::from_hsv(90.0, 0.0, 1.0).to_8bit_rgb(),
+            (255, 255, 255)
+        );
+
+        let (h, s, v) = Color::from_8bit_rgb(255, 128, 64).to_hsv();
+        let round_trip = Color::from_hsv(h, s, v);
+        assert_eq!(round_trip.to_8bit_rgb(), (255, 128, 64));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let black = Color::from_normalized(0.0, 0.0, 0.0, 0.0);
+        let white = Color::from_normalized(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(black.lerp(&white, 0.0), black);
+        assert_eq!(black.lerp(&white, 1.0), white);
+        assert_eq!(
+            black.lerp(&white, 0.25),
+            Color::from_normalized(0.25, 0.25, 0.25, 0.25)
+        );
+    }
+
+    #[test]
+    fn test_from_kelvin() {
+        // daylight is close to white
+        let noon = Color::from_kelvin(6600.0);
+        assert!(noon.r > 0.95 && noon.g > 0.95 && noon.b > 0.95);
+
+        // low temperatures are warm
+        let sunrise = Color::from_kelvin(2000.0);
+        assert_eq!(sunrise.r, 1.0);
+        assert!(sunrise.g < sunrise.r && sunrise.b < sunrise.g);
+    }
+
     #[test]
     fn test_conversion_to_vec4() {
         let color = Color {
@@ -7,9 +7,6 @@ pub struct Debug {}
 
 impl Debug {
     pub fn print(message: &str) {
-        #[cfg(debug_assertions)]
-        {
-            println!("{}", message);
-        }
+        log::debug!("{}", message);
     }
 }
@@ -0,0 +1,78 @@
+//! conversions from physical light units to the intensity used by the shaders.
+//!
+//! ## Conversion
+//! the shaders use a unitless intensity multiplier. an intensity of 1.0 matches a point light of [`REFERENCE_CANDELA`]
+//! (an 800 lumen bulb, roughly a 60W incandescent) so:
+//! - point lights: `intensity = lumens / 4π / REFERENCE_CANDELA` (the flux spread evenly over the sphere)
+//! - directional lights: `intensity = lux / REFERENCE_CANDELA` (1 candela lights a surface 1 unit away with 1 lux)
+//!
+//! both lights use the same reference so their relative brightness matches the real world. real world ratios are large
+//! (direct sunlight is ~100 000 lux, over 1500x the reference) so use exposure/tonemapping when mixing them.
+//!
+//! ## Example
+//! ```rust
+//! use quaturn::utils::light_units;
+//!
+//! // an 800 lumen bulb is the reference brightness
+//! assert!((light_units::lumens_to_intensity(800.0) - 1.0).abs() < 1e-5);
+//! ```
+
+/// the luminous intensity in candela that maps to a shader intensity of 1.0 (800 lumens spread over a sphere)
+pub const REFERENCE_CANDELA: f32 = 800.0 / (4.0 * std::f32::consts::PI);
+
+/// converts the luminous flux of a point light to its luminous intensity
+///
+/// # Arguments
+/// - `lumens` - the total light emitted in every direction
+///
+/// # Returns
+/// the luminous intensity in candela
+pub fn lumens_to_candela(lumens: f32) -> f32 {
+    lumens / (4.0 * std::f32::consts::PI)
+}
+
+/// converts a luminous intensity to the shader intensity
+///
+/// # Arguments
+/// - `candela` - the luminous intensity
+///
+/// # Returns
+/// the shader intensity
+pub fn candela_to_intensity(candela: f32) -> f32 {
+    candela / REFERENCE_CANDELA
+}
+
+/// converts the luminous flux of a point light to the shader intensity
+///
+/// # Arguments
+/// - `lumens` - the total light emitted in every direction
+///
+/// # Returns
+/// the shader intensity
+pub fn lumens_to_intensity(lumens: f32) -> f32 {
+    candela_to_intensity(lumens_to_candela(lumens))
+}
+
+/// converts the illuminance of a directional light to the shader intensity
+///
+/// # Arguments
+/// - `lux` - the light arriving on a surface facing the light
+///
+/// # Returns
+/// the shader intensity
+pub fn lux_to_intensity(lux: f32) -> f32 {
+    lux / REFERENCE_CANDELA
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_and_directional_match() {
+        // a point light lights a surface 1 unit away with its candela value in lux
+        let candela = lumens_to_candela(1600.0);
+        assert!((lumens_to_intensity(1600.0) - lux_to_intensity(candela)).abs() < 1e-5);
+        assert!((lumens_to_intensity(1600.0) - 2.0).abs() < 1e-5);
+    }
+}
@@ -1,2 +1,4 @@
+pub mod color;
 pub mod debug;
-pub mod color;
\ No newline at end of file
+pub mod light_units;
+pub mod timer;
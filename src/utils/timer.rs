@@ -0,0 +1,216 @@
+//! a countdown that fires once or repeatedly, driven by the frame time.
+//!
+//! comparing `start_time.elapsed()` against a whole second almost never matches exactly, a timer adds up the frame
+//! times instead and fires on the first frame the interval is reached.
+//!
+//! ## Example
+//! ```rust
+//! use quaturn::utils::timer::Timer;
+//! use std::time::Duration;
+//!
+//! let mut title_timer = Timer::new(Duration::from_secs(1));
+//!
+//! // in a behavior: title_timer.tick(context.frame.time_delta)
+//! assert!(!title_timer.tick(Duration::from_millis(600)));
+//! assert!(title_timer.tick(Duration::from_millis(600)));
+//! assert!(!title_timer.tick(Duration::from_millis(600)));
+//! ```
+
+use std::time::Duration;
+
+/// what a [`Timer`] does after it fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimerMode {
+    /// starts over and fires again every interval
+    #[default]
+    Repeating,
+    /// fires once and stays finished until it is reset
+    OneShot,
+}
+
+/// fires after an interval of time, see the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timer {
+    duration: Duration,
+    elapsed: Duration,
+    mode: TimerMode,
+    /// if a one shot timer already fired
+    finished: bool,
+    paused: bool,
+}
+
+impl Timer {
+    /// creates a repeating timer
+    ///
+    /// # Arguments
+    /// - `duration` - the time between firing
+    ///
+    /// # Returns
+    /// the new timer
+    pub fn new(duration: Duration) -> Timer {
+        Self::repeating(duration)
+    }
+
+    /// creates a timer that fires every interval
+    ///
+    /// # Arguments
+    /// - `duration` - the time between firing
+    pub fn repeating(duration: Duration) -> Timer {
+        Timer {
+            duration,
+            elapsed: Duration::ZERO,
+            mode: TimerMode::Repeating,
+            finished: false,
+            paused: false,
+        }
+    }
+
+    /// creates a timer that fires once, for delays and cooldowns
+    ///
+    /// # Arguments
+    /// - `duration` - the time until it fires
+    pub fn one_shot(duration: Duration) -> Timer {
+        Timer {
+            mode: TimerMode::OneShot,
+            ..Self::repeating(duration)
+        }
+    }
+
+    /// advances the timer, call it once per frame with the frame time
+    ///
+    /// a repeating timer fires once even when the time covers several intervals and keeps the leftover time, so it
+    /// doesn't drift.
+    ///
+    /// # Arguments
+    /// - `delta` - the time that passed, usually `context.frame.time_delta`
+    ///
+    /// # Returns
+    /// true if the timer fired during this tick
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        if self.paused || self.finished {
+            return false;
+        }
+
+        self.elapsed += delta;
+        if self.elapsed < self.duration {
+            return false;
+        }
+
+        match self.mode {
+            TimerMode::Repeating if self.duration.is_zero() => self.elapsed = Duration::ZERO,
+            TimerMode::Repeating => {
+                let leftover = self.elapsed.as_nanos() % self.duration.as_nanos();
+                self.elapsed = Duration::from_nanos(leftover as u64);
+            }
+            TimerMode::OneShot => {
+                self.elapsed = self.duration;
+                self.finished = true;
+            }
+        }
+        true
+    }
+
+    /// starts the timer over, also lets a finished one shot timer fire again
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+    }
+
+    /// stops the timer from advancing until [`resume`](Timer::resume)
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// lets a paused timer advance again
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// check if the timer is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// check if a one shot timer fired, always false for repeating timers
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// get the mode of the timer
+    pub fn get_mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// get the time between firing
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// sets the time between firing, the time that already passed is kept
+    ///
+    /// # Arguments
+    /// - `duration` - the new interval
+    pub fn set_duration(&mut self, duration: Duration) -> &mut Self {
+        self.duration = duration;
+        self
+    }
+
+    /// get the time since the timer started or last fired
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// get the time until the timer fires
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed)
+    }
+
+    /// get how far the timer is from 0 (just started) to 1 (about to fire), for progress bars
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeating_timer() {
+        let mut timer = Timer::new(Duration::from_millis(100));
+        assert!(!timer.tick(Duration::from_millis(60)));
+        assert!((timer.fraction() - 0.6).abs() < 1e-5);
+
+        // the leftover time counts towards the next interval
+        assert!(timer.tick(Duration::from_millis(60)));
+        assert_eq!(timer.elapsed(), Duration::from_millis(20));
+        assert!(!timer.tick(Duration::from_millis(60)));
+        assert!(timer.tick(Duration::from_millis(20)));
+
+        // a long frame fires once
+        assert!(timer.tick(Duration::from_millis(350)));
+        assert_eq!(timer.remaining(), Duration::from_millis(50));
+
+        timer.pause();
+        assert!(!timer.tick(Duration::from_secs(1)));
+        timer.resume();
+        assert!(timer.tick(Duration::from_millis(50)));
+        assert!(!timer.is_finished());
+    }
+
+    #[test]
+    fn test_one_shot_timer() {
+        let mut timer = Timer::one_shot(Duration::from_millis(100));
+        assert!(timer.tick(Duration::from_millis(150)));
+        assert!(timer.is_finished());
+        assert!(!timer.tick(Duration::from_millis(150)));
+        assert_eq!(timer.fraction(), 1.0);
+
+        timer.reset();
+        assert!(!timer.tick(Duration::from_millis(50)));
+        assert!(timer.tick(Duration::from_millis(50)));
+    }
+}